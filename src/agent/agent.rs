@@ -1,11 +1,13 @@
 use crate::agent::dispatcher::{
     NativeToolDispatcher, ParsedToolCall, ToolDispatcher, ToolExecutionResult, XmlToolDispatcher,
 };
+use crate::agent::loop_::{estimate_prompt_tokens, execute_in_batches};
 use crate::agent::memory_loader::{DefaultMemoryLoader, MemoryLoader};
 use crate::agent::prompt::{PromptContext, SystemPromptBuilder};
 use crate::config::Config;
+use crate::cost::CostTracker;
 use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
+use crate::observability::{self, Observer, ObserverEvent, ObserverMetric};
 use crate::providers::{self, ChatMessage, ChatRequest, ConversationMessage, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
@@ -26,13 +28,18 @@ pub struct Agent {
     tool_dispatcher: Box<dyn ToolDispatcher>,
     memory_loader: Box<dyn MemoryLoader>,
     config: crate::config::AgentConfig,
+    provider_name: String,
     model_name: String,
     temperature: f64,
     workspace_dir: std::path::PathBuf,
     identity_config: crate::config::IdentityConfig,
     skills: Vec<crate::skills::Skill>,
     auto_save: bool,
+    /// Records usage and enforces `[cost]` budgets when cost tracking is on
+    cost_tracker: Option<CostTracker>,
     history: Vec<ConversationMessage>,
+    /// Tokens reported by the provider so far; `None` until it reports any
+    tokens_used: Option<u64>,
 }
 
 pub struct AgentBuilder {
@@ -44,12 +51,14 @@ pub struct AgentBuilder {
     tool_dispatcher: Option<Box<dyn ToolDispatcher>>,
     memory_loader: Option<Box<dyn MemoryLoader>>,
     config: Option<crate::config::AgentConfig>,
+    provider_name: Option<String>,
     model_name: Option<String>,
     temperature: Option<f64>,
     workspace_dir: Option<std::path::PathBuf>,
     identity_config: Option<crate::config::IdentityConfig>,
     skills: Option<Vec<crate::skills::Skill>>,
     auto_save: Option<bool>,
+    cost_tracker: Option<CostTracker>,
 }
 
 impl AgentBuilder {
@@ -63,12 +72,14 @@ impl AgentBuilder {
             tool_dispatcher: None,
             memory_loader: None,
             config: None,
+            provider_name: None,
             model_name: None,
             temperature: None,
             workspace_dir: None,
             identity_config: None,
             skills: None,
             auto_save: None,
+            cost_tracker: None,
         }
     }
 
@@ -112,6 +123,11 @@ impl AgentBuilder {
         self
     }
 
+    pub fn provider_name(mut self, provider_name: String) -> Self {
+        self.provider_name = Some(provider_name);
+        self
+    }
+
     pub fn model_name(mut self, model_name: String) -> Self {
         self.model_name = Some(model_name);
         self
@@ -142,6 +158,11 @@ impl AgentBuilder {
         self
     }
 
+    pub fn cost_tracker(mut self, cost_tracker: Option<CostTracker>) -> Self {
+        self.cost_tracker = cost_tracker;
+        self
+    }
+
     pub fn build(self) -> Result<Agent> {
         let tools = self
             .tools
@@ -170,6 +191,7 @@ impl AgentBuilder {
                 .memory_loader
                 .unwrap_or_else(|| Box::new(DefaultMemoryLoader::default())),
             config: self.config.unwrap_or_default(),
+            provider_name: self.provider_name.unwrap_or_else(|| "openrouter".into()),
             model_name: self
                 .model_name
                 .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into()),
@@ -180,7 +202,9 @@ impl AgentBuilder {
             identity_config: self.identity_config.unwrap_or_default(),
             skills: self.skills.unwrap_or_default(),
             auto_save: self.auto_save.unwrap_or(false),
+            cost_tracker: self.cost_tracker,
             history: Vec::new(),
+            tokens_used: None,
         })
     }
 }
@@ -198,6 +222,11 @@ impl Agent {
         self.history.clear();
     }
 
+    /// Total tokens the provider reported across all turns, if it reports usage
+    pub fn tokens_used(&self) -> Option<u64> {
+        self.tokens_used
+    }
+
     pub fn from_config(config: &Config) -> Result<Self> {
         let observer: Arc<dyn Observer> =
             Arc::from(observability::create_observer(&config.observability));
@@ -212,6 +241,7 @@ impl Agent {
             &config.memory,
             &config.workspace_dir,
             config.api_key.as_deref(),
+            memory::MemoryCipher::from_config(config)?,
         )?);
 
         let composio_key = if config.composio.enabled {
@@ -225,10 +255,11 @@ impl Agent {
             None
         };
 
-        let tools = tools::all_tools_with_runtime(
+        let skills = crate::skills::load_skills(&config.workspace_dir);
+        let mut tools = tools::all_tools_with_runtime(
             Arc::new(config.clone()),
             &security,
-            runtime,
+            runtime.clone(),
             memory.clone(),
             composio_key,
             composio_entity_id,
@@ -239,6 +270,12 @@ impl Agent {
             config.api_key.as_deref(),
             config,
         );
+        tools.extend(tools::skill_tools(
+            &skills,
+            &security,
+            &runtime,
+            &config.http_request,
+        ));
 
         let provider_name = config.default_provider.as_deref().unwrap_or("openrouter");
 
@@ -252,6 +289,7 @@ impl Agent {
             provider_name,
             config.api_key.as_deref(),
             config.api_url.as_deref(),
+            config.default_model_vision,
             &config.reliability,
             &config.model_routes,
             &model_name,
@@ -274,12 +312,14 @@ impl Agent {
             .memory_loader(Box::new(DefaultMemoryLoader::default()))
             .prompt_builder(SystemPromptBuilder::with_defaults())
             .config(config.agent.clone())
+            .provider_name(provider_name.to_string())
             .model_name(model_name)
             .temperature(config.default_temperature)
             .workspace_dir(config.workspace_dir.clone())
             .identity_config(config.identity.clone())
-            .skills(crate::skills::load_skills(&config.workspace_dir))
+            .skills(skills)
             .auto_save(config.memory.auto_save)
+            .cost_tracker(CostTracker::from_config(config)?)
             .build()
     }
 
@@ -324,6 +364,9 @@ impl Agent {
     }
 
     async fn execute_tool_call(&self, call: &ParsedToolCall) -> ToolExecutionResult {
+        self.observer.record_event(&ObserverEvent::ToolCallStart {
+            tool: call.name.clone(),
+        });
         let start = Instant::now();
 
         let result = if let Some(tool) = self.tools.iter().find(|t| t.name() == call.name) {
@@ -350,6 +393,11 @@ impl Agent {
                 }
             }
         } else {
+            self.observer.record_event(&ObserverEvent::ToolCall {
+                tool: call.name.clone(),
+                duration: start.elapsed(),
+                success: false,
+            });
             format!("Unknown tool: {}", call.name)
         };
 
@@ -362,19 +410,18 @@ impl Agent {
     }
 
     async fn execute_tools(&self, calls: &[ParsedToolCall]) -> Vec<ToolExecutionResult> {
-        if !self.config.parallel_tools {
-            let mut results = Vec::with_capacity(calls.len());
-            for call in calls {
-                results.push(self.execute_tool_call(call).await);
-            }
-            return results;
-        }
-
-        let mut results = Vec::with_capacity(calls.len());
-        for call in calls {
-            results.push(self.execute_tool_call(call).await);
-        }
-        results
+        let jobs = calls
+            .iter()
+            .map(|call| {
+                let parallel_safe = self
+                    .tools
+                    .iter()
+                    .find(|t| t.name() == call.name)
+                    .map_or(true, |t| t.parallel_safe());
+                (parallel_safe, self.execute_tool_call(call))
+            })
+            .collect();
+        execute_in_batches(jobs, self.config.tool_concurrency()).await
     }
 
     pub async fn turn(&mut self, user_message: &str) -> Result<String> {
@@ -410,6 +457,14 @@ impl Agent {
 
         for _ in 0..self.config.max_tool_iterations {
             let messages = self.tool_dispatcher.to_provider_messages(&self.history);
+            if let Some(tracker) = &self.cost_tracker {
+                let estimate = tracker.estimate_cost(
+                    &self.provider_name,
+                    &self.model_name,
+                    estimate_prompt_tokens(&messages),
+                );
+                tracker.enforce_budget(estimate)?;
+            }
             let response = match self
                 .provider
                 .chat(
@@ -420,6 +475,8 @@ impl Agent {
                         } else {
                             None
                         },
+                        reasoning: None,
+                        response_format: None,
                     },
                     &self.model_name,
                     self.temperature,
@@ -430,6 +487,29 @@ impl Agent {
                 Err(err) => return Err(err),
             };
 
+            if let Some(usage) = response.usage {
+                self.tokens_used = Some(
+                    self.tokens_used
+                        .unwrap_or(0)
+                        .saturating_add(usage.total_tokens()),
+                );
+                self.observer
+                    .record_metric(&ObserverMetric::TokensUsed(usage.total_tokens()));
+                if let Some(tracker) = &self.cost_tracker {
+                    if let Err(e) =
+                        tracker.record_provider_usage(&self.provider_name, &self.model_name, usage)
+                    {
+                        tracing::warn!("Failed to record token usage: {e}");
+                    }
+                }
+            }
+
+            if self.config.show_reasoning {
+                if let Some(reasoning) = &response.reasoning {
+                    println!("{}", console::style(&reasoning.text).dim());
+                }
+            }
+
             let (text, calls) = self.tool_dispatcher.parse_response(&response);
             if calls.is_empty() {
                 let final_text = if text.is_empty() {
@@ -467,6 +547,7 @@ impl Agent {
             self.history.push(ConversationMessage::AssistantToolCalls {
                 text: response.text.clone(),
                 tool_calls: response.tool_calls.clone(),
+                reasoning: response.reasoning.clone(),
             });
 
             let results = self.execute_tools(&calls).await;
@@ -555,10 +636,14 @@ pub async fn run(
         agent.run_interactive().await?;
     }
 
+    let cost_summary = agent
+        .cost_tracker
+        .as_ref()
+        .and_then(|t| t.get_summary().ok());
     agent.observer.record_event(&ObserverEvent::AgentEnd {
         duration: start.elapsed(),
-        tokens_used: None,
-        cost_usd: None,
+        tokens_used: agent.tokens_used(),
+        cost_usd: cost_summary.map(|s| s.session_cost_usd),
     });
 
     Ok(())
@@ -597,6 +682,8 @@ mod tests {
                 return Ok(crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    usage: None,
+                    reasoning: None,
                 });
             }
             Ok(guard.remove(0))
@@ -634,6 +721,8 @@ mod tests {
             responses: Mutex::new(vec![crate::providers::ChatResponse {
                 text: Some("hello".into()),
                 tool_calls: vec![],
+                usage: Some(crate::providers::Usage::new(12, 3)),
+                reasoning: None,
             }]),
         });
 
@@ -642,7 +731,8 @@ mod tests {
             ..crate::config::MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> = Arc::from(
-            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None, None)
+                .unwrap(),
         );
 
         let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
@@ -656,8 +746,57 @@ mod tests {
             .build()
             .unwrap();
 
+        assert_eq!(agent.tokens_used(), None);
         let response = agent.turn("hi").await.unwrap();
         assert_eq!(response, "hello");
+        assert_eq!(agent.tokens_used(), Some(15));
+    }
+
+    #[tokio::test]
+    async fn turn_records_usage_and_enforces_budget() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let tracker = CostTracker::new(
+            crate::config::schema::CostConfig {
+                enabled: true,
+                daily_limit_usd: 1.0,
+                ..Default::default()
+            },
+            tmp.path(),
+        )
+        .unwrap();
+        let metered = || crate::providers::ChatResponse {
+            text: Some("hello".into()),
+            tool_calls: vec![],
+            usage: Some(crate::providers::Usage::new(1_000_000, 1_000_000)),
+            reasoning: None,
+        };
+        let provider = Box::new(MockProvider {
+            responses: Mutex::new(vec![metered(), metered()]),
+        });
+
+        let mem: Arc<dyn Memory> = Arc::new(crate::memory::NoneMemory::new());
+        let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
+        let mut agent = Agent::builder()
+            .provider(provider)
+            .tools(vec![])
+            .memory(mem)
+            .observer(observer)
+            .tool_dispatcher(Box::new(XmlToolDispatcher))
+            .workspace_dir(tmp.path().to_path_buf())
+            .provider_name("anthropic".into())
+            .model_name("claude-sonnet-4-20250514".into())
+            .cost_tracker(Some(tracker))
+            .build()
+            .unwrap();
+
+        // The first turn is within budget and spends $18, blowing the $1 cap.
+        assert_eq!(agent.turn("hi").await.unwrap(), "hello");
+        let err = agent.turn("again").await.unwrap_err();
+        assert!(err.to_string().contains("Daily budget exceeded"));
+
+        let summary = agent.cost_tracker.as_ref().unwrap().get_summary().unwrap();
+        assert_eq!(summary.total_tokens, 2_000_000);
+        assert!((summary.session_cost_usd - 18.0).abs() < 1e-9);
     }
 
     #[tokio::test]
@@ -671,10 +810,14 @@ mod tests {
                         name: "echo".into(),
                         arguments: "{}".into(),
                     }],
+                    usage: None,
+                    reasoning: None,
                 },
                 crate::providers::ChatResponse {
                     text: Some("done".into()),
                     tool_calls: vec![],
+                    usage: None,
+                    reasoning: None,
                 },
             ]),
         });
@@ -684,7 +827,8 @@ mod tests {
             ..crate::config::MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> = Arc::from(
-            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None).unwrap(),
+            crate::memory::create_memory(&memory_cfg, std::path::Path::new("/tmp"), None, None)
+                .unwrap(),
         );
 
         let observer: Arc<dyn Observer> = Arc::from(crate::observability::NoopObserver {});
@@ -197,11 +197,19 @@ impl ToolDispatcher for NativeToolDispatcher {
             .iter()
             .flat_map(|msg| match msg {
                 ConversationMessage::Chat(chat) => vec![chat.clone()],
-                ConversationMessage::AssistantToolCalls { text, tool_calls } => {
-                    let payload = serde_json::json!({
+                ConversationMessage::AssistantToolCalls {
+                    text,
+                    tool_calls,
+                    reasoning,
+                } => {
+                    let mut payload = serde_json::json!({
                         "content": text,
                         "tool_calls": tool_calls,
                     });
+                    // Providers that require it send the reasoning back
+                    if let Some(reasoning) = reasoning {
+                        payload["reasoning"] = serde_json::json!(reasoning);
+                    }
                     vec![ChatMessage::assistant(payload.to_string())]
                 }
                 ConversationMessage::ToolResults(results) => results
@@ -228,6 +236,7 @@ impl ToolDispatcher for NativeToolDispatcher {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::{Reasoning, ToolCall};
 
     #[test]
     fn xml_dispatcher_parses_tool_calls() {
@@ -237,6 +246,8 @@ mod tests {
                     .into(),
             ),
             tool_calls: vec![],
+            usage: None,
+            reasoning: None,
         };
         let dispatcher = XmlToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -253,6 +264,8 @@ mod tests {
                 name: "file_read".into(),
                 arguments: "{\"path\":\"a.txt\"}".into(),
             }],
+            usage: None,
+            reasoning: None,
         };
         let dispatcher = NativeToolDispatcher;
         let (_, calls) = dispatcher.parse_response(&response);
@@ -309,4 +322,36 @@ mod tests {
             _ => panic!("expected ToolResults variant"),
         }
     }
+
+    #[test]
+    fn native_history_carries_reasoning_only_when_present() {
+        let dispatcher = NativeToolDispatcher;
+        let call = ToolCall {
+            id: "tc1".into(),
+            name: "shell".into(),
+            arguments: "{}".into(),
+        };
+        let history = vec![
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![call.clone()],
+                reasoning: Some(Reasoning {
+                    text: "Need the date.".into(),
+                    replay: Some(serde_json::json!([{"type": "thinking"}])),
+                }),
+            },
+            ConversationMessage::AssistantToolCalls {
+                text: None,
+                tool_calls: vec![call],
+                reasoning: None,
+            },
+        ];
+
+        let messages = dispatcher.to_provider_messages(&history);
+        let with: Value = serde_json::from_str(&messages[0].content).unwrap();
+        assert_eq!(with["reasoning"]["text"], "Need the date.");
+        assert_eq!(with["reasoning"]["replay"][0]["type"], "thinking");
+        let without: Value = serde_json::from_str(&messages[1].content).unwrap();
+        assert!(without.get("reasoning").is_none());
+    }
 }
@@ -1,18 +1,23 @@
-use crate::approval::{ApprovalManager, ApprovalRequest, ApprovalResponse};
+use crate::approval::{ApprovalManager, ApprovalPrompter, ApprovalRequest, ApprovalResponse};
 use crate::config::Config;
-use crate::memory::{self, Memory, MemoryCategory};
-use crate::observability::{self, Observer, ObserverEvent};
-use crate::providers::{self, ChatMessage, Provider, ToolCall};
+use crate::cost::CostTracker;
+use crate::memory::{self, Memory, MemoryCategory, ResponseCache};
+use crate::observability::{self, Observer, ObserverEvent, ObserverMetric};
+use crate::providers::traits::{collect_tool_call_deltas, StreamOptions};
+use crate::providers::{
+    self, ChatMessage, ChatRequest, ChatResponse, Provider, Reasoning, ToolCall,
+};
 use crate::runtime;
 use crate::security::SecurityPolicy;
-use crate::tools::{self, Tool};
+use crate::tools::{self, Tool, ToolSpec};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
+use futures_util::StreamExt;
 use regex::{Regex, RegexSet};
 use std::fmt::Write;
 use std::io::Write as _;
 use std::sync::{Arc, LazyLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 use uuid::Uuid;
 
 /// Maximum agentic tool-use iterations per user message to prevent runaway loops.
@@ -38,7 +43,7 @@ static SENSITIVE_KV_REGEX: LazyLock<Regex> = LazyLock::new(|| {
 /// Scrub credentials from tool output to prevent accidental exfiltration.
 /// Replaces known credential patterns with a redacted placeholder while preserving
 /// a small prefix for context.
-fn scrub_credentials(input: &str) -> String {
+pub(crate) fn scrub_credentials(input: &str) -> String {
     SENSITIVE_KV_REGEX
         .replace_all(input, |caps: &regex::Captures| {
             let full_match = &caps[0];
@@ -73,7 +78,7 @@ fn scrub_credentials(input: &str) -> String {
 }
 
 /// Trigger auto-compaction when non-system message count exceeds this threshold.
-const MAX_HISTORY_MESSAGES: usize = 50;
+pub(crate) const MAX_HISTORY_MESSAGES: usize = 50;
 
 /// Keep this many most-recent non-system messages after compaction.
 const COMPACTION_KEEP_RECENT_MESSAGES: usize = 20;
@@ -101,13 +106,20 @@ fn tools_to_openai_format(tools_registry: &[Box<dyn Tool>]) -> Vec<serde_json::V
         .collect()
 }
 
+/// Rough prompt size (~4 chars per token) for pre-call budget checks.
+pub(crate) fn estimate_prompt_tokens(history: &[ChatMessage]) -> u64 {
+    let chars: usize = history.iter().map(|m| m.content.len()).sum();
+    chars.div_ceil(4) as u64
+}
+
 fn autosave_memory_key(prefix: &str) -> String {
     format!("{prefix}_{}", Uuid::new_v4())
 }
 
 /// Trim conversation history to prevent unbounded growth.
-/// Preserves the system prompt (first message if role=system) and the most recent messages.
-fn trim_history(history: &mut Vec<ChatMessage>) {
+/// Preserves the system prompt (first message if role=system) and the most
+/// recent `max_messages` other messages.
+pub(crate) fn trim_history(history: &mut Vec<ChatMessage>, max_messages: usize) {
     // Nothing to trim if within limit
     let has_system = history.first().map_or(false, |m| m.role == "system");
     let non_system_count = if has_system {
@@ -116,12 +128,12 @@ fn trim_history(history: &mut Vec<ChatMessage>) {
         history.len()
     };
 
-    if non_system_count <= MAX_HISTORY_MESSAGES {
+    if non_system_count <= max_messages {
         return;
     }
 
     let start = if has_system { 1 } else { 0 };
-    let to_remove = non_system_count - MAX_HISTORY_MESSAGES;
+    let to_remove = non_system_count - max_messages;
     history.drain(start..start + to_remove);
 }
 
@@ -149,7 +161,7 @@ fn apply_compaction_summary(
     history.splice(start..compact_end, std::iter::once(summary_msg));
 }
 
-async fn auto_compact_history(
+pub(crate) async fn auto_compact_history(
     history: &mut Vec<ChatMessage>,
     provider: &dyn Provider,
     model: &str,
@@ -198,7 +210,7 @@ async fn auto_compact_history(
 }
 
 /// Build context preamble by searching memory for relevant entries
-async fn build_context(mem: &dyn Memory, user_msg: &str) -> String {
+pub(crate) async fn build_context(mem: &dyn Memory, user_msg: &str) -> String {
     let mut context = String::new();
 
     // Pull relevant memories for this message
@@ -260,6 +272,90 @@ fn find_tool<'a>(tools: &'a [Box<dyn Tool>], name: &str) -> Option<&'a dyn Tool>
     tools.iter().find(|t| t.name() == name).map(|t| t.as_ref())
 }
 
+/// Await `jobs` in order, letting consecutive parallel-safe jobs (flagged
+/// `true`) run up to `max_parallel` at a time. A job that is not
+/// parallel-safe runs on its own, after everything before it and before
+/// anything after it. Results are returned in the same order as `jobs`.
+pub(crate) async fn execute_in_batches<Fut>(
+    jobs: Vec<(bool, Fut)>,
+    max_parallel: usize,
+) -> Vec<Fut::Output>
+where
+    Fut: std::future::Future,
+{
+    let max_parallel = max_parallel.max(1);
+    let mut results = Vec::with_capacity(jobs.len());
+    let mut jobs = jobs.into_iter().peekable();
+
+    while let Some((parallel_safe, first)) = jobs.next() {
+        let mut batch = vec![first];
+        if parallel_safe && max_parallel > 1 {
+            while let Some((_, next)) = jobs.next_if(|(safe, _)| *safe) {
+                batch.push(next);
+            }
+        }
+
+        let outputs: Vec<Fut::Output> = futures_util::stream::iter(batch)
+            .buffered(max_parallel)
+            .collect()
+            .await;
+        results.extend(outputs);
+    }
+
+    results
+}
+
+/// Execute one tool call and return the text for its `<tool_result>` block.
+/// Every call records a `ToolCallStart` followed by its matching `ToolCall`,
+/// and streams the same pair as `ToolStart` / `ToolResult`.
+async fn execute_tool_call(
+    tools_registry: &[Box<dyn Tool>],
+    call: &ParsedToolCall,
+    observer: &dyn Observer,
+    stream_tx: Option<&tokio::sync::mpsc::Sender<StreamEvent>>,
+) -> String {
+    observer.record_event(&ObserverEvent::ToolCallStart {
+        tool: call.name.clone(),
+    });
+    if let Some(tx) = stream_tx {
+        let _ = tx
+            .send(StreamEvent::ToolStart {
+                tool: call.name.clone(),
+            })
+            .await;
+    }
+    let start = Instant::now();
+
+    let (success, output) = match find_tool(tools_registry, &call.name) {
+        None => (false, format!("Unknown tool: {}", call.name)),
+        Some(tool) => match tool.execute(call.arguments.clone()).await {
+            Ok(r) if r.success => (true, scrub_credentials(&r.output)),
+            Ok(r) => (
+                false,
+                format!("Error: {}", r.error.unwrap_or_else(|| r.output)),
+            ),
+            Err(e) => (false, format!("Error executing {}: {e}", call.name)),
+        },
+    };
+
+    let duration = start.elapsed();
+    observer.record_event(&ObserverEvent::ToolCall {
+        tool: call.name.clone(),
+        duration,
+        success,
+    });
+    if let Some(tx) = stream_tx {
+        let _ = tx
+            .send(StreamEvent::ToolResult {
+                tool: call.name.clone(),
+                success,
+                duration,
+            })
+            .await;
+    }
+    output
+}
+
 fn parse_arguments_value(raw: Option<&serde_json::Value>) -> serde_json::Value {
     match raw {
         Some(serde_json::Value::String(s)) => serde_json::from_str::<serde_json::Value>(s)
@@ -346,6 +442,22 @@ fn matching_tool_call_close_tag(open_tag: &str) -> Option<&'static str> {
     }
 }
 
+/// Length of the prefix of streamed `text` that is safe to show live:
+/// everything before the first tool-call tag, minus a trailing fragment that
+/// may still grow into one.
+fn displayable_stream_len(text: &str) -> usize {
+    if let Some((idx, _)) = find_first_tag(text, &TOOL_CALL_OPEN_TAGS) {
+        return idx;
+    }
+    if let Some(lt) = text.rfind('<') {
+        let tail = &text[lt..];
+        if TOOL_CALL_OPEN_TAGS.iter().any(|tag| tag.starts_with(tail)) {
+            return lt;
+        }
+    }
+    text.len()
+}
+
 /// Extract JSON values from a string.
 ///
 /// # Security Warning
@@ -515,6 +627,163 @@ struct ParsedToolCall {
     arguments: serde_json::Value,
 }
 
+/// Live output from a streamed agent turn, for callers that render the
+/// reply progressively (e.g. channel drafts edited in place).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum StreamEvent {
+    /// Reply text appended since the previous event.
+    Delta(String),
+    /// The model asked for tools, so the text streamed so far was not the
+    /// final reply; the next round starts from scratch.
+    ToolRound,
+    /// An approved tool call started running.
+    ToolStart { tool: String },
+    /// A tool call finished.
+    ToolResult {
+        tool: String,
+        success: bool,
+        duration: Duration,
+    },
+}
+
+/// How a tool-call loop shows its progress on stdout.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub(crate) struct LoopOutput {
+    /// Print nothing to stdout (channels and the gateway)
+    pub silent: bool,
+    /// Print model reasoning dimmed; ignored when `silent`
+    pub show_reasoning: bool,
+}
+
+impl LoopOutput {
+    /// No stdout output at all.
+    pub(crate) const SILENT: Self = Self {
+        silent: true,
+        show_reasoning: false,
+    };
+
+    /// Interactive CLI output, optionally with model reasoning.
+    pub(crate) fn console(show_reasoning: bool) -> Self {
+        Self {
+            silent: false,
+            show_reasoning,
+        }
+    }
+
+    fn prints_reasoning(self) -> bool {
+        self.show_reasoning && !self.silent
+    }
+}
+
+/// Show reply text live: print it unless `silent`, and forward it to the
+/// caller's stream, if any.
+async fn emit_stream_text(
+    text: &str,
+    silent: bool,
+    stream_tx: Option<&tokio::sync::mpsc::Sender<StreamEvent>>,
+) {
+    if text.is_empty() {
+        return;
+    }
+    if !silent {
+        print!("{text}");
+        let _ = std::io::stdout().flush();
+    }
+    if let Some(tx) = stream_tx {
+        let _ = tx.send(StreamEvent::Delta(text.to_string())).await;
+    }
+}
+
+/// Print model reasoning dimmed on the CLI. Reasoning is never forwarded to
+/// a stream or channel.
+fn print_reasoning(text: &str) {
+    if text.is_empty() {
+        return;
+    }
+    print!("{}", console::style(text).dim());
+    let _ = std::io::stdout().flush();
+}
+
+/// Stream one model call, showing reply text as it arrives and assembling
+/// native tool-call fragments. Reasoning deltas are collected separately
+/// and printed only when `output` asks for them.
+///
+/// Returns `Ok(None)` when the stream failed before producing anything, so
+/// the caller can retry with the blocking API.
+#[allow(clippy::too_many_arguments)]
+async fn stream_chat_turn(
+    provider: &dyn Provider,
+    history: &[ChatMessage],
+    tool_definitions: &[serde_json::Value],
+    model: &str,
+    temperature: f64,
+    output: LoopOutput,
+    stream_tx: Option<&tokio::sync::mpsc::Sender<StreamEvent>>,
+) -> Result<Option<ChatResponse>> {
+    let options = StreamOptions::new(true);
+    let mut stream = if tool_definitions.is_empty() {
+        provider.stream_chat_with_history(history, model, temperature, options)
+    } else {
+        provider.stream_chat_with_tools(history, tool_definitions, model, temperature, options)
+    };
+
+    let mut text = String::new();
+    let mut tool_call_deltas = Vec::new();
+    let mut usage = None;
+    let mut reasoning = String::new();
+    let mut reasoning_replay = None;
+    let mut shown = 0;
+    let print_thoughts = output.prints_reasoning();
+
+    while let Some(item) = stream.next().await {
+        let chunk = match item {
+            Ok(chunk) => chunk,
+            Err(e) if text.is_empty() && tool_call_deltas.is_empty() && reasoning.is_empty() => {
+                tracing::debug!("Streaming failed, falling back to blocking chat: {e}");
+                return Ok(None);
+            }
+            Err(e) => anyhow::bail!("Streaming response failed: {e}"),
+        };
+
+        if print_thoughts {
+            print_reasoning(&chunk.reasoning);
+        }
+        reasoning.push_str(&chunk.reasoning);
+        if chunk.reasoning_replay.is_some() {
+            reasoning_replay = chunk.reasoning_replay;
+        }
+        text.push_str(&chunk.delta);
+        tool_call_deltas.extend(chunk.tool_calls);
+        if chunk.usage.is_some() {
+            usage = chunk.usage;
+        }
+
+        let visible = displayable_stream_len(&text);
+        if visible > shown {
+            if print_thoughts && shown == 0 && !reasoning.is_empty() {
+                println!();
+            }
+            emit_stream_text(&text[shown..visible], output.silent, stream_tx).await;
+            shown = visible;
+        }
+
+        if chunk.is_final {
+            break;
+        }
+    }
+
+    if text.is_empty() && tool_call_deltas.is_empty() {
+        return Ok(None);
+    }
+
+    Ok(Some(ChatResponse {
+        text: Some(text),
+        tool_calls: collect_tool_call_deltas(&tool_call_deltas),
+        usage,
+        reasoning: Reasoning::from_parts(reasoning, reasoning_replay),
+    }))
+}
+
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
 /// When `silent` is true, suppresses stdout (for channel use).
@@ -528,6 +797,8 @@ pub(crate) async fn agent_turn(
     model: &str,
     temperature: f64,
     silent: bool,
+    cost_tracker: Option<&CostTracker>,
+    max_parallel_tools: usize,
 ) -> Result<String> {
     run_tool_call_loop(
         provider,
@@ -537,16 +808,51 @@ pub(crate) async fn agent_turn(
         provider_name,
         model,
         temperature,
-        silent,
+        LoopOutput {
+            silent,
+            show_reasoning: false,
+        },
+        None,
         None,
         "channel",
+        cost_tracker,
+        None,
+        None,
+        max_parallel_tools,
     )
     .await
 }
 
+/// Response-cache key for the next call: the model, the system prompt and
+/// the full conversation so far.
+pub(crate) fn response_cache_key(model: &str, history: &[ChatMessage]) -> String {
+    let system_prompt = history
+        .iter()
+        .filter(|m| m.role == "system")
+        .map(|m| m.content.as_str())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let conversation: Vec<&ChatMessage> = history.iter().filter(|m| m.role != "system").collect();
+    let conversation = serde_json::to_string(&conversation).unwrap_or_default();
+    ResponseCache::cache_key(model, Some(&system_prompt), &conversation)
+}
+
 /// Execute a single turn of the agent loop: send messages, parse tool calls,
 /// execute tools, and loop until the LLM produces a final text response.
-#[allow(clippy::too_many_arguments)]
+///
+/// When the provider supports streaming, reply text is shown as it is
+/// generated: printed to stdout unless `output` is silent, and sent to
+/// `stream_tx` if given. Otherwise the blocking API is used and nothing is
+/// streamed. With [`LoopOutput::show_reasoning`], model reasoning is printed
+/// dimmed as well (never when silent, and never to `stream_tx`).
+///
+/// Tool calls from one response run up to `max_parallel_tools` at a time;
+/// tools that are not [`Tool::parallel_safe`] always run alone.
+///
+/// With a `response_cache`, a turn at or below the cache's temperature
+/// threshold is answered from the cache when the same conversation was seen
+/// before, and stored when the model answers without calling tools.
+#[allow(clippy::too_many_arguments, clippy::too_many_lines)]
 pub(crate) async fn run_tool_call_loop(
     provider: &dyn Provider,
     history: &mut Vec<ChatMessage>,
@@ -555,10 +861,22 @@ pub(crate) async fn run_tool_call_loop(
     provider_name: &str,
     model: &str,
     temperature: f64,
-    silent: bool,
+    output: LoopOutput,
     approval: Option<&ApprovalManager>,
+    approval_prompter: Option<&dyn ApprovalPrompter>,
     channel_name: &str,
+    cost_tracker: Option<&CostTracker>,
+    response_cache: Option<&ResponseCache>,
+    stream_tx: Option<&tokio::sync::mpsc::Sender<StreamEvent>>,
+    max_parallel_tools: usize,
 ) -> Result<String> {
+    // Providers without vision get text placeholders instead of media parts.
+    if !provider.supports_vision() {
+        for message in history.iter_mut().filter(|m| m.has_media()) {
+            *message = message.text_fallback();
+        }
+    }
+
     // Build native tool definitions once if the provider supports them.
     let use_native_tools = provider.supports_native_tools() && !tools_registry.is_empty();
     let tool_definitions = if use_native_tools {
@@ -566,8 +884,42 @@ pub(crate) async fn run_tool_call_loop(
     } else {
         Vec::new()
     };
+    let tool_specs: Vec<ToolSpec> = if use_native_tools {
+        tools_registry.iter().map(|tool| tool.spec()).collect()
+    } else {
+        Vec::new()
+    };
 
-    for _iteration in 0..MAX_TOOL_ITERATIONS {
+    let silent = output.silent;
+    let stream_live = provider.supports_streaming() && (!silent || stream_tx.is_some());
+
+    let cache_entry = response_cache
+        .filter(|cache| cache.accepts_temperature(temperature))
+        .map(|cache| (cache, response_cache_key(model, history)));
+    if let Some((cache, key)) = &cache_entry {
+        match cache.get(key) {
+            Ok(Some(cached)) => {
+                observer.record_event(&ObserverEvent::ResponseCacheLookup { hit: true });
+                let (parsed_text, _) = parse_tool_calls(&cached);
+                let display_text = if parsed_text.is_empty() {
+                    cached.clone()
+                } else {
+                    parsed_text
+                };
+                if stream_live {
+                    emit_stream_text(&display_text, silent, stream_tx).await;
+                }
+                history.push(ChatMessage::assistant(cached));
+                return Ok(display_text);
+            }
+            Ok(None) => {
+                observer.record_event(&ObserverEvent::ResponseCacheLookup { hit: false });
+            }
+            Err(e) => tracing::warn!("Response cache lookup failed: {e}"),
+        }
+    }
+
+    for iteration in 0..MAX_TOOL_ITERATIONS {
         observer.record_event(&ObserverEvent::LlmRequest {
             provider: provider_name.to_string(),
             model: model.to_string(),
@@ -576,89 +928,98 @@ pub(crate) async fn run_tool_call_loop(
 
         let llm_started_at = Instant::now();
 
-        // Choose between native tool-call API and prompt-based tool use.
-        let (response_text, parsed_text, tool_calls, assistant_history_content) =
-            if use_native_tools {
-                match provider
-                    .chat_with_tools(history, &tool_definitions, model, temperature)
-                    .await
-                {
-                    Ok(resp) => {
-                        observer.record_event(&ObserverEvent::LlmResponse {
-                            provider: provider_name.to_string(),
-                            model: model.to_string(),
-                            duration: llm_started_at.elapsed(),
-                            success: true,
-                            error_message: None,
-                        });
-                        let response_text = resp.text_or_empty().to_string();
-                        let mut calls = parse_structured_tool_calls(&resp.tool_calls);
-                        let mut parsed_text = String::new();
-
-                        if calls.is_empty() {
-                            let (fallback_text, fallback_calls) = parse_tool_calls(&response_text);
-                            if !fallback_text.is_empty() {
-                                parsed_text = fallback_text;
-                            }
-                            calls = fallback_calls;
-                        }
-
-                        let assistant_history_content = if resp.tool_calls.is_empty() {
-                            response_text.clone()
-                        } else {
-                            build_assistant_history_with_tool_calls(
-                                &response_text,
-                                &resp.tool_calls,
-                            )
-                        };
-
-                        (response_text, parsed_text, calls, assistant_history_content)
-                    }
-                    Err(e) => {
-                        observer.record_event(&ObserverEvent::LlmResponse {
-                            provider: provider_name.to_string(),
-                            model: model.to_string(),
-                            duration: llm_started_at.elapsed(),
-                            success: false,
-                            error_message: Some(crate::providers::sanitize_api_error(
-                                &e.to_string(),
-                            )),
-                        });
-                        return Err(e);
-                    }
-                }
-            } else {
-                match provider
-                    .chat_with_history(history, model, temperature)
+        if let Some(tracker) = cost_tracker {
+            let estimate =
+                tracker.estimate_cost(provider_name, model, estimate_prompt_tokens(history));
+            tracker.enforce_budget(estimate)?;
+        }
+
+        let streamed = if stream_live {
+            stream_chat_turn(
+                provider,
+                history,
+                &tool_definitions,
+                model,
+                temperature,
+                output,
+                stream_tx,
+            )
+            .await
+        } else {
+            Ok(None)
+        };
+        let streamed_turn = matches!(streamed, Ok(Some(_)));
+
+        let chat_result = match streamed {
+            Ok(Some(resp)) => Ok(resp),
+            Err(e) => Err(e),
+            // Native tool specs when supported; otherwise tools are
+            // described in the system prompt.
+            Ok(None) => {
+                provider
+                    .chat(
+                        ChatRequest {
+                            messages: history,
+                            tools: use_native_tools.then_some(tool_specs.as_slice()),
+                            reasoning: None,
+                            response_format: None,
+                        },
+                        model,
+                        temperature,
+                    )
                     .await
-                {
-                    Ok(resp) => {
-                        observer.record_event(&ObserverEvent::LlmResponse {
-                            provider: provider_name.to_string(),
-                            model: model.to_string(),
-                            duration: llm_started_at.elapsed(),
-                            success: true,
-                            error_message: None,
-                        });
-                        let response_text = resp;
-                        let assistant_history_content = response_text.clone();
-                        let (parsed_text, calls) = parse_tool_calls(&response_text);
-                        (response_text, parsed_text, calls, assistant_history_content)
-                    }
-                    Err(e) => {
-                        observer.record_event(&ObserverEvent::LlmResponse {
-                            provider: provider_name.to_string(),
-                            model: model.to_string(),
-                            duration: llm_started_at.elapsed(),
-                            success: false,
-                            error_message: Some(crate::providers::sanitize_api_error(
-                                &e.to_string(),
-                            )),
-                        });
-                        return Err(e);
-                    }
+            }
+        };
+
+        let resp = match chat_result {
+            Ok(resp) => {
+                observer.record_event(&ObserverEvent::LlmResponse {
+                    provider: provider_name.to_string(),
+                    model: model.to_string(),
+                    duration: llm_started_at.elapsed(),
+                    success: true,
+                    error_message: None,
+                });
+                resp
+            }
+            Err(e) => {
+                observer.record_event(&ObserverEvent::LlmResponse {
+                    provider: provider_name.to_string(),
+                    model: model.to_string(),
+                    duration: llm_started_at.elapsed(),
+                    success: false,
+                    error_message: Some(crate::providers::sanitize_api_error(&e.to_string())),
+                });
+                return Err(e);
+            }
+        };
+
+        if let Some(usage) = resp.usage {
+            observer.record_metric(&ObserverMetric::TokensUsed(usage.total_tokens()));
+            if let Some(tracker) = cost_tracker {
+                if let Err(e) = tracker.record_provider_usage(provider_name, model, usage) {
+                    tracing::warn!("Failed to record token usage: {e}");
                 }
-            };
+            }
+        }
+
+        let response_text = resp.text_or_empty().to_string();
+        let mut tool_calls = parse_structured_tool_calls(&resp.tool_calls);
+        let mut parsed_text = String::new();
+
+        if tool_calls.is_empty() {
+            let (fallback_text, fallback_calls) = parse_tool_calls(&response_text);
+            if !fallback_text.is_empty() {
+                parsed_text = fallback_text;
+            }
+            tool_calls = fallback_calls;
+        }
+
+        let assistant_history_content = if resp.tool_calls.is_empty() {
+            response_text.clone()
+        } else {
+            build_assistant_history_with_tool_calls(&response_text, &resp.tool_calls)
+        };
 
         let display_text = if parsed_text.is_empty() {
             response_text.clone()
@@ -666,21 +1027,53 @@ pub(crate) async fn run_tool_call_loop(
             parsed_text
         };
 
+        if output.prints_reasoning() && !streamed_turn {
+            if let Some(reasoning) = &resp.reasoning {
+                print_reasoning(&reasoning.text);
+                println!();
+            }
+        }
+
+        // A blocking fallback still shows its text when streaming is on.
+        if stream_live && !streamed_turn {
+            emit_stream_text(&display_text, silent, stream_tx).await;
+        }
+
         if tool_calls.is_empty() {
+            // Only tool-free turns are cached: a later round depends on tool output.
+            if let Some((cache, key)) = cache_entry.as_ref().filter(|_| iteration == 0) {
+                if !response_text.is_empty() {
+                    let tokens = resp
+                        .usage
+                        .map_or(0, |u| u32::try_from(u.total_tokens()).unwrap_or(u32::MAX));
+                    if let Err(e) = cache.put(key, model, &response_text, tokens) {
+                        tracing::warn!("Failed to store response in cache: {e}");
+                    }
+                }
+            }
+
             // No tool calls — this is the final response
             history.push(ChatMessage::assistant(response_text.clone()));
             return Ok(display_text);
         }
 
-        // Print any text the LLM produced alongside tool calls (unless silent)
-        if !silent && !display_text.is_empty() {
+        if stream_live {
+            if !silent && !display_text.is_empty() {
+                println!();
+            }
+            if let Some(tx) = stream_tx {
+                let _ = tx.send(StreamEvent::ToolRound).await;
+            }
+        } else if !silent && !display_text.is_empty() {
+            // Print any text the LLM produced alongside tool calls (unless silent)
             print!("{display_text}");
             let _ = std::io::stdout().flush();
         }
 
-        // Execute each tool call and build results
-        let mut tool_results = String::new();
-        for call in &tool_calls {
+        // Approval prompts stay sequential; approved calls then run in
+        // batches and their results are written back in the original order.
+        let mut denied = vec![false; tool_calls.len()];
+        for (call, denied) in tool_calls.iter().zip(denied.iter_mut()) {
             // ── Approval hook ────────────────────────────────
             if let Some(mgr) = approval {
                 if mgr.needs_approval(&call.name) {
@@ -689,57 +1082,50 @@ pub(crate) async fn run_tool_call_loop(
                         arguments: call.arguments.clone(),
                     };
 
-                    // Only prompt interactively on CLI; auto-approve on other channels.
+                    // Prompt on the CLI or through the originating channel;
+                    // callers without either auto-approve.
                     let decision = if channel_name == "cli" {
                         mgr.prompt_cli(&request)
+                    } else if let Some(prompter) = approval_prompter {
+                        prompter.prompt(&request).await
                     } else {
                         ApprovalResponse::Yes
                     };
 
                     mgr.record_decision(&call.name, &call.arguments, decision, channel_name);
-
-                    if decision == ApprovalResponse::No {
-                        let _ = writeln!(
-                            tool_results,
-                            "<tool_result name=\"{}\">\nDenied by user.\n</tool_result>",
-                            call.name
-                        );
-                        continue;
-                    }
+                    *denied = decision == ApprovalResponse::No;
                 }
             }
+        }
 
-            observer.record_event(&ObserverEvent::ToolCallStart {
-                tool: call.name.clone(),
-            });
-            let start = Instant::now();
-            let result = if let Some(tool) = find_tool(tools_registry, &call.name) {
-                match tool.execute(call.arguments.clone()).await {
-                    Ok(r) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: r.success,
-                        });
-                        if r.success {
-                            scrub_credentials(&r.output)
-                        } else {
-                            format!("Error: {}", r.error.unwrap_or_else(|| r.output))
-                        }
-                    }
-                    Err(e) => {
-                        observer.record_event(&ObserverEvent::ToolCall {
-                            tool: call.name.clone(),
-                            duration: start.elapsed(),
-                            success: false,
-                        });
-                        format!("Error executing {}: {e}", call.name)
-                    }
-                }
+        let approved: Vec<&ParsedToolCall> = tool_calls
+            .iter()
+            .zip(&denied)
+            .filter(|(_, denied)| !**denied)
+            .map(|(call, _)| call)
+            .collect();
+        let jobs = approved
+            .iter()
+            .map(|call| {
+                let parallel_safe =
+                    find_tool(tools_registry, &call.name).map_or(true, |t| t.parallel_safe());
+                (
+                    parallel_safe,
+                    execute_tool_call(tools_registry, call, observer, stream_tx),
+                )
+            })
+            .collect();
+        let mut outputs = execute_in_batches(jobs, max_parallel_tools)
+            .await
+            .into_iter();
+
+        let mut tool_results = String::new();
+        for (call, denied) in tool_calls.iter().zip(&denied) {
+            let result = if *denied {
+                "Denied by user.".to_string()
             } else {
-                format!("Unknown tool: {}", call.name)
+                outputs.next().unwrap_or_default()
             };
-
             let _ = writeln!(
                 tool_results,
                 "<tool_result name=\"{}\">\n{}\n</tool_result>",
@@ -805,10 +1191,12 @@ pub async fn run(
     ));
 
     // ── Memory (the brain) ────────────────────────────────────────
+    let memory_cipher = memory::MemoryCipher::from_config(&config)?;
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
         &config.workspace_dir,
         config.api_key.as_deref(),
+        memory_cipher.clone(),
     )?);
     tracing::info!(backend = mem.name(), "Memory initialized");
 
@@ -829,10 +1217,11 @@ pub async fn run(
     } else {
         (None, None)
     };
+    let skills = crate::skills::load_skills(&config.workspace_dir);
     let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
-        runtime,
+        runtime.clone(),
         mem.clone(),
         composio_key,
         composio_entity_id,
@@ -843,6 +1232,12 @@ pub async fn run(
         config.api_key.as_deref(),
         &config,
     );
+    tools_registry.extend(tools::skill_tools(
+        &skills,
+        &security,
+        &runtime,
+        &config.http_request,
+    ));
 
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
@@ -866,11 +1261,18 @@ pub async fn run(
         provider_name,
         config.api_key.as_deref(),
         config.api_url.as_deref(),
+        config.default_model_vision,
         &config.reliability,
         &config.model_routes,
         model_name,
     )?;
 
+    let cost_tracker = CostTracker::from_config(&config)?;
+    let response_cache =
+        memory::create_response_cache(&config.memory, &config.workspace_dir, memory_cipher);
+    // Streaming providers print replies token by token from inside the loop.
+    let live_output = provider.supports_streaming();
+
     observer.record_event(&ObserverEvent::AgentStart {
         provider: provider_name.to_string(),
         model: model_name.to_string(),
@@ -897,7 +1299,6 @@ pub async fn run(
         .collect();
 
     // ── Build system prompt from workspace MD files (OpenClaw framework) ──
-    let skills = crate::skills::load_skills(&config.workspace_dir);
     let mut tool_descs: Vec<(&str, &str)> = vec![
         (
             "shell",
@@ -1063,13 +1464,23 @@ pub async fn run(
             provider_name,
             model_name,
             temperature,
-            false,
+            LoopOutput::console(config.agent.show_reasoning),
             Some(&approval_manager),
+            None,
             "cli",
+            cost_tracker.as_ref(),
+            response_cache.as_ref(),
+            None,
+            config.agent.tool_concurrency(),
         )
         .await?;
         final_output = response.clone();
-        println!("{response}");
+        if live_output {
+            // The reply was printed as it streamed in.
+            println!();
+        } else {
+            println!("{response}");
+        }
         observer.record_event(&ObserverEvent::TurnComplete);
 
         // Auto-save assistant response to daily log
@@ -1134,6 +1545,9 @@ pub async fn run(
 
             history.push(ChatMessage::user(&enriched));
 
+            if live_output {
+                println!();
+            }
             let response = match run_tool_call_loop(
                 provider.as_ref(),
                 &mut history,
@@ -1142,9 +1556,14 @@ pub async fn run(
                 provider_name,
                 model_name,
                 temperature,
-                false,
+                LoopOutput::console(config.agent.show_reasoning),
                 Some(&approval_manager),
+                None,
                 "cli",
+                cost_tracker.as_ref(),
+                response_cache.as_ref(),
+                None,
+                config.agent.tool_concurrency(),
             )
             .await
             {
@@ -1155,7 +1574,9 @@ pub async fn run(
                 }
             };
             final_output = response.clone();
-            if let Err(e) = crate::channels::Channel::send(
+            if live_output {
+                println!("\n");
+            } else if let Err(e) = crate::channels::Channel::send(
                 &cli,
                 &crate::channels::traits::SendMessage::new(format!("\n{response}\n"), "user"),
             )
@@ -1175,7 +1596,7 @@ pub async fn run(
             }
 
             // Hard cap as a safety net.
-            trim_history(&mut history);
+            trim_history(&mut history, config.agent.max_history_messages);
 
             if config.memory.auto_save {
                 let summary = truncate_with_ellipsis(&response, 100);
@@ -1188,10 +1609,11 @@ pub async fn run(
     }
 
     let duration = start.elapsed();
+    let cost_summary = cost_tracker.as_ref().and_then(|t| t.get_summary().ok());
     observer.record_event(&ObserverEvent::AgentEnd {
         duration,
-        tokens_used: None,
-        cost_usd: None,
+        tokens_used: cost_summary.as_ref().map(|s| s.total_tokens),
+        cost_usd: cost_summary.map(|s| s.session_cost_usd),
     });
 
     Ok(final_output)
@@ -1212,6 +1634,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         &config.memory,
         &config.workspace_dir,
         config.api_key.as_deref(),
+        memory::MemoryCipher::from_config(&config)?,
     )?);
 
     let (composio_key, composio_entity_id) = if config.composio.enabled {
@@ -1222,10 +1645,11 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
     } else {
         (None, None)
     };
+    let skills = crate::skills::load_skills(&config.workspace_dir);
     let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
-        runtime,
+        runtime.clone(),
         mem.clone(),
         composio_key,
         composio_entity_id,
@@ -1236,6 +1660,12 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         config.api_key.as_deref(),
         &config,
     );
+    tools_registry.extend(tools::skill_tools(
+        &skills,
+        &security,
+        &runtime,
+        &config.http_request,
+    ));
     let peripheral_tools: Vec<Box<dyn Tool>> =
         crate::peripherals::create_peripheral_tools(&config.peripherals).await?;
     tools_registry.extend(peripheral_tools);
@@ -1249,6 +1679,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         provider_name,
         config.api_key.as_deref(),
         config.api_url.as_deref(),
+        config.default_model_vision,
         &config.reliability,
         &config.model_routes,
         &model_name,
@@ -1269,7 +1700,6 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         .map(|b| b.board.clone())
         .collect();
 
-    let skills = crate::skills::load_skills(&config.workspace_dir);
     let mut tool_descs: Vec<(&str, &str)> = vec![
         ("shell", "Execute terminal commands."),
         ("file_read", "Read file contents."),
@@ -1345,6 +1775,7 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         ChatMessage::system(&system_prompt),
         ChatMessage::user(&enriched),
     ];
+    let cost_tracker = CostTracker::from_config(&config)?;
 
     agent_turn(
         provider.as_ref(),
@@ -1355,6 +1786,8 @@ pub async fn process_message(config: Config, message: &str) -> Result<String> {
         &model_name,
         config.default_temperature,
         true,
+        cost_tracker.as_ref(),
+        config.agent.tool_concurrency(),
     )
     .await
 }
@@ -1382,6 +1815,7 @@ mod tests {
         assert!(scrubbed.contains("public"));
     }
     use crate::memory::{Memory, MemoryCategory, SqliteMemory};
+    use std::time::Duration;
     use tempfile::TempDir;
 
     #[test]
@@ -1634,7 +2068,7 @@ I will now call the tool with this payload:
         let original_len = history.len();
         assert!(original_len > MAX_HISTORY_MESSAGES + 1);
 
-        trim_history(&mut history);
+        trim_history(&mut history, MAX_HISTORY_MESSAGES);
 
         // System prompt preserved
         assert_eq!(history[0].role, "system");
@@ -1653,7 +2087,7 @@ I will now call the tool with this payload:
             ChatMessage::user("hello"),
             ChatMessage::assistant("hi"),
         ];
-        trim_history(&mut history);
+        trim_history(&mut history, MAX_HISTORY_MESSAGES);
         assert_eq!(history.len(), 3);
     }
 
@@ -1717,6 +2151,699 @@ I will now call the tool with this payload:
         assert!(recalled.iter().any(|entry| entry.content.contains("45")));
     }
 
+    struct MeteredProvider {
+        calls: std::sync::atomic::AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for MeteredProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("unused".into())
+        }
+
+        async fn chat(
+            &self,
+            _request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<providers::ChatResponse> {
+            self.calls.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(providers::ChatResponse {
+                text: Some("done".into()),
+                tool_calls: Vec::new(),
+                usage: Some(providers::Usage::new(1_000_000, 1_000_000)),
+                reasoning: None,
+            })
+        }
+    }
+
+    fn metered_provider() -> MeteredProvider {
+        MeteredProvider {
+            calls: std::sync::atomic::AtomicUsize::new(0),
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_records_provider_usage() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(
+            crate::config::schema::CostConfig {
+                enabled: true,
+                ..Default::default()
+            },
+            tmp.path(),
+        )
+        .unwrap();
+        let provider = metered_provider();
+        let mut history = vec![ChatMessage::user("hi")];
+
+        let reply = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "anthropic",
+            "claude-sonnet-4-20250514",
+            0.0,
+            LoopOutput::SILENT,
+            None,
+            None,
+            "test",
+            Some(&tracker),
+            None,
+            None,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "done");
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.total_tokens, 2_000_000);
+        assert!((summary.session_cost_usd - 18.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_stops_when_budget_exceeded() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(
+            crate::config::schema::CostConfig {
+                enabled: true,
+                daily_limit_usd: 1.0,
+                ..Default::default()
+            },
+            tmp.path(),
+        )
+        .unwrap();
+        let provider = metered_provider();
+
+        // The first call is within budget and spends $18, blowing the $1 cap.
+        for expect_ok in [true, false] {
+            let mut history = vec![ChatMessage::user("hi")];
+            let result = run_tool_call_loop(
+                &provider,
+                &mut history,
+                &[],
+                &crate::observability::NoopObserver,
+                "anthropic",
+                "claude-sonnet-4-20250514",
+                0.0,
+                LoopOutput::SILENT,
+                None,
+                None,
+                "test",
+                Some(&tracker),
+                None,
+                None,
+                1,
+            )
+            .await;
+            assert_eq!(result.is_ok(), expect_ok);
+            if let Err(err) = result {
+                assert!(err.to_string().contains("Daily budget exceeded"));
+            }
+        }
+
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[test]
+    fn displayable_stream_len_hides_tool_call_markup() {
+        assert_eq!(displayable_stream_len("plain text"), 10);
+        assert_eq!(displayable_stream_len("Checking <tool_call>{}"), 9);
+        assert_eq!(displayable_stream_len("Checking <tool-"), 9);
+        assert_eq!(displayable_stream_len("a < b"), 5);
+        assert_eq!(displayable_stream_len("x <"), 2);
+    }
+
+    struct EchoTool;
+
+    #[async_trait::async_trait]
+    impl Tool for EchoTool {
+        fn name(&self) -> &str {
+            "echo"
+        }
+
+        fn description(&self) -> &str {
+            "Echo the input back"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: format!("echoed {args}"),
+                error: None,
+            })
+        }
+    }
+
+    /// Streams a native tool call first, then a two-part final answer.
+    struct StreamingProvider {
+        blocking_calls: std::sync::atomic::AtomicUsize,
+        fail_stream: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for StreamingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            self.blocking_calls
+                .fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok("blocking answer".into())
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn stream_chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+            _options: StreamOptions,
+        ) -> futures_util::stream::BoxStream<
+            'static,
+            providers::traits::StreamResult<providers::traits::StreamChunk>,
+        > {
+            use providers::traits::{StreamChunk, StreamError, ToolCallDelta};
+
+            if self.fail_stream {
+                return futures_util::stream::once(async {
+                    Err(StreamError::Provider("connection refused".into()))
+                })
+                .boxed();
+            }
+
+            let has_results = messages
+                .iter()
+                .any(|m| m.content.contains("[Tool results]"));
+            let chunks = if has_results {
+                vec![
+                    StreamChunk::delta("The answer "),
+                    StreamChunk::delta("is 42.").with_usage(providers::Usage::new(10, 5)),
+                    StreamChunk::final_chunk(),
+                ]
+            } else {
+                vec![
+                    StreamChunk::delta("Let me check."),
+                    StreamChunk::delta("").with_tool_calls(vec![ToolCallDelta {
+                        index: 0,
+                        id: Some("call_1".into()),
+                        name: Some("echo".into()),
+                        arguments: r#"{"x":"#.into(),
+                    }]),
+                    StreamChunk::delta("").with_tool_calls(vec![ToolCallDelta {
+                        index: 0,
+                        arguments: "1}".into(),
+                        ..ToolCallDelta::default()
+                    }]),
+                    StreamChunk::final_chunk(),
+                ]
+            };
+            futures_util::stream::iter(chunks.into_iter().map(Ok)).boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_streams_text_and_native_tool_calls() {
+        let provider = StreamingProvider {
+            blocking_calls: std::sync::atomic::AtomicUsize::new(0),
+            fail_stream: false,
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(EchoTool)];
+        let mut history = vec![ChatMessage::user("what is the answer?")];
+        let (tx, mut rx) = tokio::sync::mpsc::channel(32);
+
+        let reply = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test-provider",
+            "test-model",
+            0.0,
+            LoopOutput::SILENT,
+            None,
+            None,
+            "test",
+            None,
+            None,
+            Some(&tx),
+            1,
+        )
+        .await
+        .unwrap();
+        drop(tx);
+
+        assert_eq!(reply, "The answer is 42.");
+        assert_eq!(
+            provider
+                .blocking_calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            0
+        );
+        assert!(history
+            .iter()
+            .any(|m| m.content.contains(r#"echoed {"x":1}"#)));
+
+        let mut events = Vec::new();
+        while let Some(mut event) = rx.recv().await {
+            if let StreamEvent::ToolResult { duration, .. } = &mut event {
+                *duration = Duration::ZERO;
+            }
+            events.push(event);
+        }
+        assert_eq!(
+            events,
+            vec![
+                StreamEvent::Delta("Let me check.".into()),
+                StreamEvent::ToolRound,
+                StreamEvent::ToolStart {
+                    tool: "echo".into()
+                },
+                StreamEvent::ToolResult {
+                    tool: "echo".into(),
+                    success: true,
+                    duration: Duration::ZERO,
+                },
+                StreamEvent::Delta("The answer ".into()),
+                StreamEvent::Delta("is 42.".into()),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_falls_back_when_stream_fails() {
+        let provider = StreamingProvider {
+            blocking_calls: std::sync::atomic::AtomicUsize::new(0),
+            fail_stream: true,
+        };
+        let mut history = vec![ChatMessage::user("hi")];
+        let (tx, mut rx) = tokio::sync::mpsc::channel(8);
+
+        let reply = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &[],
+            &crate::observability::NoopObserver,
+            "test-provider",
+            "test-model",
+            0.0,
+            LoopOutput::SILENT,
+            None,
+            None,
+            "test",
+            None,
+            None,
+            Some(&tx),
+            1,
+        )
+        .await
+        .unwrap();
+        drop(tx);
+
+        assert_eq!(reply, "blocking answer");
+        assert_eq!(
+            provider
+                .blocking_calls
+                .load(std::sync::atomic::Ordering::SeqCst),
+            1
+        );
+        assert_eq!(
+            rx.recv().await,
+            Some(StreamEvent::Delta("blocking answer".into()))
+        );
+        assert_eq!(rx.recv().await, None);
+    }
+
+    #[tokio::test]
+    async fn execute_in_batches_keeps_order_and_runs_unsafe_calls_alone() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let serial_saw = AtomicUsize::new(usize::MAX);
+        let job = |id: u64, safe: bool| {
+            let (in_flight, peak, serial_saw) = (&in_flight, &peak, &serial_saw);
+            let fut = async move {
+                let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                if !safe {
+                    serial_saw.store(now, Ordering::SeqCst);
+                }
+                // Later jobs finish first, so ordering must come from the batcher.
+                tokio::time::sleep(Duration::from_millis(30 - 5 * id)).await;
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+                id
+            };
+            (safe, fut)
+        };
+
+        let jobs = vec![
+            job(0, true),
+            job(1, true),
+            job(2, true),
+            job(3, false),
+            job(4, true),
+            job(5, true),
+        ];
+        let results = execute_in_batches(jobs, 2).await;
+
+        assert_eq!(results, vec![0, 1, 2, 3, 4, 5]);
+        assert_eq!(peak.load(Ordering::SeqCst), 2);
+        assert_eq!(serial_saw.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn execute_in_batches_with_cap_of_one_is_sequential() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let in_flight = AtomicUsize::new(0);
+        let peak = AtomicUsize::new(0);
+        let jobs = (1..=3_u64)
+            .map(|id| {
+                let (in_flight, peak) = (&in_flight, &peak);
+                let fut = async move {
+                    let now = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                    in_flight.fetch_sub(1, Ordering::SeqCst);
+                    id
+                };
+                (true, fut)
+            })
+            .collect();
+
+        assert_eq!(execute_in_batches(jobs, 1).await, vec![1, 2, 3]);
+        assert_eq!(peak.load(Ordering::SeqCst), 1);
+    }
+
+    /// Sleeps before answering; `parallel_safe` is configurable.
+    struct SlowTool {
+        name: &'static str,
+        parallel_safe: bool,
+    }
+
+    #[async_trait::async_trait]
+    impl Tool for SlowTool {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        fn description(&self) -> &str {
+            "Wait a moment, then answer"
+        }
+
+        fn parameters_schema(&self) -> serde_json::Value {
+            serde_json::json!({"type": "object"})
+        }
+
+        fn parallel_safe(&self) -> bool {
+            self.parallel_safe
+        }
+
+        async fn execute(&self, args: serde_json::Value) -> Result<crate::tools::ToolResult> {
+            tokio::time::sleep(Duration::from_millis(150)).await;
+            Ok(crate::tools::ToolResult {
+                success: true,
+                output: format!("{} done {}", self.name, args["n"]),
+                error: None,
+            })
+        }
+    }
+
+    /// Requests a fixed batch of native tool calls, then answers.
+    struct ToolBatchProvider {
+        calls: Vec<(&'static str, u32)>,
+    }
+
+    #[async_trait::async_trait]
+    impl Provider for ToolBatchProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<String> {
+            Ok("unused".into())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> Result<providers::ChatResponse> {
+            if request
+                .messages
+                .iter()
+                .any(|m| m.content.contains("[Tool results]"))
+            {
+                return Ok(providers::ChatResponse {
+                    text: Some("done".into()),
+                    tool_calls: Vec::new(),
+                    usage: None,
+                    reasoning: None,
+                });
+            }
+            Ok(providers::ChatResponse {
+                text: Some(String::new()),
+                tool_calls: self
+                    .calls
+                    .iter()
+                    .map(|(name, n)| ToolCall {
+                        id: format!("call_{n}"),
+                        name: (*name).to_string(),
+                        arguments: format!(r#"{{"n":{n}}}"#),
+                    })
+                    .collect(),
+                usage: None,
+                reasoning: None,
+            })
+        }
+    }
+
+    #[derive(Default)]
+    struct ToolEventObserver {
+        events: parking_lot::Mutex<Vec<String>>,
+    }
+
+    impl Observer for ToolEventObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            match event {
+                ObserverEvent::ToolCallStart { tool } => {
+                    self.events.lock().push(format!("start:{tool}"));
+                }
+                ObserverEvent::ToolCall { tool, success, .. } => {
+                    self.events.lock().push(format!("end:{tool}:{success}"));
+                }
+                _ => {}
+            }
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "tool-events"
+        }
+    }
+
+    async fn run_tool_batch(
+        calls: Vec<(&'static str, u32)>,
+        max_parallel_tools: usize,
+        observer: &ToolEventObserver,
+    ) -> (Vec<ChatMessage>, Duration) {
+        let provider = ToolBatchProvider { calls };
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(SlowTool {
+                name: "fetch",
+                parallel_safe: true,
+            }),
+            Box::new(SlowTool {
+                name: "write",
+                parallel_safe: false,
+            }),
+        ];
+        let mut history = vec![ChatMessage::user("go")];
+
+        let started = Instant::now();
+        let reply = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            observer,
+            "test-provider",
+            "test-model",
+            0.0,
+            LoopOutput::SILENT,
+            None,
+            None,
+            "test",
+            None,
+            None,
+            None,
+            max_parallel_tools,
+        )
+        .await
+        .unwrap();
+        assert_eq!(reply, "done");
+        (history, started.elapsed())
+    }
+
+    fn tool_results_text(history: &[ChatMessage]) -> &str {
+        history
+            .iter()
+            .find(|m| m.content.starts_with("[Tool results]"))
+            .map(|m| m.content.as_str())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_runs_parallel_safe_tools_concurrently() {
+        let observer = ToolEventObserver::default();
+        let (history, elapsed) =
+            run_tool_batch(vec![("fetch", 1), ("fetch", 2), ("fetch", 3)], 4, &observer).await;
+
+        // Three 150ms calls run together instead of back to back.
+        assert!(elapsed < Duration::from_millis(400), "took {elapsed:?}");
+
+        let results = tool_results_text(&history);
+        let first = results.find("fetch done 1").unwrap();
+        let second = results.find("fetch done 2").unwrap();
+        let third = results.find("fetch done 3").unwrap();
+        assert!(first < second && second < third);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_serializes_unsafe_tools_and_pairs_events() {
+        let observer = ToolEventObserver::default();
+        let (history, elapsed) = run_tool_batch(
+            vec![("fetch", 1), ("write", 2), ("missing", 3), ("fetch", 4)],
+            4,
+            &observer,
+        )
+        .await;
+
+        // fetch → write (alone) → missing + fetch: three sequential batches.
+        assert!(elapsed >= Duration::from_millis(450), "took {elapsed:?}");
+
+        let results = tool_results_text(&history);
+        let order: Vec<usize> = [
+            "fetch done 1",
+            "write done 2",
+            "Unknown tool: missing",
+            "fetch done 4",
+        ]
+        .iter()
+        .map(|needle| results.find(needle).unwrap())
+        .collect();
+        assert!(order.windows(2).all(|pair| pair[0] < pair[1]));
+
+        let events = observer.events.lock().clone();
+        assert_eq!(events.len(), 8);
+        for tool in ["fetch", "write", "missing"] {
+            let starts = events
+                .iter()
+                .filter(|e| **e == format!("start:{tool}"))
+                .count();
+            let ends = events
+                .iter()
+                .filter(|e| e.starts_with(&format!("end:{tool}:")))
+                .count();
+            assert_eq!(starts, ends, "unpaired events for {tool}: {events:?}");
+        }
+        assert!(events.contains(&"end:missing:false".to_string()));
+
+        // The serial tool's start/end are not interleaved with anything else.
+        let write_start = events.iter().position(|e| e == "start:write").unwrap();
+        assert_eq!(events[write_start + 1], "end:write:true");
+    }
+
+    /// Answers every approval request with a fixed decision.
+    struct FixedPrompter {
+        answer: ApprovalResponse,
+        asked: parking_lot::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ApprovalPrompter for FixedPrompter {
+        async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse {
+            self.asked.lock().push(request.tool_name.clone());
+            self.answer
+        }
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_asks_channel_prompter_for_approval() {
+        let provider = ToolBatchProvider {
+            calls: vec![("fetch", 1), ("write", 2)],
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![
+            Box::new(SlowTool {
+                name: "fetch",
+                parallel_safe: true,
+            }),
+            Box::new(SlowTool {
+                name: "write",
+                parallel_safe: false,
+            }),
+        ];
+        let approval = ApprovalManager::from_config(&crate::config::AutonomyConfig {
+            auto_approve: vec!["fetch".into()],
+            ..crate::config::AutonomyConfig::default()
+        });
+        let prompter = FixedPrompter {
+            answer: ApprovalResponse::No,
+            asked: parking_lot::Mutex::new(Vec::new()),
+        };
+        let mut history = vec![ChatMessage::user("go")];
+
+        let reply = run_tool_call_loop(
+            &provider,
+            &mut history,
+            &tools,
+            &crate::observability::NoopObserver,
+            "test-provider",
+            "test-model",
+            0.0,
+            LoopOutput::SILENT,
+            Some(&approval),
+            Some(&prompter),
+            "telegram",
+            None,
+            None,
+            None,
+            1,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(reply, "done");
+        assert_eq!(*prompter.asked.lock(), vec!["write".to_string()]);
+        let results = tool_results_text(&history);
+        assert!(results.contains("fetch done 1"));
+        assert!(results.contains("<tool_result name=\"write\">\nDenied by user."));
+        assert!(!results.contains("write done"));
+        assert_eq!(approval.audit_log()[0].channel, "telegram");
+    }
+
     // ═══════════════════════════════════════════════════════════════════════
     // Recovery Tests - Tool Call Parsing Edge Cases
     // ═══════════════════════════════════════════════════════════════════════
@@ -1780,7 +2907,7 @@ Done."#;
         for i in 0..MAX_HISTORY_MESSAGES + 20 {
             history.push(ChatMessage::user(format!("msg {i}")));
         }
-        trim_history(&mut history);
+        trim_history(&mut history, MAX_HISTORY_MESSAGES);
         assert_eq!(history.len(), MAX_HISTORY_MESSAGES);
     }
 
@@ -1792,7 +2919,7 @@ Done."#;
             history.push(ChatMessage::user(format!("user {i}")));
             history.push(ChatMessage::assistant(format!("assistant {i}")));
         }
-        trim_history(&mut history);
+        trim_history(&mut history, MAX_HISTORY_MESSAGES);
         assert_eq!(history[0].role, "system");
         assert_eq!(history[history.len() - 1].role, "assistant");
     }
@@ -1801,7 +2928,7 @@ Done."#;
     fn trim_history_with_only_system_prompt() {
         // Recovery: Only system prompt should not be trimmed
         let mut history = vec![ChatMessage::system("system prompt")];
-        trim_history(&mut history);
+        trim_history(&mut history, MAX_HISTORY_MESSAGES);
         assert_eq!(history.len(), 1);
     }
 
@@ -1923,4 +3050,124 @@ Done."#;
         let result = parse_tool_calls_from_json_value(&value);
         assert_eq!(result.len(), 2);
     }
+
+    #[derive(Default)]
+    struct CacheLookupObserver {
+        lookups: parking_lot::Mutex<Vec<bool>>,
+    }
+
+    impl Observer for CacheLookupObserver {
+        fn record_event(&self, event: &ObserverEvent) {
+            if let ObserverEvent::ResponseCacheLookup { hit } = event {
+                self.lookups.lock().push(*hit);
+            }
+        }
+
+        fn record_metric(&self, _metric: &ObserverMetric) {}
+
+        fn name(&self) -> &str {
+            "cache-lookups"
+        }
+    }
+
+    async fn run_cached_turn(
+        provider: &dyn Provider,
+        tools: &[Box<dyn Tool>],
+        cache: &ResponseCache,
+        temperature: f64,
+        observer: &CacheLookupObserver,
+    ) -> (String, Vec<ChatMessage>) {
+        let mut history = vec![ChatMessage::system("sys"), ChatMessage::user("go")];
+        let reply = run_tool_call_loop(
+            provider,
+            &mut history,
+            tools,
+            observer,
+            "test-provider",
+            "test-model",
+            temperature,
+            LoopOutput::SILENT,
+            None,
+            None,
+            "test",
+            None,
+            Some(cache),
+            None,
+            1,
+        )
+        .await
+        .unwrap();
+        (reply, history)
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_answers_repeated_turn_from_cache() {
+        let tmp = TempDir::new().unwrap();
+        let cache = ResponseCache::new(tmp.path(), 60, 100).unwrap();
+        let provider = metered_provider();
+        let observer = CacheLookupObserver::default();
+
+        let (first, _) = run_cached_turn(&provider, &[], &cache, 0.0, &observer).await;
+        let (second, history) = run_cached_turn(&provider, &[], &cache, 0.0, &observer).await;
+
+        assert_eq!(first, "done");
+        assert_eq!(second, "done");
+        assert_eq!(history.last().unwrap().content, "done");
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+        assert_eq!(*observer.lookups.lock(), vec![false, true]);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_skips_cache_above_temperature_threshold() {
+        let tmp = TempDir::new().unwrap();
+        let cache = ResponseCache::new(tmp.path(), 60, 100)
+            .unwrap()
+            .with_max_temperature(0.2);
+        let provider = metered_provider();
+        let observer = CacheLookupObserver::default();
+
+        run_cached_turn(&provider, &[], &cache, 0.7, &observer).await;
+        run_cached_turn(&provider, &[], &cache, 0.7, &observer).await;
+
+        assert_eq!(provider.calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+        assert!(observer.lookups.lock().is_empty());
+        assert_eq!(cache.stats().unwrap().0, 0);
+    }
+
+    #[tokio::test]
+    async fn run_tool_call_loop_does_not_cache_tool_turns() {
+        let tmp = TempDir::new().unwrap();
+        let cache = ResponseCache::new(tmp.path(), 60, 100).unwrap();
+        let provider = ToolBatchProvider {
+            calls: vec![("fetch", 1)],
+        };
+        let tools: Vec<Box<dyn Tool>> = vec![Box::new(SlowTool {
+            name: "fetch",
+            parallel_safe: true,
+        })];
+        let observer = CacheLookupObserver::default();
+
+        let (reply, _) = run_cached_turn(&provider, &tools, &cache, 0.0, &observer).await;
+
+        assert_eq!(reply, "done");
+        assert_eq!(cache.stats().unwrap().0, 0);
+    }
+
+    #[test]
+    fn response_cache_key_covers_system_prompt_and_history() {
+        let base = vec![ChatMessage::system("sys"), ChatMessage::user("hi")];
+        let key = response_cache_key("m", &base);
+
+        assert_eq!(key, response_cache_key("m", &base));
+        assert_ne!(key, response_cache_key("other", &base));
+        assert_ne!(
+            key,
+            response_cache_key("m", &[ChatMessage::system("sys2"), ChatMessage::user("hi")])
+        );
+
+        let mut longer = base.clone();
+        longer.push(ChatMessage::assistant("hello"));
+        longer.push(ChatMessage::user("hi"));
+        assert_ne!(key, response_cache_key("m", &longer));
+    }
 }
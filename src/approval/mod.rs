@@ -3,8 +3,10 @@
 //! Provides a pre-execution hook that prompts the user before tool calls,
 //! with session-scoped "Always" allowlists and audit logging.
 
+use crate::agent::loop_::scrub_credentials;
 use crate::config::AutonomyConfig;
 use crate::security::AutonomyLevel;
+use async_trait::async_trait;
 use chrono::Utc;
 use parking_lot::Mutex;
 use serde::{Deserialize, Serialize};
@@ -147,13 +149,36 @@ impl ApprovalManager {
 
     /// Prompt the user on the CLI and return their decision.
     ///
-    /// For non-CLI channels, returns `Yes` automatically (interactive
-    /// approval is only supported on CLI for now).
+    /// Messaging channels ask through an [`ApprovalPrompter`] instead.
     pub fn prompt_cli(&self, request: &ApprovalRequest) -> ApprovalResponse {
         prompt_cli_interactive(request)
     }
 }
 
+// ── Remote prompts ───────────────────────────────────────────────
+
+/// Asks for approval somewhere other than the local terminal, e.g. in the
+/// chat a channel message came from.
+#[async_trait]
+pub trait ApprovalPrompter: Send + Sync {
+    /// Show the request and wait for a decision. Implementations deny the
+    /// call when no answer arrives.
+    async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse;
+}
+
+/// Message text describing a pending tool call, with credentials redacted.
+pub fn approval_prompt_text(request: &ApprovalRequest) -> String {
+    let summary = scrub_credentials(&summarize_args(&request.arguments));
+    if summary.is_empty() {
+        format!("🔧 Agent wants to execute: {}", request.tool_name)
+    } else {
+        format!(
+            "🔧 Agent wants to execute: {}\n{summary}",
+            request.tool_name
+        )
+    }
+}
+
 // ── CLI prompt ───────────────────────────────────────────────────
 
 /// Display the approval prompt and read user input from stdin.
@@ -401,6 +426,19 @@ mod tests {
         assert!(summary.contains("just a string"));
     }
 
+    #[test]
+    fn approval_prompt_text_redacts_credentials() {
+        let req = ApprovalRequest {
+            tool_name: "http_request".into(),
+            arguments: serde_json::json!({"url": "https://example.com", "api_key": "sk-live-1234567890"}),
+        };
+        let text = approval_prompt_text(&req);
+        assert!(text.contains("http_request"));
+        assert!(text.contains("https://example.com"));
+        assert!(!text.contains("1234567890"));
+        assert!(text.contains("[REDACTED]"));
+    }
+
     // ── ApprovalResponse serde ───────────────────────────────
 
     #[test]
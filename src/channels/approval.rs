@@ -0,0 +1,401 @@
+//! Tool-call approval over messaging channels.
+//!
+//! In supervised mode the channel runtime asks the sender of a message to
+//! approve tool calls in the chat the message came from. Channels with
+//! buttons (Telegram inline keyboards, Discord components) put the answer in
+//! the button payload; everywhere else the user replies `yes`, `no`, or
+//! `always`. Each `(channel, reply_target, sender)` session keeps its own
+//! "Always" allowlist, and unanswered prompts are denied after
+//! `[autonomy] approval_timeout_secs`.
+
+use super::session::SessionKey;
+use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::approval::{
+    approval_prompt_text, ApprovalManager, ApprovalPrompter, ApprovalRequest, ApprovalResponse,
+};
+use crate::config::AutonomyConfig;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::oneshot;
+use uuid::Uuid;
+
+const CALLBACK_PREFIX: &str = "approval:";
+
+/// Button payload that answers approval `approval_id` with `response`.
+pub fn approval_callback_data(approval_id: &str, response: ApprovalResponse) -> String {
+    let answer = match response {
+        ApprovalResponse::Yes => "yes",
+        ApprovalResponse::No => "no",
+        ApprovalResponse::Always => "always",
+    };
+    format!("{CALLBACK_PREFIX}{approval_id}:{answer}")
+}
+
+/// Parse a typed answer such as `yes`, `N`, or `always.`.
+fn parse_answer(text: &str) -> Option<ApprovalResponse> {
+    let answer = text
+        .trim()
+        .trim_end_matches(['.', '!'])
+        .to_ascii_lowercase();
+    match answer.as_str() {
+        "y" | "yes" | "approve" => Some(ApprovalResponse::Yes),
+        "n" | "no" | "deny" => Some(ApprovalResponse::No),
+        "a" | "always" => Some(ApprovalResponse::Always),
+        _ => None,
+    }
+}
+
+/// Parse a button payload produced by [`approval_callback_data`].
+fn parse_callback_data(data: &str) -> Option<(&str, ApprovalResponse)> {
+    let rest = data.trim().strip_prefix(CALLBACK_PREFIX)?;
+    let (approval_id, answer) = rest.rsplit_once(':')?;
+    Some((approval_id, parse_answer(answer)?))
+}
+
+struct PendingApproval {
+    id: String,
+    key: SessionKey,
+    tx: oneshot::Sender<ApprovalResponse>,
+}
+
+/// Approval state shared by every channel worker.
+pub struct ChannelApprovals {
+    autonomy: AutonomyConfig,
+    timeout: Duration,
+    managers: Mutex<HashMap<SessionKey, Arc<ApprovalManager>>>,
+    /// Prompts waiting for an answer, oldest first.
+    pending: Mutex<Vec<PendingApproval>>,
+}
+
+impl ChannelApprovals {
+    pub fn new(autonomy: &AutonomyConfig) -> Self {
+        Self {
+            autonomy: autonomy.clone(),
+            timeout: Duration::from_secs(autonomy.approval_timeout_secs),
+            managers: Mutex::new(HashMap::new()),
+            pending: Mutex::new(Vec::new()),
+        }
+    }
+
+    /// The approval manager, and with it the "Always" allowlist, of one session.
+    pub fn manager_for(&self, key: &SessionKey) -> Arc<ApprovalManager> {
+        let mut managers = self.managers.lock();
+        Arc::clone(
+            managers
+                .entry(key.clone())
+                .or_insert_with(|| Arc::new(ApprovalManager::from_config(&self.autonomy))),
+        )
+    }
+
+    /// Drop a session's "Always" allowlist, e.g. after `/new`.
+    pub fn forget(&self, key: &SessionKey) {
+        self.managers.lock().remove(key);
+    }
+
+    /// A prompter that asks the sender of `msg` through `channel`.
+    pub fn prompter(
+        &self,
+        channel: Arc<dyn Channel>,
+        msg: &ChannelMessage,
+    ) -> ChannelApprovalPrompter<'_> {
+        ChannelApprovalPrompter {
+            approvals: self,
+            channel,
+            key: SessionKey::from_message(msg),
+        }
+    }
+
+    /// Answer a waiting approval from an inbound message.
+    ///
+    /// Returns `true` when the message was an approval answer and must not be
+    /// handed to the model. Only the sender who was asked can answer; button
+    /// presses for unknown or expired prompts are swallowed.
+    pub fn resolve(&self, msg: &ChannelMessage) -> bool {
+        let key = SessionKey::from_message(msg);
+        let mut pending = self.pending.lock();
+
+        if let Some((approval_id, answer)) = parse_callback_data(&msg.content) {
+            match pending
+                .iter()
+                .position(|p| p.id == approval_id && p.key == key)
+            {
+                Some(pos) => {
+                    let _ = pending.remove(pos).tx.send(answer);
+                }
+                None => tracing::debug!(
+                    "Ignoring approval answer {approval_id} from {} on {}: no matching prompt",
+                    msg.sender,
+                    msg.channel
+                ),
+            }
+            return true;
+        }
+
+        let Some(answer) = parse_answer(&msg.content) else {
+            return false;
+        };
+        let Some(pos) = pending.iter().position(|p| p.key == key) else {
+            return false;
+        };
+        let _ = pending.remove(pos).tx.send(answer);
+        true
+    }
+
+    fn register(&self, key: SessionKey) -> (String, oneshot::Receiver<ApprovalResponse>) {
+        let id = Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().push(PendingApproval {
+            id: id.clone(),
+            key,
+            tx,
+        });
+        (id, rx)
+    }
+
+    fn cancel(&self, approval_id: &str) {
+        self.pending.lock().retain(|p| p.id != approval_id);
+    }
+}
+
+/// Asks one channel sender to approve tool calls in their own chat.
+pub struct ChannelApprovalPrompter<'a> {
+    approvals: &'a ChannelApprovals,
+    channel: Arc<dyn Channel>,
+    key: SessionKey,
+}
+
+#[async_trait]
+impl ApprovalPrompter for ChannelApprovalPrompter<'_> {
+    async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse {
+        let (approval_id, rx) = self.approvals.register(self.key.clone());
+        let text = approval_prompt_text(request);
+
+        if let Err(e) = self
+            .channel
+            .send_approval_prompt(&self.key.reply_target, &approval_id, &text)
+            .await
+        {
+            tracing::warn!(
+                "Failed to send approval prompt on {}: {e}",
+                self.channel.name()
+            );
+            self.approvals.cancel(&approval_id);
+            return ApprovalResponse::No;
+        }
+
+        match tokio::time::timeout(self.approvals.timeout, rx).await {
+            Ok(Ok(answer)) => answer,
+            _ => {
+                self.approvals.cancel(&approval_id);
+                let notice = format!("⏱️ No approval received; skipped {}.", request.tool_name);
+                if let Err(e) = self
+                    .channel
+                    .send(&SendMessage::new(notice, &self.key.reply_target))
+                    .await
+                {
+                    tracing::debug!("Failed to send approval timeout notice: {e}");
+                }
+                ApprovalResponse::No
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::mpsc;
+
+    /// Records every approval prompt it is asked to show.
+    struct PromptRecordingChannel {
+        prompts: mpsc::UnboundedSender<(String, String)>,
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl Channel for PromptRecordingChannel {
+        fn name(&self) -> &str {
+            "telegram"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.sent.lock().push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(&self, _tx: mpsc::Sender<ChannelMessage>) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn send_approval_prompt(
+            &self,
+            _recipient: &str,
+            approval_id: &str,
+            prompt: &str,
+        ) -> anyhow::Result<()> {
+            let _ = self
+                .prompts
+                .send((approval_id.to_string(), prompt.to_string()));
+            Ok(())
+        }
+    }
+
+    fn message(sender: &str, content: &str) -> ChannelMessage {
+        ChannelMessage {
+            id: "m1".into(),
+            sender: sender.into(),
+            sender_id: None,
+            reply_target: "chat-1".into(),
+            content: content.into(),
+            channel: "telegram".into(),
+            attachments: Vec::new(),
+            timestamp: 0,
+        }
+    }
+
+    fn shell_request() -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "shell".into(),
+            arguments: serde_json::json!({"command": "ls"}),
+        }
+    }
+
+    fn approvals(timeout_secs: u64) -> Arc<ChannelApprovals> {
+        Arc::new(ChannelApprovals::new(&AutonomyConfig {
+            approval_timeout_secs: timeout_secs,
+            ..AutonomyConfig::default()
+        }))
+    }
+
+    fn recording_channel() -> (
+        Arc<PromptRecordingChannel>,
+        mpsc::UnboundedReceiver<(String, String)>,
+    ) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let channel = Arc::new(PromptRecordingChannel {
+            prompts: tx,
+            sent: Mutex::new(Vec::new()),
+        });
+        (channel, rx)
+    }
+
+    #[test]
+    fn callback_data_roundtrip() {
+        let data = approval_callback_data("abc-123", ApprovalResponse::Always);
+        assert_eq!(data, "approval:abc-123:always");
+        assert_eq!(
+            parse_callback_data(&data),
+            Some(("abc-123", ApprovalResponse::Always))
+        );
+        assert_eq!(parse_callback_data("approval:abc:maybe"), None);
+        assert_eq!(parse_callback_data("yes"), None);
+    }
+
+    #[test]
+    fn parse_answer_accepts_short_and_long_forms() {
+        assert_eq!(parse_answer(" Yes! "), Some(ApprovalResponse::Yes));
+        assert_eq!(parse_answer("n"), Some(ApprovalResponse::No));
+        assert_eq!(parse_answer("ALWAYS."), Some(ApprovalResponse::Always));
+        assert_eq!(parse_answer("yes please run it"), None);
+    }
+
+    #[test]
+    fn resolve_ignores_plain_replies_without_pending_prompt() {
+        let approvals = approvals(60);
+        assert!(!approvals.resolve(&message("alice", "yes")));
+        // Stale button presses never reach the model.
+        assert!(approvals.resolve(&message("alice", "approval:gone:yes")));
+    }
+
+    #[tokio::test]
+    async fn button_answer_resolves_prompt() {
+        let approvals = approvals(60);
+        let (channel, mut prompts) = recording_channel();
+        let msg = message("alice", "run ls");
+
+        let waiter = {
+            let approvals = Arc::clone(&approvals);
+            let channel: Arc<dyn Channel> = channel.clone();
+            tokio::spawn(async move {
+                approvals
+                    .prompter(channel, &msg)
+                    .prompt(&shell_request())
+                    .await
+            })
+        };
+
+        let (approval_id, prompt) = prompts.recv().await.unwrap();
+        assert!(prompt.contains("shell"));
+        assert!(prompt.contains("command: ls"));
+
+        // Someone else in the chat cannot answer for alice.
+        let bob = approval_callback_data(&approval_id, ApprovalResponse::Yes);
+        assert!(approvals.resolve(&message("bob", &bob)));
+
+        let alice = approval_callback_data(&approval_id, ApprovalResponse::Always);
+        assert!(approvals.resolve(&message("alice", &alice)));
+        assert_eq!(waiter.await.unwrap(), ApprovalResponse::Always);
+    }
+
+    #[tokio::test]
+    async fn text_answer_resolves_prompt() {
+        let approvals = approvals(60);
+        let (channel, mut prompts) = recording_channel();
+        let msg = message("alice", "run ls");
+
+        let waiter = {
+            let approvals = Arc::clone(&approvals);
+            let channel: Arc<dyn Channel> = channel.clone();
+            tokio::spawn(async move {
+                approvals
+                    .prompter(channel, &msg)
+                    .prompt(&shell_request())
+                    .await
+            })
+        };
+
+        prompts.recv().await.unwrap();
+        assert!(!approvals.resolve(&message("bob", "no")));
+        assert!(approvals.resolve(&message("alice", "no")));
+        assert_eq!(waiter.await.unwrap(), ApprovalResponse::No);
+    }
+
+    #[tokio::test]
+    async fn unanswered_prompt_times_out_as_denied() {
+        let approvals = approvals(0);
+        let (channel, _prompts) = recording_channel();
+        let dyn_channel: Arc<dyn Channel> = channel.clone();
+
+        let answer = approvals
+            .prompter(dyn_channel, &message("alice", "run ls"))
+            .prompt(&shell_request())
+            .await;
+
+        assert_eq!(answer, ApprovalResponse::No);
+        assert!(channel.sent.lock()[0].contains("skipped shell"));
+        assert!(!approvals.resolve(&message("alice", "yes")));
+    }
+
+    #[test]
+    fn managers_are_per_session() {
+        let approvals = approvals(60);
+        let alice = SessionKey::from_message(&message("alice", "hi"));
+        let bob = SessionKey::from_message(&message("bob", "hi"));
+
+        approvals.manager_for(&alice).record_decision(
+            "shell",
+            &serde_json::json!({}),
+            ApprovalResponse::Always,
+            "telegram",
+        );
+
+        assert!(!approvals.manager_for(&alice).needs_approval("shell"));
+        assert!(approvals.manager_for(&bob).needs_approval("shell"));
+
+        approvals.forget(&alice);
+        assert!(approvals.manager_for(&alice).needs_approval("shell"));
+    }
+}
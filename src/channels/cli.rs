@@ -40,9 +40,11 @@ impl Channel for CliChannel {
             let msg = ChannelMessage {
                 id: Uuid::new_v4().to_string(),
                 sender: "user".to_string(),
+                sender_id: None,
                 reply_target: "user".to_string(),
                 content: line,
                 channel: "cli".to_string(),
+                attachments: Vec::new(),
                 timestamp: std::time::SystemTime::now()
                     .duration_since(std::time::UNIX_EPOCH)
                     .unwrap_or_default()
@@ -103,9 +105,11 @@ mod tests {
         let msg = ChannelMessage {
             id: "test-id".into(),
             sender: "user".into(),
+            sender_id: None,
             reply_target: "user".into(),
             content: "hello".into(),
             channel: "cli".into(),
+            attachments: Vec::new(),
             timestamp: 1_234_567_890,
         };
         assert_eq!(msg.id, "test-id");
@@ -121,9 +125,11 @@ mod tests {
         let msg = ChannelMessage {
             id: "id".into(),
             sender: "s".into(),
+            sender_id: None,
             reply_target: "s".into(),
             content: "c".into(),
             channel: "ch".into(),
+            attachments: Vec::new(),
             timestamp: 0,
         };
         let cloned = msg.clone();
@@ -260,9 +260,11 @@ impl Channel for DingTalkChannel {
                     let channel_msg = ChannelMessage {
                         id: Uuid::new_v4().to_string(),
                         sender: sender_id.to_string(),
+                        sender_id: None,
                         reply_target: chat_id,
                         content: content.to_string(),
                         channel: "dingtalk".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
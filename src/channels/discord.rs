@@ -1,4 +1,6 @@
+use super::approval::approval_callback_data;
 use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::approval::ApprovalResponse;
 use async_trait::async_trait;
 use futures_util::{SinkExt, StreamExt};
 use parking_lot::Mutex;
@@ -48,6 +50,73 @@ impl DiscordChannel {
         let part = token.split('.').next()?;
         base64_decode(part)
     }
+
+    fn message_url(channel_id: &str, message_id: &str) -> String {
+        format!("https://discord.com/api/v10/channels/{channel_id}/messages/{message_id}")
+    }
+
+    /// Turn a button press (an `INTERACTION_CREATE` of type 3) into a message
+    /// whose content is the button's `custom_id`.
+    fn parse_component_interaction(&self, d: &serde_json::Value) -> Option<ChannelMessage> {
+        if d.get("type").and_then(serde_json::Value::as_u64) != Some(3) {
+            return None;
+        }
+        let custom_id = d
+            .get("data")
+            .and_then(|data| data.get("custom_id"))
+            .and_then(serde_json::Value::as_str)?;
+        // Guild interactions carry the user under `member`, DMs under `user`.
+        let user_id = d
+            .get("member")
+            .and_then(|member| member.get("user"))
+            .or_else(|| d.get("user"))
+            .and_then(|user| user.get("id"))
+            .and_then(serde_json::Value::as_str)?;
+        if !self.is_user_allowed(user_id) {
+            tracing::warn!("Discord: ignoring button press from unauthorized user: {user_id}");
+            return None;
+        }
+        let channel_id = d.get("channel_id").and_then(serde_json::Value::as_str)?;
+        let interaction_id = d
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+
+        Some(ChannelMessage {
+            id: format!("discord_interaction_{interaction_id}"),
+            sender: user_id.to_string(),
+            sender_id: None,
+            reply_target: channel_id.to_string(),
+            content: custom_id.to_string(),
+            channel: "discord".to_string(),
+            attachments: Vec::new(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+    }
+
+    /// Acknowledge a button press so Discord does not report it as failed.
+    async fn acknowledge_interaction(&self, d: &serde_json::Value) {
+        let (Some(id), Some(token)) = (
+            d.get("id").and_then(serde_json::Value::as_str),
+            d.get("token").and_then(serde_json::Value::as_str),
+        ) else {
+            return;
+        };
+        let url = format!("https://discord.com/api/v10/interactions/{id}/{token}/callback");
+        // Type 6: deferred update — acknowledge without changing the message.
+        if let Err(e) = self
+            .client
+            .post(&url)
+            .json(&json!({ "type": 6 }))
+            .send()
+            .await
+        {
+            tracing::debug!("Discord interaction callback failed: {e}");
+        }
+    }
 }
 
 const BASE64_ALPHABET: &[u8] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
@@ -105,6 +174,14 @@ fn split_message_for_discord(message: &str) -> Vec<String> {
     chunks
 }
 
+/// Cut a streamed draft down to a single Discord message.
+/// The complete reply is delivered when the draft is finalized.
+fn truncate_draft_for_discord(text: &str) -> &str {
+    text.char_indices()
+        .nth(DISCORD_MAX_MESSAGE_LENGTH)
+        .map_or(text, |(idx, _)| &text[..idx])
+}
+
 fn mention_tags(bot_user_id: &str) -> [String; 2] {
     [format!("<@{bot_user_id}>"), format!("<@!{bot_user_id}>")]
 }
@@ -345,8 +422,23 @@ impl Channel for DiscordChannel {
                         _ => {}
                     }
 
-                    // Only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     let event_type = event.get("t").and_then(|t| t.as_str()).unwrap_or("");
+
+                    // Button presses (approval prompts) arrive as interactions
+                    if event_type == "INTERACTION_CREATE" {
+                        let Some(d) = event.get("d") else {
+                            continue;
+                        };
+                        if let Some(channel_msg) = self.parse_component_interaction(d) {
+                            self.acknowledge_interaction(d).await;
+                            if tx.send(channel_msg).await.is_err() {
+                                break;
+                            }
+                        }
+                        continue;
+                    }
+
+                    // Otherwise only handle MESSAGE_CREATE (opcode 0, type "MESSAGE_CREATE")
                     if event_type != "MESSAGE_CREATE" {
                         continue;
                     }
@@ -400,13 +492,15 @@ impl Channel for DiscordChannel {
                             format!("discord_{message_id}")
                         },
                         sender: author_id.to_string(),
+                        sender_id: None,
                         reply_target: if channel_id.is_empty() {
                             author_id.to_string()
                         } else {
-                            channel_id.clone()
+                            channel_id
                         },
                         content: clean_content,
-                        channel: channel_id,
+                        channel: "discord".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -465,6 +559,140 @@ impl Channel for DiscordChannel {
         }
         Ok(())
     }
+
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        approval_id: &str,
+        prompt: &str,
+    ) -> anyhow::Result<()> {
+        let button = |label: &str, style: u8, response: ApprovalResponse| {
+            json!({
+                "type": 2,
+                "style": style,
+                "label": label,
+                "custom_id": approval_callback_data(approval_id, response),
+            })
+        };
+        // Styles: 3 = green, 4 = red, 1 = blurple.
+        let body = json!({
+            "content": prompt,
+            "components": [{
+                "type": 1,
+                "components": [
+                    button("Yes", 3, ApprovalResponse::Yes),
+                    button("No", 4, ApprovalResponse::No),
+                    button("Always", 1, ApprovalResponse::Always),
+                ],
+            }],
+        });
+
+        let url = format!("https://discord.com/api/v10/channels/{recipient}/messages");
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord send approval prompt failed ({status}): {err}");
+        }
+        Ok(())
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let url = format!(
+            "https://discord.com/api/v10/channels/{}/messages",
+            message.recipient
+        );
+        let body = json!({ "content": truncate_draft_for_discord(&message.content) });
+
+        let resp = self
+            .client
+            .post(&url)
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord send draft failed ({status}): {err}");
+        }
+
+        let created: serde_json::Value = resp.json().await?;
+        let id = created
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| anyhow::anyhow!("Discord message response is missing id"))?;
+        Ok(Some(id.to_string()))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let body = json!({ "content": truncate_draft_for_discord(text) });
+
+        let resp = self
+            .client
+            .patch(Self::message_url(recipient, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let err = resp
+                .text()
+                .await
+                .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
+            anyhow::bail!("Discord edit message failed ({status}): {err}");
+        }
+
+        Ok(())
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        if !text.is_empty() && text.chars().count() <= DISCORD_MAX_MESSAGE_LENGTH {
+            return self.update_draft(recipient, message_id, text).await;
+        }
+
+        // Too long for one message: replace the draft with a chunked reply.
+        let resp = self
+            .client
+            .delete(Self::message_url(recipient, message_id))
+            .header("Authorization", format!("Bot {}", self.bot_token))
+            .send()
+            .await;
+        if let Err(e) = resp.and_then(reqwest::Response::error_for_status) {
+            tracing::debug!("Discord: failed to delete draft {message_id}: {e}");
+        }
+        self.send(&SendMessage::new(text, recipient)).await
+    }
 }
 
 #[cfg(test)]
@@ -477,6 +705,23 @@ mod tests {
         assert_eq!(ch.name(), "discord");
     }
 
+    #[test]
+    fn discord_draft_truncation_counts_characters() {
+        assert_eq!(truncate_draft_for_discord("draft"), "draft");
+
+        let msg = "🦀".repeat(DISCORD_MAX_MESSAGE_LENGTH + 5);
+        let truncated = truncate_draft_for_discord(&msg);
+        assert_eq!(truncated.chars().count(), DISCORD_MAX_MESSAGE_LENGTH);
+    }
+
+    #[test]
+    fn discord_message_url_targets_channel_message() {
+        assert_eq!(
+            DiscordChannel::message_url("42", "7"),
+            "https://discord.com/api/v10/channels/42/messages/7"
+        );
+    }
+
     #[test]
     fn base64_decode_bot_id() {
         // "MTIzNDU2" decodes to "123456"
@@ -506,6 +751,37 @@ mod tests {
         assert!(ch.is_user_allowed("anyone"));
     }
 
+    #[test]
+    fn component_interaction_becomes_message() {
+        let ch = DiscordChannel::new("fake".into(), None, vec!["111".into()], false, false);
+        let d = json!({
+            "id": "9001",
+            "type": 3,
+            "token": "tok",
+            "channel_id": "555",
+            "member": { "user": { "id": "111" } },
+            "data": { "custom_id": "approval:abc:no" }
+        });
+
+        let msg = ch.parse_component_interaction(&d).unwrap();
+        assert_eq!(msg.sender, "111");
+        assert_eq!(msg.reply_target, "555");
+        assert_eq!(msg.content, "approval:abc:no");
+        assert_eq!(msg.channel, "discord");
+
+        let dm_from_stranger = json!({
+            "id": "9002",
+            "type": 3,
+            "channel_id": "556",
+            "user": { "id": "333" },
+            "data": { "custom_id": "approval:abc:yes" }
+        });
+        assert!(ch.parse_component_interaction(&dm_from_stranger).is_none());
+
+        let slash_command = json!({ "id": "9003", "type": 2, "channel_id": "555" });
+        assert!(ch.parse_component_interaction(&slash_command).is_none());
+    }
+
     #[test]
     fn specific_allowlist_filters() {
         let ch = DiscordChannel::new(
@@ -428,10 +428,12 @@ impl Channel for EmailChannel {
                         } // MutexGuard dropped before await
                         let msg = ChannelMessage {
                             id,
+                            sender_id: None,
                             reply_target: sender.clone(),
                             sender,
                             content,
                             channel: "email".to_string(),
+                            attachments: Vec::new(),
                             timestamp: ts,
                         };
                         if tx.send(msg).await.is_err() {
@@ -172,9 +172,11 @@ end tell"#
                         let msg = ChannelMessage {
                             id: rowid.to_string(),
                             sender: sender.clone(),
+                            sender_id: None,
                             reply_target: sender.clone(),
                             content: text,
                             channel: "imessage".to_string(),
+                            attachments: Vec::new(),
                             timestamp: std::time::SystemTime::now()
                                 .duration_since(std::time::UNIX_EPOCH)
                                 .unwrap_or_default()
@@ -566,9 +566,11 @@ impl Channel for IrcChannel {
                     let channel_msg = ChannelMessage {
                         id: format!("irc_{}_{seq}", chrono::Utc::now().timestamp_millis()),
                         sender: sender_nick.to_string(),
+                        sender_id: None,
                         reply_target: reply_to,
                         content,
                         channel: "irc".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
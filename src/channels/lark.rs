@@ -450,9 +450,11 @@ impl LarkChannel {
                     let channel_msg = ChannelMessage {
                         id: Uuid::new_v4().to_string(),
                         sender: lark_msg.chat_id.clone(),
+                        sender_id: None,
                         reply_target: lark_msg.chat_id.clone(),
                         content: text,
                         channel: "lark".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -614,9 +616,11 @@ impl LarkChannel {
         messages.push(ChannelMessage {
             id: Uuid::new_v4().to_string(),
             sender: chat_id.to_string(),
+            sender_id: None,
             reply_target: chat_id.to_string(),
             content: text,
             channel: "lark".to_string(),
+            attachments: Vec::new(),
             timestamp,
         });
 
@@ -62,6 +62,11 @@ struct WhoAmIResponse {
     user_id: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct SendEventResponse {
+    event_id: String,
+}
+
 impl MatrixChannel {
     pub fn new(
         homeserver: String,
@@ -109,6 +114,48 @@ impl MatrixChannel {
         let who: WhoAmIResponse = resp.json().await?;
         Ok(who.user_id)
     }
+
+    /// Send an `m.room.message` event to the room and return its event id.
+    async fn send_room_message(&self, body: &serde_json::Value) -> anyhow::Result<String> {
+        // Unique transaction ids: drafts can be edited several times a second.
+        let txn_id = format!("zc_{}", uuid::Uuid::new_v4());
+        let url = format!(
+            "{}/_matrix/client/v3/rooms/{}/send/m.room.message/{}",
+            self.homeserver, self.room_id, txn_id
+        );
+
+        let resp = self
+            .client
+            .put(&url)
+            .header("Authorization", format!("Bearer {}", self.access_token))
+            .json(body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Matrix send failed: {err}");
+        }
+
+        let sent: SendEventResponse = resp.json().await?;
+        Ok(sent.event_id)
+    }
+}
+
+/// Build an `m.replace` edit of `event_id` carrying `text` as the new body.
+fn edit_event_content(event_id: &str, text: &str) -> serde_json::Value {
+    serde_json::json!({
+        "msgtype": "m.text",
+        "body": format!("* {text}"),
+        "m.new_content": {
+            "msgtype": "m.text",
+            "body": text
+        },
+        "m.relates_to": {
+            "rel_type": "m.replace",
+            "event_id": event_id
+        }
+    })
 }
 
 #[async_trait]
@@ -230,9 +277,11 @@ impl Channel for MatrixChannel {
                     let msg = ChannelMessage {
                         id: format!("mx_{}", chrono::Utc::now().timestamp_millis()),
                         sender: event.sender.clone(),
+                        sender_id: None,
                         reply_target: event.sender.clone(),
                         content: body.clone(),
                         channel: "matrix".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -261,6 +310,29 @@ impl Channel for MatrixChannel {
 
         resp.status().is_success()
     }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let body = serde_json::json!({
+            "msgtype": "m.text",
+            "body": message.content
+        });
+        self.send_room_message(&body).await.map(Some)
+    }
+
+    async fn update_draft(
+        &self,
+        _recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.send_room_message(&edit_event_content(message_id, text))
+            .await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -285,6 +357,15 @@ mod tests {
         assert_eq!(ch.allowed_users.len(), 1);
     }
 
+    #[test]
+    fn edit_event_content_replaces_original_event() {
+        let content = edit_event_content("$draft:matrix.org", "final answer");
+        assert_eq!(content["body"], "* final answer");
+        assert_eq!(content["m.new_content"]["body"], "final answer");
+        assert_eq!(content["m.relates_to"]["rel_type"], "m.replace");
+        assert_eq!(content["m.relates_to"]["event_id"], "$draft:matrix.org");
+    }
+
     #[test]
     fn strips_trailing_slash() {
         let ch = MatrixChannel::new(
@@ -216,9 +216,11 @@ impl MattermostChannel {
         Some(ChannelMessage {
             id: format!("mattermost_{id}"),
             sender: user_id.to_string(),
+            sender_id: None,
             reply_target,
             content: text.to_string(),
             channel: "mattermost".to_string(),
+            attachments: Vec::new(),
             #[allow(clippy::cast_sign_loss)]
             timestamp: (create_at / 1000) as u64,
         })
@@ -1,3 +1,4 @@
+pub mod approval;
 pub mod cli;
 pub mod dingtalk;
 pub mod discord;
@@ -8,6 +9,7 @@ pub mod lark;
 pub mod matrix;
 pub mod mattermost;
 pub mod qq;
+pub mod session;
 pub mod signal;
 pub mod slack;
 pub mod telegram;
@@ -24,23 +26,32 @@ pub use lark::LarkChannel;
 pub use matrix::MatrixChannel;
 pub use mattermost::MattermostChannel;
 pub use qq::QQChannel;
+pub use session::SessionStore;
 pub use signal::SignalChannel;
 pub use slack::SlackChannel;
 pub use telegram::TelegramChannel;
 pub use traits::{Channel, SendMessage};
 pub use whatsapp::WhatsAppChannel;
 
-use crate::agent::loop_::{build_tool_instructions, run_tool_call_loop};
+use crate::agent::loop_::{
+    auto_compact_history, build_tool_instructions, run_tool_call_loop, trim_history, LoopOutput,
+    StreamEvent, MAX_HISTORY_MESSAGES,
+};
+use crate::approval::ApprovalPrompter;
 use crate::config::Config;
+use crate::cost::CostTracker;
 use crate::identity;
-use crate::memory::{self, Memory};
+use crate::memory::{self, Memory, ResponseCache};
 use crate::observability::{self, Observer};
-use crate::providers::{self, ChatMessage, Provider};
+use crate::providers::{self, ChatMessage, ContentPart, Provider};
 use crate::runtime;
 use crate::security::SecurityPolicy;
+use crate::tenant::router::{self as tenant_router, Tenant, TenantRouter};
 use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::{Context, Result};
+use approval::ChannelApprovals;
+use session::{parse_session_command, SessionCommand, SessionKey};
 use std::collections::HashMap;
 use std::fmt::Write;
 use std::path::PathBuf;
@@ -59,10 +70,14 @@ const CHANNEL_MESSAGE_TIMEOUT_SECS: u64 = 300;
 const CHANNEL_PARALLELISM_PER_CHANNEL: usize = 4;
 const CHANNEL_MIN_IN_FLIGHT_MESSAGES: usize = 8;
 const CHANNEL_MAX_IN_FLIGHT_MESSAGES: usize = 64;
+/// Minimum spacing between edits of a streamed draft reply, to stay inside
+/// platform rate limits.
+const DRAFT_UPDATE_INTERVAL: Duration = Duration::from_millis(1_000);
 
 #[derive(Clone)]
 struct ChannelRuntimeContext {
     channels_by_name: Arc<HashMap<String, Arc<dyn Channel>>>,
+    provider_name: Arc<String>,
     provider: Arc<dyn Provider>,
     memory: Arc<dyn Memory>,
     tools_registry: Arc<Vec<Box<dyn Tool>>>,
@@ -71,6 +86,12 @@ struct ChannelRuntimeContext {
     model: Arc<String>,
     temperature: f64,
     auto_save_memory: bool,
+    sessions: Option<Arc<SessionStore>>,
+    cost_tracker: Option<Arc<CostTracker>>,
+    response_cache: Option<Arc<ResponseCache>>,
+    max_parallel_tools: usize,
+    approvals: Option<Arc<ChannelApprovals>>,
+    tenants: Option<Arc<TenantRouter>>,
 }
 
 fn conversation_memory_key(msg: &traits::ChannelMessage) -> String {
@@ -157,6 +178,187 @@ fn log_worker_join_result(result: Result<(), tokio::task::JoinError>) {
     }
 }
 
+/// Compact, trim and store the non-system part of a finished turn's history.
+async fn persist_session_turns(
+    ctx: &ChannelRuntimeContext,
+    sessions: &SessionStore,
+    key: &SessionKey,
+    history: Vec<ChatMessage>,
+) {
+    let mut turns: Vec<ChatMessage> = history.into_iter().filter(|m| m.role != "system").collect();
+
+    if let Err(e) = auto_compact_history(&mut turns, ctx.provider.as_ref(), &ctx.model).await {
+        tracing::debug!("Session compaction failed for {}: {e}", key.sender);
+    }
+    trim_history(&mut turns, MAX_HISTORY_MESSAGES);
+
+    if let Err(e) = sessions.save(key, &turns) {
+        tracing::warn!("Failed to save session for {}: {e}", key.sender);
+    }
+}
+
+/// Handle `/new` / `/reset`. Returns `true` when the message was consumed.
+async fn handle_session_command(ctx: &ChannelRuntimeContext, msg: &traits::ChannelMessage) -> bool {
+    let Some(SessionCommand::Reset) = parse_session_command(&msg.content) else {
+        return false;
+    };
+
+    let key = SessionKey::from_message(msg);
+    if let Some(approvals) = ctx.approvals.as_ref() {
+        approvals.forget(&key);
+    }
+
+    let reply = match ctx.sessions.as_ref() {
+        Some(sessions) => match sessions.reset(&key) {
+            Ok(_) => "🆕 Started a new conversation.".to_string(),
+            Err(e) => format!("⚠️ Failed to reset conversation: {e}"),
+        },
+        None => {
+            "ℹ️ Conversation sessions are disabled; every message already starts fresh.".to_string()
+        }
+    };
+
+    if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+        if let Err(e) = channel
+            .send(&SendMessage::new(reply, &msg.reply_target))
+            .await
+        {
+            eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
+        }
+    }
+    true
+}
+
+/// Per-tenant system context: the sender's profile and any tools their
+/// feature settings switch off.
+fn tenant_system_messages(tenant: &Tenant, disabled_tools: &[String]) -> Vec<ChatMessage> {
+    let mut messages = Vec::new();
+    let profile = tenant_router::profile_context(&tenant.db);
+    if !profile.is_empty() {
+        messages.push(ChatMessage::system(profile));
+    }
+    if !disabled_tools.is_empty() {
+        messages.push(ChatMessage::system(format!(
+            "The user has turned off these tools; do not offer them: {}",
+            disabled_tools.join(", ")
+        )));
+    }
+    messages
+}
+
+/// Build the user turn for an inbound message, attaching any media parts.
+fn channel_user_message(text: &str, attachments: &[ContentPart]) -> ChatMessage {
+    if attachments.is_empty() {
+        return ChatMessage::user(text);
+    }
+    let mut parts = Vec::with_capacity(attachments.len() + 1);
+    if !text.is_empty() {
+        parts.push(ContentPart::text(text));
+    }
+    parts.extend(attachments.iter().cloned());
+    ChatMessage::user_with_parts(parts)
+}
+
+async fn edit_draft(channel: &dyn Channel, recipient: &str, draft_id: &str, text: &str) {
+    if let Err(e) = channel.update_draft(recipient, draft_id, text).await {
+        tracing::debug!("Failed to update draft on {}: {e}", channel.name());
+    }
+}
+
+/// Mirror a streamed reply into an editable draft message.
+///
+/// The draft is posted as soon as there is visible text and then edited at
+/// most once per [`DRAFT_UPDATE_INTERVAL`]. Returns the draft's message id,
+/// if one was posted, so the caller can finalize it with the full reply.
+async fn relay_stream_to_draft(
+    channel: Arc<dyn Channel>,
+    recipient: String,
+    mut rx: tokio::sync::mpsc::Receiver<StreamEvent>,
+) -> Option<String> {
+    let mut text = String::new();
+    let mut shown = String::new();
+    let mut draft_id: Option<String> = None;
+    let mut drafts_available = true;
+    let mut last_edit = Instant::now();
+
+    loop {
+        let pending_edit = draft_id.is_some() && !text.trim().is_empty() && text != shown;
+        let event = if pending_edit {
+            let wait = DRAFT_UPDATE_INTERVAL.saturating_sub(last_edit.elapsed());
+            match tokio::time::timeout(wait, rx.recv()).await {
+                Ok(event) => event,
+                Err(_) => {
+                    // Quiet period (e.g. a tool is running): flush the edit.
+                    if let Some(id) = draft_id.as_deref() {
+                        edit_draft(channel.as_ref(), &recipient, id, &text).await;
+                    }
+                    shown.clone_from(&text);
+                    last_edit = Instant::now();
+                    continue;
+                }
+            }
+        } else {
+            rx.recv().await
+        };
+
+        match event {
+            Some(StreamEvent::Delta(delta)) => text.push_str(&delta),
+            Some(StreamEvent::ToolRound) => text.clear(),
+            Some(StreamEvent::ToolStart { .. } | StreamEvent::ToolResult { .. }) => continue,
+            None => break,
+        }
+
+        if !drafts_available || text.trim().is_empty() {
+            continue;
+        }
+
+        match draft_id.as_deref() {
+            None => {
+                match channel
+                    .send_draft(&SendMessage::new(text.as_str(), recipient.as_str()))
+                    .await
+                {
+                    Ok(Some(id)) => draft_id = Some(id),
+                    Ok(None) => drafts_available = false,
+                    Err(e) => {
+                        tracing::debug!("Failed to post draft on {}: {e}", channel.name());
+                        drafts_available = false;
+                    }
+                }
+                shown.clone_from(&text);
+                last_edit = Instant::now();
+            }
+            Some(id) if last_edit.elapsed() >= DRAFT_UPDATE_INTERVAL => {
+                edit_draft(channel.as_ref(), &recipient, id, &text).await;
+                shown.clone_from(&text);
+                last_edit = Instant::now();
+            }
+            Some(_) => {}
+        }
+    }
+
+    draft_id
+}
+
+/// Send a reply, replacing the streamed draft when one was posted.
+async fn deliver_reply(
+    channel: &dyn Channel,
+    recipient: &str,
+    draft_id: Option<&str>,
+    text: String,
+) -> anyhow::Result<()> {
+    if let Some(id) = draft_id {
+        match channel.finalize_draft(recipient, id, &text).await {
+            Ok(()) => return Ok(()),
+            Err(e) => tracing::debug!(
+                "Failed to finalize draft on {}: {e}; sending a new message",
+                channel.name()
+            ),
+        }
+    }
+    channel.send(&SendMessage::new(text, recipient)).await
+}
+
 async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::ChannelMessage) {
     println!(
         "  💬 [{}] from {}: {}",
@@ -165,12 +367,42 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
         truncate_with_ellipsis(&msg.content, 80)
     );
 
-    let memory_context = build_memory_context(ctx.memory.as_ref(), &msg.content).await;
+    if handle_session_command(&ctx, &msg).await {
+        return;
+    }
+
+    // With tenant routing, memory and tools belong to the sender's tenant.
+    // History stays in the session store, keyed by the same stable sender id.
+    let tenant = match ctx.tenants.as_ref() {
+        Some(router) => match router.tenant(
+            &msg.channel,
+            msg.sender_id.as_deref().unwrap_or(&msg.sender),
+        ) {
+            Ok(tenant) => Some(tenant),
+            Err(e) => {
+                tracing::error!("Failed to open tenant for {}: {e}", msg.sender);
+                if let Some(channel) = ctx.channels_by_name.get(&msg.channel) {
+                    let _ = channel
+                        .send(&SendMessage::new(
+                            "⚠️ Failed to load your workspace. Please try again.",
+                            &msg.reply_target,
+                        ))
+                        .await;
+                }
+                return;
+            }
+        },
+        None => None,
+    };
+    let memory: &dyn Memory = tenant
+        .as_ref()
+        .map_or(ctx.memory.as_ref(), |tenant| tenant.memory.as_ref());
+
+    let memory_context = build_memory_context(memory, &msg.content).await;
 
     if ctx.auto_save_memory {
         let autosave_key = conversation_memory_key(&msg);
-        let _ = ctx
-            .memory
+        let _ = memory
             .store(
                 &autosave_key,
                 &msg.content,
@@ -197,32 +429,87 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
     println!("  ⏳ Processing message...");
     let started_at = Instant::now();
 
-    let mut history = vec![
-        ChatMessage::system(ctx.system_prompt.as_str()),
-        ChatMessage::user(&enriched_message),
-    ];
+    let session_key = SessionKey::from_message(&msg);
+    let prior_turns = match ctx.sessions.as_ref() {
+        Some(sessions) => sessions.load(&session_key).unwrap_or_else(|e| {
+            tracing::warn!("Failed to load session for {}: {e}", msg.sender);
+            Vec::new()
+        }),
+        None => Vec::new(),
+    };
 
+    let mut history = vec![ChatMessage::system(ctx.system_prompt.as_str())];
     if let Some(instructions) = channel_delivery_instructions(&msg.channel) {
         history.push(ChatMessage::system(instructions));
     }
+    let tenant_tools = match (ctx.tenants.as_ref(), tenant.as_ref()) {
+        (Some(router), Some(tenant)) => {
+            let (tools, disabled) = router.tools_for(tenant, &ctx.tools_registry);
+            history.extend(tenant_system_messages(tenant, &disabled));
+            Some(tools)
+        }
+        _ => None,
+    };
+    let user_index = history.len() + prior_turns.len();
+    history.extend(prior_turns);
+    history.push(channel_user_message(&enriched_message, &msg.attachments));
+
+    // Stream into an editable draft when both the channel and provider allow it.
+    let (stream_tx, draft_task) = match target_channel.as_ref() {
+        Some(channel) if channel.supports_draft_updates() && ctx.provider.supports_streaming() => {
+            let (tx, rx) = tokio::sync::mpsc::channel(64);
+            let task = tokio::spawn(relay_stream_to_draft(
+                Arc::clone(channel),
+                msg.reply_target.clone(),
+                rx,
+            ));
+            (Some(tx), Some(task))
+        }
+        _ => (None, None),
+    };
+
+    // Supervised tool calls are approved by the sender in the same chat.
+    let approval_manager = ctx
+        .approvals
+        .as_ref()
+        .map(|approvals| approvals.manager_for(&session_key));
+    let approval_prompter = match (ctx.approvals.as_ref(), target_channel.as_ref()) {
+        (Some(approvals), Some(channel)) => Some(approvals.prompter(Arc::clone(channel), &msg)),
+        _ => None,
+    };
 
     let llm_result = tokio::time::timeout(
         Duration::from_secs(CHANNEL_MESSAGE_TIMEOUT_SECS),
         run_tool_call_loop(
             ctx.provider.as_ref(),
             &mut history,
-            ctx.tools_registry.as_ref(),
+            tenant_tools
+                .as_deref()
+                .unwrap_or(ctx.tools_registry.as_slice()),
             ctx.observer.as_ref(),
-            "channel-runtime",
+            ctx.provider_name.as_str(),
             ctx.model.as_str(),
             ctx.temperature,
-            true, // silent — channels don't write to stdout
-            None,
+            LoopOutput::SILENT, // channels don't write to stdout
+            approval_manager.as_deref(),
+            approval_prompter
+                .as_ref()
+                .map(|prompter| prompter as &dyn ApprovalPrompter),
             msg.channel.as_str(),
+            ctx.cost_tracker.as_deref(),
+            ctx.response_cache.as_deref(),
+            stream_tx.as_ref(),
+            ctx.max_parallel_tools,
         ),
     )
     .await;
 
+    drop(stream_tx);
+    let draft_id = match draft_task {
+        Some(task) => task.await.ok().flatten(),
+        None => None,
+    };
+
     if let Some(channel) = target_channel.as_ref() {
         if let Err(e) = channel.stop_typing(&msg.reply_target).await {
             tracing::debug!("Failed to stop typing on {}: {e}", channel.name());
@@ -236,10 +523,22 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                 started_at.elapsed().as_millis(),
                 truncate_with_ellipsis(&response, 80)
             );
+            if let Some(sessions) = ctx.sessions.as_ref() {
+                // Store the raw user text, not the memory-enriched prompt,
+                // so recalled context doesn't pile up across turns. Media is
+                // reduced to placeholders to keep the session DB small.
+                history[user_index] =
+                    channel_user_message(&msg.content, &msg.attachments).text_fallback();
+                persist_session_turns(&ctx, sessions, &session_key, history).await;
+            }
             if let Some(channel) = target_channel.as_ref() {
-                if let Err(e) = channel
-                    .send(&SendMessage::new(response, &msg.reply_target))
-                    .await
+                if let Err(e) = deliver_reply(
+                    channel.as_ref(),
+                    &msg.reply_target,
+                    draft_id.as_deref(),
+                    response,
+                )
+                .await
                 {
                     eprintln!("  ❌ Failed to reply on {}: {e}", channel.name());
                 }
@@ -251,12 +550,13 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                 started_at.elapsed().as_millis()
             );
             if let Some(channel) = target_channel.as_ref() {
-                let _ = channel
-                    .send(&SendMessage::new(
-                        format!("⚠️ Error: {e}"),
-                        &msg.reply_target,
-                    ))
-                    .await;
+                let _ = deliver_reply(
+                    channel.as_ref(),
+                    &msg.reply_target,
+                    draft_id.as_deref(),
+                    format!("⚠️ Error: {e}"),
+                )
+                .await;
             }
         }
         Err(_) => {
@@ -270,12 +570,14 @@ async fn process_channel_message(ctx: Arc<ChannelRuntimeContext>, msg: traits::C
                 started_at.elapsed().as_millis()
             );
             if let Some(channel) = target_channel.as_ref() {
-                let _ = channel
-                    .send(&SendMessage::new(
-                        "⚠️ Request timed out while waiting for the model. Please try again.",
-                        &msg.reply_target,
-                    ))
-                    .await;
+                let _ = deliver_reply(
+                    channel.as_ref(),
+                    &msg.reply_target,
+                    draft_id.as_deref(),
+                    "⚠️ Request timed out while waiting for the model. Please try again."
+                        .to_string(),
+                )
+                .await;
             }
         }
     }
@@ -290,6 +592,16 @@ async fn run_message_dispatch_loop(
     let mut workers = tokio::task::JoinSet::new();
 
     while let Some(msg) = rx.recv().await {
+        // Approval answers go straight to the worker waiting for them; they
+        // must not queue behind that same worker for a permit.
+        if ctx
+            .approvals
+            .as_ref()
+            .is_some_and(|approvals| approvals.resolve(&msg))
+        {
+            continue;
+        }
+
         let permit = match Arc::clone(&semaphore).acquire_owned().await {
             Ok(permit) => permit,
             Err(_) => break,
@@ -957,6 +1269,7 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &provider_name,
         config.api_key.as_deref(),
         config.api_url.as_deref(),
+        config.default_model_vision,
         &config.reliability,
     )?);
 
@@ -979,10 +1292,12 @@ pub async fn start_channels(config: Config) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "anthropic/claude-sonnet-4-20250514".into());
     let temperature = config.default_temperature;
+    let memory_cipher = memory::MemoryCipher::from_config(&config)?;
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
         &config.workspace_dir,
         config.api_key.as_deref(),
+        memory_cipher.clone(),
     )?);
     let (composio_key, composio_entity_id) = if config.composio.enabled {
         (
@@ -994,10 +1309,11 @@ pub async fn start_channels(config: Config) -> Result<()> {
     };
     // Build system prompt from workspace identity files + skills
     let workspace = config.workspace_dir.clone();
-    let tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let skills = crate::skills::load_skills(&workspace);
+    let mut tools = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
-        runtime,
+        runtime.clone(),
         Arc::clone(&mem),
         composio_key,
         composio_entity_id,
@@ -1007,9 +1323,14 @@ pub async fn start_channels(config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
+    );
+    tools.extend(tools::skill_tools(
+        &skills,
+        &security,
+        &runtime,
+        &config.http_request,
     ));
-
-    let skills = crate::skills::load_skills(&workspace);
+    let tools_registry = Arc::new(tools);
 
     // Collect tool descriptions for the prompt
     let mut tool_descs: Vec<(&str, &str)> = vec![
@@ -1213,6 +1534,25 @@ pub async fn start_channels(config: Config) -> Result<()> {
         config.memory.backend,
         if config.memory.auto_save { "on" } else { "off" }
     );
+    println!(
+        "  💭 Sessions: {}",
+        if config.channels_config.sessions.enabled {
+            format!(
+                "on (idle timeout: {}m, /new to reset)",
+                config.channels_config.sessions.idle_timeout_minutes
+            )
+        } else {
+            "off".to_string()
+        }
+    );
+    println!(
+        "  👥 Tenants:  {}",
+        if config.channels_config.tenants.enabled {
+            "per sender (workspace/tenants)"
+        } else {
+            "shared"
+        }
+    );
     println!(
         "  📡 Channels: {}",
         channels
@@ -1261,8 +1601,67 @@ pub async fn start_channels(config: Config) -> Result<()> {
 
     println!("  🚦 In-flight message limit: {max_in_flight_messages}");
 
+    let sessions = if config.channels_config.sessions.enabled {
+        match SessionStore::new(
+            &config.workspace_dir,
+            config.channels_config.sessions.idle_timeout_minutes,
+        ) {
+            Ok(store) => {
+                match store.prune_expired() {
+                    Ok(pruned) if pruned > 0 => {
+                        tracing::info!("Pruned {pruned} idle channel sessions");
+                    }
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Failed to prune channel sessions: {e}"),
+                }
+                Some(Arc::new(store))
+            }
+            Err(e) => {
+                tracing::warn!("Channel sessions disabled — failed to open store: {e}");
+                None
+            }
+        }
+    } else {
+        None
+    };
+
+    let cost_tracker = match CostTracker::from_config(&config) {
+        Ok(tracker) => tracker.map(Arc::new),
+        Err(e) => {
+            tracing::warn!("Cost tracking disabled — failed to open cost storage: {e}");
+            None
+        }
+    };
+
+    let tenants = if config.channels_config.tenants.enabled {
+        let auth = match std::env::var("JWT_SECRET") {
+            Ok(jwt_secret) if !jwt_secret.is_empty() => {
+                match crate::auth::AuthManager::new(&config.workspace_dir, jwt_secret) {
+                    Ok(auth) => Some(Arc::new(auth)),
+                    Err(e) => {
+                        tracing::warn!("Tenant routing without linked accounts: {e}");
+                        None
+                    }
+                }
+            }
+            _ => None,
+        };
+        Some(Arc::new(
+            TenantRouter::new(
+                &config.workspace_dir,
+                &config.memory,
+                config.api_key.as_deref(),
+                memory_cipher.clone(),
+            )
+            .with_auth_manager(auth),
+        ))
+    } else {
+        None
+    };
+
     let runtime_ctx = Arc::new(ChannelRuntimeContext {
         channels_by_name,
+        provider_name: Arc::new(provider_name.clone()),
         provider: Arc::clone(&provider),
         memory: Arc::clone(&mem),
         tools_registry: Arc::clone(&tools_registry),
@@ -1271,6 +1670,17 @@ pub async fn start_channels(config: Config) -> Result<()> {
         model: Arc::new(model.clone()),
         temperature,
         auto_save_memory: config.memory.auto_save,
+        sessions,
+        cost_tracker,
+        response_cache: memory::create_response_cache(
+            &config.memory,
+            &config.workspace_dir,
+            memory_cipher,
+        )
+        .map(Arc::new),
+        max_parallel_tools: config.agent.tool_concurrency(),
+        approvals: Some(Arc::new(ChannelApprovals::new(&config.autonomy))),
+        tenants,
     });
 
     run_message_dispatch_loop(rx, runtime_ctx, max_in_flight_messages).await;
@@ -1343,6 +1753,167 @@ mod tests {
         }
     }
 
+    /// Records draft traffic separately from regular sends.
+    #[derive(Default)]
+    struct DraftRecordingChannel {
+        drafts: tokio::sync::Mutex<Vec<String>>,
+        finalized: tokio::sync::Mutex<Vec<String>>,
+        sent_messages: tokio::sync::Mutex<Vec<String>>,
+    }
+
+    #[async_trait::async_trait]
+    impl Channel for DraftRecordingChannel {
+        fn name(&self) -> &str {
+            "draft-channel"
+        }
+
+        async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+            self.sent_messages
+                .lock()
+                .await
+                .push(message.content.clone());
+            Ok(())
+        }
+
+        async fn listen(
+            &self,
+            _tx: tokio::sync::mpsc::Sender<traits::ChannelMessage>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn supports_draft_updates(&self) -> bool {
+            true
+        }
+
+        async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+            self.drafts.lock().await.push(message.content.clone());
+            Ok(Some("draft-1".to_string()))
+        }
+
+        async fn finalize_draft(
+            &self,
+            recipient: &str,
+            message_id: &str,
+            text: &str,
+        ) -> anyhow::Result<()> {
+            self.finalized
+                .lock()
+                .await
+                .push(format!("{recipient}:{message_id}:{text}"));
+            Ok(())
+        }
+    }
+
+    struct StreamingReplyProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for StreamingReplyProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            anyhow::bail!("blocking chat should not be used when streaming")
+        }
+
+        fn supports_streaming(&self) -> bool {
+            true
+        }
+
+        fn stream_chat_with_history(
+            &self,
+            _messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+            _options: crate::providers::traits::StreamOptions,
+        ) -> futures_util::stream::BoxStream<
+            'static,
+            crate::providers::traits::StreamResult<crate::providers::traits::StreamChunk>,
+        > {
+            use crate::providers::traits::StreamChunk;
+            use futures_util::StreamExt;
+
+            futures_util::stream::iter(
+                [
+                    StreamChunk::delta("Streaming "),
+                    StreamChunk::delta("reply"),
+                    StreamChunk::final_chunk(),
+                ]
+                .into_iter()
+                .map(Ok),
+            )
+            .boxed()
+        }
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_streams_reply_into_draft() {
+        let channel_impl = Arc::new(DraftRecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
+            provider: Arc::new(StreamingReplyProvider),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            sessions: None,
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
+        });
+
+        process_channel_message(
+            runtime_ctx,
+            traits::ChannelMessage {
+                id: "msg-draft".to_string(),
+                sender: "alice".to_string(),
+                sender_id: None,
+                reply_target: "chat-7".to_string(),
+                content: "hello".to_string(),
+                channel: "draft-channel".to_string(),
+                attachments: Vec::new(),
+                timestamp: 1,
+            },
+        )
+        .await;
+
+        assert_eq!(
+            channel_impl.drafts.lock().await.as_slice(),
+            ["Streaming ".to_string()]
+        );
+        assert_eq!(
+            channel_impl.finalized.lock().await.as_slice(),
+            ["chat-7:draft-1:Streaming reply".to_string()]
+        );
+        assert!(channel_impl.sent_messages.lock().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn relay_stream_to_draft_skips_channels_without_drafts() {
+        let channel: Arc<dyn Channel> = Arc::new(RecordingChannel::default());
+        let (tx, rx) = tokio::sync::mpsc::channel(4);
+        let relay = tokio::spawn(relay_stream_to_draft(channel, "chat-1".into(), rx));
+
+        tx.send(StreamEvent::Delta("partial".into())).await.unwrap();
+        drop(tx);
+
+        assert_eq!(relay.await.unwrap(), None);
+    }
+
     struct SlowProvider {
         delay: Duration,
     }
@@ -1487,6 +2058,7 @@ mod tests {
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
             channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
             provider: Arc::new(ToolCallingProvider),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
@@ -1495,6 +2067,12 @@ mod tests {
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            sessions: None,
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
         });
 
         process_channel_message(
@@ -1502,9 +2080,11 @@ mod tests {
             traits::ChannelMessage {
                 id: "msg-1".to_string(),
                 sender: "alice".to_string(),
+                sender_id: None,
                 reply_target: "chat-42".to_string(),
                 content: "What is the BTC price now?".to_string(),
                 channel: "test-channel".to_string(),
+                attachments: Vec::new(),
                 timestamp: 1,
             },
         )
@@ -1528,6 +2108,7 @@ mod tests {
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
             channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
             provider: Arc::new(ToolCallingAliasProvider),
             memory: Arc::new(NoopMemory),
             tools_registry: Arc::new(vec![Box::new(MockPriceTool)]),
@@ -1536,6 +2117,12 @@ mod tests {
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            sessions: None,
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
         });
 
         process_channel_message(
@@ -1543,9 +2130,11 @@ mod tests {
             traits::ChannelMessage {
                 id: "msg-2".to_string(),
                 sender: "bob".to_string(),
+                sender_id: None,
                 reply_target: "chat-84".to_string(),
                 content: "What is the BTC price now?".to_string(),
                 channel: "test-channel".to_string(),
+                attachments: Vec::new(),
                 timestamp: 2,
             },
         )
@@ -1611,6 +2200,216 @@ mod tests {
         }
     }
 
+    struct HistoryCountingProvider;
+
+    #[async_trait::async_trait]
+    impl Provider for HistoryCountingProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok("seen 1 user messages".to_string())
+        }
+
+        async fn chat_with_history(
+            &self,
+            messages: &[ChatMessage],
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            let user_count = messages.iter().filter(|m| m.role == "user").count();
+            Ok(format!("seen {user_count} user messages"))
+        }
+    }
+
+    fn session_test_message(id: &str, sender: &str, content: &str) -> traits::ChannelMessage {
+        traits::ChannelMessage {
+            id: id.to_string(),
+            sender: sender.to_string(),
+            sender_id: None,
+            reply_target: "chat-1".to_string(),
+            content: content.to_string(),
+            channel: "test-channel".to_string(),
+            attachments: Vec::new(),
+            timestamp: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_keeps_per_sender_session_history() {
+        let tmp = TempDir::new().unwrap();
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
+            provider: Arc::new(HistoryCountingProvider),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            sessions: Some(Arc::new(SessionStore::new(tmp.path(), 60).unwrap())),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
+        });
+
+        for (id, sender, content) in [
+            ("1", "alice", "hi"),
+            ("2", "alice", "remember me?"),
+            ("3", "bob", "hello"),
+            ("4", "alice", "/new"),
+            ("5", "alice", "fresh start"),
+        ] {
+            process_channel_message(
+                Arc::clone(&runtime_ctx),
+                session_test_message(id, sender, content),
+            )
+            .await;
+        }
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 5);
+        assert_eq!(sent_messages[0], "chat-1:seen 1 user messages");
+        assert_eq!(sent_messages[1], "chat-1:seen 2 user messages");
+        assert_eq!(sent_messages[2], "chat-1:seen 1 user messages");
+        assert!(sent_messages[3].contains("new conversation"));
+        assert_eq!(sent_messages[4], "chat-1:seen 1 user messages");
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_routes_memory_and_history_per_tenant() {
+        let tmp = TempDir::new().unwrap();
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let memory_config = crate::config::MemoryConfig {
+            backend: "sqlite".into(),
+            embedding_provider: "none".into(),
+            ..crate::config::MemoryConfig::default()
+        };
+        let router = Arc::new(TenantRouter::new(tmp.path(), &memory_config, None, None));
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
+            provider: Arc::new(HistoryCountingProvider),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: true,
+            sessions: Some(Arc::new(SessionStore::new(tmp.path(), 60).unwrap())),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: Some(Arc::clone(&router)),
+        });
+
+        for (id, sender, content) in [
+            ("1", "alice", "my favourite colour is teal"),
+            ("2", "alice", "and I like jazz"),
+            ("3", "bob", "hello"),
+        ] {
+            process_channel_message(
+                Arc::clone(&runtime_ctx),
+                session_test_message(id, sender, content),
+            )
+            .await;
+        }
+
+        let alice = router.tenant("test-channel", "alice").unwrap();
+        let bob = router.tenant("test-channel", "bob").unwrap();
+        assert_eq!(alice.memory.count().await.unwrap(), 2);
+        assert_eq!(bob.memory.count().await.unwrap(), 1);
+        assert!(bob.memory.recall("teal", 5, None).await.unwrap().is_empty());
+
+        // History lives only in the session store
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages[1], "chat-1:seen 2 user messages");
+        assert_eq!(sent_messages[2], "chat-1:seen 1 user messages");
+        assert!(alice.db.get_conversation_history(10).unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn process_channel_message_without_sessions_starts_fresh_each_time() {
+        let channel_impl = Arc::new(RecordingChannel::default());
+        let channel: Arc<dyn Channel> = channel_impl.clone();
+
+        let mut channels_by_name = HashMap::new();
+        channels_by_name.insert(channel.name().to_string(), channel);
+
+        let runtime_ctx = Arc::new(ChannelRuntimeContext {
+            channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
+            provider: Arc::new(HistoryCountingProvider),
+            memory: Arc::new(NoopMemory),
+            tools_registry: Arc::new(vec![]),
+            observer: Arc::new(NoopObserver),
+            system_prompt: Arc::new("test-system-prompt".to_string()),
+            model: Arc::new("test-model".to_string()),
+            temperature: 0.0,
+            auto_save_memory: false,
+            sessions: None,
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
+        });
+
+        for id in ["1", "2"] {
+            process_channel_message(
+                Arc::clone(&runtime_ctx),
+                session_test_message(id, "alice", "hi"),
+            )
+            .await;
+        }
+
+        let sent_messages = channel_impl.sent_messages.lock().await;
+        assert_eq!(sent_messages.len(), 2);
+        assert_eq!(sent_messages[1], "chat-1:seen 1 user messages");
+    }
+
+    #[test]
+    fn channel_user_message_attaches_media_parts() {
+        use crate::providers::MediaSource;
+
+        let plain = channel_user_message("hi", &[]);
+        assert!(plain.parts.is_empty());
+        assert_eq!(plain.content, "hi");
+
+        let photo = ContentPart::image(MediaSource::from_bytes("image/jpeg", b"jpg"));
+        let captioned = channel_user_message("look", std::slice::from_ref(&photo));
+        assert_eq!(captioned.content, "look");
+        assert_eq!(
+            captioned.parts,
+            vec![ContentPart::text("look"), photo.clone()]
+        );
+
+        let bare = channel_user_message("", std::slice::from_ref(&photo));
+        assert_eq!(bare.parts, vec![photo]);
+        assert!(bare.text_fallback().content.contains("image/jpeg"));
+    }
+
     #[tokio::test]
     async fn message_dispatch_processes_messages_in_parallel() {
         let channel_impl = Arc::new(RecordingChannel::default());
@@ -1621,6 +2420,7 @@ mod tests {
 
         let runtime_ctx = Arc::new(ChannelRuntimeContext {
             channels_by_name: Arc::new(channels_by_name),
+            provider_name: Arc::new("test-provider".to_string()),
             provider: Arc::new(SlowProvider {
                 delay: Duration::from_millis(250),
             }),
@@ -1631,15 +2431,23 @@ mod tests {
             model: Arc::new("test-model".to_string()),
             temperature: 0.0,
             auto_save_memory: false,
+            sessions: None,
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            approvals: None,
+            tenants: None,
         });
 
         let (tx, rx) = tokio::sync::mpsc::channel::<traits::ChannelMessage>(4);
         tx.send(traits::ChannelMessage {
             id: "1".to_string(),
             sender: "alice".to_string(),
+            sender_id: None,
             reply_target: "alice".to_string(),
             content: "hello".to_string(),
             channel: "test-channel".to_string(),
+            attachments: Vec::new(),
             timestamp: 1,
         })
         .await
@@ -1647,9 +2455,11 @@ mod tests {
         tx.send(traits::ChannelMessage {
             id: "2".to_string(),
             sender: "bob".to_string(),
+            sender_id: None,
             reply_target: "bob".to_string(),
             content: "world".to_string(),
             channel: "test-channel".to_string(),
+            attachments: Vec::new(),
             timestamp: 2,
         })
         .await
@@ -1910,9 +2720,11 @@ mod tests {
         let msg = traits::ChannelMessage {
             id: "msg_abc123".into(),
             sender: "U123".into(),
+            sender_id: None,
             reply_target: "C456".into(),
             content: "hello".into(),
             channel: "slack".into(),
+            attachments: Vec::new(),
             timestamp: 1,
         };
 
@@ -1924,17 +2736,21 @@ mod tests {
         let msg1 = traits::ChannelMessage {
             id: "msg_1".into(),
             sender: "U123".into(),
+            sender_id: None,
             reply_target: "C456".into(),
             content: "first".into(),
             channel: "slack".into(),
+            attachments: Vec::new(),
             timestamp: 1,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
             sender: "U123".into(),
+            sender_id: None,
             reply_target: "C456".into(),
             content: "second".into(),
             channel: "slack".into(),
+            attachments: Vec::new(),
             timestamp: 2,
         };
 
@@ -1952,17 +2768,21 @@ mod tests {
         let msg1 = traits::ChannelMessage {
             id: "msg_1".into(),
             sender: "U123".into(),
+            sender_id: None,
             reply_target: "C456".into(),
             content: "I'm Paul".into(),
             channel: "slack".into(),
+            attachments: Vec::new(),
             timestamp: 1,
         };
         let msg2 = traits::ChannelMessage {
             id: "msg_2".into(),
             sender: "U123".into(),
+            sender_id: None,
             reply_target: "C456".into(),
             content: "I'm 45".into(),
             channel: "slack".into(),
+            attachments: Vec::new(),
             timestamp: 2,
         };
 
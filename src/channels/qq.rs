@@ -352,9 +352,11 @@ impl Channel for QQChannel {
                             let channel_msg = ChannelMessage {
                                 id: Uuid::new_v4().to_string(),
                                 sender: user_openid.to_string(),
+                                sender_id: None,
                                 reply_target: chat_id,
                                 content: content.to_string(),
                                 channel: "qq".to_string(),
+                                attachments: Vec::new(),
                                 timestamp: std::time::SystemTime::now()
                                     .duration_since(std::time::UNIX_EPOCH)
                                     .unwrap_or_default()
@@ -390,9 +392,11 @@ impl Channel for QQChannel {
                             let channel_msg = ChannelMessage {
                                 id: Uuid::new_v4().to_string(),
                                 sender: author_id.to_string(),
+                                sender_id: None,
                                 reply_target: chat_id,
                                 content: content.to_string(),
                                 channel: "qq".to_string(),
+                                attachments: Vec::new(),
                                 timestamp: std::time::SystemTime::now()
                                     .duration_since(std::time::UNIX_EPOCH)
                                     .unwrap_or_default()
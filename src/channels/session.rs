@@ -0,0 +1,381 @@
+//! Per-sender conversation sessions for the channel runtime.
+//!
+//! Each `(channel, reply_target, sender)` triple gets its own bounded chat
+//! history so the bot remembers earlier turns on Telegram, Discord, Slack,
+//! etc. Sessions are persisted in `channels/sessions.db` under the workspace
+//! so they survive daemon restarts, and are dropped once they have been idle
+//! longer than `[channels_config.sessions] idle_timeout_minutes`.
+
+use super::traits::ChannelMessage;
+use crate::providers::ChatMessage;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
+use parking_lot::Mutex;
+use rusqlite::{params, Connection, OptionalExtension};
+use std::path::Path;
+
+/// Identifies one conversation: who is talking, where, and where replies go.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SessionKey {
+    pub channel: String,
+    pub reply_target: String,
+    /// The sender's stable account id when the channel has one, so a
+    /// re-claimed handle never inherits someone else's history.
+    pub sender: String,
+}
+
+impl SessionKey {
+    pub fn from_message(msg: &ChannelMessage) -> Self {
+        Self {
+            channel: msg.channel.clone(),
+            reply_target: msg.reply_target.clone(),
+            sender: msg.sender_id.clone().unwrap_or_else(|| msg.sender.clone()),
+        }
+    }
+}
+
+/// In-chat commands that control the session rather than reaching the model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionCommand {
+    /// `/new` or `/reset` — forget the current conversation and start over.
+    Reset,
+}
+
+/// Parse a session command from raw message text.
+///
+/// Accepts Telegram-style bot suffixes (`/new@my_bot`) and ignores case.
+pub fn parse_session_command(content: &str) -> Option<SessionCommand> {
+    let first = content.split_whitespace().next()?;
+    let command = first.split('@').next().unwrap_or(first);
+    if command.eq_ignore_ascii_case("/new") || command.eq_ignore_ascii_case("/reset") {
+        Some(SessionCommand::Reset)
+    } else {
+        None
+    }
+}
+
+/// SQLite-backed store of per-sender chat histories.
+///
+/// Only non-system messages are kept; the runtime re-injects the current
+/// system prompt on every turn so prompt changes apply to existing sessions.
+pub struct SessionStore {
+    conn: Mutex<Connection>,
+    idle_timeout_minutes: i64,
+}
+
+impl SessionStore {
+    /// Open (or create) the session database under `workspace_dir/channels`.
+    pub fn new(workspace_dir: &Path, idle_timeout_minutes: u32) -> Result<Self> {
+        let db_dir = workspace_dir.join("channels");
+        std::fs::create_dir_all(&db_dir).with_context(|| {
+            format!("Failed to create sessions directory: {}", db_dir.display())
+        })?;
+        let db_path = db_dir.join("sessions.db");
+
+        let conn = Connection::open(&db_path)
+            .with_context(|| format!("Failed to open sessions DB: {}", db_path.display()))?;
+
+        conn.execute_batch(
+            "PRAGMA journal_mode = WAL;
+             PRAGMA synchronous  = NORMAL;
+             CREATE TABLE IF NOT EXISTS channel_sessions (
+                channel      TEXT NOT NULL,
+                reply_target TEXT NOT NULL,
+                sender       TEXT NOT NULL,
+                history      TEXT NOT NULL,
+                created_at   TEXT NOT NULL,
+                updated_at   TEXT NOT NULL,
+                PRIMARY KEY (channel, reply_target, sender)
+            );
+            CREATE INDEX IF NOT EXISTS idx_cs_updated ON channel_sessions(updated_at);",
+        )?;
+
+        Ok(Self {
+            conn: Mutex::new(conn),
+            idle_timeout_minutes: i64::from(idle_timeout_minutes),
+        })
+    }
+
+    /// Sessions last updated at or before this instant are idle.
+    fn idle_cutoff(&self) -> DateTime<Utc> {
+        Utc::now() - Duration::minutes(self.idle_timeout_minutes)
+    }
+
+    /// Load the stored history for a session.
+    ///
+    /// Returns an empty history for unknown or expired sessions; expired rows
+    /// are deleted on the way out.
+    pub fn load(&self, key: &SessionKey) -> Result<Vec<ChatMessage>> {
+        let conn = self.conn.lock();
+
+        let row: Option<(String, String)> = conn
+            .query_row(
+                "SELECT history, updated_at FROM channel_sessions
+                 WHERE channel = ?1 AND reply_target = ?2 AND sender = ?3",
+                params![key.channel, key.reply_target, key.sender],
+                |row| Ok((row.get(0)?, row.get(1)?)),
+            )
+            .optional()?;
+
+        let Some((history_json, updated_at)) = row else {
+            return Ok(Vec::new());
+        };
+
+        // Older rows may carry a local UTC offset, so compare instants, not text.
+        let active =
+            DateTime::parse_from_rfc3339(&updated_at).is_ok_and(|at| at > self.idle_cutoff());
+        if !active {
+            conn.execute(
+                "DELETE FROM channel_sessions
+                 WHERE channel = ?1 AND reply_target = ?2 AND sender = ?3",
+                params![key.channel, key.reply_target, key.sender],
+            )?;
+            return Ok(Vec::new());
+        }
+
+        match serde_json::from_str(&history_json) {
+            Ok(history) => Ok(history),
+            Err(e) => {
+                tracing::warn!(
+                    "Discarding unreadable session history for {}:{}: {e}",
+                    key.channel,
+                    key.sender
+                );
+                Ok(Vec::new())
+            }
+        }
+    }
+
+    /// Replace the stored history for a session and bump its idle timer.
+    pub fn save(&self, key: &SessionKey, history: &[ChatMessage]) -> Result<()> {
+        let conn = self.conn.lock();
+        let now = Utc::now().to_rfc3339_opts(SecondsFormat::Micros, true);
+        let history_json = serde_json::to_string(history)?;
+
+        conn.execute(
+            "INSERT INTO channel_sessions
+             (channel, reply_target, sender, history, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?5)
+             ON CONFLICT(channel, reply_target, sender)
+             DO UPDATE SET history = excluded.history, updated_at = excluded.updated_at",
+            params![key.channel, key.reply_target, key.sender, history_json, now],
+        )?;
+
+        Ok(())
+    }
+
+    /// Forget a session. Returns `true` if one existed.
+    pub fn reset(&self, key: &SessionKey) -> Result<bool> {
+        let conn = self.conn.lock();
+        let affected = conn.execute(
+            "DELETE FROM channel_sessions
+             WHERE channel = ?1 AND reply_target = ?2 AND sender = ?3",
+            params![key.channel, key.reply_target, key.sender],
+        )?;
+        Ok(affected > 0)
+    }
+
+    /// Delete every session idle past the timeout. Returns the number removed.
+    pub fn prune_expired(&self) -> Result<usize> {
+        let conn = self.conn.lock();
+        let affected = conn.execute(
+            "DELETE FROM channel_sessions WHERE julianday(updated_at) <= julianday(?1)",
+            params![self
+                .idle_cutoff()
+                .to_rfc3339_opts(SecondsFormat::Micros, true)],
+        )?;
+        Ok(affected)
+    }
+
+    /// Number of stored sessions (including ones not yet pruned).
+    pub fn count(&self) -> Result<usize> {
+        let conn = self.conn.lock();
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM channel_sessions", [], |row| {
+            row.get(0)
+        })?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(count as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_store(idle_timeout_minutes: u32) -> (TempDir, SessionStore) {
+        let tmp = TempDir::new().unwrap();
+        let store = SessionStore::new(tmp.path(), idle_timeout_minutes).unwrap();
+        (tmp, store)
+    }
+
+    fn key(sender: &str) -> SessionKey {
+        SessionKey {
+            channel: "telegram".into(),
+            reply_target: "chat-1".into(),
+            sender: sender.into(),
+        }
+    }
+
+    #[test]
+    fn parse_session_command_recognizes_new_and_reset() {
+        assert_eq!(parse_session_command("/new"), Some(SessionCommand::Reset));
+        assert_eq!(
+            parse_session_command(" /reset "),
+            Some(SessionCommand::Reset)
+        );
+        assert_eq!(
+            parse_session_command("/NEW@zeroclaw_bot"),
+            Some(SessionCommand::Reset)
+        );
+        assert_eq!(parse_session_command("/newsletter"), None);
+        assert_eq!(parse_session_command("start a /new thing"), None);
+        assert_eq!(parse_session_command(""), None);
+    }
+
+    #[test]
+    fn session_key_prefers_stable_sender_id() {
+        let mut msg = ChannelMessage {
+            id: "1".into(),
+            sender: "alice".into(),
+            sender_id: Some("4242".into()),
+            reply_target: "chat-1".into(),
+            content: "hi".into(),
+            channel: "telegram".into(),
+            attachments: Vec::new(),
+            timestamp: 0,
+        };
+        assert_eq!(SessionKey::from_message(&msg), key("4242"));
+
+        msg.sender_id = None;
+        assert_eq!(SessionKey::from_message(&msg), key("alice"));
+    }
+
+    #[test]
+    fn load_unknown_session_is_empty() {
+        let (_tmp, store) = temp_store(60);
+        assert!(store.load(&key("alice")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn save_and_load_roundtrip() {
+        let (_tmp, store) = temp_store(60);
+        let history = vec![ChatMessage::user("hi"), ChatMessage::assistant("hello!")];
+
+        store.save(&key("alice"), &history).unwrap();
+        let loaded = store.load(&key("alice")).unwrap();
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].role, "user");
+        assert_eq!(loaded[1].content, "hello!");
+    }
+
+    #[test]
+    fn sessions_are_isolated_per_sender() {
+        let (_tmp, store) = temp_store(60);
+        store
+            .save(&key("alice"), &[ChatMessage::user("alice secret")])
+            .unwrap();
+
+        assert!(store.load(&key("bob")).unwrap().is_empty());
+        assert_eq!(store.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn save_overwrites_previous_history() {
+        let (_tmp, store) = temp_store(60);
+        store
+            .save(&key("alice"), &[ChatMessage::user("one")])
+            .unwrap();
+        store
+            .save(
+                &key("alice"),
+                &[ChatMessage::user("one"), ChatMessage::user("two")],
+            )
+            .unwrap();
+
+        assert_eq!(store.load(&key("alice")).unwrap().len(), 2);
+        assert_eq!(store.count().unwrap(), 1);
+    }
+
+    #[test]
+    fn reset_forgets_session() {
+        let (_tmp, store) = temp_store(60);
+        store
+            .save(&key("alice"), &[ChatMessage::user("hi")])
+            .unwrap();
+
+        assert!(store.reset(&key("alice")).unwrap());
+        assert!(!store.reset(&key("alice")).unwrap());
+        assert!(store.load(&key("alice")).unwrap().is_empty());
+    }
+
+    #[test]
+    fn idle_sessions_expire() {
+        let (_tmp, store) = temp_store(0); // 0-minute timeout → instantly idle
+        store
+            .save(&key("alice"), &[ChatMessage::user("hi")])
+            .unwrap();
+
+        assert!(store.load(&key("alice")).unwrap().is_empty());
+        assert_eq!(store.count().unwrap(), 0);
+    }
+
+    #[test]
+    fn prune_expired_removes_idle_sessions() {
+        let (_tmp, store) = temp_store(0);
+        store
+            .save(&key("alice"), &[ChatMessage::user("hi")])
+            .unwrap();
+        store
+            .save(&key("bob"), &[ChatMessage::user("hey")])
+            .unwrap();
+
+        assert_eq!(store.prune_expired().unwrap(), 2);
+        assert_eq!(store.count().unwrap(), 0);
+    }
+
+    #[test]
+    fn idle_check_compares_instants_across_utc_offsets() {
+        let (_tmp, store) = temp_store(60);
+        store
+            .save(&key("alice"), &[ChatMessage::user("hi")])
+            .unwrap();
+        store
+            .save(&key("bob"), &[ChatMessage::user("hey")])
+            .unwrap();
+
+        // Two hours ago, written with a +10:00 offset: as text it sorts
+        // after the UTC cutoff, as an instant it is long idle.
+        let offset = chrono::FixedOffset::east_opt(10 * 3600).unwrap();
+        let stale = (Utc::now() - Duration::hours(2))
+            .with_timezone(&offset)
+            .to_rfc3339();
+        store
+            .conn
+            .lock()
+            .execute(
+                "UPDATE channel_sessions SET updated_at = ?1",
+                params![stale],
+            )
+            .unwrap();
+
+        assert!(store.load(&key("alice")).unwrap().is_empty());
+        assert_eq!(store.prune_expired().unwrap(), 1);
+        assert_eq!(store.count().unwrap(), 0);
+    }
+
+    #[test]
+    fn sessions_survive_reopen() {
+        let tmp = TempDir::new().unwrap();
+        {
+            let store = SessionStore::new(tmp.path(), 60).unwrap();
+            store
+                .save(&key("alice"), &[ChatMessage::user("hi")])
+                .unwrap();
+        }
+
+        let reopened = SessionStore::new(tmp.path(), 60).unwrap();
+        assert_eq!(reopened.load(&key("alice")).unwrap().len(), 1);
+    }
+}
@@ -255,9 +255,11 @@ impl SignalChannel {
         Some(ChannelMessage {
             id: format!("sig_{timestamp}"),
             sender: sender.clone(),
+            sender_id: None,
             reply_target: target,
             content: text.to_string(),
             channel: "signal".to_string(),
+            attachments: Vec::new(),
             timestamp: timestamp / 1000, // millis → secs
         })
     }
@@ -43,25 +43,18 @@ impl SlackChannel {
             .and_then(|u| u.as_str())
             .map(String::from)
     }
-}
-
-#[async_trait]
-impl Channel for SlackChannel {
-    fn name(&self) -> &str {
-        "slack"
-    }
-
-    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
-        let body = serde_json::json!({
-            "channel": message.recipient,
-            "text": message.content
-        });
 
+    /// POST a Web API method and return its JSON body.
+    async fn call_api(
+        &self,
+        method: &str,
+        body: &serde_json::Value,
+    ) -> anyhow::Result<serde_json::Value> {
         let resp = self
             .client
-            .post("https://slack.com/api/chat.postMessage")
+            .post(format!("https://slack.com/api/{method}"))
             .bearer_auth(&self.bot_token)
-            .json(&body)
+            .json(body)
             .send()
             .await?;
 
@@ -72,7 +65,7 @@ impl Channel for SlackChannel {
             .unwrap_or_else(|e| format!("<failed to read response body: {e}>"));
 
         if !status.is_success() {
-            anyhow::bail!("Slack chat.postMessage failed ({status}): {body}");
+            anyhow::bail!("Slack {method} failed ({status}): {body}");
         }
 
         // Slack returns 200 for most app-level errors; check JSON "ok" field
@@ -82,9 +75,26 @@ impl Channel for SlackChannel {
                 .get("error")
                 .and_then(|e| e.as_str())
                 .unwrap_or("unknown");
-            anyhow::bail!("Slack chat.postMessage failed: {err}");
+            anyhow::bail!("Slack {method} failed: {err}");
         }
 
+        Ok(parsed)
+    }
+}
+
+#[async_trait]
+impl Channel for SlackChannel {
+    fn name(&self) -> &str {
+        "slack"
+    }
+
+    async fn send(&self, message: &SendMessage) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "channel": message.recipient,
+            "text": message.content
+        });
+
+        self.call_api("chat.postMessage", &body).await?;
         Ok(())
     }
 
@@ -161,9 +171,11 @@ impl Channel for SlackChannel {
                     let channel_msg = ChannelMessage {
                         id: format!("slack_{channel_id}_{ts}"),
                         sender: user.to_string(),
+                        sender_id: None,
                         reply_target: channel_id.clone(),
                         content: text.to_string(),
                         channel: "slack".to_string(),
+                        attachments: Vec::new(),
                         timestamp: std::time::SystemTime::now()
                             .duration_since(std::time::UNIX_EPOCH)
                             .unwrap_or_default()
@@ -187,6 +199,40 @@ impl Channel for SlackChannel {
             .map(|r| r.status().is_success())
             .unwrap_or(false)
     }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        let body = serde_json::json!({
+            "channel": message.recipient,
+            "text": message.content
+        });
+
+        let posted = self.call_api("chat.postMessage", &body).await?;
+        let ts = posted
+            .get("ts")
+            .and_then(|ts| ts.as_str())
+            .ok_or_else(|| anyhow::anyhow!("Slack chat.postMessage response is missing ts"))?;
+        Ok(Some(ts.to_string()))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "channel": recipient,
+            "ts": message_id,
+            "text": text
+        });
+
+        self.call_api("chat.update", &body).await?;
+        Ok(())
+    }
 }
 
 #[cfg(test)]
@@ -199,6 +245,12 @@ mod tests {
         assert_eq!(ch.name(), "slack");
     }
 
+    #[test]
+    fn slack_supports_draft_updates() {
+        let ch = SlackChannel::new("xoxb-fake".into(), None, vec![]);
+        assert!(ch.supports_draft_updates());
+    }
+
     #[test]
     fn slack_channel_with_channel_id() {
         let ch = SlackChannel::new("xoxb-fake".into(), Some("C12345".into()), vec![]);
@@ -1,6 +1,9 @@
+use super::approval::approval_callback_data;
 use super::traits::{Channel, ChannelMessage, SendMessage};
+use crate::approval::ApprovalResponse;
 use crate::auth::AuthManager;
 use crate::config::Config;
+use crate::providers::{ContentPart, MediaSource};
 use crate::security::pairing::PairingGuard;
 use anyhow::Context;
 use async_trait::async_trait;
@@ -16,6 +19,10 @@ use std::time::Duration;
 const TELEGRAM_MAX_MESSAGE_LENGTH: usize = 4096;
 const TELEGRAM_BIND_COMMAND: &str = "/bind";
 const TELEGRAM_START_COMMAND: &str = "/start";
+/// Bot API refuses `getFile` downloads above 20 MB.
+const TELEGRAM_MAX_DOWNLOAD_BYTES: u64 = 20 * 1024 * 1024;
+/// Text documents larger than this are referenced, not inlined.
+const TELEGRAM_MAX_INLINE_TEXT_BYTES: usize = 64 * 1024;
 
 // ══════════════════════════════════════════════════════════════════════════════
 // TELEGRAM KEYBOARD BUILDER
@@ -254,6 +261,19 @@ fn split_message_for_telegram(message: &str) -> Vec<String> {
     chunks
 }
 
+/// Cut a streamed draft down to a single Telegram message.
+/// The complete reply is delivered when the draft is finalized.
+fn truncate_draft_for_telegram(text: &str) -> &str {
+    if text.len() <= TELEGRAM_MAX_MESSAGE_LENGTH {
+        return text;
+    }
+    let mut end = TELEGRAM_MAX_MESSAGE_LENGTH;
+    while !text.is_char_boundary(end) {
+        end -= 1;
+    }
+    &text[..end]
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum TelegramAttachmentKind {
     Image,
@@ -444,6 +464,94 @@ fn parse_attachment_markers(message: &str) -> (String, Vec<TelegramAttachment>)
     (cleaned.trim().to_string(), attachments)
 }
 
+/// A photo, document, voice note or audio file referenced by an inbound message.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct InboundFile {
+    file_id: String,
+    mime_type: String,
+    file_name: Option<String>,
+    file_size: Option<u64>,
+}
+
+impl InboundFile {
+    fn label(&self) -> &str {
+        self.file_name.as_deref().unwrap_or(&self.mime_type)
+    }
+}
+
+/// Collect downloadable files from a Telegram `message` object.
+///
+/// For photos Telegram sends several resolutions; the last one is the largest.
+fn inbound_files(message: &serde_json::Value) -> Vec<InboundFile> {
+    let mut files = Vec::new();
+
+    if let Some(photo) = message
+        .get("photo")
+        .and_then(serde_json::Value::as_array)
+        .and_then(|sizes| sizes.last())
+    {
+        if let Some(file) = inbound_file(photo, "image/jpeg") {
+            files.push(file);
+        }
+    }
+
+    for (field, default_mime) in [
+        ("document", "application/octet-stream"),
+        ("voice", "audio/ogg"),
+        ("audio", "audio/mpeg"),
+    ] {
+        if let Some(file) = message
+            .get(field)
+            .and_then(|value| inbound_file(value, default_mime))
+        {
+            files.push(file);
+        }
+    }
+
+    files
+}
+
+fn inbound_file(value: &serde_json::Value, default_mime: &str) -> Option<InboundFile> {
+    let file_id = value.get("file_id").and_then(serde_json::Value::as_str)?;
+    Some(InboundFile {
+        file_id: file_id.to_string(),
+        mime_type: value
+            .get("mime_type")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or(default_mime)
+            .to_string(),
+        file_name: value
+            .get("file_name")
+            .and_then(serde_json::Value::as_str)
+            .map(ToString::to_string),
+        file_size: value.get("file_size").and_then(serde_json::Value::as_u64),
+    })
+}
+
+/// Turn downloaded bytes into a content part: images and audio go to the
+/// model as media, small text files are inlined, anything else is noted.
+fn inbound_file_part(file: &InboundFile, bytes: &[u8]) -> ContentPart {
+    let mime = file.mime_type.as_str();
+    if mime.starts_with("image/") {
+        return ContentPart::image(MediaSource::from_bytes(mime, bytes));
+    }
+    if mime.starts_with("audio/") {
+        return ContentPart::audio(MediaSource::from_bytes(mime, bytes));
+    }
+    let is_text =
+        mime.starts_with("text/") || matches!(mime, "application/json" | "application/xml");
+    if is_text && bytes.len() <= TELEGRAM_MAX_INLINE_TEXT_BYTES {
+        if let Ok(text) = std::str::from_utf8(bytes) {
+            return ContentPart::text(format!("[File: {}]\n{text}", file.label()));
+        }
+    }
+    ContentPart::text(format!(
+        "[File attached: {} ({mime}, {} bytes) — content not readable by the model]",
+        file.label(),
+        bytes.len()
+    ))
+}
+
 /// Telegram channel — long-polls the Bot API for updates
 pub struct TelegramChannel {
     bot_token: String,
@@ -844,23 +952,24 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         }
     }
 
-    fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
-        let message = update.get("message")?;
-
-        let text = message.get("text").and_then(serde_json::Value::as_str)?;
+    /// Numeric id of a Telegram `from` user. Unlike the username it never
+    /// changes, so tenant routing keys on it.
+    fn sender_user_id(from: Option<&serde_json::Value>) -> Option<String> {
+        from.and_then(|from| from.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())
+    }
 
-        let username = message
-            .get("from")
+    /// Identity of a Telegram `from` user (username, else numeric id), or
+    /// `None` if that user is not allowed to talk to the bot.
+    fn allowed_sender_identity(&self, from: Option<&serde_json::Value>) -> Option<String> {
+        let username = from
             .and_then(|from| from.get("username"))
             .and_then(serde_json::Value::as_str)
             .unwrap_or("unknown")
             .to_string();
 
-        let user_id = message
-            .get("from")
-            .and_then(|from| from.get("id"))
-            .and_then(serde_json::Value::as_i64)
-            .map(|id| id.to_string());
+        let user_id = Self::sender_user_id(from);
 
         let sender_identity = if username == "unknown" {
             user_id.clone().unwrap_or_else(|| "unknown".to_string())
@@ -876,6 +985,72 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         if !self.is_any_user_allowed(identities.iter().copied()) {
             return None;
         }
+        Some(sender_identity)
+    }
+
+    /// Turn an inline-keyboard button press into a message whose content is
+    /// the button's callback data.
+    fn parse_callback_query(&self, query: &serde_json::Value) -> Option<ChannelMessage> {
+        let data = query.get("data").and_then(serde_json::Value::as_str)?;
+        let sender_identity = self.allowed_sender_identity(query.get("from"))?;
+
+        let message = query.get("message")?;
+        let chat_id = message
+            .get("chat")
+            .and_then(|chat| chat.get("id"))
+            .and_then(serde_json::Value::as_i64)
+            .map(|id| id.to_string())?;
+        let query_id = query
+            .get("id")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or_default();
+
+        Some(ChannelMessage {
+            id: format!("telegram_{chat_id}_callback_{query_id}"),
+            sender: sender_identity,
+            sender_id: Self::sender_user_id(query.get("from")),
+            reply_target: chat_id,
+            content: data.to_string(),
+            channel: "telegram".to_string(),
+            attachments: Vec::new(),
+            timestamp: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs(),
+        })
+    }
+
+    /// Stop the loading spinner on a pressed inline-keyboard button.
+    async fn answer_callback_query(&self, query: &serde_json::Value) {
+        let Some(query_id) = query.get("id").and_then(serde_json::Value::as_str) else {
+            return;
+        };
+        let body = serde_json::json!({ "callback_query_id": query_id });
+        if let Err(e) = self
+            .client
+            .post(self.api_url("answerCallbackQuery"))
+            .json(&body)
+            .send()
+            .await
+        {
+            tracing::debug!("Telegram answerCallbackQuery failed: {e}");
+        }
+    }
+
+    fn parse_update_message(&self, update: &serde_json::Value) -> Option<ChannelMessage> {
+        let message = update.get("message")?;
+
+        // Photos and documents carry their text in `caption`.
+        let text = message
+            .get("text")
+            .or_else(|| message.get("caption"))
+            .and_then(serde_json::Value::as_str);
+        if text.is_none() && inbound_files(message).is_empty() {
+            return None;
+        }
+        let text = text.unwrap_or_default();
+
+        let sender_identity = self.allowed_sender_identity(message.get("from"))?;
 
         let chat_id = message
             .get("chat")
@@ -891,9 +1066,11 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Some(ChannelMessage {
             id: format!("telegram_{chat_id}_{message_id}"),
             sender: sender_identity,
+            sender_id: Self::sender_user_id(message.get("from")),
             reply_target: chat_id,
             content: text.to_string(),
             channel: "telegram".to_string(),
+            attachments: Vec::new(),
             timestamp: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
                 .unwrap_or_default()
@@ -901,6 +1078,72 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         })
     }
 
+    /// Download a file by id via `getFile`.
+    ///
+    /// The download URL embeds the bot token, so bytes are fetched here and
+    /// never handed to the model as a URL.
+    async fn download_file(&self, file_id: &str) -> anyhow::Result<Vec<u8>> {
+        let resp = self
+            .client
+            .post(self.api_url("getFile"))
+            .json(&serde_json::json!({ "file_id": file_id }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let err = resp.text().await?;
+            anyhow::bail!("Telegram getFile failed: {err}");
+        }
+
+        let data: serde_json::Value = resp.json().await?;
+        let file_path = data
+            .get("result")
+            .and_then(|result| result.get("file_path"))
+            .and_then(serde_json::Value::as_str)
+            .context("Telegram getFile returned no file_path")?;
+
+        let url = format!(
+            "https://api.telegram.org/file/bot{}/{file_path}",
+            self.bot_token
+        );
+        let resp = self.client.get(&url).send().await?;
+        if !resp.status().is_success() {
+            anyhow::bail!("Telegram file download failed: {}", resp.status());
+        }
+        Ok(resp.bytes().await?.to_vec())
+    }
+
+    /// Download the photos/documents of an update into content parts.
+    async fn download_attachments(&self, update: &serde_json::Value) -> Vec<ContentPart> {
+        let Some(message) = update.get("message") else {
+            return Vec::new();
+        };
+
+        let mut parts = Vec::new();
+        for file in inbound_files(message) {
+            if file
+                .file_size
+                .is_some_and(|size| size > TELEGRAM_MAX_DOWNLOAD_BYTES)
+            {
+                parts.push(ContentPart::text(format!(
+                    "[File attached: {} — too large to download]",
+                    file.label()
+                )));
+                continue;
+            }
+            match self.download_file(&file.file_id).await {
+                Ok(bytes) => parts.push(inbound_file_part(&file, &bytes)),
+                Err(e) => {
+                    tracing::warn!("Telegram: failed to download {}: {e}", file.label());
+                    parts.push(ContentPart::text(format!(
+                        "[File attached: {} — download failed]",
+                        file.label()
+                    )));
+                }
+            }
+        }
+        parts
+    }
+
     async fn send_text_chunks(&self, message: &str, chat_id: &str) -> anyhow::Result<()> {
         let chunks = split_message_for_telegram(message);
 
@@ -975,6 +1218,64 @@ Allowlist Telegram username (without '@') or numeric user ID.",
         Ok(())
     }
 
+    /// Edit the text of a sent message. "Message is not modified" counts as
+    /// success, since repeated draft edits can legitimately be no-ops.
+    async fn edit_message_text(
+        &self,
+        chat_id: &str,
+        message_id: &str,
+        text: &str,
+        parse_mode: Option<&str>,
+    ) -> anyhow::Result<()> {
+        let mut body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id.parse::<i64>().context("Invalid Telegram message id")?,
+            "text": text,
+        });
+        if let Some(mode) = parse_mode {
+            body["parse_mode"] = serde_json::Value::String(mode.to_string());
+        }
+
+        let resp = self
+            .client
+            .post(self.api_url("editMessageText"))
+            .json(&body)
+            .send()
+            .await?;
+
+        if resp.status().is_success() {
+            return Ok(());
+        }
+
+        let status = resp.status();
+        let err = resp.text().await.unwrap_or_default();
+        if err.contains("message is not modified") {
+            return Ok(());
+        }
+        anyhow::bail!("Telegram editMessageText failed ({status}): {err}");
+    }
+
+    async fn delete_message(&self, chat_id: &str, message_id: &str) -> anyhow::Result<()> {
+        let body = serde_json::json!({
+            "chat_id": chat_id,
+            "message_id": message_id.parse::<i64>().context("Invalid Telegram message id")?,
+        });
+
+        let resp = self
+            .client
+            .post(self.api_url("deleteMessage"))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram deleteMessage failed: {err}");
+        }
+
+        Ok(())
+    }
+
     async fn send_media_by_url(
         &self,
         method: &str,
@@ -1540,7 +1841,7 @@ impl Channel for TelegramChannel {
             let body = serde_json::json!({
                 "offset": offset,
                 "timeout": 30,
-                "allowed_updates": ["message"]
+                "allowed_updates": ["message", "callback_query"]
             });
 
             let resp = match self.client.post(&url).json(&body).send().await {
@@ -1598,10 +1899,21 @@ Ensure only one `zeroclaw` process is using this bot token."
                         offset = uid + 1;
                     }
 
-                    let Some(msg) = self.parse_update_message(update) else {
+                    if let Some(query) = update.get("callback_query") {
+                        self.answer_callback_query(query).await;
+                        if let Some(msg) = self.parse_callback_query(query) {
+                            if tx.send(msg).await.is_err() {
+                                return Ok(());
+                            }
+                        }
+                        continue;
+                    }
+
+                    let Some(mut msg) = self.parse_update_message(update) else {
                         self.handle_unauthorized_message(update).await;
                         continue;
                     };
+                    msg.attachments = self.download_attachments(update).await;
                     // Send "typing" indicator immediately when we receive a message
                     let typing_body = serde_json::json!({
                         "chat_id": &msg.reply_target,
@@ -1622,6 +1934,112 @@ Ensure only one `zeroclaw` process is using this bot token."
         }
     }
 
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        approval_id: &str,
+        prompt: &str,
+    ) -> anyhow::Result<()> {
+        let keyboard = InlineKeyboardMarkup::new(vec![vec![
+            InlineKeyboardButton::callback(
+                "✅ Yes",
+                approval_callback_data(approval_id, ApprovalResponse::Yes),
+            ),
+            InlineKeyboardButton::callback(
+                "❌ No",
+                approval_callback_data(approval_id, ApprovalResponse::No),
+            ),
+            InlineKeyboardButton::callback(
+                "♾️ Always",
+                approval_callback_data(approval_id, ApprovalResponse::Always),
+            ),
+        ]]);
+        self.send_with_inline_keyboard(recipient, prompt, &keyboard)
+            .await
+    }
+
+    fn supports_draft_updates(&self) -> bool {
+        true
+    }
+
+    async fn send_draft(&self, message: &SendMessage) -> anyhow::Result<Option<String>> {
+        // Drafts are sent without parse_mode: half-streamed Markdown is
+        // usually unbalanced and would be rejected.
+        let body = serde_json::json!({
+            "chat_id": message.recipient,
+            "text": truncate_draft_for_telegram(&message.content),
+        });
+
+        let resp = self
+            .client
+            .post(self.api_url("sendMessage"))
+            .json(&body)
+            .send()
+            .await?;
+
+        if !resp.status().is_success() {
+            let err = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Telegram sendMessage (draft) failed: {err}");
+        }
+
+        let data: serde_json::Value = resp.json().await?;
+        let message_id = data
+            .get("result")
+            .and_then(|result| result.get("message_id"))
+            .and_then(serde_json::Value::as_i64)
+            .context("Telegram sendMessage response is missing message_id")?;
+
+        Ok(Some(message_id.to_string()))
+    }
+
+    async fn update_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.edit_message_text(
+            recipient,
+            message_id,
+            truncate_draft_for_telegram(text),
+            None,
+        )
+        .await
+    }
+
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        let content = strip_tool_call_tags(text);
+        let (_, attachments) = parse_attachment_markers(&content);
+
+        // Attachments and multi-part replies can't be expressed as an edit:
+        // drop the draft and deliver the reply the normal way.
+        if content.is_empty()
+            || content.len() > TELEGRAM_MAX_MESSAGE_LENGTH
+            || !attachments.is_empty()
+            || parse_path_only_attachment(&content).is_some()
+        {
+            if let Err(e) = self.delete_message(recipient, message_id).await {
+                tracing::debug!("Telegram: failed to delete draft {message_id}: {e}");
+            }
+            return self.send(&SendMessage::new(text, recipient)).await;
+        }
+
+        if let Err(e) = self
+            .edit_message_text(recipient, message_id, &content, Some("Markdown"))
+            .await
+        {
+            tracing::warn!("{e}; retrying without parse_mode");
+            self.edit_message_text(recipient, message_id, &content, None)
+                .await?;
+        }
+        Ok(())
+    }
+
     async fn health_check(&self) -> bool {
         let timeout_duration = Duration::from_secs(5);
 
@@ -1835,6 +2253,7 @@ mod tests {
             .expect("message should parse");
 
         assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.sender_id.as_deref(), Some("555"));
         assert_eq!(msg.reply_target, "-100200300");
         assert_eq!(msg.content, "hello");
         assert_eq!(msg.id, "telegram_-100200300_33");
@@ -1865,6 +2284,121 @@ mod tests {
         assert_eq!(msg.reply_target, "12345");
     }
 
+    #[test]
+    fn parse_update_message_accepts_photo_with_caption() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()]);
+        let update = serde_json::json!({
+            "update_id": 3,
+            "message": {
+                "message_id": 10,
+                "caption": "what is this?",
+                "photo": [
+                    { "file_id": "small", "file_size": 100 },
+                    { "file_id": "large", "file_size": 5000 }
+                ],
+                "from": { "id": 555, "username": "alice" },
+                "chat": { "id": 12345 }
+            }
+        });
+
+        let msg = ch
+            .parse_update_message(&update)
+            .expect("photo message should parse");
+        assert_eq!(msg.content, "what is this?");
+
+        let files = inbound_files(&update["message"]);
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].file_id, "large");
+        assert_eq!(files[0].mime_type, "image/jpeg");
+    }
+
+    #[test]
+    fn parse_update_message_drops_messages_without_text_or_files() {
+        let ch = TelegramChannel::new("token".into(), vec!["*".into()]);
+        let update = serde_json::json!({
+            "update_id": 4,
+            "message": {
+                "message_id": 11,
+                "sticker": { "emoji": "👍" },
+                "from": { "id": 555, "username": "alice" },
+                "chat": { "id": 12345 }
+            }
+        });
+
+        assert!(ch.parse_update_message(&update).is_none());
+    }
+
+    #[test]
+    fn parse_callback_query_uses_button_data_as_content() {
+        let ch = TelegramChannel::new("token".into(), vec!["alice".into()]);
+        let query = serde_json::json!({
+            "id": "cb-1",
+            "from": { "id": 555, "username": "alice" },
+            "message": { "message_id": 12, "chat": { "id": -100_200 } },
+            "data": "approval:abc:yes"
+        });
+
+        let msg = ch.parse_callback_query(&query).unwrap();
+        assert_eq!(msg.sender, "alice");
+        assert_eq!(msg.reply_target, "-100200");
+        assert_eq!(msg.content, "approval:abc:yes");
+        assert_eq!(msg.channel, "telegram");
+
+        let stranger = serde_json::json!({
+            "id": "cb-2",
+            "from": { "id": 777, "username": "mallory" },
+            "message": { "message_id": 12, "chat": { "id": -100_200 } },
+            "data": "approval:abc:yes"
+        });
+        assert!(ch.parse_callback_query(&stranger).is_none());
+    }
+
+    #[test]
+    fn inbound_files_reads_documents_and_voice() {
+        let message = serde_json::json!({
+            "document": {
+                "file_id": "doc",
+                "file_name": "notes.txt",
+                "mime_type": "text/plain",
+                "file_size": 12
+            },
+            "voice": { "file_id": "voice" }
+        });
+
+        let files = inbound_files(&message);
+        assert_eq!(files.len(), 2);
+        assert_eq!(files[0].file_name.as_deref(), Some("notes.txt"));
+        assert_eq!(files[1].mime_type, "audio/ogg");
+    }
+
+    #[test]
+    fn inbound_file_part_maps_mime_types() {
+        let file = |mime: &str| InboundFile {
+            file_id: "f".into(),
+            mime_type: mime.into(),
+            file_name: Some("file".into()),
+            file_size: None,
+        };
+
+        assert!(matches!(
+            inbound_file_part(&file("image/png"), b"png"),
+            ContentPart::Image { .. }
+        ));
+        assert!(matches!(
+            inbound_file_part(&file("audio/ogg"), b"ogg"),
+            ContentPart::Audio { .. }
+        ));
+        assert_eq!(
+            inbound_file_part(&file("text/plain"), b"hello"),
+            ContentPart::text("[File: file]\nhello")
+        );
+        let ContentPart::Text { text } = inbound_file_part(&file("application/pdf"), b"%PDF")
+        else {
+            panic!("expected text note");
+        };
+        assert!(text.contains("application/pdf"));
+    }
+
     // ── File sending API URL tests ──────────────────────────────────
 
     #[test]
@@ -2105,6 +2639,22 @@ mod tests {
         }
     }
 
+    #[test]
+    fn telegram_draft_truncation_respects_limit_and_char_boundaries() {
+        assert_eq!(truncate_draft_for_telegram("short draft"), "short draft");
+
+        let msg = format!("{}é", "x".repeat(TELEGRAM_MAX_MESSAGE_LENGTH - 1));
+        let truncated = truncate_draft_for_telegram(&msg);
+        assert_eq!(truncated.len(), TELEGRAM_MAX_MESSAGE_LENGTH - 1);
+        assert!(truncated.chars().all(|c| c == 'x'));
+    }
+
+    #[test]
+    fn telegram_supports_draft_updates() {
+        let ch = TelegramChannel::new("fake-token".into(), vec!["*".into()]);
+        assert!(ch.supports_draft_updates());
+    }
+
     // ── Caption handling tests ──────────────────────────────────────
 
     #[tokio::test]
@@ -1,3 +1,4 @@
+use crate::providers::ContentPart;
 use async_trait::async_trait;
 
 /// A message received from or sent to a channel
@@ -5,9 +6,15 @@ use async_trait::async_trait;
 pub struct ChannelMessage {
     pub id: String,
     pub sender: String,
+    /// Stable platform account id of the sender when `sender` is a display
+    /// handle that can change or be re-claimed (Telegram's numeric user id).
+    pub sender_id: Option<String>,
     pub reply_target: String,
     pub content: String,
     pub channel: String,
+    /// Images, audio and documents that arrived with the message.
+    /// `content` holds the caption or text, if any.
+    pub attachments: Vec<ContentPart>,
     pub timestamp: u64,
 }
 
@@ -70,6 +77,55 @@ pub trait Channel: Send + Sync {
     async fn stop_typing(&self, _recipient: &str) -> anyhow::Result<()> {
         Ok(())
     }
+
+    /// Whether replies can be posted early as a draft and edited in place
+    /// while the model is still generating them.
+    fn supports_draft_updates(&self) -> bool {
+        false
+    }
+
+    /// Post the first version of a draft reply.
+    /// Returns the platform message id used for later edits, or `None` if
+    /// drafts are not supported.
+    async fn send_draft(&self, _message: &SendMessage) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Replace the text of a draft posted with `send_draft`.
+    async fn update_draft(
+        &self,
+        _recipient: &str,
+        _message_id: &str,
+        _text: &str,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    /// Turn a draft into the finished reply.
+    /// Defaults to a last `update_draft`; channels override this when the
+    /// final message needs more (formatting, attachments, splitting).
+    async fn finalize_draft(
+        &self,
+        recipient: &str,
+        message_id: &str,
+        text: &str,
+    ) -> anyhow::Result<()> {
+        self.update_draft(recipient, message_id, text).await
+    }
+
+    /// Ask `recipient` to approve a tool call.
+    /// `approval_id` identifies the request in button payloads (see
+    /// `channels::approval`). The default posts plain text and relies on a
+    /// `yes` / `no` / `always` reply.
+    async fn send_approval_prompt(
+        &self,
+        recipient: &str,
+        _approval_id: &str,
+        prompt: &str,
+    ) -> anyhow::Result<()> {
+        let text = format!("{prompt}\n\nReply \"yes\", \"no\", or \"always\".");
+        self.send(&SendMessage::new(text, recipient)).await
+    }
 }
 
 #[cfg(test)]
@@ -95,9 +151,11 @@ mod tests {
             tx.send(ChannelMessage {
                 id: "1".into(),
                 sender: "tester".into(),
+                sender_id: None,
                 reply_target: "tester".into(),
                 content: "hello".into(),
                 channel: "dummy".into(),
+                attachments: Vec::new(),
                 timestamp: 123,
             })
             .await
@@ -110,9 +168,11 @@ mod tests {
         let message = ChannelMessage {
             id: "42".into(),
             sender: "alice".into(),
+            sender_id: None,
             reply_target: "alice".into(),
             content: "ping".into(),
             channel: "dummy".into(),
+            attachments: Vec::new(),
             timestamp: 999,
         };
 
@@ -138,6 +198,22 @@ mod tests {
             .is_ok());
     }
 
+    #[tokio::test]
+    async fn default_draft_methods_are_noops() {
+        let channel = DummyChannel;
+
+        assert!(!channel.supports_draft_updates());
+        assert_eq!(
+            channel
+                .send_draft(&SendMessage::new("partial", "bob"))
+                .await
+                .unwrap(),
+            None
+        );
+        assert!(channel.update_draft("bob", "1", "more").await.is_ok());
+        assert!(channel.finalize_draft("bob", "1", "done").await.is_ok());
+    }
+
     #[tokio::test]
     async fn listen_sends_message_to_channel() {
         let channel = DummyChannel;
@@ -119,10 +119,12 @@ impl WhatsAppChannel {
 
                     messages.push(ChannelMessage {
                         id: Uuid::new_v4().to_string(),
+                        sender_id: None,
                         reply_target: normalized_from.clone(),
                         sender: normalized_from,
                         content,
                         channel: "whatsapp".to_string(),
+                        attachments: Vec::new(),
                         timestamp,
                     });
                 }
@@ -24,6 +24,10 @@ pub struct Config {
     pub default_provider: Option<String>,
     pub default_model: Option<String>,
     pub default_temperature: f64,
+    /// Whether `default_model` accepts images. Only OpenAI-compatible and
+    /// Ollama providers read this; others send images when their model can.
+    #[serde(default)]
+    pub default_model_vision: bool,
 
     #[serde(default)]
     pub observability: ObservabilityConfig,
@@ -201,10 +205,23 @@ pub struct AgentConfig {
     pub max_tool_iterations: usize,
     #[serde(default = "default_agent_max_history_messages")]
     pub max_history_messages: usize,
+    /// Run independent tool calls from one model response concurrently.
+    /// Tools that opt out (shell, file writes, git, hardware) still run alone.
     #[serde(default)]
     pub parallel_tools: bool,
+    /// Upper bound on tool calls executing at once when `parallel_tools` is on.
+    #[serde(default = "default_agent_max_parallel_tools")]
+    pub max_parallel_tools: usize,
     #[serde(default = "default_agent_tool_dispatcher")]
     pub tool_dispatcher: String,
+    /// Print model reasoning (extended thinking) in the CLI before the
+    /// answer. Reasoning is never sent to channels.
+    #[serde(default)]
+    pub show_reasoning: bool,
+}
+
+fn default_agent_max_parallel_tools() -> usize {
+    4
 }
 
 fn default_agent_max_tool_iterations() -> usize {
@@ -226,7 +243,20 @@ impl Default for AgentConfig {
             max_tool_iterations: default_agent_max_tool_iterations(),
             max_history_messages: default_agent_max_history_messages(),
             parallel_tools: false,
+            max_parallel_tools: default_agent_max_parallel_tools(),
             tool_dispatcher: default_agent_tool_dispatcher(),
+            show_reasoning: false,
+        }
+    }
+}
+
+impl AgentConfig {
+    /// How many tool calls may execute at once (1 = sequential).
+    pub fn tool_concurrency(&self) -> usize {
+        if self.parallel_tools {
+            self.max_parallel_tools.max(1)
+        } else {
+            1
         }
     }
 }
@@ -298,6 +328,24 @@ pub struct ModelPricing {
     /// Output price per 1M tokens
     #[serde(default)]
     pub output: f64,
+
+    /// Price per 1M input tokens read from the prompt cache (default: `input`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_read: Option<f64>,
+
+    /// Price per 1M input tokens written to the prompt cache (default: `input`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_write: Option<f64>,
+}
+
+impl ModelPricing {
+    pub fn cache_read_price(&self) -> f64 {
+        self.cache_read.unwrap_or(self.input)
+    }
+
+    pub fn cache_write_price(&self) -> f64 {
+        self.cache_write.unwrap_or(self.input)
+    }
 }
 
 fn default_daily_limit() -> f64 {
@@ -329,12 +377,14 @@ impl Default for CostConfig {
 fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
     let mut prices = std::collections::HashMap::new();
 
-    // Anthropic models
+    // Anthropic models (cache writes cost 1.25x input, reads 0.1x)
     prices.insert(
         "anthropic/claude-sonnet-4-20250514".into(),
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cache_read: Some(0.30),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -342,6 +392,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 75.0,
+            cache_read: Some(1.50),
+            cache_write: Some(18.75),
         },
     );
     prices.insert(
@@ -349,6 +401,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 3.0,
             output: 15.0,
+            cache_read: Some(0.30),
+            cache_write: Some(3.75),
         },
     );
     prices.insert(
@@ -356,15 +410,19 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.25,
             output: 1.25,
+            cache_read: Some(0.03),
+            cache_write: Some(0.30),
         },
     );
 
-    // OpenAI models
+    // OpenAI models (cached input is half price, no write surcharge)
     prices.insert(
         "openai/gpt-4o".into(),
         ModelPricing {
             input: 5.0,
             output: 15.0,
+            cache_read: Some(2.50),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -372,6 +430,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.15,
             output: 0.60,
+            cache_read: Some(0.075),
+            cache_write: None,
         },
     );
     prices.insert(
@@ -379,6 +439,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 15.0,
             output: 60.0,
+            cache_read: Some(7.50),
+            cache_write: None,
         },
     );
 
@@ -388,6 +450,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 0.10,
             output: 0.40,
+            cache_read: None,
+            cache_write: None,
         },
     );
     prices.insert(
@@ -395,6 +459,8 @@ fn get_default_pricing() -> std::collections::HashMap<String, ModelPricing> {
         ModelPricing {
             input: 1.25,
             output: 5.0,
+            cache_read: None,
+            cache_write: None,
         },
     );
 
@@ -714,7 +780,7 @@ pub struct MemoryConfig {
     /// For sqlite backend: prune conversation rows older than this many days
     #[serde(default = "default_conversation_retention_days")]
     pub conversation_retention_days: u32,
-    /// Embedding provider: "none" | "openai" | "custom:URL"
+    /// Embedding provider: "none" | "openai" | "custom:URL" | "ollama" | "ollama:URL" | "hash" (offline)
     #[serde(default = "default_embedding_provider")]
     pub embedding_provider: String,
     /// Embedding model name (e.g. "text-embedding-3-small")
@@ -736,6 +802,50 @@ pub struct MemoryConfig {
     #[serde(default = "default_chunk_size")]
     pub chunk_max_tokens: usize,
 
+    // ── Vector Index (approximate recall for large stores) ─────
+    /// Use an IVF index for vector recall on the sqlite backend
+    #[serde(default = "default_true")]
+    pub vector_index_enabled: bool,
+    /// Below this many embedded memories, recall scans every vector exactly
+    #[serde(default = "default_vector_index_min_rows")]
+    pub vector_index_min_rows: usize,
+    /// IVF lists scanned per query (higher = better recall, slower)
+    #[serde(default = "default_vector_index_probes")]
+    pub vector_index_probes: usize,
+
+    // ── Recall Ranking (recency, importance, access, diversity) ─
+    /// Re-rank recall results beyond plain vector/keyword relevance
+    #[serde(default = "default_true")]
+    pub ranking_enabled: bool,
+    /// Weight of normalized hybrid-search relevance
+    #[serde(default = "default_ranking_relevance_weight")]
+    pub ranking_relevance_weight: f64,
+    /// Weight of recency decay (see the `ranking_*_half_life_days` settings)
+    #[serde(default = "default_ranking_recency_weight")]
+    pub ranking_recency_weight: f64,
+    /// Weight of `importance` metadata (unset counts as 0.5)
+    #[serde(default = "default_ranking_importance_weight")]
+    pub ranking_importance_weight: f64,
+    /// Weight of how often a memory has been recalled before
+    #[serde(default = "default_ranking_access_weight")]
+    pub ranking_access_weight: f64,
+    /// Maximal-marginal-relevance trade-off: 1.0 = no diversification,
+    /// lower values push near-duplicate results down
+    #[serde(default = "default_ranking_mmr_lambda")]
+    pub ranking_mmr_lambda: f64,
+    /// Days for a `core` memory's recency to halve (0 = never decays)
+    #[serde(default)]
+    pub ranking_core_half_life_days: f64,
+    /// Days for a `daily` memory's recency to halve
+    #[serde(default = "default_ranking_daily_half_life")]
+    pub ranking_daily_half_life_days: f64,
+    /// Days for a `conversation` memory's recency to halve
+    #[serde(default = "default_ranking_conversation_half_life")]
+    pub ranking_conversation_half_life_days: f64,
+    /// Days for a custom-category memory's recency to halve
+    #[serde(default = "default_ranking_custom_half_life")]
+    pub ranking_custom_half_life_days: f64,
+
     // ── Response Cache (saves tokens on repeated prompts) ──────
     /// Enable LLM response caching to avoid paying for duplicate prompts
     #[serde(default)]
@@ -746,6 +856,38 @@ pub struct MemoryConfig {
     /// Max number of cached responses before LRU eviction (default: 5000)
     #[serde(default = "default_response_cache_max")]
     pub response_cache_max_entries: usize,
+    /// Only cache replies requested at or below this temperature (default: 0.2)
+    #[serde(default = "default_response_cache_max_temperature")]
+    pub response_cache_max_temperature: f64,
+
+    // ── Consolidation (merge near-duplicate memories) ──────────
+    /// Merge clusters of similar conversation/daily memories into core facts
+    /// on the hygiene cadence
+    #[serde(default)]
+    pub consolidation_enabled: bool,
+    /// Similarity (cosine for embeddings, token overlap otherwise) at which
+    /// two memories belong to the same cluster
+    #[serde(default = "default_consolidation_similarity")]
+    pub consolidation_similarity: f64,
+    /// Approximate LLM tokens one consolidation pass may spend
+    #[serde(default = "default_consolidation_token_budget")]
+    pub consolidation_token_budget: usize,
+
+    // ── Encryption at rest ─────────────────────────────────────
+    /// Encrypt memory content and embeddings with a key derived from
+    /// `.secret_key` (keyword search uses a blind index). Migrate existing
+    /// stores with `zeroclaw memory encrypt` / `zeroclaw memory decrypt`.
+    #[serde(default)]
+    pub encryption_enabled: bool,
+
+    // ── Knowledge Base (workspace document ingestion) ──────────
+    /// Files or directories the daemon keeps ingested into the `docs` category
+    /// (relative paths resolve against the workspace)
+    #[serde(default)]
+    pub ingest_paths: Vec<String>,
+    /// Seconds between daemon re-scans of `ingest_paths` (0 = no watching)
+    #[serde(default = "default_ingest_interval")]
+    pub ingest_interval_secs: u64,
 
     // ── Memory Snapshot (soul backup to Markdown) ─────────────
     /// Enable periodic export of core memories to MEMORY_SNAPSHOT.md
@@ -792,12 +934,54 @@ fn default_cache_size() -> usize {
 fn default_chunk_size() -> usize {
     512
 }
+fn default_vector_index_min_rows() -> usize {
+    2_000
+}
+fn default_vector_index_probes() -> usize {
+    8
+}
+fn default_ranking_relevance_weight() -> f64 {
+    1.0
+}
+fn default_ranking_recency_weight() -> f64 {
+    0.2
+}
+fn default_ranking_importance_weight() -> f64 {
+    0.15
+}
+fn default_ranking_access_weight() -> f64 {
+    0.1
+}
+fn default_ranking_mmr_lambda() -> f64 {
+    0.8
+}
+fn default_ranking_daily_half_life() -> f64 {
+    7.0
+}
+fn default_ranking_conversation_half_life() -> f64 {
+    3.0
+}
+fn default_ranking_custom_half_life() -> f64 {
+    30.0
+}
 fn default_response_cache_ttl() -> u32 {
     60
 }
 fn default_response_cache_max() -> usize {
     5_000
 }
+fn default_response_cache_max_temperature() -> f64 {
+    0.2
+}
+fn default_consolidation_similarity() -> f64 {
+    0.85
+}
+fn default_consolidation_token_budget() -> usize {
+    20_000
+}
+fn default_ingest_interval() -> u64 {
+    300
+}
 
 impl Default for MemoryConfig {
     fn default() -> Self {
@@ -815,9 +999,29 @@ impl Default for MemoryConfig {
             keyword_weight: default_keyword_weight(),
             embedding_cache_size: default_cache_size(),
             chunk_max_tokens: default_chunk_size(),
+            vector_index_enabled: true,
+            vector_index_min_rows: default_vector_index_min_rows(),
+            vector_index_probes: default_vector_index_probes(),
+            ranking_enabled: true,
+            ranking_relevance_weight: default_ranking_relevance_weight(),
+            ranking_recency_weight: default_ranking_recency_weight(),
+            ranking_importance_weight: default_ranking_importance_weight(),
+            ranking_access_weight: default_ranking_access_weight(),
+            ranking_mmr_lambda: default_ranking_mmr_lambda(),
+            ranking_core_half_life_days: 0.0,
+            ranking_daily_half_life_days: default_ranking_daily_half_life(),
+            ranking_conversation_half_life_days: default_ranking_conversation_half_life(),
+            ranking_custom_half_life_days: default_ranking_custom_half_life(),
             response_cache_enabled: false,
             response_cache_ttl_minutes: default_response_cache_ttl(),
             response_cache_max_entries: default_response_cache_max(),
+            response_cache_max_temperature: default_response_cache_max_temperature(),
+            consolidation_enabled: false,
+            consolidation_similarity: default_consolidation_similarity(),
+            consolidation_token_budget: default_consolidation_token_budget(),
+            encryption_enabled: false,
+            ingest_paths: Vec::new(),
+            ingest_interval_secs: default_ingest_interval(),
             snapshot_enabled: false,
             snapshot_on_hygiene: false,
             auto_hydrate: true,
@@ -877,6 +1081,11 @@ pub struct AutonomyConfig {
     /// Tools that always require interactive approval, even after "Always".
     #[serde(default = "default_always_ask")]
     pub always_ask: Vec<String>,
+
+    /// How long to wait for an approval answer on a messaging channel before
+    /// the tool call is denied.
+    #[serde(default = "default_approval_timeout_secs")]
+    pub approval_timeout_secs: u64,
 }
 
 fn default_auto_approve() -> Vec<String> {
@@ -887,6 +1096,10 @@ fn default_always_ask() -> Vec<String> {
     vec![]
 }
 
+fn default_approval_timeout_secs() -> u64 {
+    120
+}
+
 impl Default for AutonomyConfig {
     fn default() -> Self {
         Self {
@@ -932,6 +1145,7 @@ impl Default for AutonomyConfig {
             block_high_risk_commands: true,
             auto_approve: default_auto_approve(),
             always_ask: default_always_ask(),
+            approval_timeout_secs: default_approval_timeout_secs(),
         }
     }
 }
@@ -1149,6 +1363,18 @@ impl Default for SchedulerConfig {
 /// hint = "fast"
 /// provider = "groq"
 /// model = "llama-3.3-70b-versatile"
+///
+/// [[model_routes]]
+/// hint = "see"
+/// provider = "ollama"
+/// model = "llama3.2-vision"
+/// vision = true
+///
+/// [[model_routes]]
+/// hint = "think"
+/// provider = "anthropic"
+/// model = "claude-sonnet-4-20250514"
+/// reasoning = { budget_tokens = 8000 }   # or { effort = "high" }
 /// ```
 ///
 /// Usage: pass `hint:reasoning` as the model parameter to route the request.
@@ -1163,6 +1389,13 @@ pub struct ModelRouteConfig {
     /// Optional API key override for this route's provider
     #[serde(default)]
     pub api_key: Option<String>,
+    /// Extended thinking for requests on this route (token budget or
+    /// effort level; providers without reasoning controls ignore it)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reasoning: Option<crate::providers::ReasoningOptions>,
+    /// Whether this route's model accepts images (see `default_model_vision`)
+    #[serde(default)]
+    pub vision: bool,
 }
 
 // ── Heartbeat ────────────────────────────────────────────────────
@@ -1171,6 +1404,13 @@ pub struct ModelRouteConfig {
 pub struct HeartbeatConfig {
     pub enabled: bool,
     pub interval_minutes: u32,
+    /// Channel that receives heartbeat task results (`telegram`, `discord`,
+    /// `slack`, `mattermost`). Results are only recorded when unset.
+    #[serde(default)]
+    pub channel: Option<String>,
+    /// Recipient on `channel` (chat or channel id).
+    #[serde(default)]
+    pub to: Option<String>,
 }
 
 impl Default for HeartbeatConfig {
@@ -1178,6 +1418,8 @@ impl Default for HeartbeatConfig {
         Self {
             enabled: false,
             interval_minutes: 30,
+            channel: None,
+            to: None,
         }
     }
 }
@@ -1290,6 +1532,12 @@ pub struct ChannelsConfig {
     pub lark: Option<LarkConfig>,
     pub dingtalk: Option<DingTalkConfig>,
     pub qq: Option<QQConfig>,
+    /// Per-sender conversation sessions (`[channels_config.sessions]`).
+    #[serde(default)]
+    pub sessions: ChannelSessionConfig,
+    /// Per-sender tenant isolation (`[channels_config.tenants]`).
+    #[serde(default)]
+    pub tenants: ChannelTenantConfig,
 }
 
 impl Default for ChannelsConfig {
@@ -1310,10 +1558,62 @@ impl Default for ChannelsConfig {
             lark: None,
             dingtalk: None,
             qq: None,
+            sessions: ChannelSessionConfig::default(),
+            tenants: ChannelTenantConfig::default(),
+        }
+    }
+}
+
+/// Conversation session settings for the channel runtime.
+///
+/// ```toml
+/// [channels_config.sessions]
+/// enabled = true
+/// idle_timeout_minutes = 720
+/// ```
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChannelSessionConfig {
+    /// Keep per-sender chat history across messages (default: true).
+    /// When false, every inbound message starts a fresh conversation.
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// Drop a session after this many minutes without messages (default: 720).
+    #[serde(default = "default_session_idle_timeout_minutes")]
+    pub idle_timeout_minutes: u32,
+}
+
+fn default_session_idle_timeout_minutes() -> u32 {
+    720
+}
+
+impl Default for ChannelSessionConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            idle_timeout_minutes: default_session_idle_timeout_minutes(),
         }
     }
 }
 
+/// Tenant routing for the channel runtime.
+///
+/// When enabled, each sender is resolved to a tenant under
+/// `workspace/tenants/{user_id}` (linked accounts via the auth database,
+/// otherwise a stable hash of channel and sender). Memory, conversation
+/// history, profile context and feature-gated tools are then per tenant
+/// instead of shared by everyone who talks to the bot.
+///
+/// ```toml
+/// [channels_config.tenants]
+/// enabled = true
+/// ```
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ChannelTenantConfig {
+    /// Isolate memory and history per sender (default: false)
+    #[serde(default)]
+    pub enabled: bool,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TelegramConfig {
     pub bot_token: String,
@@ -1678,6 +1978,7 @@ impl Default for Config {
             default_provider: Some("openrouter".to_string()),
             default_model: Some("anthropic/claude-sonnet-4".to_string()),
             default_temperature: 0.7,
+            default_model_vision: false,
             observability: ObservabilityConfig::default(),
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -2208,6 +2509,7 @@ mod tests {
         assert_eq!(a.max_cost_per_day_cents, 500);
         assert!(a.require_approval_for_medium_risk);
         assert!(a.block_high_risk_commands);
+        assert_eq!(a.approval_timeout_secs, 120);
     }
 
     #[test]
@@ -2227,6 +2529,8 @@ mod tests {
         let h = HeartbeatConfig::default();
         assert!(!h.enabled);
         assert_eq!(h.interval_minutes, 30);
+        assert!(h.channel.is_none());
+        assert!(h.to.is_none());
     }
 
     #[test]
@@ -2278,6 +2582,23 @@ default_temperature = 0.7
         assert!(c.cli);
         assert!(c.telegram.is_none());
         assert!(c.discord.is_none());
+        assert!(c.sessions.enabled);
+        assert_eq!(c.sessions.idle_timeout_minutes, 720);
+    }
+
+    #[test]
+    fn channels_config_sessions_parse_partial_section() {
+        let c: ChannelsConfig = toml::from_str(
+            r#"
+cli = true
+
+[sessions]
+enabled = false
+"#,
+        )
+        .unwrap();
+        assert!(!c.sessions.enabled);
+        assert_eq!(c.sessions.idle_timeout_minutes, 720);
     }
 
     // ── Serde round-trip ─────────────────────────────────────
@@ -2292,6 +2613,7 @@ default_temperature = 0.7
             default_provider: Some("openrouter".into()),
             default_model: Some("gpt-4o".into()),
             default_temperature: 0.5,
+            default_model_vision: false,
             observability: ObservabilityConfig {
                 backend: "log".into(),
                 ..ObservabilityConfig::default()
@@ -2307,6 +2629,7 @@ default_temperature = 0.7
                 block_high_risk_commands: true,
                 auto_approve: vec!["file_read".into()],
                 always_ask: vec![],
+                approval_timeout_secs: 60,
             },
             runtime: RuntimeConfig {
                 kind: "docker".into(),
@@ -2318,6 +2641,8 @@ default_temperature = 0.7
             heartbeat: HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 15,
+                channel: Some("telegram".into()),
+                to: Some("123456".into()),
             },
             cron: CronConfig::default(),
             channels_config: ChannelsConfig {
@@ -2339,6 +2664,8 @@ default_temperature = 0.7
                 lark: None,
                 dingtalk: None,
                 qq: None,
+                sessions: ChannelSessionConfig::default(),
+                tenants: ChannelTenantConfig::default(),
             },
             memory: MemoryConfig::default(),
             tunnel: TunnelConfig::default(),
@@ -2365,9 +2692,12 @@ default_temperature = 0.7
         assert_eq!(parsed.observability.backend, "log");
         assert_eq!(parsed.autonomy.level, AutonomyLevel::Full);
         assert!(!parsed.autonomy.workspace_only);
+        assert_eq!(parsed.autonomy.approval_timeout_secs, 60);
         assert_eq!(parsed.runtime.kind, "docker");
         assert!(parsed.heartbeat.enabled);
         assert_eq!(parsed.heartbeat.interval_minutes, 15);
+        assert_eq!(parsed.heartbeat.channel.as_deref(), Some("telegram"));
+        assert_eq!(parsed.heartbeat.to.as_deref(), Some("123456"));
         assert!(parsed.channels_config.telegram.is_some());
         assert_eq!(
             parsed.channels_config.telegram.unwrap().bot_token,
@@ -2403,6 +2733,8 @@ default_temperature = 0.7
         assert_eq!(cfg.max_tool_iterations, 10);
         assert_eq!(cfg.max_history_messages, 50);
         assert!(!cfg.parallel_tools);
+        assert_eq!(cfg.max_parallel_tools, 4);
+        assert_eq!(cfg.tool_concurrency(), 1);
         assert_eq!(cfg.tool_dispatcher, "auto");
     }
 
@@ -2415,6 +2747,7 @@ compact_context = true
 max_tool_iterations = 20
 max_history_messages = 80
 parallel_tools = true
+max_parallel_tools = 8
 tool_dispatcher = "xml"
 "#;
         let parsed: Config = toml::from_str(raw).unwrap();
@@ -2422,6 +2755,7 @@ tool_dispatcher = "xml"
         assert_eq!(parsed.agent.max_tool_iterations, 20);
         assert_eq!(parsed.agent.max_history_messages, 80);
         assert!(parsed.agent.parallel_tools);
+        assert_eq!(parsed.agent.tool_concurrency(), 8);
         assert_eq!(parsed.agent.tool_dispatcher, "xml");
     }
 
@@ -2440,6 +2774,7 @@ tool_dispatcher = "xml"
             default_provider: Some("openrouter".into()),
             default_model: Some("test-model".into()),
             default_temperature: 0.9,
+            default_model_vision: false,
             observability: ObservabilityConfig::default(),
             autonomy: AutonomyConfig::default(),
             runtime: RuntimeConfig::default(),
@@ -2755,6 +3090,8 @@ tool_dispatcher = "xml"
             lark: None,
             dingtalk: None,
             qq: None,
+            sessions: ChannelSessionConfig::default(),
+            tenants: ChannelTenantConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -2918,6 +3255,8 @@ channel_id = "C123"
             lark: None,
             dingtalk: None,
             qq: None,
+            sessions: ChannelSessionConfig::default(),
+            tenants: ChannelTenantConfig::default(),
         };
         let toml_str = toml::to_string_pretty(&c).unwrap();
         let parsed: ChannelsConfig = toml::from_str(&toml_str).unwrap();
@@ -0,0 +1,72 @@
+use super::CostTracker;
+use crate::config::Config;
+use anyhow::Result;
+use chrono::{Datelike, Utc};
+
+/// Print spend for today, this month, the last `days` days, and per model.
+pub fn run(config: &Config, days: u32) -> Result<()> {
+    let (tracker, tracking) = match CostTracker::from_config(config)? {
+        Some(tracker) => (tracker, true),
+        // Still read recorded history so past spend stays visible.
+        None => (
+            CostTracker::new(config.cost.clone(), &config.workspace_dir)?,
+            false,
+        ),
+    };
+
+    let summary = tracker.get_summary()?;
+    let limits = tracker.config();
+
+    println!("💰 ZeroClaw Cost Summary");
+    println!();
+    if tracking {
+        println!("  Tracking:    on");
+        println!(
+            "  Today:       ${:.4} / {}",
+            summary.daily_cost_usd,
+            format_limit(limits.daily_limit_usd)
+        );
+        println!(
+            "  This month:  ${:.4} / {}",
+            summary.monthly_cost_usd,
+            format_limit(limits.monthly_limit_usd)
+        );
+    } else {
+        println!("  Tracking:    off (enable [cost] or set autonomy.max_cost_per_day_cents)");
+        println!("  Today:       ${:.4}", summary.daily_cost_usd);
+        println!("  This month:  ${:.4}", summary.monthly_cost_usd);
+    }
+
+    if days > 0 {
+        println!();
+        println!("Last {days} days:");
+        for (date, cost) in tracker.get_daily_costs(days)? {
+            println!("  {date}  ${cost:.4}");
+        }
+    }
+
+    let now = Utc::now();
+    let by_model = tracker.get_monthly_model_stats(now.year(), now.month())?;
+    println!();
+    if by_model.is_empty() {
+        println!("No usage recorded this month.");
+    } else {
+        println!("By model (this month):");
+        for stats in by_model {
+            println!(
+                "  {:<45} ${:.4}  {} tokens  {} requests",
+                stats.model, stats.cost_usd, stats.total_tokens, stats.request_count
+            );
+        }
+    }
+
+    Ok(())
+}
+
+fn format_limit(limit_usd: f64) -> String {
+    if limit_usd.is_finite() {
+        format!("${limit_usd:.2}")
+    } else {
+        "unlimited".to_string()
+    }
+}
@@ -1,7 +1,9 @@
 use super::types::{BudgetCheck, CostRecord, CostSummary, ModelStats, TokenUsage, UsagePeriod};
-use crate::config::schema::CostConfig;
-use anyhow::{anyhow, Context, Result};
-use chrono::{Datelike, NaiveDate, Utc};
+use crate::config::schema::{CostConfig, ModelPricing};
+use crate::config::Config;
+use crate::providers::Usage;
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::{Datelike, Duration, NaiveDate, Utc};
 use parking_lot::{Mutex, MutexGuard};
 use std::collections::HashMap;
 use std::fs::{self, File, OpenOptions};
@@ -34,11 +36,113 @@ impl CostTracker {
         })
     }
 
+    /// Build the tracker the runtime should use, or `None` when neither
+    /// `[cost]` nor `autonomy.max_cost_per_day_cents` asks for tracking.
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        match effective_cost_config(&config.cost, config.autonomy.max_cost_per_day_cents) {
+            Some(cost) => Self::new(cost, &config.workspace_dir).map(Some),
+            None => Ok(None),
+        }
+    }
+
     /// Get the session ID.
     pub fn session_id(&self) -> &str {
         &self.session_id
     }
 
+    /// The limits and pricing this tracker enforces.
+    pub fn config(&self) -> &CostConfig {
+        &self.config
+    }
+
+    /// Look up pricing for a model, trying `provider/model`, the bare model
+    /// name, and finally any `vendor/model` entry with the same model name.
+    pub fn price_for(&self, provider: &str, model: &str) -> Option<&ModelPricing> {
+        let prices = &self.config.prices;
+        prices
+            .get(&format!("{provider}/{model}"))
+            .or_else(|| prices.get(model))
+            .or_else(|| {
+                prices
+                    .iter()
+                    .find(|(key, _)| key.split_once('/').is_some_and(|(_, name)| name == model))
+                    .map(|(_, pricing)| pricing)
+            })
+    }
+
+    /// Rough cost of sending `input_tokens` to a model, used for pre-call
+    /// budget checks before the real usage is known.
+    pub fn estimate_cost(&self, provider: &str, model: &str, input_tokens: u64) -> f64 {
+        let input_price = self.price_for(provider, model).map_or(0.0, |p| p.input);
+        TokenUsage::new(model, input_tokens, 0, input_price, 0.0).cost_usd
+    }
+
+    /// Price and record the usage a provider reported for one call.
+    pub fn record_provider_usage(
+        &self,
+        provider: &str,
+        model: &str,
+        usage: Usage,
+    ) -> Result<TokenUsage> {
+        let pricing = self.price_for(provider, model);
+        let (input_price, output_price) = pricing.map_or((0.0, 0.0), |p| (p.input, p.output));
+        let (cache_read_price, cache_write_price) = pricing.map_or((0.0, 0.0), |p| {
+            (p.cache_read_price(), p.cache_write_price())
+        });
+        let token_usage = TokenUsage::new(
+            format!("{provider}/{model}"),
+            usage.input_tokens,
+            usage.output_tokens,
+            input_price,
+            output_price,
+        )
+        .with_cache(
+            usage.cache_read_tokens,
+            usage.cache_write_tokens,
+            input_price,
+            cache_read_price,
+            cache_write_price,
+        );
+        self.record_usage(token_usage.clone())?;
+        Ok(token_usage)
+    }
+
+    /// Check the budget before a call: errors when a limit is exceeded
+    /// (unless `allow_override` is set) and logs a warning past `warn_at_percent`.
+    pub fn enforce_budget(&self, estimated_cost_usd: f64) -> Result<()> {
+        match self.check_budget(estimated_cost_usd)? {
+            BudgetCheck::Allowed => Ok(()),
+            BudgetCheck::Warning {
+                current_usd,
+                limit_usd,
+                period,
+            } => {
+                tracing::warn!(
+                    "Approaching {} budget: ${current_usd:.4} of ${limit_usd:.2} spent",
+                    period_label(period)
+                );
+                Ok(())
+            }
+            BudgetCheck::Exceeded {
+                current_usd,
+                limit_usd,
+                period,
+            } => {
+                if self.config.allow_override {
+                    tracing::warn!(
+                        "{} budget exceeded (${current_usd:.4} of ${limit_usd:.2}); continuing because cost.allow_override is set",
+                        period_label(period)
+                    );
+                    return Ok(());
+                }
+                bail!(
+                    "{} budget exceeded: ${current_usd:.4} of ${limit_usd:.2} spent. Raise the limit in [cost] or autonomy.max_cost_per_day_cents.",
+                    period_label(period)
+                )
+            }
+        }
+    }
+
     fn lock_storage(&self) -> MutexGuard<'_, CostStorage> {
         self.storage.lock()
     }
@@ -173,6 +277,82 @@ impl CostTracker {
         let storage = self.lock_storage();
         storage.get_cost_for_month(year, month)
     }
+
+    /// Cost per day for the last `days` days (oldest first, today last).
+    pub fn get_daily_costs(&self, days: u32) -> Result<Vec<(NaiveDate, f64)>> {
+        let today = Utc::now().date_naive();
+        let mut costs: Vec<(NaiveDate, f64)> = (0..i64::from(days))
+            .rev()
+            .map(|offset| (today - Duration::days(offset), 0.0))
+            .collect();
+        let Some(&(first_day, _)) = costs.first() else {
+            return Ok(costs);
+        };
+
+        let storage = self.lock_storage();
+        storage.for_each_record(|record| {
+            let date = record.usage.timestamp.naive_utc().date();
+            if date < first_day || date > today {
+                return;
+            }
+            #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+            let index = (date - first_day).num_days() as usize;
+            costs[index].1 += record.usage.cost_usd;
+        })?;
+
+        Ok(costs)
+    }
+
+    /// Per-model totals for a specific month, most expensive first.
+    pub fn get_monthly_model_stats(&self, year: i32, month: u32) -> Result<Vec<ModelStats>> {
+        let mut records = Vec::new();
+        {
+            let storage = self.lock_storage();
+            storage.for_each_record(|record| {
+                let timestamp = record.usage.timestamp.naive_utc();
+                if timestamp.year() == year && timestamp.month() == month {
+                    records.push(record);
+                }
+            })?;
+        }
+
+        let mut stats: Vec<ModelStats> =
+            build_session_model_stats(&records).into_values().collect();
+        stats.sort_by(|a, b| b.cost_usd.total_cmp(&a.cost_usd));
+        Ok(stats)
+    }
+}
+
+/// Merge `[cost]` with the autonomy daily cap.
+///
+/// `autonomy.max_cost_per_day_cents` tightens the daily limit when cost
+/// tracking is enabled, and turns on a daily-only budget when it is not.
+fn effective_cost_config(cost: &CostConfig, max_cost_per_day_cents: u32) -> Option<CostConfig> {
+    let autonomy_cap =
+        (max_cost_per_day_cents > 0).then(|| f64::from(max_cost_per_day_cents) / 100.0);
+
+    if cost.enabled {
+        let mut effective = cost.clone();
+        if let Some(cap) = autonomy_cap {
+            effective.daily_limit_usd = effective.daily_limit_usd.min(cap);
+        }
+        return Some(effective);
+    }
+
+    autonomy_cap.map(|cap| CostConfig {
+        enabled: true,
+        daily_limit_usd: cap,
+        monthly_limit_usd: f64::INFINITY,
+        ..cost.clone()
+    })
+}
+
+fn period_label(period: UsagePeriod) -> &'static str {
+    match period {
+        UsagePeriod::Session => "Session",
+        UsagePeriod::Day => "Daily",
+        UsagePeriod::Month => "Monthly",
+    }
 }
 
 fn resolve_storage_path(workspace_dir: &Path) -> Result<PathBuf> {
@@ -533,4 +713,127 @@ mod tests {
             .to_string()
             .contains("Estimated cost must be a finite, non-negative value"));
     }
+
+    #[test]
+    fn autonomy_cap_tightens_or_enables_daily_budget() {
+        let enabled = enabled_config();
+        let merged = effective_cost_config(&enabled, 250).unwrap();
+        assert!((merged.daily_limit_usd - 2.5).abs() < f64::EPSILON);
+        assert!((merged.monthly_limit_usd - enabled.monthly_limit_usd).abs() < f64::EPSILON);
+
+        let disabled = CostConfig::default();
+        assert!(effective_cost_config(&disabled, 0).is_none());
+
+        let cap_only = effective_cost_config(&disabled, 500).unwrap();
+        assert!(cap_only.enabled);
+        assert!((cap_only.daily_limit_usd - 5.0).abs() < f64::EPSILON);
+        assert!(cap_only.monthly_limit_usd.is_infinite());
+    }
+
+    #[test]
+    fn price_for_matches_prefixed_and_bare_model_names() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let direct = tracker
+            .price_for("anthropic", "claude-sonnet-4-20250514")
+            .unwrap();
+        assert!((direct.input - 3.0).abs() < f64::EPSILON);
+
+        let routed = tracker
+            .price_for("openrouter", "anthropic/claude-sonnet-4-20250514")
+            .unwrap();
+        assert!((routed.output - 15.0).abs() < f64::EPSILON);
+
+        let suffix = tracker.price_for("custom", "claude-sonnet-4-20250514");
+        assert!(suffix.is_some());
+
+        assert!(tracker.price_for("anthropic", "unknown-model").is_none());
+    }
+
+    #[test]
+    fn record_provider_usage_prices_reported_tokens() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        let recorded = tracker
+            .record_provider_usage(
+                "anthropic",
+                "claude-sonnet-4-20250514",
+                Usage::new(1_000_000, 100_000),
+            )
+            .unwrap();
+        assert_eq!(recorded.model, "anthropic/claude-sonnet-4-20250514");
+        assert!((recorded.cost_usd - 4.5).abs() < 1e-9);
+
+        let summary = tracker.get_summary().unwrap();
+        assert_eq!(summary.total_tokens, 1_100_000);
+        assert_eq!(summary.request_count, 1);
+    }
+
+    #[test]
+    fn record_provider_usage_applies_cache_discount() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+
+        // 1M prompt tokens, 900k of them cache reads: 0.1M * 3.0 + 0.9M * 0.3
+        let recorded = tracker
+            .record_provider_usage(
+                "anthropic",
+                "claude-sonnet-4-20250514",
+                Usage::new(1_000_000, 0).with_cache(900_000, 0),
+            )
+            .unwrap();
+        assert!((recorded.cost_usd - 0.57).abs() < 1e-9);
+        assert_eq!(recorded.cache_read_tokens, 900_000);
+    }
+
+    #[test]
+    fn enforce_budget_blocks_unless_override_allowed() {
+        let tmp = TempDir::new().unwrap();
+        let config = CostConfig {
+            enabled: true,
+            daily_limit_usd: 0.01,
+            ..Default::default()
+        };
+        let tracker = CostTracker::new(config.clone(), tmp.path()).unwrap();
+        tracker
+            .record_usage(TokenUsage::new("test/model", 10_000, 5_000, 1.0, 2.0))
+            .unwrap();
+
+        let err = tracker.enforce_budget(0.0).unwrap_err();
+        assert!(err.to_string().contains("Daily budget exceeded"));
+
+        let permissive = CostTracker::new(
+            CostConfig {
+                allow_override: true,
+                ..config
+            },
+            tmp.path(),
+        )
+        .unwrap();
+        assert!(permissive.enforce_budget(0.0).is_ok());
+    }
+
+    #[test]
+    fn daily_costs_cover_requested_window() {
+        let tmp = TempDir::new().unwrap();
+        let tracker = CostTracker::new(enabled_config(), tmp.path()).unwrap();
+        tracker
+            .record_usage(TokenUsage::new("test/model", 1_000_000, 0, 2.0, 0.0))
+            .unwrap();
+
+        let days = tracker.get_daily_costs(7).unwrap();
+        assert_eq!(days.len(), 7);
+        assert_eq!(days[6].0, Utc::now().date_naive());
+        assert!((days[6].1 - 2.0).abs() < 1e-9);
+        assert!(days[..6].iter().all(|(_, cost)| *cost == 0.0));
+
+        let now = Utc::now();
+        let stats = tracker
+            .get_monthly_model_stats(now.year(), now.month())
+            .unwrap();
+        assert_eq!(stats.len(), 1);
+        assert_eq!(stats[0].model, "test/model");
+    }
 }
@@ -9,6 +9,12 @@ pub struct TokenUsage {
     pub input_tokens: u64,
     /// Output/completion tokens
     pub output_tokens: u64,
+    /// Input tokens read from the provider's prompt cache
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    /// Input tokens written to the provider's prompt cache
+    #[serde(default)]
+    pub cache_write_tokens: u64,
     /// Total tokens
     pub total_tokens: u64,
     /// Calculated cost in USD
@@ -48,12 +54,42 @@ impl TokenUsage {
             model,
             input_tokens,
             output_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
             total_tokens,
             cost_usd,
             timestamp: chrono::Utc::now(),
         }
     }
 
+    /// Re-price the cached share of the input: cache reads and writes are
+    /// part of `input_tokens` but billed at their own rates instead of the
+    /// input price.
+    pub fn with_cache(
+        mut self,
+        cache_read_tokens: u64,
+        cache_write_tokens: u64,
+        input_price_per_million: f64,
+        cache_read_price_per_million: f64,
+        cache_write_price_per_million: f64,
+    ) -> Self {
+        let cache_read_tokens = cache_read_tokens.min(self.input_tokens);
+        let cache_write_tokens = cache_write_tokens.min(self.input_tokens - cache_read_tokens);
+        let cost =
+            |tokens: u64, price: f64| (tokens as f64 / 1_000_000.0) * Self::sanitize_price(price);
+
+        self.cost_usd += cost(cache_read_tokens, cache_read_price_per_million)
+            + cost(cache_write_tokens, cache_write_price_per_million)
+            - cost(
+                cache_read_tokens + cache_write_tokens,
+                input_price_per_million,
+            );
+        self.cost_usd = self.cost_usd.max(0.0);
+        self.cache_read_tokens = cache_read_tokens;
+        self.cache_write_tokens = cache_write_tokens;
+        self
+    }
+
     /// Get the total cost.
     pub fn cost(&self) -> f64 {
         self.cost_usd
@@ -181,6 +217,35 @@ mod tests {
         assert_eq!(usage.total_tokens, 2000);
     }
 
+    #[test]
+    fn cache_reads_and_writes_are_repriced() {
+        // 1M prompt tokens: 600k cache reads at 0.3, 200k writes at 3.75,
+        // 200k uncached at 3.0, plus no output
+        let usage = TokenUsage::new("test/model", 1_000_000, 0, 3.0, 15.0)
+            .with_cache(600_000, 200_000, 3.0, 0.3, 3.75);
+        assert!((usage.cost_usd - (0.18 + 0.75 + 0.6)).abs() < 1e-9);
+        assert_eq!(usage.cache_read_tokens, 600_000);
+        assert_eq!(usage.cache_write_tokens, 200_000);
+        assert_eq!(usage.total_tokens, 1_000_000);
+    }
+
+    #[test]
+    fn cache_counts_are_clamped_to_input() {
+        let usage =
+            TokenUsage::new("test/model", 100, 0, 3.0, 15.0).with_cache(80, 80, 3.0, 0.3, 3.75);
+        assert_eq!(usage.cache_read_tokens, 80);
+        assert_eq!(usage.cache_write_tokens, 20);
+        assert!(usage.cost_usd >= 0.0);
+    }
+
+    #[test]
+    fn records_without_cache_fields_still_deserialize() {
+        let json = r#"{"model":"m","input_tokens":1,"output_tokens":2,"total_tokens":3,"cost_usd":0.0,"timestamp":"2025-01-01T00:00:00Z"}"#;
+        let usage: TokenUsage = serde_json::from_str(json).unwrap();
+        assert_eq!(usage.cache_read_tokens, 0);
+        assert_eq!(usage.cache_write_tokens, 0);
+    }
+
     #[test]
     fn cost_record_creation() {
         let usage = TokenUsage::new("test/model", 100, 50, 1.0, 2.0);
@@ -226,6 +226,17 @@ async fn deliver_if_configured(config: &Config, job: &CronJob, output: &str) ->
         .as_deref()
         .ok_or_else(|| anyhow::anyhow!("delivery.to is required for announce mode"))?;
 
+    deliver_to_channel(config, channel, target, output).await
+}
+
+/// Send `output` to `target` on one of the configured outbound channels.
+/// Shared by cron announce delivery and heartbeat task results.
+pub(crate) async fn deliver_to_channel(
+    config: &Config,
+    channel: &str,
+    target: &str,
+    output: &str,
+) -> Result<()> {
     match channel.to_ascii_lowercase().as_str() {
         "telegram" => {
             let tg = config
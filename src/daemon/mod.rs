@@ -7,6 +7,8 @@ use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
 const STATUS_FLUSH_SECONDS: u64 = 5;
+/// How often the daemon checks whether a consolidation pass is due.
+const CONSOLIDATION_CHECK_SECONDS: u64 = 3_600;
 
 pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
     let initial_backoff = config.reliability.channel_initial_backoff_secs.max(1);
@@ -79,7 +81,7 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
             max_backoff,
             move || {
                 let cfg = scheduler_cfg.clone();
-                async move { crate::cron::scheduler::run(cfg).await }
+                async move { Box::pin(crate::cron::scheduler::run(cfg)).await }
             },
         ));
     } else {
@@ -87,6 +89,32 @@ pub async fn run(config: Config, host: String, port: u16) -> Result<()> {
         tracing::info!("Cron disabled; scheduler supervisor not started");
     }
 
+    if !config.memory.ingest_paths.is_empty() && config.memory.ingest_interval_secs > 0 {
+        let ingest_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "ingest",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = ingest_cfg.clone();
+                async move { crate::memory::ingest::run_watcher(cfg).await }
+            },
+        ));
+    }
+
+    if config.memory.consolidation_enabled {
+        let consolidation_cfg = config.clone();
+        handles.push(spawn_component_supervisor(
+            "consolidation",
+            initial_backoff,
+            max_backoff,
+            move || {
+                let cfg = consolidation_cfg.clone();
+                async move { run_consolidation_worker(cfg).await }
+            },
+        ));
+    }
+
     println!("🧠 ZeroClaw daemon started");
     println!("   Gateway:  http://{host}:{port}");
     println!("   Components: gateway, channels, heartbeat, scheduler");
@@ -173,6 +201,16 @@ where
     })
 }
 
+async fn run_consolidation_worker(config: Config) -> Result<()> {
+    let mut interval = tokio::time::interval(Duration::from_secs(CONSOLIDATION_CHECK_SECONDS));
+    loop {
+        interval.tick().await;
+        if let Err(e) = crate::memory::consolidate::run_if_due(&config).await {
+            tracing::warn!("memory consolidation skipped: {e}");
+        }
+    }
+}
+
 async fn run_heartbeat_worker(config: Config) -> Result<()> {
     let observer: std::sync::Arc<dyn crate::observability::Observer> =
         std::sync::Arc::from(crate::observability::create_observer(&config.observability));
@@ -182,30 +220,27 @@ async fn run_heartbeat_worker(config: Config) -> Result<()> {
         observer,
     );
 
-    let interval_mins = config.heartbeat.interval_minutes.max(5);
-    let mut interval = tokio::time::interval(Duration::from_secs(u64::from(interval_mins) * 60));
-
-    loop {
-        interval.tick().await;
-
-        let tasks = engine.collect_tasks().await?;
-        if tasks.is_empty() {
-            continue;
-        }
+    engine
+        .run(|task| run_heartbeat_task(config.clone(), task))
+        .await
+}
 
-        for task in tasks {
-            let prompt = format!("[Heartbeat Task] {task}");
-            let temp = config.default_temperature;
-            if let Err(e) =
-                crate::agent::run(config.clone(), Some(prompt), None, None, temp, vec![]).await
-            {
-                crate::health::mark_component_error("heartbeat", e.to_string());
-                tracing::warn!("Heartbeat task failed: {e}");
-            } else {
-                crate::health::mark_component_ok("heartbeat");
-            }
+async fn run_heartbeat_task(config: Config, task: String) -> Result<String> {
+    let prompt = format!("[Heartbeat Task] {task}");
+    let output = Box::pin(crate::agent::process_message(config.clone(), &prompt)).await?;
+
+    if let (Some(channel), Some(target)) = (
+        config.heartbeat.channel.as_deref(),
+        config.heartbeat.to.as_deref(),
+    ) {
+        if let Err(e) =
+            crate::cron::scheduler::deliver_to_channel(&config, channel, target, &output).await
+        {
+            tracing::warn!("Heartbeat delivery to {channel} failed: {e}");
         }
     }
+
+    Ok(output)
 }
 
 fn has_supervised_channels(config: &Config) -> bool {
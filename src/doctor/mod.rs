@@ -64,6 +64,7 @@ pub fn run(config: &Config) -> Result<()> {
     check_config_semantics(config, &mut items);
     check_workspace(config, &mut items);
     check_daemon_state(config, &mut items);
+    check_heartbeat(config, &mut items);
     check_environment(&mut items);
 
     // Print report
@@ -514,6 +515,53 @@ fn check_daemon_state(config: &Config, items: &mut Vec<DiagItem>) {
     }
 }
 
+// ── Heartbeat task outcomes ──────────────────────────────────────
+
+fn check_heartbeat(config: &Config, items: &mut Vec<DiagItem>) {
+    let cat = "heartbeat";
+
+    if !config.heartbeat.enabled {
+        items.push(DiagItem::ok(cat, "disabled"));
+        return;
+    }
+
+    let runs = match crate::heartbeat::store::load_runs(&config.workspace_dir) {
+        Ok(runs) => runs,
+        Err(e) => {
+            items.push(DiagItem::error(
+                cat,
+                format!("cannot read run history: {e}"),
+            ));
+            return;
+        }
+    };
+
+    if runs.is_empty() {
+        items.push(DiagItem::warn(cat, "no heartbeat tasks have run yet"));
+        return;
+    }
+
+    for (task, run) in &runs {
+        let age = run.last_run().map_or_else(
+            || "unknown time".to_string(),
+            |dt| {
+                format!(
+                    "{}s ago",
+                    Utc::now().signed_duration_since(dt).num_seconds()
+                )
+            },
+        );
+        if run.success {
+            items.push(DiagItem::ok(cat, format!("\"{task}\" ok ({age})")));
+        } else {
+            items.push(DiagItem::warn(
+                cat,
+                format!("\"{task}\" failed ({age}): {}", run.output),
+            ));
+        }
+    }
+}
+
 // ── Environment checks ───────────────────────────────────────────
 
 fn check_environment(items: &mut Vec<DiagItem>) {
@@ -719,6 +767,8 @@ mod tests {
             provider: "groq".into(),
             model: String::new(),
             api_key: None,
+            reasoning: None,
+            vision: false,
         }];
         let mut items = Vec::new();
         check_config_semantics(&config, &mut items);
@@ -762,4 +812,43 @@ mod tests {
             .and_then(|name| name.to_str())
             .is_some_and(|name| name.starts_with(".zeroclaw_doctor_probe_")));
     }
+
+    #[test]
+    fn heartbeat_check_reports_last_outcome_per_task() {
+        use crate::heartbeat::store::{record_run, TaskRun};
+
+        let tmp = TempDir::new().unwrap();
+        let mut config = Config {
+            workspace_dir: tmp.path().to_path_buf(),
+            ..Config::default()
+        };
+        config.heartbeat.enabled = true;
+
+        let mut items = Vec::new();
+        check_heartbeat(&config, &mut items);
+        assert_eq!(items.len(), 1);
+        assert_eq!(items[0].severity, Severity::Warn);
+
+        let now = Utc::now();
+        record_run(
+            tmp.path(),
+            "check inbox",
+            TaskRun::new(now, now, true, "ok"),
+        )
+        .unwrap();
+        record_run(
+            tmp.path(),
+            "sync notes",
+            TaskRun::new(now, now, false, "timeout"),
+        )
+        .unwrap();
+
+        let mut items = Vec::new();
+        check_heartbeat(&config, &mut items);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].severity, Severity::Ok);
+        assert!(items[0].message.contains("check inbox"));
+        assert_eq!(items[1].severity, Severity::Warn);
+        assert!(items[1].message.contains("timeout"));
+    }
 }
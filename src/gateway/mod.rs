@@ -8,16 +8,20 @@
 //! - Header sanitization (handled by axum/hyper)
 
 pub mod auth_handlers;
+pub mod openai_handlers;
 pub mod payment_handlers;
+pub mod ws_handlers;
 
 use crate::channels::{Channel, SendMessage, WhatsAppChannel};
 use crate::config::Config;
-use crate::memory::{self, Memory, MemoryCategory};
+use crate::cost::CostTracker;
+use crate::memory::{self, Memory, MemoryCategory, ResponseCache};
+use crate::observability::{self, Observer, ObserverEvent};
 use crate::providers::{self, Provider};
 use crate::runtime;
 use crate::security::pairing::{constant_time_eq, is_public_bind, PairingGuard};
 use crate::security::SecurityPolicy;
-use crate::tools;
+use crate::tools::{self, Tool};
 use crate::util::truncate_with_ellipsis;
 use anyhow::Result;
 use axum::{
@@ -197,6 +201,18 @@ pub struct AppState {
     pub token_meter: Option<Arc<crate::billing::TokenMeter>>,
     pub cryptomus_api_key: Option<String>,
     pub cryptomus_merchant_id: Option<String>,
+    // Agent loop backing the OpenAI-compatible /v1 endpoints
+    pub provider_name: String,
+    pub system_prompt: Arc<str>,
+    pub tools_registry: Arc<Vec<Box<dyn Tool>>>,
+    pub observer: Arc<dyn Observer>,
+    pub cost_tracker: Option<Arc<CostTracker>>,
+    pub response_cache: Option<Arc<ResponseCache>>,
+    pub max_parallel_tools: usize,
+    /// Messages a /ws session keeps besides the system prompt
+    pub max_history_messages: usize,
+    /// Approval policy for interactive sessions on /ws
+    pub autonomy: crate::config::AutonomyConfig,
 }
 
 /// Run the HTTP gateway using axum with proper HTTP/1.1 compliance.
@@ -217,10 +233,15 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
     let actual_port = listener.local_addr()?.port();
     let display_addr = format!("{host}:{actual_port}");
 
+    let provider_name = config
+        .default_provider
+        .clone()
+        .unwrap_or_else(|| "openrouter".into());
     let provider: Arc<dyn Provider> = Arc::from(providers::create_resilient_provider(
-        config.default_provider.as_deref().unwrap_or("openrouter"),
+        &provider_name,
         config.api_key.as_deref(),
         config.api_url.as_deref(),
+        config.default_model_vision,
         &config.reliability,
     )?);
     let model = config
@@ -228,10 +249,12 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .clone()
         .unwrap_or_else(|| "anthropic/claude-sonnet-4".into());
     let temperature = config.default_temperature;
+    let memory_cipher = memory::MemoryCipher::from_config(&config)?;
     let mem: Arc<dyn Memory> = Arc::from(memory::create_memory(
         &config.memory,
         &config.workspace_dir,
         config.api_key.as_deref(),
+        memory_cipher.clone(),
     )?);
     let runtime: Arc<dyn runtime::RuntimeAdapter> =
         Arc::from(runtime::create_runtime(&config.runtime)?);
@@ -249,10 +272,11 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         (None, None)
     };
 
-    let _tools_registry = Arc::new(tools::all_tools_with_runtime(
+    let skills = crate::skills::load_skills(&config.workspace_dir);
+    let mut tools_registry = tools::all_tools_with_runtime(
         Arc::new(config.clone()),
         &security,
-        runtime,
+        runtime.clone(),
         Arc::clone(&mem),
         composio_key,
         composio_entity_id,
@@ -262,7 +286,37 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         &config.agents,
         config.api_key.as_deref(),
         &config,
+    );
+    tools_registry.extend(tools::skill_tools(
+        &skills,
+        &security,
+        &runtime,
+        &config.http_request,
     ));
+    let tool_descs: Vec<(&str, &str)> = tools_registry
+        .iter()
+        .map(|tool| (tool.name(), tool.description()))
+        .collect();
+    let mut system_prompt = crate::channels::build_system_prompt(
+        &config.workspace_dir,
+        &model,
+        &tool_descs,
+        &skills,
+        Some(&config.identity),
+        config.agent.compact_context.then_some(6000),
+    );
+    system_prompt.push_str(&crate::agent::loop_::build_tool_instructions(
+        &tools_registry,
+    ));
+    let observer: Arc<dyn Observer> =
+        Arc::from(observability::create_observer(&config.observability));
+    let cost_tracker = match CostTracker::from_config(&config) {
+        Ok(tracker) => tracker.map(Arc::new),
+        Err(e) => {
+            tracing::warn!("Cost tracking disabled — failed to open cost storage: {e}");
+            None
+        }
+    };
     // Extract webhook secret for authentication
     let webhook_secret_hash: Option<Arc<str>> =
         config.channels_config.webhook.as_ref().and_then(|webhook| {
@@ -344,6 +398,9 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         println!("  GET  /whatsapp  — Meta webhook verification");
         println!("  POST /whatsapp  — WhatsApp message webhook");
     }
+    println!("  POST /v1/chat/completions — OpenAI-compatible chat (stream supported)");
+    println!("  GET  /v1/models — OpenAI-compatible model list");
+    println!("  GET  /ws        — WebSocket chat with live tool events");
     println!("  GET  /health    — health check");
     if let Some(code) = pairing.pairing_code() {
         println!();
@@ -403,8 +460,33 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         token_meter,
         cryptomus_api_key,
         cryptomus_merchant_id,
+        provider_name,
+        system_prompt: Arc::from(system_prompt),
+        tools_registry: Arc::new(tools_registry),
+        observer,
+        cost_tracker,
+        response_cache: memory::create_response_cache(
+            &config.memory,
+            &config.workspace_dir,
+            memory_cipher,
+        )
+        .map(Arc::new),
+        max_parallel_tools: config.agent.tool_concurrency(),
+        max_history_messages: config.agent.max_history_messages,
+        autonomy: config.autonomy.clone(),
     };
 
+    // OpenAI-compatible API: larger bodies and a longer timeout for tool turns
+    let openai_api = Router::new()
+        .route("/v1/models", get(openai_handlers::handle_list_models))
+        .route("/v1/chat/completions", post(openai_handlers::handle_chat_completions))
+        .with_state(state.clone())
+        .layer(RequestBodyLimitLayer::new(openai_handlers::MAX_CHAT_BODY_SIZE))
+        .layer(TimeoutLayer::with_status_code(
+            StatusCode::REQUEST_TIMEOUT,
+            Duration::from_secs(openai_handlers::CHAT_TIMEOUT_SECS),
+        ));
+
     // Build router with middleware
     let app = Router::new()
         .route("/health", get(handle_health))
@@ -412,6 +494,7 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .route("/webhook", post(handle_webhook))
         .route("/whatsapp", get(handle_whatsapp_verify))
         .route("/whatsapp", post(handle_whatsapp_message))
+        .route("/ws", get(ws_handlers::handle_ws))
         // Payment routes (Cryptomus integration)
         .route("/api/payment/packages", get(payment_handlers::handle_list_packages))
         .route("/api/payment/webhook", post(payment_handlers::handle_cryptomus_webhook))
@@ -427,7 +510,8 @@ pub async fn run_gateway(host: &str, port: u16, config: Config) -> Result<()> {
         .layer(TimeoutLayer::with_status_code(
             StatusCode::REQUEST_TIMEOUT,
             Duration::from_secs(REQUEST_TIMEOUT_SECS),
-        ));
+        ))
+        .merge(openai_api);
 
     // Run the server
     axum::serve(listener, app).await?;
@@ -590,12 +674,48 @@ async fn handle_webhook(
             .await;
     }
 
+    // Deterministic webhook calls can be answered from the response cache.
+    let cache_entry = state
+        .response_cache
+        .as_deref()
+        .filter(|cache| cache.accepts_temperature(state.temperature))
+        .map(|cache| {
+            let history = [providers::ChatMessage::user(message.as_str())];
+            let key = crate::agent::loop_::response_cache_key(&state.model, &history);
+            (cache, key)
+        });
+    if let Some((cache, key)) = &cache_entry {
+        match cache.get(key) {
+            Ok(Some(response)) => {
+                state
+                    .observer
+                    .record_event(&ObserverEvent::ResponseCacheLookup { hit: true });
+                let body = serde_json::json!({"response": response, "model": state.model});
+                return (StatusCode::OK, Json(body));
+            }
+            Ok(None) => {
+                state
+                    .observer
+                    .record_event(&ObserverEvent::ResponseCacheLookup { hit: false });
+            }
+            Err(e) => tracing::warn!("Response cache lookup failed: {e}"),
+        }
+    }
+
     match state
         .provider
         .simple_chat(message, &state.model, state.temperature)
         .await
     {
         Ok(response) => {
+            if let Some((cache, key)) = &cache_entry {
+                // simple_chat reports no usage; estimate ~4 chars per token
+                let tokens = (message.len() + response.len()).div_ceil(4);
+                let tokens = u32::try_from(tokens).unwrap_or(u32::MAX);
+                if let Err(e) = cache.put(key, &state.model, &response, tokens) {
+                    tracing::warn!("Failed to store webhook response in cache: {e}");
+                }
+            }
             let body = serde_json::json!({"response": response, "model": state.model});
             (StatusCode::OK, Json(body))
         }
@@ -898,9 +1018,11 @@ mod tests {
         let msg = ChannelMessage {
             id: "wamid-123".into(),
             sender: "+1234567890".into(),
+            sender_id: None,
             reply_target: "+1234567890".into(),
             content: "hello".into(),
             channel: "whatsapp".into(),
+            attachments: Vec::new(),
             timestamp: 1,
         };
 
@@ -1059,6 +1181,15 @@ mod tests {
             token_meter: None,
             cryptomus_api_key: None,
             cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
         };
 
         let mut headers = HeaderMap::new();
@@ -1111,6 +1242,15 @@ mod tests {
             token_meter: None,
             cryptomus_api_key: None,
             cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
         };
 
         let headers = HeaderMap::new();
@@ -1139,6 +1279,59 @@ mod tests {
         assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 2);
     }
 
+    #[tokio::test]
+    async fn webhook_repeated_message_is_served_from_response_cache() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let provider_impl = Arc::new(MockProvider::default());
+        let provider: Arc<dyn Provider> = provider_impl.clone();
+        let memory: Arc<dyn Memory> = Arc::new(MockMemory);
+        let cache = ResponseCache::new(tmp.path(), 60, 100).unwrap();
+
+        let state = AppState {
+            provider,
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: memory,
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(false, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            auth_manager: None,
+            token_meter: None,
+            cryptomus_api_key: None,
+            cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: Some(Arc::new(cache)),
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
+        };
+
+        for _ in 0..2 {
+            let response = handle_webhook(
+                State(state.clone()),
+                HeaderMap::new(),
+                Ok(Json(WebhookBody {
+                    message: "same question".into(),
+                })),
+            )
+            .await
+            .into_response();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        assert_eq!(provider_impl.calls.load(Ordering::SeqCst), 1);
+        let lookups = state.response_cache.as_ref().unwrap().lookups().unwrap();
+        assert_eq!(lookups, (1, 1));
+    }
+
     #[test]
     fn webhook_secret_hash_is_deterministic_and_nonempty() {
         let one = hash_webhook_secret("secret-value");
@@ -1172,6 +1365,15 @@ mod tests {
             token_meter: None,
             cryptomus_api_key: None,
             cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
         };
 
         let response = handle_webhook(
@@ -1210,6 +1412,15 @@ mod tests {
             token_meter: None,
             cryptomus_api_key: None,
             cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
         };
 
         let mut headers = HeaderMap::new();
@@ -1251,6 +1462,15 @@ mod tests {
             token_meter: None,
             cryptomus_api_key: None,
             cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
         };
 
         let mut headers = HeaderMap::new();
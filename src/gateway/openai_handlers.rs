@@ -0,0 +1,771 @@
+//! OpenAI-compatible API (`/v1/models`, `/v1/chat/completions`).
+//!
+//! Lets OpenAI SDK clients and IDE plugins talk to ZeroClaw unchanged. Each
+//! request runs through the same tool-call loop as the channels, using the
+//! gateway's provider, tools, memory and pairing-token auth. Client-side
+//! `tools`/`functions` are ignored: the agent uses its own registry.
+
+use super::{client_key_from_headers, AppState, RATE_LIMIT_WINDOW_SECS};
+use crate::agent::loop_::{build_context, run_tool_call_loop, LoopOutput, StreamEvent};
+use crate::memory::MemoryCategory;
+use crate::providers::{self, ChatMessage, ContentPart, MediaSource};
+use axum::{
+    extract::State,
+    http::{header, HeaderMap, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::VecDeque;
+use std::convert::Infallible;
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+/// Larger than the webhook limit: chat clients resend the whole conversation.
+pub const MAX_CHAT_BODY_SIZE: usize = 1_048_576;
+/// Tool-using turns routinely outlast the gateway's default 30s timeout.
+pub const CHAT_TIMEOUT_SECS: u64 = 300;
+
+/// `POST /v1/chat/completions` request body (the subset ZeroClaw honors).
+#[derive(Debug, Deserialize)]
+pub struct ChatCompletionRequest {
+    #[serde(default)]
+    pub model: Option<String>,
+    pub messages: Vec<OpenAiMessage>,
+    #[serde(default)]
+    pub stream: bool,
+    #[serde(default)]
+    pub temperature: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAiMessage {
+    pub role: String,
+    #[serde(default)]
+    pub content: Option<OpenAiContent>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+pub enum OpenAiContent {
+    Text(String),
+    Parts(Vec<OpenAiContentPart>),
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OpenAiContentPart {
+    Text {
+        text: String,
+    },
+    ImageUrl {
+        image_url: OpenAiImageUrl,
+    },
+    #[serde(other)]
+    Unsupported,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OpenAiImageUrl {
+    pub url: String,
+}
+
+fn openai_error(status: StatusCode, kind: &str, message: impl Into<String>) -> Response {
+    let body = json!({
+        "error": {
+            "message": message.into(),
+            "type": kind,
+            "code": status.as_u16(),
+        }
+    });
+    (status, Json(body)).into_response()
+}
+
+/// Rate limit and pairing checks shared by both endpoints; returns the error
+/// response when the request must be rejected.
+fn reject_unauthorized(state: &AppState, headers: &HeaderMap) -> Option<Response> {
+    let client_key = client_key_from_headers(headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        tracing::warn!("/v1 rate limit exceeded for key: {client_key}");
+        return Some(openai_error(
+            StatusCode::TOO_MANY_REQUESTS,
+            "rate_limit_exceeded",
+            format!("Too many requests. Retry in {RATE_LIMIT_WINDOW_SECS}s."),
+        ));
+    }
+
+    if state.pairing.require_pairing() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            tracing::warn!("/v1: rejected — not paired / invalid bearer token");
+            return Some(openai_error(
+                StatusCode::UNAUTHORIZED,
+                "invalid_api_key",
+                "Pair first via POST /pair, then send the token as the API key",
+            ));
+        }
+    }
+
+    None
+}
+
+fn image_source(url: &str) -> MediaSource {
+    url.strip_prefix("data:")
+        .and_then(|rest| rest.split_once(";base64,"))
+        .map_or_else(
+            || MediaSource::Url {
+                url: url.to_string(),
+            },
+            |(mime_type, data)| MediaSource::Base64 {
+                mime_type: mime_type.to_string(),
+                data: data.to_string(),
+            },
+        )
+}
+
+/// Map OpenAI messages onto agent history. Client system messages follow the
+/// agent's own system prompt; tool-role messages from client-side tool use
+/// are dropped because the agent runs its own tools.
+fn convert_messages(system_prompt: &str, messages: Vec<OpenAiMessage>) -> Vec<ChatMessage> {
+    let mut history = vec![ChatMessage::system(system_prompt)];
+
+    for message in messages {
+        let content = message.content;
+        match message.role.as_str() {
+            "system" | "developer" => {
+                if let Some(OpenAiContent::Text(text)) = content {
+                    history.push(ChatMessage::system(text));
+                } else if let Some(OpenAiContent::Parts(parts)) = content {
+                    history.push(ChatMessage::system(parts_text(&parts)));
+                }
+            }
+            "assistant" => match content {
+                Some(OpenAiContent::Text(text)) => history.push(ChatMessage::assistant(text)),
+                Some(OpenAiContent::Parts(parts)) => {
+                    history.push(ChatMessage::assistant(parts_text(&parts)));
+                }
+                None => {}
+            },
+            "user" => match content {
+                Some(OpenAiContent::Text(text)) => history.push(ChatMessage::user(text)),
+                Some(OpenAiContent::Parts(parts)) => {
+                    let parts = parts
+                        .into_iter()
+                        .filter_map(|part| match part {
+                            OpenAiContentPart::Text { text } => Some(ContentPart::text(text)),
+                            OpenAiContentPart::ImageUrl { image_url } => {
+                                Some(ContentPart::image(image_source(&image_url.url)))
+                            }
+                            OpenAiContentPart::Unsupported => None,
+                        })
+                        .collect();
+                    history.push(ChatMessage::user_with_parts(parts));
+                }
+                None => {}
+            },
+            _ => {}
+        }
+    }
+
+    history
+}
+
+fn parts_text(parts: &[OpenAiContentPart]) -> String {
+    parts
+        .iter()
+        .filter_map(|part| match part {
+            OpenAiContentPart::Text { text } => Some(text.as_str()),
+            _ => None,
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn chat_memory_key() -> String {
+    format!("openai_msg_{}", Uuid::new_v4())
+}
+
+/// Prepend recalled memories to the latest user turn and auto-save it.
+pub(super) async fn apply_memory(state: &AppState, history: &mut [ChatMessage]) {
+    let Some(last_user) = history.iter_mut().rev().find(|m| m.role == "user") else {
+        return;
+    };
+    let query = last_user.content.clone();
+    if query.trim().is_empty() {
+        return;
+    }
+
+    let context = build_context(state.mem.as_ref(), &query).await;
+
+    if state.auto_save {
+        let _ = state
+            .mem
+            .store(
+                &chat_memory_key(),
+                &query,
+                MemoryCategory::Conversation,
+                None,
+            )
+            .await;
+    }
+
+    if context.is_empty() {
+        return;
+    }
+    if last_user.parts.is_empty() {
+        last_user.content = format!("{context}{}", last_user.content);
+    } else {
+        last_user.parts.insert(0, ContentPart::text(context));
+        *last_user = ChatMessage::user_with_parts(std::mem::take(&mut last_user.parts));
+    }
+}
+
+async fn run_agent(
+    state: AppState,
+    mut history: Vec<ChatMessage>,
+    temperature: f64,
+    stream_tx: Option<tokio::sync::mpsc::Sender<StreamEvent>>,
+) -> anyhow::Result<String> {
+    Box::pin(run_tool_call_loop(
+        state.provider.as_ref(),
+        &mut history,
+        state.tools_registry.as_ref(),
+        state.observer.as_ref(),
+        &state.provider_name,
+        &state.model,
+        temperature,
+        LoopOutput::SILENT,
+        None,
+        None,
+        "gateway",
+        state.cost_tracker.as_deref(),
+        state.response_cache.as_deref(),
+        stream_tx.as_ref(),
+        state.max_parallel_tools,
+    ))
+    .await
+}
+
+/// GET /v1/models — the configured model, so clients can pick it
+pub async fn handle_list_models(State(state): State<AppState>, headers: HeaderMap) -> Response {
+    if let Some(response) = reject_unauthorized(&state, &headers) {
+        return response;
+    }
+
+    Json(json!({
+        "object": "list",
+        "data": [{
+            "id": state.model,
+            "object": "model",
+            "created": 0,
+            "owned_by": "zeroclaw",
+        }],
+    }))
+    .into_response()
+}
+
+/// POST /v1/chat/completions — run a turn through the agent loop
+pub async fn handle_chat_completions(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    body: Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection>,
+) -> Response {
+    if let Some(response) = reject_unauthorized(&state, &headers) {
+        return response;
+    }
+
+    let Json(request) = match body {
+        Ok(body) => body,
+        Err(e) => {
+            return openai_error(
+                StatusCode::BAD_REQUEST,
+                "invalid_request_error",
+                format!("Invalid request body: {e}"),
+            );
+        }
+    };
+    if !request.messages.iter().any(|m| m.role == "user") {
+        return openai_error(
+            StatusCode::BAD_REQUEST,
+            "invalid_request_error",
+            "messages must include at least one user message",
+        );
+    }
+    if let Some(model) = request.model.as_deref() {
+        if model != state.model {
+            tracing::debug!(
+                "/v1: client asked for model '{model}', using '{}'",
+                state.model
+            );
+        }
+    }
+
+    let temperature = request.temperature.unwrap_or(state.temperature);
+    let mut history = convert_messages(&state.system_prompt, request.messages);
+    apply_memory(&state, &mut history).await;
+
+    let id = format!("chatcmpl-{}", Uuid::new_v4().simple());
+    let created = chrono::Utc::now().timestamp();
+
+    if request.stream {
+        return stream_completion(state, history, temperature, id, created);
+    }
+
+    match run_agent(state.clone(), history, temperature, None).await {
+        Ok(reply) => Json(json!({
+            "id": id,
+            "object": "chat.completion",
+            "created": created,
+            "model": state.model,
+            "choices": [{
+                "index": 0,
+                "message": { "role": "assistant", "content": reply },
+                "finish_reason": "stop",
+            }],
+        }))
+        .into_response(),
+        Err(e) => {
+            tracing::error!(
+                "/v1 agent error: {}",
+                providers::sanitize_api_error(&e.to_string())
+            );
+            openai_error(
+                StatusCode::INTERNAL_SERVER_ERROR,
+                "server_error",
+                "LLM request failed",
+            )
+        }
+    }
+}
+
+/// State threaded through the SSE stream: queued events, the agent's live
+/// deltas, and the agent task whose result closes the stream.
+struct StreamState {
+    pending: VecDeque<Event>,
+    rx: tokio::sync::mpsc::Receiver<StreamEvent>,
+    task: Option<JoinHandle<anyhow::Result<String>>>,
+    streamed_text: bool,
+    id: String,
+    created: i64,
+    model: String,
+}
+
+/// A client that disconnects drops the SSE stream; stop the agent with it
+/// instead of letting it keep calling the provider.
+impl Drop for StreamState {
+    fn drop(&mut self) {
+        if let Some(task) = self.task.take() {
+            task.abort();
+        }
+    }
+}
+
+impl StreamState {
+    fn chunk(&self, delta: serde_json::Value, finish_reason: Option<&str>) -> Event {
+        chunk_event(&self.id, self.created, &self.model, delta, finish_reason)
+    }
+
+    /// Queue the closing events once the agent task has finished.
+    async fn finish(&mut self) {
+        let Some(task) = self.task.take() else {
+            return;
+        };
+        let result = task
+            .await
+            .unwrap_or_else(|e| Err(anyhow::anyhow!("agent task failed: {e}")));
+
+        match result {
+            Ok(reply) => {
+                // Providers without native streaming deliver the reply whole.
+                if !self.streamed_text && !reply.is_empty() {
+                    let event = self.chunk(json!({ "content": reply }), None);
+                    self.pending.push_back(event);
+                }
+                let event = self.chunk(json!({}), Some("stop"));
+                self.pending.push_back(event);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "/v1 stream agent error: {}",
+                    providers::sanitize_api_error(&e.to_string())
+                );
+                let error = json!({
+                    "error": { "message": "LLM request failed", "type": "server_error" }
+                });
+                self.pending
+                    .push_back(Event::default().data(error.to_string()));
+            }
+        }
+        self.pending.push_back(Event::default().data("[DONE]"));
+    }
+}
+
+fn chunk_event(
+    id: &str,
+    created: i64,
+    model: &str,
+    delta: serde_json::Value,
+    finish_reason: Option<&str>,
+) -> Event {
+    Event::default().data(
+        json!({
+            "id": id,
+            "object": "chat.completion.chunk",
+            "created": created,
+            "model": model,
+            "choices": [{
+                "index": 0,
+                "delta": delta,
+                "finish_reason": finish_reason,
+            }],
+        })
+        .to_string(),
+    )
+}
+
+fn stream_completion(
+    state: AppState,
+    history: Vec<ChatMessage>,
+    temperature: f64,
+    id: String,
+    created: i64,
+) -> Response {
+    let (tx, rx) = tokio::sync::mpsc::channel(64);
+    let model = state.model.clone();
+    let task = tokio::spawn(run_agent(state, history, temperature, Some(tx)));
+
+    let mut initial = StreamState {
+        pending: VecDeque::new(),
+        rx,
+        task: Some(task),
+        streamed_text: false,
+        id,
+        created,
+        model,
+    };
+    let opening = initial.chunk(json!({ "role": "assistant", "content": "" }), None);
+    initial.pending.push_back(opening);
+
+    let events = futures::stream::unfold(initial, |mut st| async move {
+        loop {
+            if let Some(event) = st.pending.pop_front() {
+                return Some((Ok::<Event, Infallible>(event), st));
+            }
+            // The agent task is taken once it finished and its events queued.
+            st.task.as_ref()?;
+            match st.rx.recv().await {
+                Some(StreamEvent::Delta(text)) => {
+                    st.streamed_text = true;
+                    let event = st.chunk(json!({ "content": text }), None);
+                    st.pending.push_back(event);
+                }
+                // Text before a tool round was narration; keep it apart from
+                // what follows.
+                Some(StreamEvent::ToolRound) => {
+                    let event = st.chunk(json!({ "content": "\n\n" }), None);
+                    st.pending.push_back(event);
+                }
+                Some(StreamEvent::ToolStart { .. } | StreamEvent::ToolResult { .. }) => {}
+                None => st.finish().await,
+            }
+        }
+    });
+
+    Sse::new(events)
+        .keep_alive(KeepAlive::default())
+        .into_response()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateway::{GatewayRateLimiter, IdempotencyStore};
+    use crate::memory::{Memory, MemoryEntry};
+    use crate::providers::Provider;
+    use crate::security::pairing::PairingGuard;
+    use async_trait::async_trait;
+    use http_body_util::BodyExt;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    struct EchoProvider;
+
+    #[async_trait]
+    impl Provider for EchoProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(format!("echo: {message}"))
+        }
+    }
+
+    struct EmptyMemory;
+
+    #[async_trait]
+    impl Memory for EmptyMemory {
+        fn name(&self) -> &str {
+            "empty"
+        }
+
+        async fn store(
+            &self,
+            _key: &str,
+            _content: &str,
+            _category: MemoryCategory,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        async fn recall(
+            &self,
+            _query: &str,
+            _limit: usize,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<Vec<MemoryEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn get(&self, _key: &str) -> anyhow::Result<Option<MemoryEntry>> {
+            Ok(None)
+        }
+
+        async fn list(
+            &self,
+            _category: Option<&MemoryCategory>,
+            _session_id: Option<&str>,
+        ) -> anyhow::Result<Vec<MemoryEntry>> {
+            Ok(Vec::new())
+        }
+
+        async fn forget(&self, _key: &str) -> anyhow::Result<bool> {
+            Ok(false)
+        }
+
+        async fn count(&self) -> anyhow::Result<usize> {
+            Ok(0)
+        }
+
+        async fn health_check(&self) -> bool {
+            true
+        }
+    }
+
+    fn test_state(require_pairing: bool) -> AppState {
+        AppState {
+            provider: Arc::new(EchoProvider),
+            model: "test-model".into(),
+            temperature: 0.0,
+            mem: Arc::new(EmptyMemory),
+            auto_save: false,
+            webhook_secret_hash: None,
+            pairing: Arc::new(PairingGuard::new(require_pairing, &[])),
+            rate_limiter: Arc::new(GatewayRateLimiter::new(100, 100)),
+            idempotency_store: Arc::new(IdempotencyStore::new(Duration::from_secs(300))),
+            whatsapp: None,
+            whatsapp_app_secret: None,
+            auth_manager: None,
+            token_meter: None,
+            cryptomus_api_key: None,
+            cryptomus_merchant_id: None,
+            provider_name: "test".into(),
+            system_prompt: Arc::from("system"),
+            tools_registry: Arc::new(Vec::new()),
+            observer: Arc::new(crate::observability::NoopObserver),
+            cost_tracker: None,
+            response_cache: None,
+            max_parallel_tools: 1,
+            max_history_messages: 50,
+            autonomy: crate::config::AutonomyConfig::default(),
+        }
+    }
+
+    fn request(
+        body: serde_json::Value,
+    ) -> Result<Json<ChatCompletionRequest>, axum::extract::rejection::JsonRejection> {
+        Ok(Json(serde_json::from_value(body).unwrap()))
+    }
+
+    async fn body_text(response: Response) -> String {
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        String::from_utf8(bytes.to_vec()).unwrap()
+    }
+
+    #[test]
+    fn convert_messages_maps_roles_and_image_parts() {
+        let messages: Vec<OpenAiMessage> = serde_json::from_value(json!([
+            { "role": "system", "content": "Be brief." },
+            { "role": "user", "content": "hi" },
+            { "role": "assistant", "content": "hello" },
+            { "role": "tool", "content": "client tool output" },
+            { "role": "user", "content": [
+                { "type": "text", "text": "what is this?" },
+                { "type": "image_url", "image_url": { "url": "data:image/png;base64,AAAA" } },
+                { "type": "input_audio", "input_audio": {} }
+            ]}
+        ]))
+        .unwrap();
+
+        let history = convert_messages("agent prompt", messages);
+        let roles: Vec<&str> = history.iter().map(|m| m.role.as_str()).collect();
+        assert_eq!(roles, ["system", "system", "user", "assistant", "user"]);
+        assert_eq!(history[0].content, "agent prompt");
+
+        let last = history.last().unwrap();
+        assert_eq!(last.content, "what is this?");
+        assert_eq!(
+            last.parts[1],
+            ContentPart::image(MediaSource::Base64 {
+                mime_type: "image/png".into(),
+                data: "AAAA".into(),
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn list_models_reports_configured_model() {
+        let response = handle_list_models(State(test_state(false)), HeaderMap::new()).await;
+        assert_eq!(response.status(), StatusCode::OK);
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["object"], "list");
+        assert_eq!(body["data"][0]["id"], "test-model");
+    }
+
+    #[tokio::test]
+    async fn chat_completions_requires_pairing_token() {
+        let response = handle_chat_completions(
+            State(test_state(true)),
+            HeaderMap::new(),
+            request(json!({ "messages": [{ "role": "user", "content": "hi" }] })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["error"]["type"], "invalid_api_key");
+    }
+
+    #[tokio::test]
+    async fn chat_completions_rejects_requests_without_user_message() {
+        let response = handle_chat_completions(
+            State(test_state(false)),
+            HeaderMap::new(),
+            request(json!({ "messages": [{ "role": "system", "content": "x" }] })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn chat_completions_returns_openai_shaped_reply() {
+        let response = handle_chat_completions(
+            State(test_state(false)),
+            HeaderMap::new(),
+            request(json!({
+                "model": "gpt-4o",
+                "messages": [{ "role": "user", "content": "ping" }]
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let body: serde_json::Value = serde_json::from_str(&body_text(response).await).unwrap();
+        assert_eq!(body["object"], "chat.completion");
+        assert_eq!(body["model"], "test-model");
+        assert_eq!(body["choices"][0]["message"]["role"], "assistant");
+        assert!(body["choices"][0]["message"]["content"]
+            .as_str()
+            .unwrap()
+            .contains("ping"));
+        assert_eq!(body["choices"][0]["finish_reason"], "stop");
+    }
+
+    #[tokio::test]
+    async fn dropping_the_stream_aborts_the_agent_task() {
+        struct SetOnDrop(Arc<std::sync::atomic::AtomicBool>);
+        impl Drop for SetOnDrop {
+            fn drop(&mut self) {
+                self.0.store(true, std::sync::atomic::Ordering::SeqCst);
+            }
+        }
+
+        let aborted = Arc::new(std::sync::atomic::AtomicBool::new(false));
+        let guard = SetOnDrop(Arc::clone(&aborted));
+        let task = tokio::spawn(async move {
+            let _guard = guard;
+            std::future::pending::<anyhow::Result<String>>().await
+        });
+        let (_tx, rx) = tokio::sync::mpsc::channel(1);
+        let state = StreamState {
+            pending: VecDeque::new(),
+            rx,
+            task: Some(task),
+            streamed_text: false,
+            id: "chatcmpl-test".into(),
+            created: 0,
+            model: "test-model".into(),
+        };
+
+        drop(state);
+        for _ in 0..10 {
+            if aborted.load(std::sync::atomic::Ordering::SeqCst) {
+                break;
+            }
+            tokio::task::yield_now().await;
+        }
+        assert!(aborted.load(std::sync::atomic::Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn chat_completions_streams_sse_chunks() {
+        let response = handle_chat_completions(
+            State(test_state(false)),
+            HeaderMap::new(),
+            request(json!({
+                "stream": true,
+                "messages": [{ "role": "user", "content": "ping" }]
+            })),
+        )
+        .await;
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(
+            response.headers()[header::CONTENT_TYPE],
+            "text/event-stream"
+        );
+
+        let text = body_text(response).await;
+        let payloads: Vec<&str> = text
+            .lines()
+            .filter_map(|line| line.strip_prefix("data: "))
+            .collect();
+        assert_eq!(payloads.last(), Some(&"[DONE]"));
+
+        let chunks: Vec<serde_json::Value> = payloads[..payloads.len() - 1]
+            .iter()
+            .map(|p| serde_json::from_str(p).unwrap())
+            .collect();
+        assert!(chunks
+            .iter()
+            .all(|c| c["object"] == "chat.completion.chunk"));
+        assert_eq!(chunks[0]["choices"][0]["delta"]["role"], "assistant");
+        let content: String = chunks
+            .iter()
+            .filter_map(|c| c["choices"][0]["delta"]["content"].as_str())
+            .collect();
+        assert!(content.contains("ping"));
+        assert_eq!(
+            chunks.last().unwrap()["choices"][0]["finish_reason"],
+            "stop"
+        );
+    }
+}
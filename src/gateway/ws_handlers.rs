@@ -0,0 +1,481 @@
+//! WebSocket chat (`GET /ws`).
+//!
+//! One agent session per connection, with a JSON protocol in both directions.
+//!
+//! Client → server:
+//! - `{"type": "message", "content": "..."}` starts a turn
+//! - `{"type": "approval", "id": "...", "decision": "yes" | "no" | "always"}`
+//!   answers an `approval_request`
+//!
+//! Server → client: `ready`, then per turn any number of `delta`,
+//! `tool_round`, `tool_start`, `tool_result` and `approval_request` frames,
+//! closed by `done` (the final answer) or `error`.
+//!
+//! Browsers cannot set headers on a WebSocket handshake, so the pairing token
+//! is also accepted as a `?token=` query parameter.
+
+use super::openai_handlers::{apply_memory, MAX_CHAT_BODY_SIZE};
+use super::{client_key_from_headers, AppState};
+use crate::agent::loop_::{run_tool_call_loop, trim_history, LoopOutput, StreamEvent};
+use crate::approval::{
+    approval_prompt_text, ApprovalManager, ApprovalPrompter, ApprovalRequest, ApprovalResponse,
+};
+use crate::providers::ChatMessage;
+use async_trait::async_trait;
+use axum::{
+    extract::{
+        ws::{Message, WebSocket, WebSocketUpgrade},
+        Query, State,
+    },
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
+};
+use futures_util::{SinkExt, StreamExt};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+use tokio::task::JoinHandle;
+use uuid::Uuid;
+
+#[derive(Debug, Deserialize)]
+pub struct WsQuery {
+    pub token: Option<String>,
+}
+
+/// Frames sent by the client.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ClientFrame {
+    Message {
+        content: String,
+    },
+    Approval {
+        id: String,
+        decision: ApprovalResponse,
+    },
+}
+
+/// Frames sent to the client.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ServerFrame {
+    Ready {
+        session_id: String,
+    },
+    /// Reply text appended since the previous delta.
+    Delta {
+        content: String,
+    },
+    /// The text streamed so far was narration before tool calls; the final
+    /// answer follows.
+    ToolRound,
+    ToolStart {
+        tool: String,
+    },
+    ToolResult {
+        tool: String,
+        success: bool,
+        duration_ms: u64,
+    },
+    ApprovalRequest {
+        id: String,
+        tool: String,
+        prompt: String,
+    },
+    Done {
+        content: String,
+    },
+    Error {
+        message: String,
+    },
+}
+
+type Outbound = mpsc::UnboundedSender<ServerFrame>;
+
+/// Approval prompts of one connection, answered by `approval` frames.
+struct WsApprovals {
+    out: Outbound,
+    timeout: Duration,
+    pending: Mutex<HashMap<String, oneshot::Sender<ApprovalResponse>>>,
+}
+
+impl WsApprovals {
+    fn resolve(&self, id: &str, decision: ApprovalResponse) -> bool {
+        match self.pending.lock().remove(id) {
+            Some(tx) => tx.send(decision).is_ok(),
+            None => false,
+        }
+    }
+}
+
+#[async_trait]
+impl ApprovalPrompter for WsApprovals {
+    async fn prompt(&self, request: &ApprovalRequest) -> ApprovalResponse {
+        let id = Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+        self.pending.lock().insert(id.clone(), tx);
+
+        let frame = ServerFrame::ApprovalRequest {
+            id: id.clone(),
+            tool: request.tool_name.clone(),
+            prompt: approval_prompt_text(request),
+        };
+        if self.out.send(frame).is_err() {
+            self.pending.lock().remove(&id);
+            return ApprovalResponse::No;
+        }
+
+        match tokio::time::timeout(self.timeout, rx).await {
+            Ok(Ok(answer)) => answer,
+            _ => {
+                self.pending.lock().remove(&id);
+                let _ = self.out.send(ServerFrame::Error {
+                    message: format!("No approval received; skipped {}.", request.tool_name),
+                });
+                ApprovalResponse::No
+            }
+        }
+    }
+}
+
+/// GET /ws — upgrade to a WebSocket chat session
+pub async fn handle_ws(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    Query(query): Query<WsQuery>,
+    ws: WebSocketUpgrade,
+) -> Response {
+    let client_key = client_key_from_headers(&headers);
+    if !state.rate_limiter.allow_webhook(&client_key) {
+        tracing::warn!("/ws rate limit exceeded for key: {client_key}");
+        let err = serde_json::json!({"error": "Too many requests. Please retry later."});
+        return (StatusCode::TOO_MANY_REQUESTS, Json(err)).into_response();
+    }
+
+    if state.pairing.require_pairing() {
+        let token = headers
+            .get(header::AUTHORIZATION)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|auth| auth.strip_prefix("Bearer "))
+            .or(query.token.as_deref())
+            .unwrap_or("");
+        if !state.pairing.is_authenticated(token) {
+            tracing::warn!("/ws: rejected — not paired / invalid bearer token");
+            let err = serde_json::json!({
+                "error": "Unauthorized — pair first via POST /pair, then send Authorization: Bearer <token> or ?token=<token>"
+            });
+            return (StatusCode::UNAUTHORIZED, Json(err)).into_response();
+        }
+    }
+
+    ws.max_message_size(MAX_CHAT_BODY_SIZE)
+        .on_upgrade(move |socket| handle_socket(state, socket))
+}
+
+async fn handle_socket(state: AppState, socket: WebSocket) {
+    let (mut sink, mut stream) = socket.split();
+    let (out_tx, mut out_rx) = mpsc::unbounded_channel::<ServerFrame>();
+    let (in_tx, in_rx) = mpsc::channel::<String>(16);
+
+    let writer = tokio::spawn(async move {
+        while let Some(frame) = out_rx.recv().await {
+            let Ok(text) = serde_json::to_string(&frame) else {
+                continue;
+            };
+            if sink.send(Message::Text(text.into())).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    let reader = tokio::spawn(async move {
+        while let Some(Ok(message)) = stream.next().await {
+            match message {
+                Message::Text(text) => {
+                    if in_tx.send(text.to_string()).await.is_err() {
+                        break;
+                    }
+                }
+                Message::Close(_) => break,
+                _ => {}
+            }
+        }
+    });
+
+    run_session(state, in_rx, out_tx).await;
+    reader.abort();
+    let _ = writer.await;
+}
+
+/// Drive one session: start turns for client messages and route approval
+/// answers while a turn runs. Returns when the client goes away.
+async fn run_session(state: AppState, mut inbound: mpsc::Receiver<String>, out: Outbound) {
+    let session_id = Uuid::new_v4().to_string();
+    let _ = out.send(ServerFrame::Ready {
+        session_id: session_id.clone(),
+    });
+
+    let approvals = Arc::new(WsApprovals {
+        out: out.clone(),
+        timeout: Duration::from_secs(state.autonomy.approval_timeout_secs),
+        pending: Mutex::new(HashMap::new()),
+    });
+    let approval_manager = Arc::new(ApprovalManager::from_config(&state.autonomy));
+    let mut history = vec![ChatMessage::system(state.system_prompt.as_ref())];
+    let mut turn: Option<JoinHandle<(Vec<ChatMessage>, anyhow::Result<String>)>> = None;
+
+    loop {
+        tokio::select! {
+            text = inbound.recv() => {
+                let Some(text) = text else { break };
+                match serde_json::from_str::<ClientFrame>(&text) {
+                    Ok(ClientFrame::Message { content }) => {
+                        if turn.is_some() {
+                            let _ = out.send(ServerFrame::Error {
+                                message: "A reply is still in progress".into(),
+                            });
+                            continue;
+                        }
+                        if content.trim().is_empty() {
+                            let _ = out.send(ServerFrame::Error {
+                                message: "Message content is empty".into(),
+                            });
+                            continue;
+                        }
+                        turn = Some(tokio::spawn(run_turn(
+                            state.clone(),
+                            std::mem::take(&mut history),
+                            content,
+                            out.clone(),
+                            Arc::clone(&approvals),
+                            Arc::clone(&approval_manager),
+                        )));
+                    }
+                    Ok(ClientFrame::Approval { id, decision }) => {
+                        if !approvals.resolve(&id, decision) {
+                            let _ = out.send(ServerFrame::Error {
+                                message: format!("No pending approval with id {id}"),
+                            });
+                        }
+                    }
+                    Err(e) => {
+                        let _ = out.send(ServerFrame::Error {
+                            message: format!("Invalid frame: {e}"),
+                        });
+                    }
+                }
+            }
+            finished = async { turn.as_mut().expect("guarded by select condition").await }, if turn.is_some() => {
+                turn = None;
+                match finished {
+                    Ok((next_history, result)) => {
+                        history = next_history;
+                        let frame = match result {
+                            Ok(content) => ServerFrame::Done { content },
+                            Err(e) => ServerFrame::Error {
+                                message: crate::providers::sanitize_api_error(&e.to_string()),
+                            },
+                        };
+                        let _ = out.send(frame);
+                    }
+                    Err(e) => {
+                        tracing::error!("/ws session {session_id}: turn task failed: {e}");
+                        history = vec![ChatMessage::system(state.system_prompt.as_ref())];
+                        let _ = out.send(ServerFrame::Error {
+                            message: "Internal error; session history was reset".into(),
+                        });
+                    }
+                }
+            }
+        }
+    }
+
+    if let Some(turn) = turn {
+        turn.abort();
+    }
+}
+
+/// Run one user message through the agent loop, streaming progress to `out`.
+async fn run_turn(
+    state: AppState,
+    mut history: Vec<ChatMessage>,
+    content: String,
+    out: Outbound,
+    approvals: Arc<WsApprovals>,
+    approval_manager: Arc<ApprovalManager>,
+) -> (Vec<ChatMessage>, anyhow::Result<String>) {
+    let turn_start = history.len();
+    history.push(ChatMessage::user(content.as_str()));
+    apply_memory(&state, &mut history).await;
+
+    // Text and tool progress share one channel so frames keep their order.
+    let (stream_tx, mut stream_rx) = mpsc::channel(64);
+    let relay = tokio::spawn(async move {
+        while let Some(event) = stream_rx.recv().await {
+            let _ = out.send(stream_frame(event));
+        }
+    });
+
+    let result = Box::pin(run_tool_call_loop(
+        state.provider.as_ref(),
+        &mut history,
+        state.tools_registry.as_ref(),
+        state.observer.as_ref(),
+        &state.provider_name,
+        &state.model,
+        state.temperature,
+        LoopOutput::SILENT,
+        Some(approval_manager.as_ref()),
+        Some(approvals.as_ref() as &dyn ApprovalPrompter),
+        "ws",
+        state.cost_tracker.as_deref(),
+        state.response_cache.as_deref(),
+        Some(&stream_tx),
+        state.max_parallel_tools,
+    ))
+    .await;
+    drop(stream_tx);
+    let _ = relay.await;
+
+    if result.is_ok() {
+        // Keep the raw text so recalled memory doesn't pile up across turns.
+        history[turn_start] = ChatMessage::user(content);
+    } else {
+        history.truncate(turn_start);
+    }
+    trim_history(&mut history, state.max_history_messages);
+
+    (history, result)
+}
+
+fn stream_frame(event: StreamEvent) -> ServerFrame {
+    match event {
+        StreamEvent::Delta(content) => ServerFrame::Delta { content },
+        StreamEvent::ToolRound => ServerFrame::ToolRound,
+        StreamEvent::ToolStart { tool } => ServerFrame::ToolStart { tool },
+        StreamEvent::ToolResult {
+            tool,
+            success,
+            duration,
+        } => ServerFrame::ToolResult {
+            tool,
+            success,
+            duration_ms: u64::try_from(duration.as_millis()).unwrap_or(u64::MAX),
+        },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn request() -> ApprovalRequest {
+        ApprovalRequest {
+            tool_name: "shell".into(),
+            arguments: json!({"command": "ls"}),
+        }
+    }
+
+    #[test]
+    fn client_frames_parse_messages_and_approvals() {
+        let frame: ClientFrame =
+            serde_json::from_value(json!({"type": "message", "content": "hi"})).unwrap();
+        assert!(matches!(frame, ClientFrame::Message { content } if content == "hi"));
+
+        let frame: ClientFrame =
+            serde_json::from_value(json!({"type": "approval", "id": "a1", "decision": "always"}))
+                .unwrap();
+        assert!(matches!(
+            frame,
+            ClientFrame::Approval { id, decision: ApprovalResponse::Always } if id == "a1"
+        ));
+
+        assert!(serde_json::from_value::<ClientFrame>(json!({"type": "nope"})).is_err());
+    }
+
+    #[test]
+    fn server_frames_serialize_with_type_tag() {
+        let frame = ServerFrame::ToolResult {
+            tool: "shell".into(),
+            success: true,
+            duration_ms: 12,
+        };
+        assert_eq!(
+            serde_json::to_value(&frame).unwrap(),
+            json!({"type": "tool_result", "tool": "shell", "success": true, "duration_ms": 12})
+        );
+        assert_eq!(
+            serde_json::to_value(ServerFrame::ToolRound).unwrap(),
+            json!({"type": "tool_round"})
+        );
+    }
+
+    #[test]
+    fn stream_events_map_to_frames() {
+        assert_eq!(
+            stream_frame(StreamEvent::Delta("hi".into())),
+            ServerFrame::Delta {
+                content: "hi".into()
+            }
+        );
+        assert_eq!(
+            stream_frame(StreamEvent::ToolResult {
+                tool: "shell".into(),
+                success: true,
+                duration: Duration::from_millis(12),
+            }),
+            ServerFrame::ToolResult {
+                tool: "shell".into(),
+                success: true,
+                duration_ms: 12,
+            }
+        );
+    }
+
+    #[tokio::test]
+    async fn approval_prompt_waits_for_matching_answer() {
+        let (out, mut frames) = mpsc::unbounded_channel();
+        let approvals = Arc::new(WsApprovals {
+            out,
+            timeout: Duration::from_secs(5),
+            pending: Mutex::new(HashMap::new()),
+        });
+
+        let prompter = Arc::clone(&approvals);
+        let pending = tokio::spawn(async move { prompter.prompt(&request()).await });
+
+        let Some(ServerFrame::ApprovalRequest { id, tool, .. }) = frames.recv().await else {
+            panic!("expected an approval request");
+        };
+        assert_eq!(tool, "shell");
+        assert!(!approvals.resolve("unknown", ApprovalResponse::Yes));
+        assert!(approvals.resolve(&id, ApprovalResponse::Yes));
+        assert_eq!(pending.await.unwrap(), ApprovalResponse::Yes);
+    }
+
+    #[tokio::test]
+    async fn approval_prompt_denies_on_timeout() {
+        let (out, mut frames) = mpsc::unbounded_channel();
+        let approvals = WsApprovals {
+            out,
+            timeout: Duration::from_millis(10),
+            pending: Mutex::new(HashMap::new()),
+        };
+
+        assert_eq!(approvals.prompt(&request()).await, ApprovalResponse::No);
+        assert!(approvals.pending.lock().is_empty());
+        assert!(matches!(
+            frames.recv().await,
+            Some(ServerFrame::ApprovalRequest { .. })
+        ));
+        assert!(matches!(
+            frames.recv().await,
+            Some(ServerFrame::Error { .. })
+        ));
+    }
+}
@@ -1,11 +1,71 @@
 use crate::config::HeartbeatConfig;
+use crate::heartbeat::store::{self, TaskRun};
 use crate::observability::{Observer, ObserverEvent};
 use anyhow::Result;
+use chrono::{DateTime, Utc};
+use std::future::Future;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::time::{self, Duration};
 use tracing::{info, warn};
 
+/// A single `- ` line from HEARTBEAT.md.
+///
+/// A leading `[every 2h]` annotation (units `m`, `h`, `d`) limits how often the
+/// task runs; tasks without one run on every tick.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HeartbeatTask {
+    pub text: String,
+    pub every: Option<chrono::Duration>,
+}
+
+impl HeartbeatTask {
+    pub fn parse(raw: &str) -> Self {
+        let raw = raw.trim();
+        let annotated = raw.strip_prefix('[').and_then(|rest| {
+            let (annotation, text) = rest.split_once(']')?;
+            let every = parse_every(annotation)?;
+            let text = text.trim();
+            (!text.is_empty()).then(|| (text, every))
+        });
+
+        match annotated {
+            Some((text, every)) => Self {
+                text: text.to_string(),
+                every: Some(every),
+            },
+            None => Self {
+                text: raw.to_string(),
+                every: None,
+            },
+        }
+    }
+
+    /// Whether the task should run now given when it last ran.
+    ///
+    /// A minute of slack keeps a `[every 1h]` task from slipping a whole tick
+    /// when the previous run finished slightly after the tick boundary.
+    pub fn is_due(&self, last_run: Option<DateTime<Utc>>, now: DateTime<Utc>) -> bool {
+        match (self.every, last_run) {
+            (Some(every), Some(last)) => now - last + chrono::Duration::minutes(1) >= every,
+            _ => true,
+        }
+    }
+}
+
+fn parse_every(annotation: &str) -> Option<chrono::Duration> {
+    let spec = annotation.trim().strip_prefix("every")?.trim();
+    let unit_at = spec.find(|c: char| !c.is_ascii_digit())?;
+    let (amount, unit) = spec.split_at(unit_at);
+    let amount: i64 = amount.parse().ok().filter(|n| *n > 0)?;
+    match unit.trim() {
+        "m" | "min" | "mins" | "minutes" => chrono::Duration::try_minutes(amount),
+        "h" | "hr" | "hrs" | "hours" => chrono::Duration::try_hours(amount),
+        "d" | "day" | "days" => chrono::Duration::try_days(amount),
+        _ => None,
+    }
+}
+
 /// Heartbeat engine — reads HEARTBEAT.md and executes tasks periodically
 pub struct HeartbeatEngine {
     config: HeartbeatConfig,
@@ -26,8 +86,14 @@ impl HeartbeatEngine {
         }
     }
 
-    /// Start the heartbeat loop (runs until cancelled)
-    pub async fn run(&self) -> Result<()> {
+    /// Start the heartbeat loop (runs until cancelled).
+    ///
+    /// `execute` runs one task prompt and returns the agent's reply.
+    pub async fn run<F, Fut>(&self, execute: F) -> Result<()>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
         if !self.config.enabled {
             info!("Heartbeat disabled");
             return Ok(());
@@ -42,7 +108,7 @@ impl HeartbeatEngine {
             interval.tick().await;
             self.observer.record_event(&ObserverEvent::HeartbeatTick);
 
-            match self.tick().await {
+            match self.tick(&execute).await {
                 Ok(tasks) => {
                     if tasks > 0 {
                         info!("💓 Heartbeat: processed {} tasks", tasks);
@@ -59,19 +125,69 @@ impl HeartbeatEngine {
         }
     }
 
-    /// Single heartbeat tick — read HEARTBEAT.md and return task count
-    async fn tick(&self) -> Result<usize> {
-        Ok(self.collect_tasks().await?.len())
+    /// Single heartbeat tick — run every due task and return how many ran
+    async fn tick<F, Fut>(&self, execute: &F) -> Result<usize>
+    where
+        F: Fn(String) -> Fut,
+        Fut: Future<Output = Result<String>>,
+    {
+        let tasks = self.collect_tasks().await?;
+        if tasks.is_empty() {
+            return Ok(0);
+        }
+
+        let runs = store::load_runs(&self.workspace_dir).unwrap_or_else(|e| {
+            warn!("💓 Heartbeat: ignoring unreadable run history: {e}");
+            std::collections::BTreeMap::default()
+        });
+
+        let mut executed = 0;
+        for task in tasks {
+            let last_run = runs.get(&task.text).and_then(TaskRun::last_run);
+            if !task.is_due(last_run, Utc::now()) {
+                continue;
+            }
+
+            let started_at = Utc::now();
+            let result = execute(task.text.clone()).await;
+            let finished_at = Utc::now();
+            executed += 1;
+
+            let run = match &result {
+                Ok(output) => {
+                    crate::health::mark_component_ok("heartbeat");
+                    TaskRun::new(started_at, finished_at, true, output)
+                }
+                Err(e) => {
+                    warn!("💓 Heartbeat task failed ({}): {e}", task.text);
+                    crate::health::mark_component_error("heartbeat", e.to_string());
+                    self.observer.record_event(&ObserverEvent::Error {
+                        component: "heartbeat".into(),
+                        message: format!("{}: {e}", task.text),
+                    });
+                    TaskRun::new(started_at, finished_at, false, &e.to_string())
+                }
+            };
+            // A history write failure must not starve the remaining tasks.
+            if let Err(e) = store::record_run(&self.workspace_dir, &task.text, run) {
+                warn!("💓 Heartbeat: failed to record run of '{}': {e}", task.text);
+            }
+        }
+
+        Ok(executed)
     }
 
     /// Read HEARTBEAT.md and return all parsed tasks.
-    pub async fn collect_tasks(&self) -> Result<Vec<String>> {
+    pub async fn collect_tasks(&self) -> Result<Vec<HeartbeatTask>> {
         let heartbeat_path = self.workspace_dir.join("HEARTBEAT.md");
         if !heartbeat_path.exists() {
             return Ok(Vec::new());
         }
         let content = tokio::fs::read_to_string(&heartbeat_path).await?;
-        Ok(Self::parse_tasks(&content))
+        Ok(Self::parse_tasks(&content)
+            .iter()
+            .map(|raw| HeartbeatTask::parse(raw))
+            .collect())
     }
 
     /// Parse tasks from HEARTBEAT.md (lines starting with `- `)
@@ -92,11 +208,12 @@ impl HeartbeatEngine {
             let default = "# Periodic Tasks\n\n\
                            # Add tasks below (one per line, starting with `- `)\n\
                            # The agent will check this file on each heartbeat tick.\n\
+                           # Prefix a task with [every 2h] (or 30m, 1d) to run it less often.\n\
                            #\n\
                            # Examples:\n\
                            # - Check my email for important messages\n\
-                           # - Review my calendar for upcoming events\n\
-                           # - Check the weather forecast\n";
+                           # - [every 4h] Review my calendar for upcoming events\n\
+                           # - [every 1d] Check the weather forecast\n";
             tokio::fs::write(&path, default).await?;
         }
         Ok(())
@@ -107,6 +224,10 @@ impl HeartbeatEngine {
 mod tests {
     use super::*;
 
+    async fn echo(task: String) -> Result<String> {
+        Ok(format!("done: {task}"))
+    }
+
     #[test]
     fn parse_tasks_basic() {
         let content = "# Tasks\n\n- Check email\n- Review calendar\nNot a task\n- Third task";
@@ -248,11 +369,12 @@ mod tests {
             HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 30,
+                ..HeartbeatConfig::default()
             },
             dir.clone(),
             observer,
         );
-        let count = engine.tick().await.unwrap();
+        let count = engine.tick(&echo).await.unwrap();
         assert_eq!(count, 0);
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
@@ -273,11 +395,12 @@ mod tests {
             HeartbeatConfig {
                 enabled: true,
                 interval_minutes: 30,
+                ..HeartbeatConfig::default()
             },
             dir.clone(),
             observer,
         );
-        let count = engine.tick().await.unwrap();
+        let count = engine.tick(&echo).await.unwrap();
         assert_eq!(count, 3);
 
         let _ = tokio::fs::remove_dir_all(&dir).await;
@@ -290,12 +413,123 @@ mod tests {
             HeartbeatConfig {
                 enabled: false,
                 interval_minutes: 30,
+                ..HeartbeatConfig::default()
             },
             std::env::temp_dir(),
             observer,
         );
         // Should return Ok immediately, not loop forever
-        let result = engine.run().await;
+        let result = engine.run(echo).await;
         assert!(result.is_ok());
     }
+
+    #[test]
+    fn heartbeat_task_parses_cadence_annotation() {
+        let task = HeartbeatTask::parse("[every 2h] check inbox");
+        assert_eq!(task.text, "check inbox");
+        assert_eq!(task.every, Some(chrono::Duration::hours(2)));
+
+        assert_eq!(
+            HeartbeatTask::parse("[every 30m] ping").every,
+            Some(chrono::Duration::minutes(30))
+        );
+        assert_eq!(
+            HeartbeatTask::parse("[every 1 days] report").every,
+            Some(chrono::Duration::days(1))
+        );
+    }
+
+    #[test]
+    fn heartbeat_task_keeps_unrecognized_brackets_as_text() {
+        for raw in [
+            "[urgent] call back",
+            "[every 0h] nope",
+            "[every 2w] later",
+            "[every 1h]",
+        ] {
+            let task = HeartbeatTask::parse(raw);
+            assert_eq!(task.text, raw);
+            assert!(task.every.is_none(), "{raw} should have no cadence");
+        }
+    }
+
+    #[test]
+    fn heartbeat_task_is_due_respects_cadence() {
+        let now = Utc::now();
+        let plain = HeartbeatTask::parse("check inbox");
+        assert!(plain.is_due(Some(now), now));
+
+        let hourly = HeartbeatTask::parse("[every 1h] check inbox");
+        assert!(hourly.is_due(None, now));
+        assert!(!hourly.is_due(Some(now - chrono::Duration::minutes(30)), now));
+        assert!(hourly.is_due(Some(now - chrono::Duration::minutes(59)), now));
+    }
+
+    #[tokio::test]
+    async fn tick_records_outcomes_and_skips_tasks_not_yet_due() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(
+            tmp.path().join("HEARTBEAT.md"),
+            "- [every 1d] daily digest\n- fails",
+        )
+        .await
+        .unwrap();
+
+        let engine = HeartbeatEngine::new(
+            HeartbeatConfig {
+                enabled: true,
+                ..HeartbeatConfig::default()
+            },
+            tmp.path().to_path_buf(),
+            Arc::new(crate::observability::NoopObserver),
+        );
+        let execute = |task: String| async move {
+            if task == "fails" {
+                anyhow::bail!("provider unavailable")
+            }
+            Ok(format!("done: {task}"))
+        };
+
+        assert_eq!(engine.tick(&execute).await.unwrap(), 2);
+        let runs = store::load_runs(tmp.path()).unwrap();
+        assert!(runs["daily digest"].success);
+        assert_eq!(runs["daily digest"].output, "done: daily digest");
+        assert!(!runs["fails"].success);
+        assert!(runs["fails"].output.contains("provider unavailable"));
+
+        // The daily task already ran; only the uncadenced task runs again.
+        assert_eq!(engine.tick(&execute).await.unwrap(), 1);
+    }
+
+    #[tokio::test]
+    async fn tick_keeps_running_tasks_when_history_cannot_be_written() {
+        let tmp = tempfile::tempdir().unwrap();
+        tokio::fs::write(tmp.path().join("HEARTBEAT.md"), "- first\n- second")
+            .await
+            .unwrap();
+        // A file where the state directory should be makes every write fail.
+        tokio::fs::write(tmp.path().join("state"), "")
+            .await
+            .unwrap();
+
+        let engine = HeartbeatEngine::new(
+            HeartbeatConfig {
+                enabled: true,
+                ..HeartbeatConfig::default()
+            },
+            tmp.path().to_path_buf(),
+            Arc::new(crate::observability::NoopObserver),
+        );
+        let ran = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let execute = |task: String| {
+            let ran = Arc::clone(&ran);
+            async move {
+                ran.lock().push(task);
+                Ok(String::new())
+            }
+        };
+
+        assert_eq!(engine.tick(&execute).await.unwrap(), 2);
+        assert_eq!(*ran.lock(), vec!["first".to_string(), "second".to_string()]);
+    }
 }
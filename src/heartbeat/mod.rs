@@ -1,4 +1,5 @@
 pub mod engine;
+pub mod store;
 
 #[cfg(test)]
 mod tests {
@@ -0,0 +1,165 @@
+//! Last outcome of each heartbeat task.
+//!
+//! Kept in `state/heartbeat_runs.json` under the workspace, keyed by task
+//! text. The engine reads it to honor per-task cadences across restarts and
+//! `zeroclaw doctor` reads it to report how each task last went.
+
+use crate::util::truncate_with_ellipsis;
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const STATE_FILE: &str = "heartbeat_runs.json";
+const OUTPUT_PREVIEW_CHARS: usize = 200;
+
+/// The most recent run of one heartbeat task.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TaskRun {
+    pub last_run_at: String,
+    pub success: bool,
+    /// Agent reply or error message, shortened for display.
+    pub output: String,
+    pub duration_ms: i64,
+}
+
+impl TaskRun {
+    pub fn new(
+        started_at: DateTime<Utc>,
+        finished_at: DateTime<Utc>,
+        success: bool,
+        output: &str,
+    ) -> Self {
+        Self {
+            last_run_at: started_at.to_rfc3339(),
+            success,
+            output: truncate_with_ellipsis(output.trim(), OUTPUT_PREVIEW_CHARS),
+            duration_ms: (finished_at - started_at).num_milliseconds(),
+        }
+    }
+
+    pub fn last_run(&self) -> Option<DateTime<Utc>> {
+        DateTime::parse_from_rfc3339(&self.last_run_at)
+            .ok()
+            .map(|dt| dt.with_timezone(&Utc))
+    }
+}
+
+/// Load recorded runs. A missing file means nothing has run yet.
+pub fn load_runs(workspace_dir: &Path) -> Result<BTreeMap<String, TaskRun>> {
+    let path = state_path(workspace_dir);
+    if !path.exists() {
+        return Ok(BTreeMap::new());
+    }
+    let raw =
+        fs::read_to_string(&path).with_context(|| format!("Failed to read {}", path.display()))?;
+    serde_json::from_str(&raw).with_context(|| format!("Invalid JSON in {}", path.display()))
+}
+
+/// Record the outcome of `task`, replacing its previous run.
+///
+/// A state file that no longer parses is moved aside to `.bak` rather than
+/// overwritten, so the history in it can still be recovered by hand.
+pub fn record_run(workspace_dir: &Path, task: &str, run: TaskRun) -> Result<()> {
+    let path = state_path(workspace_dir);
+    let mut runs = match load_runs(workspace_dir) {
+        Ok(runs) => runs,
+        Err(e) if e.downcast_ref::<serde_json::Error>().is_some() => {
+            let backup = path.with_extension("json.bak");
+            fs::rename(&path, &backup)
+                .with_context(|| format!("Failed to move corrupt {} aside", path.display()))?;
+            tracing::warn!("{e:#}; moved it to {}", backup.display());
+            BTreeMap::new()
+        }
+        Err(e) => return Err(e),
+    };
+    runs.insert(task.to_string(), run);
+
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(&path, serde_json::to_vec_pretty(&runs)?)?;
+    Ok(())
+}
+
+pub fn state_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("state").join(STATE_FILE)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn load_runs_without_file_is_empty() {
+        let tmp = TempDir::new().unwrap();
+        assert!(load_runs(tmp.path()).unwrap().is_empty());
+    }
+
+    #[test]
+    fn record_run_replaces_previous_outcome() {
+        let tmp = TempDir::new().unwrap();
+        let start = Utc::now();
+        let end = start + chrono::Duration::milliseconds(1500);
+
+        record_run(
+            tmp.path(),
+            "check inbox",
+            TaskRun::new(start, end, false, "boom"),
+        )
+        .unwrap();
+        record_run(
+            tmp.path(),
+            "check inbox",
+            TaskRun::new(start, end, true, "all quiet"),
+        )
+        .unwrap();
+        record_run(
+            tmp.path(),
+            "water plants",
+            TaskRun::new(start, end, true, "done"),
+        )
+        .unwrap();
+
+        let runs = load_runs(tmp.path()).unwrap();
+        assert_eq!(runs.len(), 2);
+        let inbox = &runs["check inbox"];
+        assert!(inbox.success);
+        assert_eq!(inbox.output, "all quiet");
+        assert_eq!(inbox.duration_ms, 1500);
+        assert_eq!(
+            inbox.last_run().unwrap().timestamp_millis(),
+            start.timestamp_millis()
+        );
+    }
+
+    #[test]
+    fn record_run_moves_corrupt_state_aside() {
+        let tmp = TempDir::new().unwrap();
+        let path = state_path(tmp.path());
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "{not json").unwrap();
+
+        let now = Utc::now();
+        record_run(
+            tmp.path(),
+            "check inbox",
+            TaskRun::new(now, now, true, "ok"),
+        )
+        .unwrap();
+
+        let backup = path.with_extension("json.bak");
+        assert_eq!(fs::read_to_string(backup).unwrap(), "{not json");
+        assert_eq!(load_runs(tmp.path()).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn task_run_shortens_long_output() {
+        let now = Utc::now();
+        let run = TaskRun::new(now, now, true, &"x".repeat(1000));
+        assert!(run.output.chars().count() <= OUTPUT_PREVIEW_CHARS + 3);
+    }
+}
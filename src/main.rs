@@ -46,6 +46,7 @@ mod rag {
     pub use zeroclaw::rag::*;
 }
 mod config;
+mod cost;
 mod cron;
 mod daemon;
 mod doctor;
@@ -183,12 +184,25 @@ enum Commands {
     /// Show system status (full details)
     Status,
 
+    /// Show token spend against the configured budgets
+    Cost {
+        /// Number of days to include in the daily breakdown
+        #[arg(long, default_value_t = 7)]
+        days: u32,
+    },
+
     /// Configure and manage scheduled tasks
     Cron {
         #[command(subcommand)]
         cron_command: CronCommands,
     },
 
+    /// Inspect and manage stored memories
+    Memory {
+        #[command(subcommand)]
+        memory_command: MemoryCommands,
+    },
+
     /// Manage provider model catalogs
     Models {
         #[command(subcommand)]
@@ -301,6 +315,131 @@ enum CronCommands {
     },
 }
 
+#[derive(Subcommand, Debug)]
+enum MemoryCommands {
+    /// List stored memories
+    List {
+        /// Only entries in this category (core, daily, conversation, or a custom name)
+        #[arg(long)]
+        category: Option<String>,
+        /// Only entries from this session
+        #[arg(long)]
+        session: Option<String>,
+        /// Maximum number of entries to show
+        #[arg(long, default_value_t = 50)]
+        limit: usize,
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Search memories by relevance to a query
+    Search {
+        /// Search query
+        query: String,
+        /// Only entries in this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Only entries from this session
+        #[arg(long)]
+        session: Option<String>,
+        /// Maximum number of results
+        #[arg(long, default_value_t = 10)]
+        limit: usize,
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Show a single memory by key
+    Get {
+        /// Memory key
+        key: String,
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Store a memory (overwrites an existing key)
+    Store {
+        /// Memory key
+        key: String,
+        /// Memory content
+        content: String,
+        /// Category (core, daily, conversation, or a custom name)
+        #[arg(long, default_value = "core")]
+        category: String,
+        /// Session to scope the memory to
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Delete a memory by key
+    Forget {
+        /// Memory key
+        key: String,
+    },
+    /// Show backend health and entry counts per category
+    Stats {
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Rebuild full-text and vector indexes
+    Reindex,
+    /// Ingest a file or directory into the `docs` knowledge base (incremental)
+    Ingest {
+        /// File or directory to ingest
+        path: std::path::PathBuf,
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Merge clusters of near-duplicate memories into core facts
+    Consolidate {
+        /// Show the clusters that would be merged without calling the LLM
+        #[arg(long)]
+        dry_run: bool,
+        /// Approximate token budget (defaults to memory.consolidation_token_budget)
+        #[arg(long)]
+        budget: Option<usize>,
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Encrypt existing memory stores in place and enable encryption at rest
+    Encrypt {
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Decrypt memory stores back to plaintext and disable encryption at rest
+    Decrypt {
+        /// Print JSON instead of human-readable output
+        #[arg(long)]
+        json: bool,
+    },
+    /// Export memories as JSONL (one entry per line) or a Markdown snapshot
+    Export {
+        /// Output file (defaults to stdout)
+        #[arg(long)]
+        output: Option<std::path::PathBuf>,
+        /// Export format: jsonl or markdown
+        #[arg(long, default_value = "jsonl")]
+        format: String,
+        /// Only entries in this category
+        #[arg(long)]
+        category: Option<String>,
+        /// Only entries from this session
+        #[arg(long)]
+        session: Option<String>,
+    },
+    /// Import memories from a JSONL export or Markdown snapshot
+    Import {
+        /// File to import
+        input: std::path::PathBuf,
+        /// Import format: jsonl or markdown (defaults to the file extension)
+        #[arg(long)]
+        format: Option<String>,
+    },
+}
+
 #[derive(Subcommand, Debug)]
 enum ModelCommands {
     /// Refresh and cache provider models
@@ -500,6 +639,25 @@ async fn main() -> Result<()> {
                 config.memory.backend,
                 if config.memory.auto_save { "on" } else { "off" }
             );
+            let response_cache = if config.memory.response_cache_enabled {
+                memory::ResponseCache::new(
+                    &config.workspace_dir,
+                    config.memory.response_cache_ttl_minutes,
+                    config.memory.response_cache_max_entries,
+                )
+                .and_then(|cache| Ok((cache.stats()?, cache.lookups()?)))
+                .map_or_else(
+                    |e| format!("unavailable ({e})"),
+                    |((entries, _, tokens_saved), (hits, misses))| {
+                        format!(
+                            "{entries} entries, {hits} hits / {misses} misses, ~{tokens_saved} tokens saved"
+                        )
+                    },
+                )
+            } else {
+                "disabled".into()
+            };
+            println!("💾 Response cache: {response_cache}");
 
             println!();
             println!("Security:");
@@ -549,8 +707,14 @@ async fn main() -> Result<()> {
             Ok(())
         }
 
+        Commands::Cost { days } => cost::report::run(&config, days),
+
         Commands::Cron { cron_command } => cron::handle_command(cron_command, &config),
 
+        Commands::Memory { memory_command } => {
+            memory::cli::handle_command(memory_command, &config).await
+        }
+
         Commands::Models { model_command } => match model_command {
             ModelCommands::Refresh { provider, force } => {
                 onboard::run_models_refresh(&config, provider.as_deref(), force)
@@ -106,6 +106,52 @@ pub fn chunk_markdown(text: &str, max_tokens: usize) -> Vec<Chunk> {
     chunks
 }
 
+/// Split plain text or source code into chunks, without heading detection.
+///
+/// Blank-line separated blocks are packed together up to `max_tokens`;
+/// blocks that are still too large are split on line boundaries.
+pub fn chunk_plain(text: &str, max_tokens: usize) -> Vec<Chunk> {
+    if text.trim().is_empty() {
+        return Vec::new();
+    }
+
+    let max_chars = max_tokens * 4;
+    let mut pieces = Vec::new();
+    let mut current = String::new();
+
+    for block in split_on_blank_lines(text) {
+        if current.len() + block.len() > max_chars && !current.trim().is_empty() {
+            pieces.push(std::mem::take(&mut current));
+        }
+
+        if block.len() > max_chars {
+            if !current.trim().is_empty() {
+                pieces.push(std::mem::take(&mut current));
+            }
+            pieces.extend(split_on_lines(&block, max_chars));
+        } else {
+            current.push_str(&block);
+            current.push('\n');
+        }
+    }
+
+    if !current.trim().is_empty() {
+        pieces.push(current);
+    }
+
+    pieces
+        .into_iter()
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty())
+        .enumerate()
+        .map(|(index, content)| Chunk {
+            index,
+            content,
+            heading: None,
+        })
+        .collect()
+}
+
 /// Split text into `(heading, body)` sections.
 fn split_on_headings(text: &str) -> Vec<(Option<String>, String)> {
     let mut sections = Vec::new();
@@ -374,4 +420,31 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn plain_ignores_hash_comments() {
+        let text = "# not a heading\nfn main() {}\n\n# another comment\nlet x = 1;";
+        let chunks = chunk_plain(text, 512);
+        assert_eq!(chunks.len(), 1);
+        assert!(chunks[0].heading.is_none());
+        assert!(chunks[0].content.contains("fn main()"));
+        assert!(chunks[0].content.contains("let x = 1;"));
+    }
+
+    #[test]
+    fn plain_splits_large_text() {
+        let block = "line of source code\n".repeat(20);
+        let text = format!("{block}\n{block}\n{block}");
+        let chunks = chunk_plain(&text, 50);
+        assert!(chunks.len() > 1);
+        for (i, chunk) in chunks.iter().enumerate() {
+            assert_eq!(chunk.index, i);
+            assert!(chunk.content.len() <= 200);
+        }
+    }
+
+    #[test]
+    fn plain_empty_text() {
+        assert!(chunk_plain("  \n\n ", 512).is_empty());
+    }
 }
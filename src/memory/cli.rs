@@ -0,0 +1,683 @@
+//! `zeroclaw memory` — inspect and manage stored memories from the shell.
+//!
+//! Everything goes through the [`Memory`] trait, so the commands behave the
+//! same for the sqlite, lucid and markdown backends.
+
+use super::{
+    consolidate, encryption, ingest, snapshot, Memory, MemoryCategory, MemoryCipher, MemoryEntry,
+    MemoryMetadata, RecallFilter,
+};
+use crate::config::Config;
+use crate::util::truncate_with_ellipsis;
+use anyhow::{bail, Context, Result};
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write;
+use std::path::Path;
+
+/// Content preview length in human-readable listings.
+const PREVIEW_CHARS: usize = 120;
+
+/// On-disk formats understood by `export` / `import`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    /// One serialized `MemoryEntry` per line
+    Jsonl,
+    /// `MEMORY_SNAPSHOT.md` layout (key and content only)
+    Markdown,
+}
+
+impl Format {
+    fn parse(raw: &str) -> Result<Self> {
+        match raw.trim().to_ascii_lowercase().as_str() {
+            "jsonl" | "json" => Ok(Self::Jsonl),
+            "markdown" | "md" => Ok(Self::Markdown),
+            other => bail!("Unknown memory format '{other}' (expected jsonl or markdown)"),
+        }
+    }
+
+    fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|e| e.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+                Self::Markdown
+            }
+            _ => Self::Jsonl,
+        }
+    }
+}
+
+pub async fn handle_command(command: crate::MemoryCommands, config: &Config) -> Result<()> {
+    let cipher = MemoryCipher::from_config(config)?;
+    let mem = super::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        cipher.clone(),
+    )?;
+    run(command, mem.as_ref(), config, cipher.as_deref()).await
+}
+
+#[allow(clippy::too_many_lines)]
+async fn run(
+    command: crate::MemoryCommands,
+    mem: &dyn Memory,
+    config: &Config,
+    cipher: Option<&MemoryCipher>,
+) -> Result<()> {
+    match command {
+        crate::MemoryCommands::List {
+            category,
+            session,
+            limit,
+            json,
+        } => {
+            let category = category.as_deref().map(parse_category);
+            let mut entries = mem.list(category.as_ref(), session.as_deref()).await?;
+            entries.truncate(limit);
+            print_entries(&entries, json)
+        }
+        crate::MemoryCommands::Search {
+            query,
+            category,
+            session,
+            limit,
+            json,
+        } => {
+            let entries = search(
+                mem,
+                &query,
+                category.as_deref().map(parse_category).as_ref(),
+                session.as_deref(),
+                limit,
+            )
+            .await?;
+            print_entries(&entries, json)
+        }
+        crate::MemoryCommands::Get { key, json } => {
+            let Some(entry) = mem.get(&key).await? else {
+                bail!("No memory with key '{key}'");
+            };
+            if json {
+                println!("{}", serde_json::to_string_pretty(&entry)?);
+            } else {
+                println!("🔑 {}", entry.key);
+                println!("   Category:  {}", entry.category);
+                if let Some(session) = &entry.session_id {
+                    println!("   Session:   {session}");
+                }
+                println!("   Updated:   {}", entry.timestamp);
+                println!("   ID:        {}", entry.id);
+                let originals = consolidate::provenance(&config.workspace_dir, &entry.key, cipher)
+                    .unwrap_or_default();
+                if !originals.is_empty() {
+                    println!("   Merged from {} archived memories:", originals.len());
+                    for original in &originals {
+                        println!(
+                            "     - {} [{}] {}",
+                            original.key,
+                            original.category,
+                            truncate_with_ellipsis(&original.content, PREVIEW_CHARS)
+                        );
+                    }
+                }
+                println!();
+                println!("{}", entry.content);
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Store {
+            key,
+            content,
+            category,
+            session,
+        } => {
+            mem.store(
+                &key,
+                &content,
+                parse_category(&category),
+                session.as_deref(),
+            )
+            .await?;
+            println!("✅ Stored memory '{key}' ({})", mem.name());
+            Ok(())
+        }
+        crate::MemoryCommands::Forget { key } => {
+            if mem.forget(&key).await? {
+                println!("✅ Forgot memory '{key}'");
+                Ok(())
+            } else {
+                bail!("No memory with key '{key}'");
+            }
+        }
+        crate::MemoryCommands::Stats { json } => {
+            let stats = collect_stats(mem).await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&stats)?);
+            } else {
+                println!(
+                    "🧠 Memory backend: {}",
+                    stats["backend"].as_str().unwrap_or("")
+                );
+                println!(
+                    "   Healthy:   {}",
+                    if stats["healthy"].as_bool().unwrap_or(false) {
+                        "yes"
+                    } else {
+                        "no"
+                    }
+                );
+                println!("   Entries:   {}", stats["total"]);
+                println!("   Sessions:  {}", stats["sessions"]);
+                if let Some(categories) = stats["categories"].as_object() {
+                    for (name, count) in categories {
+                        println!("     {name:<14} {count}");
+                    }
+                }
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Encrypt { json } => migrate_encryption(config, true, json),
+        crate::MemoryCommands::Decrypt { json } => migrate_encryption(config, false, json),
+        crate::MemoryCommands::Reindex => {
+            let reembedded = mem.reindex().await?;
+            println!(
+                "✅ Reindexed {} memory ({reembedded} entries re-embedded)",
+                mem.name()
+            );
+            Ok(())
+        }
+        crate::MemoryCommands::Ingest { path, json } => {
+            let report = ingest::ingest_path(
+                mem,
+                &config.workspace_dir,
+                &path,
+                config.memory.chunk_max_tokens,
+            )
+            .await?;
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+            } else {
+                println!("📚 Ingested {} into {}", path.display(), mem.name());
+                println!(
+                    "   Files:   {} scanned, {} updated, {} unchanged, {} removed",
+                    report.files_scanned,
+                    report.files_ingested,
+                    report.files_unchanged,
+                    report.files_removed
+                );
+                println!(
+                    "   Chunks:  {} stored, {} removed",
+                    report.chunks_stored, report.chunks_removed
+                );
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Consolidate {
+            dry_run,
+            budget,
+            json,
+        } => {
+            let mut settings = consolidate::ConsolidationSettings::from_config(&config.memory);
+            if let Some(budget) = budget {
+                settings.token_budget = budget;
+            }
+            let report = if dry_run {
+                consolidate::plan(&config.workspace_dir, &settings, cipher)?
+            } else {
+                let provider = crate::providers::create_resilient_provider(
+                    config.default_provider.as_deref().unwrap_or("openrouter"),
+                    config.api_key.as_deref(),
+                    config.api_url.as_deref(),
+                    config.default_model_vision,
+                    &config.reliability,
+                )?;
+                let model = config
+                    .default_model
+                    .as_deref()
+                    .unwrap_or("anthropic/claude-sonnet-4");
+                consolidate::consolidate(
+                    mem,
+                    &config.workspace_dir,
+                    provider.as_ref(),
+                    model,
+                    &settings,
+                    cipher,
+                    false,
+                )
+                .await?
+            };
+
+            if json {
+                println!("{}", serde_json::to_string_pretty(&report)?);
+                return Ok(());
+            }
+            let verb = if report.dry_run {
+                "Would merge"
+            } else {
+                "Merged"
+            };
+            println!(
+                "🧹 Consolidation{}: {} candidates, {} clusters (~{} tokens, budget {})",
+                if report.dry_run { " (dry run)" } else { "" },
+                report.candidates,
+                report.clusters.len(),
+                report.estimated_tokens,
+                settings.token_budget
+            );
+            for cluster in &report.clusters {
+                if !report.dry_run && cluster.merged_key.is_none() {
+                    println!("- Merge failed for {}", cluster.keys.join(", "));
+                    continue;
+                }
+                println!(
+                    "- {verb} {} memories{} (~{} tokens):",
+                    cluster.keys.len(),
+                    cluster
+                        .merged_key
+                        .as_deref()
+                        .map_or_else(String::new, |k| format!(" into {k}")),
+                    cluster.estimated_tokens
+                );
+                for content in &cluster.contents {
+                    println!("    {}", truncate_with_ellipsis(content, PREVIEW_CHARS));
+                }
+            }
+            if report.skipped_for_budget > 0 {
+                println!(
+                    "   {} clusters skipped: token budget exhausted",
+                    report.skipped_for_budget
+                );
+            }
+            if !report.dry_run {
+                println!(
+                    "✅ Archived {} memories into {} core facts",
+                    report.entries_archived, report.clusters_merged
+                );
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Export {
+            output,
+            format,
+            category,
+            session,
+        } => {
+            let format = Format::parse(&format)?;
+            let category = category.as_deref().map(parse_category);
+            let entries = mem.list(category.as_ref(), session.as_deref()).await?;
+            let rendered = export_entries(&entries, format)?;
+            match output {
+                Some(path) => {
+                    std::fs::write(&path, rendered)
+                        .with_context(|| format!("Failed to write {}", path.display()))?;
+                    println!(
+                        "✅ Exported {} memories to {}",
+                        entries.len(),
+                        path.display()
+                    );
+                }
+                None => print!("{rendered}"),
+            }
+            Ok(())
+        }
+        crate::MemoryCommands::Import { input, format } => {
+            let format = match format {
+                Some(raw) => Format::parse(&raw)?,
+                None => Format::from_path(&input),
+            };
+            let raw = std::fs::read_to_string(&input)
+                .with_context(|| format!("Failed to read {}", input.display()))?;
+            let imported = import_entries(mem, &raw, format).await?;
+            println!(
+                "✅ Imported {imported} memories from {} into {}",
+                input.display(),
+                mem.name()
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Rewrite every store in place, then persist `memory.encryption_enabled`.
+fn migrate_encryption(config: &Config, encrypt: bool, json: bool) -> Result<()> {
+    let report = encryption::migrate(config, encrypt)?;
+
+    let mut updated = config.clone();
+    updated.memory.encryption_enabled = encrypt;
+    updated.save()?;
+
+    if json {
+        println!("{}", serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    if encrypt {
+        println!("🔐 Memory encrypted at rest");
+    } else {
+        println!("🔓 Memory decrypted to plaintext");
+    }
+    println!("   Memories:        {} rows rewritten", report.memory_rows);
+    println!("   Tenants:         {}", report.tenants);
+    println!("   Snapshot:        {} entries", report.snapshot_entries);
+    println!(
+        "   Response cache:  {} entries cleared",
+        report.cache_entries_cleared
+    );
+    println!(
+        "   Saved memory.encryption_enabled = {encrypt} to {}",
+        updated.config_path.display()
+    );
+    println!("   Restart the daemon so running components pick up the change.");
+    Ok(())
+}
+
+/// Parse a category name as used on the command line.
+fn parse_category(raw: &str) -> MemoryCategory {
+    match raw.trim().to_ascii_lowercase().as_str() {
+        "core" => MemoryCategory::Core,
+        "daily" => MemoryCategory::Daily,
+        "conversation" => MemoryCategory::Conversation,
+        _ => MemoryCategory::Custom(raw.trim().to_string()),
+    }
+}
+
+async fn search(
+    mem: &dyn Memory,
+    query: &str,
+    category: Option<&MemoryCategory>,
+    session: Option<&str>,
+    limit: usize,
+) -> Result<Vec<MemoryEntry>> {
+    let filter = RecallFilter {
+        session_id: session.map(str::to_string),
+        categories: category.cloned().into_iter().collect(),
+        record_access: true,
+        ..RecallFilter::default()
+    };
+    mem.recall_filtered(query, limit, &filter).await
+}
+
+async fn collect_stats(mem: &dyn Memory) -> Result<serde_json::Value> {
+    let entries = mem.list(None, None).await?;
+    let mut categories: BTreeMap<String, usize> = BTreeMap::new();
+    let mut sessions = BTreeSet::new();
+    for entry in &entries {
+        *categories.entry(entry.category.to_string()).or_default() += 1;
+        if let Some(session) = &entry.session_id {
+            sessions.insert(session.as_str());
+        }
+    }
+
+    Ok(serde_json::json!({
+        "backend": mem.name(),
+        "healthy": mem.health_check().await,
+        "total": mem.count().await?,
+        "sessions": sessions.len(),
+        "categories": categories,
+    }))
+}
+
+fn print_entries(entries: &[MemoryEntry], json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(entries)?);
+        return Ok(());
+    }
+
+    if entries.is_empty() {
+        println!("No memories found.");
+        return Ok(());
+    }
+
+    println!("🧠 Memories ({}):", entries.len());
+    for entry in entries {
+        let mut line = format!("- {} [{}] {}", entry.key, entry.category, entry.timestamp);
+        if let Some(session) = &entry.session_id {
+            let _ = write!(line, " session={session}");
+        }
+        if let Some(score) = entry.score {
+            let _ = write!(line, " score={score:.3}");
+        }
+        println!("{line}");
+        if let Some(cite) = ingest::citation(entry) {
+            match cite.heading {
+                Some(heading) => println!("    source: {} § {heading}", cite.source),
+                None => println!("    source: {}", cite.source),
+            }
+        }
+        println!(
+            "    {}",
+            truncate_with_ellipsis(&entry.content.replace('\n', " "), PREVIEW_CHARS)
+        );
+    }
+    Ok(())
+}
+
+/// Serialize entries in the requested export format.
+fn export_entries(entries: &[MemoryEntry], format: Format) -> Result<String> {
+    match format {
+        Format::Jsonl => {
+            let mut out = String::new();
+            for entry in entries {
+                out.push_str(&serde_json::to_string(entry)?);
+                out.push('\n');
+            }
+            Ok(out)
+        }
+        Format::Markdown => Ok(snapshot::render_snapshot(entries.iter().map(|e| {
+            (
+                e.key.as_str(),
+                e.content.as_str(),
+                e.timestamp.as_str(),
+                e.timestamp.as_str(),
+            )
+        }))),
+    }
+}
+
+/// Store every entry found in `raw`; returns the number imported.
+///
+/// JSONL keeps id, timestamp, category, session and metadata on backends
+/// that store them (see [`Memory::import`]). Markdown snapshots only carry
+/// key and content, so those entries are imported as `core` like
+/// auto-hydration does.
+async fn import_entries(mem: &dyn Memory, raw: &str, format: Format) -> Result<usize> {
+    let entries: Vec<MemoryEntry> = match format {
+        Format::Jsonl => raw
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(idx, line)| {
+                serde_json::from_str(line)
+                    .with_context(|| format!("Invalid memory entry on line {}", idx + 1))
+            })
+            .collect::<Result<_>>()?,
+        Format::Markdown => snapshot::parse_snapshot(raw)
+            .into_iter()
+            .map(|(key, content)| MemoryEntry {
+                id: String::new(),
+                key,
+                content,
+                category: MemoryCategory::Core,
+                timestamp: String::new(),
+                session_id: None,
+                score: None,
+                metadata: MemoryMetadata::default(),
+            })
+            .collect(),
+    };
+
+    for entry in &entries {
+        mem.import(entry)
+            .await
+            .with_context(|| format!("Failed to import memory '{}'", entry.key))?;
+    }
+    Ok(entries.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::{MarkdownMemory, SqliteMemory};
+    use tempfile::TempDir;
+
+    async fn seeded_sqlite(tmp: &TempDir) -> SqliteMemory {
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("lang", "User prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store(
+            "standup",
+            "Discussed the Rust release",
+            MemoryCategory::Daily,
+            Some("sess-1"),
+        )
+        .await
+        .unwrap();
+        mem.store(
+            "notes",
+            "Project uses Rust and SQLite",
+            MemoryCategory::Custom("project".into()),
+            Some("sess-2"),
+        )
+        .await
+        .unwrap();
+        mem
+    }
+
+    #[test]
+    fn parse_category_maps_builtins_and_custom() {
+        assert_eq!(parse_category("core"), MemoryCategory::Core);
+        assert_eq!(parse_category(" Daily "), MemoryCategory::Daily);
+        assert_eq!(parse_category("conversation"), MemoryCategory::Conversation);
+        assert_eq!(
+            parse_category("project"),
+            MemoryCategory::Custom("project".into())
+        );
+    }
+
+    #[test]
+    fn format_parse_and_extension_detection() {
+        assert_eq!(Format::parse("JSONL").unwrap(), Format::Jsonl);
+        assert_eq!(Format::parse("md").unwrap(), Format::Markdown);
+        assert!(Format::parse("csv").is_err());
+        assert_eq!(Format::from_path(Path::new("dump.md")), Format::Markdown);
+        assert_eq!(Format::from_path(Path::new("dump.jsonl")), Format::Jsonl);
+        assert_eq!(Format::from_path(Path::new("dump")), Format::Jsonl);
+    }
+
+    #[test]
+    fn jsonl_export_roundtrips_scores_and_sessions() {
+        let entry = MemoryEntry {
+            id: "id-1".into(),
+            key: "lang".into(),
+            content: "Rust\nand more".into(),
+            category: MemoryCategory::Custom("project".into()),
+            timestamp: "2026-02-16T00:00:00Z".into(),
+            session_id: Some("sess-1".into()),
+            score: Some(0.75),
+            metadata: MemoryMetadata::default(),
+        };
+        let out = export_entries(std::slice::from_ref(&entry), Format::Jsonl).unwrap();
+        assert_eq!(out.lines().count(), 1);
+
+        let parsed: MemoryEntry = serde_json::from_str(out.trim_end()).unwrap();
+        assert_eq!(parsed.id, entry.id);
+        assert_eq!(parsed.content, entry.content);
+        assert_eq!(parsed.category, entry.category);
+        assert_eq!(parsed.session_id, entry.session_id);
+        assert_eq!(parsed.score, entry.score);
+    }
+
+    #[tokio::test]
+    async fn jsonl_roundtrip_between_backends() {
+        let src_dir = TempDir::new().unwrap();
+        let src = seeded_sqlite(&src_dir).await;
+        let originals = src.list(None, None).await.unwrap();
+        let exported = export_entries(&originals, Format::Jsonl).unwrap();
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = SqliteMemory::new(dst_dir.path()).unwrap();
+        let imported = import_entries(&dst, &exported, Format::Jsonl)
+            .await
+            .unwrap();
+        assert_eq!(imported, 3);
+
+        for original in &originals {
+            let restored = dst.get(&original.key).await.unwrap().unwrap();
+            assert_eq!(restored.id, original.id);
+            assert_eq!(restored.timestamp, original.timestamp);
+        }
+
+        let notes = dst.get("notes").await.unwrap().unwrap();
+        assert_eq!(notes.category, MemoryCategory::Custom("project".into()));
+        assert_eq!(notes.session_id.as_deref(), Some("sess-2"));
+        let standup = dst.get("standup").await.unwrap().unwrap();
+        assert_eq!(standup.category, MemoryCategory::Daily);
+        assert_eq!(standup.session_id.as_deref(), Some("sess-1"));
+    }
+
+    #[tokio::test]
+    async fn markdown_export_reuses_snapshot_format() {
+        let src_dir = TempDir::new().unwrap();
+        let src = seeded_sqlite(&src_dir).await;
+        let core = src.list(Some(&MemoryCategory::Core), None).await.unwrap();
+        let exported = export_entries(&core, Format::Markdown).unwrap();
+        assert!(exported.contains("### 🔑 `lang`"));
+
+        let dst_dir = TempDir::new().unwrap();
+        let dst = MarkdownMemory::new(dst_dir.path());
+        let imported = import_entries(&dst, &exported, Format::Markdown)
+            .await
+            .unwrap();
+        assert_eq!(imported, 1);
+        let entries = dst.list(Some(&MemoryCategory::Core), None).await.unwrap();
+        assert!(entries
+            .iter()
+            .any(|e| e.content.contains("User prefers Rust")));
+    }
+
+    #[tokio::test]
+    async fn import_reports_bad_jsonl_line() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let err = import_entries(&mem, "\n{not json}\n", Format::Jsonl)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("line 2"));
+        assert_eq!(mem.count().await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn search_filters_by_category_and_session() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_sqlite(&tmp).await;
+
+        let all = search(&mem, "Rust", None, None, 10).await.unwrap();
+        assert_eq!(all.len(), 3);
+
+        let daily = search(&mem, "Rust", Some(&MemoryCategory::Daily), None, 10)
+            .await
+            .unwrap();
+        assert_eq!(daily.len(), 1);
+        assert_eq!(daily[0].key, "standup");
+
+        let scoped = search(&mem, "Rust", None, Some("sess-2"), 10)
+            .await
+            .unwrap();
+        assert_eq!(scoped.len(), 1);
+        assert_eq!(scoped[0].key, "notes");
+    }
+
+    #[tokio::test]
+    async fn stats_counts_categories_and_sessions() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded_sqlite(&tmp).await;
+        let stats = collect_stats(&mem).await.unwrap();
+
+        assert_eq!(stats["backend"], "sqlite");
+        assert_eq!(stats["healthy"], true);
+        assert_eq!(stats["total"], 3);
+        assert_eq!(stats["sessions"], 2);
+        assert_eq!(stats["categories"]["core"], 1);
+        assert_eq!(stats["categories"]["project"], 1);
+    }
+}
@@ -0,0 +1,628 @@
+//! Memory consolidation — fold near-duplicate memories into core facts.
+//!
+//! Auto-save stores every user message as its own `conversation` row, so the
+//! store fills with restatements of the same few facts. A consolidation pass:
+//!
+//! 1. **Clusters** `conversation`/`daily` rows by cosine similarity of their
+//!    embeddings, or by token overlap when either side has no embedding.
+//!    Rows only cluster with others of the same category and session.
+//! 2. **Merges** each cluster into one `Core` memory with a single LLM call,
+//!    within an approximate token budget. The merged memory stays in the
+//!    originals' session.
+//! 3. **Archives** the originals into `memory_archive`, linked to the merged
+//!    key so every consolidated fact keeps its provenance, and drops their
+//!    access counts.
+//!
+//! [`plan`] only reads, so it doubles as the dry-run report.
+
+use super::encryption::{self, MemoryCipher};
+use super::{vector, Memory, MemoryCategory};
+use crate::config::Config;
+use crate::providers::Provider;
+use anyhow::{bail, Result};
+use chrono::Local;
+use rusqlite::{params, Connection};
+use serde::Serialize;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+/// Only the most recent rows are clustered, bounding the O(n²) scan.
+const MAX_CANDIDATES: usize = 2_000;
+/// Upper bound on originals folded into one merged memory.
+const MAX_CLUSTER_SIZE: usize = 20;
+/// Rough token overhead of the merge instructions and the reply.
+const MERGE_OVERHEAD_TOKENS: usize = 200;
+
+const MERGE_SYSTEM_PROMPT: &str = "You consolidate an assistant's long-term memory. \
+    You receive several notes that say roughly the same thing. Reply with ONE concise, \
+    self-contained statement that preserves every distinct fact, preference or decision \
+    they contain. Do not add commentary, headings or quotes.";
+
+/// Tuning for one consolidation pass.
+#[derive(Debug, Clone, Copy)]
+pub struct ConsolidationSettings {
+    /// Minimum similarity for two memories to share a cluster
+    pub similarity: f64,
+    /// Approximate LLM tokens the pass may spend
+    pub token_budget: usize,
+}
+
+impl ConsolidationSettings {
+    pub fn from_config(config: &crate::config::MemoryConfig) -> Self {
+        Self {
+            similarity: config.consolidation_similarity,
+            token_budget: config.consolidation_token_budget,
+        }
+    }
+}
+
+/// A group of similar memories that would be merged together.
+#[derive(Debug, Clone, Serialize)]
+pub struct Cluster {
+    /// Session every member belongs to (`None` for global memories)
+    pub session_id: Option<String>,
+    pub ids: Vec<String>,
+    pub keys: Vec<String>,
+    pub contents: Vec<String>,
+    /// Approximate tokens the merge call will cost
+    pub estimated_tokens: usize,
+    /// Key of the merged memory, once written
+    pub merged_key: Option<String>,
+}
+
+/// Outcome (or, for a dry run, forecast) of a consolidation pass.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ConsolidationReport {
+    pub dry_run: bool,
+    pub candidates: usize,
+    pub clusters: Vec<Cluster>,
+    pub clusters_merged: usize,
+    pub entries_archived: usize,
+    /// Clusters left alone because the token budget ran out
+    pub skipped_for_budget: usize,
+    pub estimated_tokens: usize,
+}
+
+/// An original memory archived by consolidation.
+#[derive(Debug, Clone, Serialize)]
+pub struct ArchivedMemory {
+    pub key: String,
+    pub content: String,
+    pub category: String,
+    pub session_id: Option<String>,
+    pub created_at: String,
+    pub archived_at: String,
+}
+
+struct Candidate {
+    id: String,
+    key: String,
+    category: String,
+    session_id: Option<String>,
+    content: String,
+    embedding: Option<Vec<f32>>,
+    tokens: HashSet<String>,
+}
+
+fn db_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("memory").join("brain.db")
+}
+
+fn open_db(workspace_dir: &Path) -> Result<Connection> {
+    let path = db_path(workspace_dir);
+    if !path.exists() {
+        bail!("memory consolidation requires the sqlite or lucid backend (no brain.db found)");
+    }
+    let conn = Connection::open(path)?;
+    conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+    Ok(conn)
+}
+
+fn tokenize(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+/// Only memories of one category and session may be merged together
+fn same_group(a: &Candidate, b: &Candidate) -> bool {
+    a.category == b.category && a.session_id == b.session_id
+}
+
+fn similarity(a: &Candidate, b: &Candidate) -> f64 {
+    match (&a.embedding, &b.embedding) {
+        (Some(x), Some(y)) if x.len() == y.len() => f64::from(vector::cosine_similarity(x, y)),
+        _ => jaccard(&a.tokens, &b.tokens),
+    }
+}
+
+fn estimate_tokens(contents: &[String]) -> usize {
+    contents.iter().map(|c| c.len().div_ceil(4)).sum::<usize>() + MERGE_OVERHEAD_TOKENS
+}
+
+fn load_candidates(conn: &Connection, cipher: Option<&MemoryCipher>) -> Result<Vec<Candidate>> {
+    let mut stmt = conn.prepare(
+        "SELECT id, key, content, embedding, category, session_id FROM memories
+         WHERE category IN ('conversation', 'daily')
+         ORDER BY updated_at DESC
+         LIMIT ?1",
+    )?;
+    #[allow(clippy::cast_possible_wrap)]
+    let rows = stmt.query_map(params![MAX_CANDIDATES as i64], |row| {
+        let content = encryption::open_text(cipher, row.get(2)?)
+            .map_err(|e| encryption::column_error(2, e))?;
+        let embedding = match row.get::<_, Option<Vec<u8>>>(3)? {
+            Some(blob) => Some(vector::bytes_to_vec(
+                &encryption::open_bytes(cipher, blob)
+                    .map_err(|e| encryption::column_error(3, e))?,
+            )),
+            None => None,
+        };
+        Ok(Candidate {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            category: row.get(4)?,
+            session_id: row.get(5)?,
+            tokens: tokenize(&content),
+            content,
+            embedding,
+        })
+    })?;
+    Ok(rows.filter_map(std::result::Result::ok).collect())
+}
+
+/// Cluster similar memories and estimate the cost of merging them. Read-only.
+///
+/// `cipher` decrypts stores encrypted at rest.
+pub fn plan(
+    workspace_dir: &Path,
+    settings: &ConsolidationSettings,
+    cipher: Option<&MemoryCipher>,
+) -> Result<ConsolidationReport> {
+    let conn = open_db(workspace_dir)?;
+    let mut candidates = load_candidates(&conn, cipher)?;
+    // Oldest first, so clusters list originals chronologically
+    candidates.reverse();
+
+    let mut assigned = vec![false; candidates.len()];
+    let mut clusters = Vec::new();
+    for seed in 0..candidates.len() {
+        if assigned[seed] {
+            continue;
+        }
+        let mut members = vec![seed];
+        for other in seed + 1..candidates.len() {
+            if members.len() >= MAX_CLUSTER_SIZE {
+                break;
+            }
+            if !assigned[other]
+                && same_group(&candidates[seed], &candidates[other])
+                && similarity(&candidates[seed], &candidates[other]) >= settings.similarity
+            {
+                members.push(other);
+            }
+        }
+        if members.len() < 2 {
+            continue;
+        }
+        for &m in &members {
+            assigned[m] = true;
+        }
+        let contents: Vec<String> = members
+            .iter()
+            .map(|&m| candidates[m].content.clone())
+            .collect();
+        clusters.push(Cluster {
+            session_id: candidates[seed].session_id.clone(),
+            ids: members.iter().map(|&m| candidates[m].id.clone()).collect(),
+            keys: members.iter().map(|&m| candidates[m].key.clone()).collect(),
+            estimated_tokens: estimate_tokens(&contents),
+            contents,
+            merged_key: None,
+        });
+    }
+
+    let mut report = ConsolidationReport {
+        dry_run: true,
+        candidates: candidates.len(),
+        ..ConsolidationReport::default()
+    };
+    for cluster in clusters {
+        if report.estimated_tokens + cluster.estimated_tokens > settings.token_budget {
+            report.skipped_for_budget += 1;
+            continue;
+        }
+        report.estimated_tokens += cluster.estimated_tokens;
+        report.clusters.push(cluster);
+    }
+    Ok(report)
+}
+
+/// Run a consolidation pass: merge every planned cluster via `provider` and
+/// archive its originals. With `dry_run`, only the plan is returned.
+pub async fn consolidate(
+    mem: &dyn Memory,
+    workspace_dir: &Path,
+    provider: &dyn Provider,
+    model: &str,
+    settings: &ConsolidationSettings,
+    cipher: Option<&MemoryCipher>,
+    dry_run: bool,
+) -> Result<ConsolidationReport> {
+    let mut report = plan(workspace_dir, settings, cipher)?;
+    if dry_run {
+        return Ok(report);
+    }
+    report.dry_run = false;
+
+    for cluster in &mut report.clusters {
+        let notes = cluster
+            .contents
+            .iter()
+            .enumerate()
+            .map(|(i, c)| format!("{}. {}", i + 1, c.trim()))
+            .collect::<Vec<_>>()
+            .join("\n");
+        let merged = match provider
+            .chat_with_system(Some(MERGE_SYSTEM_PROMPT), &notes, model, 0.0)
+            .await
+        {
+            Ok(merged) if !merged.trim().is_empty() => merged.trim().to_string(),
+            Ok(_) => continue,
+            Err(e) => {
+                tracing::warn!("memory consolidation: merge call failed: {e}");
+                continue;
+            }
+        };
+
+        let key = format!("consolidated_{}", uuid::Uuid::new_v4().simple());
+        mem.store(
+            &key,
+            &merged,
+            MemoryCategory::Core,
+            cluster.session_id.as_deref(),
+        )
+        .await?;
+        report.entries_archived += archive(workspace_dir, &cluster.ids, &key)?;
+        report.clusters_merged += 1;
+        cluster.merged_key = Some(key);
+    }
+
+    Ok(report)
+}
+
+/// Move the originals out of `memories` into `memory_archive`. Their access
+/// counts go too, so archived rows stop influencing ranking.
+fn archive(workspace_dir: &Path, ids: &[String], merged_key: &str) -> Result<usize> {
+    let mut conn = open_db(workspace_dir)?;
+    let tx = conn.transaction()?;
+    let now = Local::now().to_rfc3339();
+    let mut archived = 0;
+    for id in ids {
+        archived += tx.execute(
+            "INSERT OR REPLACE INTO memory_archive
+                (id, key, content, category, session_id, created_at, updated_at,
+                 consolidated_into, archived_at)
+             SELECT id, key, content, category, session_id, created_at, updated_at, ?2, ?3
+             FROM memories WHERE id = ?1",
+            params![id, merged_key, now],
+        )?;
+        tx.execute("DELETE FROM memories WHERE id = ?1", params![id])?;
+        tx.execute(
+            "DELETE FROM memory_access WHERE memory_id = ?1",
+            params![id],
+        )?;
+    }
+    tx.commit()?;
+    Ok(archived)
+}
+
+/// Originals a consolidated memory was merged from, oldest first.
+pub fn provenance(
+    workspace_dir: &Path,
+    key: &str,
+    cipher: Option<&MemoryCipher>,
+) -> Result<Vec<ArchivedMemory>> {
+    if !db_path(workspace_dir).exists() {
+        return Ok(Vec::new());
+    }
+    let conn = open_db(workspace_dir)?;
+    let mut stmt = conn.prepare(
+        "SELECT key, content, category, session_id, created_at, archived_at
+         FROM memory_archive WHERE consolidated_into = ?1
+         ORDER BY created_at ASC",
+    )?;
+    let rows = stmt.query_map(params![key], |row| {
+        Ok(ArchivedMemory {
+            key: row.get(0)?,
+            content: encryption::open_text(cipher, row.get(1)?)
+                .map_err(|e| encryption::column_error(1, e))?,
+            category: row.get(2)?,
+            session_id: row.get(3)?,
+            created_at: row.get(4)?,
+            archived_at: row.get(5)?,
+        })
+    })?;
+    Ok(rows.filter_map(std::result::Result::ok).collect())
+}
+
+/// Run consolidation when hygiene has flagged it due.
+///
+/// Called periodically by the daemon; runs the regular hygiene pass first so
+/// the cadence advances even when no other component reopens memory.
+pub async fn run_if_due(config: &Config) -> Result<Option<ConsolidationReport>> {
+    super::hygiene::run_if_due(&config.memory, &config.workspace_dir)?;
+    if !config.memory.consolidation_enabled
+        || !super::hygiene::take_consolidation_due(&config.workspace_dir)?
+        || !db_path(&config.workspace_dir).exists()
+    {
+        return Ok(None);
+    }
+
+    let cipher = MemoryCipher::from_config(config)?;
+    let mem = super::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        cipher.clone(),
+    )?;
+    let provider = crate::providers::create_resilient_provider(
+        config.default_provider.as_deref().unwrap_or("openrouter"),
+        config.api_key.as_deref(),
+        config.api_url.as_deref(),
+        config.default_model_vision,
+        &config.reliability,
+    )?;
+    let model = config
+        .default_model
+        .as_deref()
+        .unwrap_or("anthropic/claude-sonnet-4");
+
+    let report = consolidate(
+        mem.as_ref(),
+        &config.workspace_dir,
+        provider.as_ref(),
+        model,
+        &ConsolidationSettings::from_config(&config.memory),
+        cipher.as_deref(),
+        false,
+    )
+    .await?;
+    if report.clusters_merged > 0 {
+        tracing::info!(
+            "memory consolidation complete: merged {} clusters, archived {} entries (~{} tokens)",
+            report.clusters_merged,
+            report.entries_archived,
+            report.estimated_tokens
+        );
+    }
+    Ok(Some(report))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use async_trait::async_trait;
+    use parking_lot::Mutex;
+    use tempfile::TempDir;
+
+    struct MergeProvider {
+        calls: Mutex<Vec<String>>,
+    }
+
+    impl MergeProvider {
+        fn new() -> Self {
+            Self {
+                calls: Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for MergeProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.calls.lock().push(message.to_string());
+            Ok("User prefers Rust for systems work".into())
+        }
+    }
+
+    fn settings() -> ConsolidationSettings {
+        ConsolidationSettings {
+            similarity: 0.5,
+            token_budget: 10_000,
+        }
+    }
+
+    async fn seeded(tmp: &TempDir) -> SqliteMemory {
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        for (key, content) in [
+            ("m1", "I prefer Rust for systems programming"),
+            ("m2", "I really prefer Rust for systems programming"),
+            ("m3", "Reminder: I prefer Rust for systems programming"),
+            ("m4", "The weather in Lisbon is sunny today"),
+        ] {
+            mem.store(key, content, MemoryCategory::Conversation, None)
+                .await
+                .unwrap();
+        }
+        mem.store(
+            "core_fact",
+            "I prefer Rust for systems programming",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+        mem
+    }
+
+    #[test]
+    fn jaccard_scores_overlap() {
+        let a = tokenize("prefer rust for systems");
+        let b = tokenize("prefer rust for web");
+        assert!((jaccard(&a, &b) - 0.6).abs() < 1e-9);
+        assert!(jaccard(&a, &HashSet::new()).abs() < f64::EPSILON);
+    }
+
+    #[tokio::test]
+    async fn plan_clusters_similar_conversation_rows_only() {
+        let tmp = TempDir::new().unwrap();
+        let _mem = seeded(&tmp).await;
+
+        let report = plan(tmp.path(), &settings(), None).unwrap();
+        assert!(report.dry_run);
+        assert_eq!(report.candidates, 4);
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].keys, vec!["m1", "m2", "m3"]);
+        assert!(report.estimated_tokens > 0);
+    }
+
+    #[tokio::test]
+    async fn dry_run_changes_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded(&tmp).await;
+        let provider = MergeProvider::new();
+
+        let report = consolidate(&mem, tmp.path(), &provider, "m", &settings(), None, true)
+            .await
+            .unwrap();
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters_merged, 0);
+        assert!(provider.calls.lock().is_empty());
+        assert_eq!(mem.count().await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn consolidate_merges_and_archives_with_provenance() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded(&tmp).await;
+        let provider = MergeProvider::new();
+
+        let report = consolidate(&mem, tmp.path(), &provider, "m", &settings(), None, false)
+            .await
+            .unwrap();
+        assert_eq!(report.clusters_merged, 1);
+        assert_eq!(report.entries_archived, 3);
+        assert_eq!(provider.calls.lock().len(), 1);
+        assert!(provider.calls.lock()[0].contains("1. I prefer Rust"));
+
+        let merged_key = report.clusters[0].merged_key.clone().unwrap();
+        let merged = mem.get(&merged_key).await.unwrap().unwrap();
+        assert_eq!(merged.category, MemoryCategory::Core);
+        assert!(mem.get("m1").await.unwrap().is_none());
+        assert!(mem.get("m4").await.unwrap().is_some());
+        assert!(mem.get("core_fact").await.unwrap().is_some());
+
+        let originals = provenance(tmp.path(), &merged_key, None).unwrap();
+        let keys: Vec<&str> = originals.iter().map(|o| o.key.as_str()).collect();
+        assert_eq!(keys, vec!["m1", "m2", "m3"]);
+        assert_eq!(originals[0].category, "conversation");
+    }
+
+    #[tokio::test]
+    async fn clusters_stay_within_one_session() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        for (key, content, session) in [
+            ("a1", "I prefer Rust for systems programming", Some("alice")),
+            (
+                "a2",
+                "I really prefer Rust for systems programming",
+                Some("alice"),
+            ),
+            ("b1", "I prefer Rust for systems programming", Some("bob")),
+            ("g1", "I prefer Rust for systems programming", None),
+        ] {
+            mem.store(key, content, MemoryCategory::Conversation, session)
+                .await
+                .unwrap();
+        }
+        let provider = MergeProvider::new();
+
+        let report = consolidate(&mem, tmp.path(), &provider, "m", &settings(), None, false)
+            .await
+            .unwrap();
+        assert_eq!(report.clusters.len(), 1);
+        assert_eq!(report.clusters[0].keys, vec!["a1", "a2"]);
+        assert_eq!(report.clusters[0].session_id.as_deref(), Some("alice"));
+
+        let merged_key = report.clusters[0].merged_key.clone().unwrap();
+        let merged = mem.get(&merged_key).await.unwrap().unwrap();
+        assert_eq!(merged.session_id.as_deref(), Some("alice"));
+        assert!(mem.get("b1").await.unwrap().is_some());
+        assert!(mem.get("g1").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn archived_originals_lose_their_access_counts() {
+        let tmp = TempDir::new().unwrap();
+        let mem = seeded(&tmp).await;
+        let explicit = crate::memory::RecallFilter {
+            record_access: true,
+            ..crate::memory::RecallFilter::default()
+        };
+        mem.recall_filtered("Rust systems", 10, &explicit)
+            .await
+            .unwrap();
+        let access_rows = || -> i64 {
+            open_db(tmp.path())
+                .unwrap()
+                .query_row(
+                    "SELECT COUNT(*) FROM memory_access
+                     WHERE memory_id NOT IN (SELECT id FROM memories)",
+                    [],
+                    |row| row.get(0),
+                )
+                .unwrap()
+        };
+        let recalled: i64 = open_db(tmp.path())
+            .unwrap()
+            .query_row("SELECT COUNT(*) FROM memory_access", [], |row| row.get(0))
+            .unwrap();
+        assert!(recalled >= 3);
+
+        let provider = MergeProvider::new();
+        consolidate(&mem, tmp.path(), &provider, "m", &settings(), None, false)
+            .await
+            .unwrap();
+        assert_eq!(access_rows(), 0);
+    }
+
+    #[tokio::test]
+    async fn token_budget_skips_clusters() {
+        let tmp = TempDir::new().unwrap();
+        let _mem = seeded(&tmp).await;
+        let tight = ConsolidationSettings {
+            token_budget: 10,
+            ..settings()
+        };
+
+        let report = plan(tmp.path(), &tight, None).unwrap();
+        assert!(report.clusters.is_empty());
+        assert_eq!(report.skipped_for_budget, 1);
+    }
+
+    #[test]
+    fn plan_requires_sqlite_store() {
+        let tmp = TempDir::new().unwrap();
+        assert!(plan(tmp.path(), &settings(), None).is_err());
+        assert!(provenance(tmp.path(), "k", None).unwrap().is_empty());
+    }
+}
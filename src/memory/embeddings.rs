@@ -1,4 +1,5 @@
 use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
 /// Trait for embedding providers — convert text to vectors
 #[async_trait]
@@ -12,6 +13,12 @@ pub trait EmbeddingProvider: Send + Sync {
     /// Embed a batch of texts into vectors
     async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>>;
 
+    /// Identifies the vector space this provider produces. Stored vectors
+    /// from a different fingerprint are not comparable and must be re-embedded.
+    fn fingerprint(&self) -> String {
+        format!("{}:{}", self.name(), self.dimensions())
+    }
+
     /// Embed a single text
     async fn embed_one(&self, text: &str) -> anyhow::Result<Vec<f32>> {
         let mut results = self.embed(&[text]).await?;
@@ -101,6 +108,10 @@ impl EmbeddingProvider for OpenAiEmbedding {
         self.dims
     }
 
+    fn fingerprint(&self) -> String {
+        format!("openai:{}:{}:{}", self.base_url, self.model, self.dims)
+    }
+
     async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
         if texts.is_empty() {
             return Ok(Vec::new());
@@ -152,6 +163,208 @@ impl EmbeddingProvider for OpenAiEmbedding {
     }
 }
 
+// ── Ollama native embedding provider ─────────────────────────
+
+/// Default local Ollama endpoint.
+pub const DEFAULT_OLLAMA_URL: &str = "http://localhost:11434";
+/// Model used when the configured one is an OpenAI model name.
+pub const DEFAULT_OLLAMA_EMBEDDING_MODEL: &str = "nomic-embed-text";
+/// Texts sent per `/api/embed` request.
+const OLLAMA_EMBED_BATCH: usize = 32;
+
+/// Embeddings via Ollama's native `/api/embed` endpoint.
+///
+/// The vector size is learned from the first response, so `dimensions` only
+/// needs to be a non-zero hint until then.
+pub struct OllamaEmbedding {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: Option<String>,
+    model: String,
+    dims_hint: usize,
+    detected_dims: AtomicUsize,
+}
+
+impl OllamaEmbedding {
+    pub fn new(base_url: &str, api_key: Option<&str>, model: &str, dims: usize) -> Self {
+        let model = if model.trim().is_empty() || model.starts_with("text-embedding-") {
+            DEFAULT_OLLAMA_EMBEDDING_MODEL
+        } else {
+            model
+        };
+        let base_url = base_url.trim_end_matches('/');
+        Self {
+            client: reqwest::Client::new(),
+            base_url: if base_url.is_empty() {
+                DEFAULT_OLLAMA_URL.to_string()
+            } else {
+                base_url.to_string()
+            },
+            api_key: api_key.map(str::to_string).filter(|k| !k.is_empty()),
+            model: model.to_string(),
+            dims_hint: if dims == 0 { 768 } else { dims },
+            detected_dims: AtomicUsize::new(0),
+        }
+    }
+
+    fn embed_url(&self) -> String {
+        format!("{}/api/embed", self.base_url)
+    }
+
+    async fn embed_batch(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let body = serde_json::json!({
+            "model": self.model,
+            "input": texts,
+        });
+
+        let mut req = self.client.post(self.embed_url()).json(&body);
+        if let Some(key) = &self.api_key {
+            req = req.bearer_auth(key);
+        }
+        let resp = req.send().await?;
+
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await.unwrap_or_default();
+            anyhow::bail!("Ollama embedding error {status}: {text}");
+        }
+
+        let json: serde_json::Value = resp.json().await?;
+        let embeddings = json
+            .get("embeddings")
+            .and_then(|d| d.as_array())
+            .ok_or_else(|| anyhow::anyhow!("Invalid Ollama response: missing 'embeddings'"))?;
+        if embeddings.len() != texts.len() {
+            anyhow::bail!(
+                "Ollama returned {} embeddings for {} inputs",
+                embeddings.len(),
+                texts.len()
+            );
+        }
+
+        #[allow(clippy::cast_possible_truncation)]
+        let vectors: Vec<Vec<f32>> = embeddings
+            .iter()
+            .map(|e| {
+                e.as_array()
+                    .map(|v| {
+                        v.iter()
+                            .filter_map(|x| x.as_f64().map(|f| f as f32))
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            })
+            .collect();
+        Ok(vectors)
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for OllamaEmbedding {
+    fn name(&self) -> &str {
+        "ollama"
+    }
+
+    fn dimensions(&self) -> usize {
+        match self.detected_dims.load(Ordering::Relaxed) {
+            0 => self.dims_hint,
+            detected => detected,
+        }
+    }
+
+    fn fingerprint(&self) -> String {
+        // The model fixes the vector size, so detection never changes this
+        format!("ollama:{}:{}", self.base_url, self.model)
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        let mut out = Vec::with_capacity(texts.len());
+        for batch in texts.chunks(OLLAMA_EMBED_BATCH) {
+            out.extend(self.embed_batch(batch).await?);
+        }
+        if let Some(dims) = out.first().map(Vec::len).filter(|d| *d > 0) {
+            self.detected_dims.store(dims, Ordering::Relaxed);
+        }
+        Ok(out)
+    }
+}
+
+// ── Built-in hashed lexical embeddings (offline) ─────────────
+
+/// Vector size used when no dimensions are configured.
+const DEFAULT_HASH_DIMS: usize = 256;
+
+/// Offline lexical embeddings: word and character-trigram features hashed
+/// into a fixed-size signed vector, then L2-normalized.
+///
+/// No semantics, but texts sharing vocabulary or word fragments land close
+/// together, which keeps hybrid recall working without any model or network.
+pub struct HashEmbedding {
+    dims: usize,
+}
+
+impl HashEmbedding {
+    pub fn new(dims: usize) -> Self {
+        Self {
+            dims: if dims == 0 { DEFAULT_HASH_DIMS } else { dims },
+        }
+    }
+
+    /// 64-bit FNV-1a — stable across platforms and Rust versions.
+    fn fnv1a(bytes: &[u8]) -> u64 {
+        bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+            (hash ^ u64::from(*b)).wrapping_mul(0x0100_0000_01b3)
+        })
+    }
+
+    fn add_feature(&self, vec: &mut [f32], feature: &str, weight: f32) {
+        let hash = Self::fnv1a(feature.as_bytes());
+        #[allow(clippy::cast_possible_truncation)]
+        let bucket = (hash % self.dims as u64) as usize;
+        let sign = if hash >> 63 == 0 { 1.0 } else { -1.0 };
+        vec[bucket] += sign * weight;
+    }
+
+    fn embed_text(&self, text: &str) -> Vec<f32> {
+        let mut vec = vec![0.0_f32; self.dims];
+        for word in text
+            .split(|c: char| !c.is_alphanumeric())
+            .filter(|w| !w.is_empty())
+            .map(str::to_lowercase)
+        {
+            self.add_feature(&mut vec, &word, 1.0);
+            let padded: Vec<char> = format!(" {word} ").chars().collect();
+            for trigram in padded.windows(3) {
+                let trigram: String = trigram.iter().collect();
+                self.add_feature(&mut vec, &format!("#{trigram}"), 0.5);
+            }
+        }
+
+        let norm = vec.iter().map(|x| x * x).sum::<f32>().sqrt();
+        if norm > 0.0 {
+            for x in &mut vec {
+                *x /= norm;
+            }
+        }
+        vec
+    }
+}
+
+#[async_trait]
+impl EmbeddingProvider for HashEmbedding {
+    fn name(&self) -> &str {
+        "hash"
+    }
+
+    fn dimensions(&self) -> usize {
+        self.dims
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| self.embed_text(t)).collect())
+    }
+}
+
 // ── Factory ──────────────────────────────────────────────────
 
 pub fn create_embedding_provider(
@@ -175,6 +388,22 @@ pub fn create_embedding_provider(
             let key = api_key.unwrap_or("");
             Box::new(OpenAiEmbedding::new(base_url, key, model, dims))
         }
+        "ollama" => Box::new(OllamaEmbedding::new(
+            DEFAULT_OLLAMA_URL,
+            std::env::var("OLLAMA_API_KEY").ok().as_deref(),
+            model,
+            dims,
+        )),
+        name if name.starts_with("ollama:") => {
+            let base_url = name.strip_prefix("ollama:").unwrap_or("");
+            Box::new(OllamaEmbedding::new(
+                base_url,
+                std::env::var("OLLAMA_API_KEY").ok().as_deref(),
+                model,
+                dims,
+            ))
+        }
+        "hash" | "local" => Box::new(HashEmbedding::new(dims)),
         _ => Box::new(NoopEmbedding),
     }
 }
@@ -318,4 +547,87 @@ mod tests {
             "https://my-api.example.com/api/v2/embeddings"
         );
     }
+
+    // ── Ollama ───────────────────────────────────────────────────
+
+    #[test]
+    fn factory_ollama_defaults() {
+        let p = create_embedding_provider("ollama", None, "text-embedding-3-small", 0);
+        assert_eq!(p.name(), "ollama");
+        assert!(p.dimensions() > 0);
+        assert_eq!(
+            p.fingerprint(),
+            "ollama:http://localhost:11434:nomic-embed-text"
+        );
+    }
+
+    #[test]
+    fn factory_ollama_custom_url() {
+        let p = create_embedding_provider(
+            "ollama:http://gpu-box:11434/",
+            None,
+            "mxbai-embed-large",
+            1024,
+        );
+        assert_eq!(p.name(), "ollama");
+        assert_eq!(p.dimensions(), 1024);
+        assert_eq!(
+            p.fingerprint(),
+            "ollama:http://gpu-box:11434:mxbai-embed-large"
+        );
+    }
+
+    #[test]
+    fn ollama_embed_url_uses_native_api() {
+        let p = OllamaEmbedding::new("http://localhost:11434", None, "nomic-embed-text", 768);
+        assert_eq!(p.embed_url(), "http://localhost:11434/api/embed");
+    }
+
+    #[tokio::test]
+    async fn ollama_empty_batch_makes_no_request() {
+        // Unroutable URL: any request would fail
+        let p = OllamaEmbedding::new("http://127.0.0.1:9", None, "m", 768);
+        assert!(p.embed(&[]).await.unwrap().is_empty());
+    }
+
+    // ── Hash ─────────────────────────────────────────────────────
+
+    #[test]
+    fn factory_hash_and_local_alias() {
+        let p = create_embedding_provider("hash", None, "ignored", 0);
+        assert_eq!(p.name(), "hash");
+        assert_eq!(p.dimensions(), DEFAULT_HASH_DIMS);
+        let p = create_embedding_provider("local", None, "ignored", 128);
+        assert_eq!(p.name(), "hash");
+        assert_eq!(p.fingerprint(), "hash:128");
+    }
+
+    #[tokio::test]
+    async fn hash_embeddings_are_deterministic_and_normalized() {
+        let p = HashEmbedding::new(64);
+        let a = p.embed_one("Rust memory engine").await.unwrap();
+        let b = p.embed_one("Rust memory engine").await.unwrap();
+        assert_eq!(a, b);
+        assert_eq!(a.len(), 64);
+        let norm: f32 = a.iter().map(|x| x * x).sum::<f32>().sqrt();
+        assert!((norm - 1.0).abs() < 1e-5);
+    }
+
+    #[tokio::test]
+    async fn hash_embeddings_rank_shared_vocabulary_higher() {
+        let p = HashEmbedding::new(256);
+        let query = p.embed_one("deploying rust services").await.unwrap();
+        let close = p.embed_one("how we deploy Rust services").await.unwrap();
+        let far = p.embed_one("favourite pizza toppings").await.unwrap();
+        assert!(
+            crate::memory::vector::cosine_similarity(&query, &close)
+                > crate::memory::vector::cosine_similarity(&query, &far)
+        );
+    }
+
+    #[tokio::test]
+    async fn hash_empty_text_is_zero_vector() {
+        let p = HashEmbedding::new(16);
+        assert_eq!(p.embed_one("").await.unwrap(), vec![0.0; 16]);
+    }
 }
@@ -0,0 +1,399 @@
+//! Encryption at rest for memory stores.
+//!
+//! Field-level ChaCha20-Poly1305 encryption of memory content and embeddings,
+//! keyed by subkeys derived from the workspace `.secret_key` (see
+//! [`SecretStore::derive_key`]). Enabled with `[memory] encryption_enabled`.
+//!
+//! - **Text** fields become `menc:<base64(nonce ‖ ciphertext ‖ tag)>`.
+//! - **Blobs** (embeddings, IVF centroids) become `MENC ‖ nonce ‖ ciphertext ‖ tag`.
+//! - **Keyword search** keeps working through a *blind index*: every word is
+//!   replaced by a truncated keyed HMAC, so FTS5 matches exact words without
+//!   plaintext ever reaching disk. Prefix/stemming matches are lost, and word
+//!   frequencies remain observable to someone holding the database.
+//!
+//! Values without the prefix/magic are treated as plaintext, so stores can be
+//! migrated in place (`zeroclaw memory encrypt` / `zeroclaw memory decrypt`).
+
+use crate::config::Config;
+use crate::security::SecretStore;
+use anyhow::{bail, Context, Result};
+use base64::Engine;
+use chacha20poly1305::aead::{Aead, KeyInit, OsRng};
+use chacha20poly1305::{AeadCore, ChaCha20Poly1305, Key, Nonce};
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Prefix of encrypted text values.
+const TEXT_PREFIX: &str = "menc:";
+/// Leading bytes of encrypted blobs.
+const BLOB_MAGIC: &[u8; 4] = b"MENC";
+/// ChaCha20-Poly1305 nonce length in bytes.
+const NONCE_LEN: usize = 12;
+/// Bytes of HMAC kept per blind-index token (16 hex chars).
+const BLIND_TOKEN_BYTES: usize = 8;
+
+/// Encrypts memory fields and computes blind-index tokens.
+pub struct MemoryCipher {
+    aead: ChaCha20Poly1305,
+    index_key: [u8; 32],
+}
+
+impl std::fmt::Debug for MemoryCipher {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MemoryCipher { .. }")
+    }
+}
+
+impl MemoryCipher {
+    /// Derive the content and index keys from the store's master key.
+    pub fn new(secrets: &SecretStore) -> Result<Self> {
+        let content_key = secrets.derive_key("memory-content")?;
+        Ok(Self {
+            aead: ChaCha20Poly1305::new(Key::from_slice(&content_key)),
+            index_key: secrets.derive_key("memory-index")?,
+        })
+    }
+
+    /// Cipher keyed by the `.secret_key` next to `config.toml`.
+    pub fn for_config_dir(zeroclaw_dir: &Path) -> Result<Self> {
+        Self::new(&SecretStore::new(zeroclaw_dir, true))
+    }
+
+    /// Cipher for `config`, or `None` when memory encryption is off.
+    pub fn from_config(config: &Config) -> Result<Option<Arc<Self>>> {
+        if !config.memory.encryption_enabled {
+            return Ok(None);
+        }
+        let zeroclaw_dir = config
+            .config_path
+            .parent()
+            .context("Config path must have a parent directory")?;
+        Ok(Some(Arc::new(Self::for_config_dir(zeroclaw_dir)?)))
+    }
+
+    /// Whether `value` is an encrypted text field.
+    pub fn is_encrypted(value: &str) -> bool {
+        value.starts_with(TEXT_PREFIX)
+    }
+
+    /// Whether `blob` is an encrypted binary field.
+    pub fn is_encrypted_blob(blob: &[u8]) -> bool {
+        blob.starts_with(BLOB_MAGIC)
+    }
+
+    fn seal(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+        let ciphertext = self
+            .aead
+            .encrypt(&nonce, plaintext)
+            .map_err(|e| anyhow::anyhow!("Memory encryption failed: {e}"))?;
+        let mut sealed = Vec::with_capacity(NONCE_LEN + ciphertext.len());
+        sealed.extend_from_slice(&nonce);
+        sealed.extend_from_slice(&ciphertext);
+        Ok(sealed)
+    }
+
+    fn unseal(&self, sealed: &[u8]) -> Result<Vec<u8>> {
+        anyhow::ensure!(
+            sealed.len() > NONCE_LEN,
+            "Encrypted memory value too short (missing nonce)"
+        );
+        let (nonce, ciphertext) = sealed.split_at(NONCE_LEN);
+        self.aead
+            .decrypt(Nonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow::anyhow!("Memory decryption failed — wrong key or tampered data"))
+    }
+
+    /// Encrypt a text field. Always seals, even when `plaintext` happens to
+    /// start with the `menc:` prefix.
+    pub fn encrypt(&self, plaintext: &str) -> Result<String> {
+        let sealed = self.seal(plaintext.as_bytes())?;
+        Ok(format!(
+            "{TEXT_PREFIX}{}",
+            base64::engine::general_purpose::STANDARD.encode(sealed)
+        ))
+    }
+
+    /// Decrypt a text field. Plaintext values pass through unchanged.
+    pub fn decrypt(&self, value: &str) -> Result<String> {
+        let Some(encoded) = value.strip_prefix(TEXT_PREFIX) else {
+            return Ok(value.to_string());
+        };
+        let sealed = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .context("Encrypted memory value is not valid base64")?;
+        String::from_utf8(self.unseal(&sealed)?)
+            .context("Decrypted memory value is not valid UTF-8")
+    }
+
+    /// Encrypt a binary field. Always seals, whatever the leading bytes.
+    pub fn encrypt_bytes(&self, data: &[u8]) -> Result<Vec<u8>> {
+        let sealed = self.seal(data)?;
+        let mut blob = Vec::with_capacity(BLOB_MAGIC.len() + sealed.len());
+        blob.extend_from_slice(BLOB_MAGIC);
+        blob.extend_from_slice(&sealed);
+        Ok(blob)
+    }
+
+    /// Decrypt a binary field. Plaintext blobs pass through unchanged.
+    pub fn decrypt_bytes(&self, blob: &[u8]) -> Result<Vec<u8>> {
+        match blob.strip_prefix(BLOB_MAGIC.as_slice()) {
+            Some(sealed) => self.unseal(sealed),
+            None => Ok(blob.to_vec()),
+        }
+    }
+
+    /// Truncated keyed hash of `text` (16 hex chars). Stands in for plain
+    /// content hashes so equal texts can't be confirmed by guessing.
+    pub fn keyed_hash(&self, text: &str) -> String {
+        let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(&self.index_key)
+            .expect("HMAC accepts keys of any length");
+        mac.update(text.as_bytes());
+        hex::encode(&mac.finalize().into_bytes()[..BLIND_TOKEN_BYTES])
+    }
+
+    /// Blind-index tokens for `text`, in order (duplicates kept so BM25 term
+    /// frequencies survive). Words are split and lowercased the way FTS5's
+    /// default tokenizer does.
+    pub fn blind_terms(&self, text: &str) -> Vec<String> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .filter(|word| !word.is_empty())
+            .map(|word| self.keyed_hash(&word.to_lowercase()))
+            .collect()
+    }
+
+    /// Space-separated blind-index document for the FTS5 `content` column.
+    pub fn blind_index(&self, text: &str) -> String {
+        self.blind_terms(text).join(" ")
+    }
+}
+
+/// Encrypt `text` when a cipher is configured.
+pub fn seal_text(cipher: Option<&MemoryCipher>, text: &str) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.encrypt(text),
+        None => Ok(text.to_string()),
+    }
+}
+
+/// Decrypt a stored text field. Fails when the value is encrypted but no
+/// cipher is configured rather than leaking ciphertext into prompts.
+pub fn open_text(cipher: Option<&MemoryCipher>, value: String) -> Result<String> {
+    match cipher {
+        Some(cipher) => cipher.decrypt(&value),
+        None if MemoryCipher::is_encrypted(&value) => bail!(
+            "memory is encrypted at rest; set [memory] encryption_enabled = true \
+             or run `zeroclaw memory decrypt`"
+        ),
+        None => Ok(value),
+    }
+}
+
+/// Encrypt a binary field when a cipher is configured.
+pub fn seal_bytes(cipher: Option<&MemoryCipher>, data: Vec<u8>) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.encrypt_bytes(&data),
+        None => Ok(data),
+    }
+}
+
+/// Decrypt a stored binary field (see [`open_text`]).
+pub fn open_bytes(cipher: Option<&MemoryCipher>, blob: Vec<u8>) -> Result<Vec<u8>> {
+    match cipher {
+        Some(cipher) => cipher.decrypt_bytes(&blob),
+        None if MemoryCipher::is_encrypted_blob(&blob) => {
+            bail!("memory embeddings are encrypted at rest; set [memory] encryption_enabled = true")
+        }
+        None => Ok(blob),
+    }
+}
+
+/// Rewrite a stored text field for `migrate_encryption`. When encrypting,
+/// values that are already sealed (and open with `cipher`) are kept as-is.
+pub fn reseal_text(cipher: &MemoryCipher, value: &str, encrypt: bool) -> Result<String> {
+    let plaintext = cipher.decrypt(value)?;
+    if encrypt && MemoryCipher::is_encrypted(value) {
+        return Ok(value.to_string());
+    }
+    seal_text(encrypt.then_some(cipher), &plaintext)
+}
+
+/// Rewrite a stored binary field for `migrate_encryption` (see [`reseal_text`]).
+pub fn reseal_bytes(cipher: &MemoryCipher, blob: &[u8], encrypt: bool) -> Result<Vec<u8>> {
+    let data = cipher.decrypt_bytes(blob)?;
+    if encrypt && MemoryCipher::is_encrypted_blob(blob) {
+        return Ok(blob.to_vec());
+    }
+    seal_bytes(encrypt.then_some(cipher), data)
+}
+
+/// Make SQLite overwrite deleted content instead of leaving it in free pages.
+/// Enabled on every connection that holds encrypted data.
+pub fn enable_secure_delete(conn: &rusqlite::Connection) -> rusqlite::Result<()> {
+    conn.execute_batch("PRAGMA secure_delete = ON;")
+}
+
+/// Purge leftovers of rewritten rows after a migration commits: merge the
+/// `fts_tables` into fresh segments, fold the WAL into the main file and
+/// truncate it, then `VACUUM` so freed pages holding old values are dropped.
+pub fn scrub_database(conn: &rusqlite::Connection, fts_tables: &[&str]) -> Result<()> {
+    for table in fts_tables {
+        conn.execute(
+            &format!("INSERT INTO {table}({table}) VALUES('optimize')"),
+            [],
+        )
+        .with_context(|| format!("Failed to optimize {table}"))?;
+    }
+    let checkpoint = |conn: &rusqlite::Connection| {
+        conn.query_row("PRAGMA wal_checkpoint(TRUNCATE)", [], |_| Ok(()))
+            .context("Failed to checkpoint the WAL")
+    };
+    checkpoint(conn)?;
+    conn.execute_batch("VACUUM;")
+        .context("Failed to vacuum database")?;
+    // VACUUM in WAL mode writes the rebuilt pages to the log; fold them back in
+    checkpoint(conn)
+}
+
+/// Map a decryption failure inside a rusqlite row mapper.
+pub fn column_error(index: usize, err: anyhow::Error) -> rusqlite::Error {
+    rusqlite::Error::FromSqlConversionFailure(index, rusqlite::types::Type::Text, err.into())
+}
+
+/// What `zeroclaw memory encrypt|decrypt` rewrote.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MigrationReport {
+    pub encrypt: bool,
+    /// Memory and archive rows rewritten in `brain.db`
+    pub memory_rows: usize,
+    /// Cached LLM responses dropped from `response_cache.db`
+    pub cache_entries_cleared: usize,
+    /// Tenant databases rewritten
+    pub tenants: usize,
+    /// Core memories re-exported to `MEMORY_SNAPSHOT.md`
+    pub snapshot_entries: usize,
+}
+
+/// Encrypt (or decrypt) every memory store of `config` in place.
+///
+/// Covers `brain.db`, tenant databases and `MEMORY_SNAPSHOT.md`. The response
+/// and embedding caches are cleared instead of rewritten, since their keys are
+/// hashed differently once encryption is on. Does not touch the config file.
+pub fn migrate(config: &Config, encrypt: bool) -> Result<MigrationReport> {
+    let zeroclaw_dir = config
+        .config_path
+        .parent()
+        .context("Config path must have a parent directory")?;
+    let cipher = MemoryCipher::for_config_dir(zeroclaw_dir)?;
+    let workspace_dir = &config.workspace_dir;
+
+    let mut report = MigrationReport {
+        encrypt,
+        ..MigrationReport::default()
+    };
+    report.memory_rows = super::SqliteMemory::migrate_encryption(workspace_dir, &cipher, encrypt)?;
+
+    if workspace_dir
+        .join("memory")
+        .join("response_cache.db")
+        .exists()
+    {
+        report.cache_entries_cleared = super::ResponseCache::new(
+            workspace_dir,
+            config.memory.response_cache_ttl_minutes,
+            config.memory.response_cache_max_entries,
+        )?
+        .clear()?;
+    }
+
+    report.tenants =
+        crate::tenant::TenantManager::new(workspace_dir).migrate_encryption(&cipher, encrypt)?;
+
+    if workspace_dir
+        .join(super::snapshot::SNAPSHOT_FILENAME)
+        .exists()
+    {
+        report.snapshot_entries =
+            super::snapshot::export_snapshot(workspace_dir, encrypt.then_some(&cipher))?;
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn cipher(dir: &TempDir) -> MemoryCipher {
+        MemoryCipher::for_config_dir(dir.path()).unwrap()
+    }
+
+    #[test]
+    fn text_roundtrip_and_passthrough() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = cipher(&tmp);
+        let sealed = cipher.encrypt("User prefers Rust 🦀").unwrap();
+        assert!(MemoryCipher::is_encrypted(&sealed));
+        assert!(!sealed.contains("Rust"));
+        assert_eq!(cipher.decrypt(&sealed).unwrap(), "User prefers Rust 🦀");
+        assert_eq!(cipher.decrypt("plain").unwrap(), "plain");
+        // Content that merely looks sealed is still encrypted
+        let lookalike = cipher.encrypt("menc:not really").unwrap();
+        assert_ne!(lookalike, "menc:not really");
+        assert_eq!(cipher.decrypt(&lookalike).unwrap(), "menc:not really");
+        // Migration keeps already-sealed values as they are
+        assert_eq!(reseal_text(&cipher, &sealed, true).unwrap(), sealed);
+        assert_eq!(
+            reseal_text(&cipher, &sealed, false).unwrap(),
+            "User prefers Rust 🦀"
+        );
+    }
+
+    #[test]
+    fn bytes_roundtrip_and_passthrough() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = cipher(&tmp);
+        let data = vec![1_u8, 2, 3, 4, 5, 6, 7, 8];
+        let sealed = cipher.encrypt_bytes(&data).unwrap();
+        assert!(MemoryCipher::is_encrypted_blob(&sealed));
+        assert_eq!(cipher.decrypt_bytes(&sealed).unwrap(), data);
+        assert_eq!(cipher.decrypt_bytes(&data).unwrap(), data);
+    }
+
+    #[test]
+    fn wrong_key_or_tampering_is_rejected() {
+        let (a, b) = (TempDir::new().unwrap(), TempDir::new().unwrap());
+        let sealed = cipher(&a).encrypt("secret").unwrap();
+        assert!(cipher(&b).decrypt(&sealed).is_err());
+
+        let mut blob = cipher(&a).encrypt_bytes(b"vector").unwrap();
+        let last = blob.len() - 1;
+        blob[last] ^= 0xff;
+        assert!(cipher(&a).decrypt_bytes(&blob).is_err());
+    }
+
+    #[test]
+    fn blind_terms_normalize_like_fts() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = cipher(&tmp);
+        let doc = cipher.blind_terms("Rust, rust! RUST-lang");
+        assert_eq!(doc.len(), 4);
+        assert_eq!(doc[0], doc[1]);
+        assert_eq!(doc[1], doc[2]);
+        assert_ne!(doc[2], doc[3]);
+        assert!(doc.iter().all(|t| t.len() == BLIND_TOKEN_BYTES * 2));
+        assert!(!cipher.blind_index("rust").contains("rust"));
+    }
+
+    #[test]
+    fn open_text_without_cipher_refuses_ciphertext() {
+        let tmp = TempDir::new().unwrap();
+        let sealed = cipher(&tmp).encrypt("secret").unwrap();
+        assert!(open_text(None, sealed).is_err());
+        assert_eq!(open_text(None, "plain".into()).unwrap(), "plain");
+    }
+}
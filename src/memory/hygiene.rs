@@ -33,6 +33,9 @@ impl HygieneReport {
 struct HygieneState {
     last_run_at: Option<String>,
     last_report: HygieneReport,
+    /// Set by a hygiene pass, cleared once consolidation has run
+    #[serde(default)]
+    consolidation_due: bool,
 }
 
 /// Run memory/session hygiene if the cadence window has elapsed.
@@ -61,7 +64,7 @@ pub fn run_if_due(config: &MemoryConfig, workspace_dir: &Path) -> Result<()> {
         )?,
     };
 
-    write_state(workspace_dir, &report)?;
+    write_state(workspace_dir, &report, config.consolidation_enabled)?;
 
     if report.total_actions() > 0 {
         tracing::info!(
@@ -101,7 +104,32 @@ fn should_run_now(workspace_dir: &Path) -> Result<bool> {
     Ok(Utc::now().signed_duration_since(last) >= Duration::hours(HYGIENE_INTERVAL_HOURS))
 }
 
-fn write_state(workspace_dir: &Path, report: &HygieneReport) -> Result<()> {
+/// Consume the consolidation flag left by the last hygiene pass.
+///
+/// Returns `true` at most once per hygiene pass; the async consolidation job
+/// (see `memory::consolidate::run_if_due`) runs only when this does.
+pub fn take_consolidation_due(workspace_dir: &Path) -> Result<bool> {
+    let path = state_path(workspace_dir);
+    let Ok(raw) = fs::read_to_string(&path) else {
+        return Ok(false);
+    };
+    let Ok(mut state) = serde_json::from_str::<HygieneState>(&raw) else {
+        return Ok(false);
+    };
+    if !state.consolidation_due {
+        return Ok(false);
+    }
+
+    state.consolidation_due = false;
+    fs::write(path, serde_json::to_vec_pretty(&state)?)?;
+    Ok(true)
+}
+
+fn write_state(
+    workspace_dir: &Path,
+    report: &HygieneReport,
+    consolidation_due: bool,
+) -> Result<()> {
     let path = state_path(workspace_dir);
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)?;
@@ -110,6 +138,7 @@ fn write_state(workspace_dir: &Path, report: &HygieneReport) -> Result<()> {
     let state = HygieneState {
         last_run_at: Some(Utc::now().to_rfc3339()),
         last_report: report.clone(),
+        consolidation_due,
     };
     let json = serde_json::to_vec_pretty(&state)?;
     fs::write(path, json)?;
@@ -537,4 +566,26 @@ mod tests {
             "core memory should remain"
         );
     }
+
+    #[test]
+    fn flags_consolidation_once_per_pass_when_enabled() {
+        let tmp = TempDir::new().unwrap();
+        let workspace = tmp.path();
+
+        run_if_due(&default_cfg(), workspace).unwrap();
+        assert!(!take_consolidation_due(workspace).unwrap());
+
+        fs::remove_file(state_path(workspace)).unwrap();
+        let cfg = MemoryConfig {
+            consolidation_enabled: true,
+            ..default_cfg()
+        };
+        run_if_due(&cfg, workspace).unwrap();
+        assert!(take_consolidation_due(workspace).unwrap());
+        assert!(!take_consolidation_due(workspace).unwrap());
+
+        // Still inside the cadence window: no new pass, no new flag
+        run_if_due(&cfg, workspace).unwrap();
+        assert!(!take_consolidation_due(workspace).unwrap());
+    }
 }
@@ -0,0 +1,453 @@
+//! Workspace knowledge-base ingestion — chunks documents into memory.
+//!
+//! Markdown, plain text, source files and (with `rag-pdf`) PDFs are split into
+//! chunks stored under `Custom("docs")` with keys `docs:<source>#<n>` and
+//! `metadata.source` set to the source path, so recall can filter on it. Every
+//! chunk starts with a `[<source> § <heading>]` line so recall results carry
+//! their citation. A manifest of content hashes makes re-ingestion
+//! incremental: unchanged files are skipped, changed files are re-chunked
+//! (dropping surplus chunks) and deleted files lose all of their chunks.
+
+use super::{chunker, Memory, MemoryCategory, MemoryEntry, MemoryMetadata};
+use crate::config::Config;
+use anyhow::{Context, Result};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, BTreeSet};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+/// Custom category holding ingested document chunks.
+pub const DOCS_CATEGORY: &str = "docs";
+
+/// Manifest of ingested files (lives next to `brain.db`).
+const MANIFEST_FILENAME: &str = "ingest_manifest.json";
+
+/// Files larger than this are skipped.
+const MAX_FILE_BYTES: u64 = 2 * 1024 * 1024;
+
+/// Prose formats chunked on Markdown headings.
+const MARKDOWN_EXTENSIONS: &[&str] = &["md", "markdown", "mdx"];
+
+/// Plain text and source formats chunked on blank lines.
+const PLAIN_EXTENSIONS: &[&str] = &[
+    "txt", "rst", "adoc", "rs", "py", "js", "jsx", "ts", "tsx", "go", "java", "kt", "c", "h", "cc",
+    "cpp", "hpp", "cs", "rb", "php", "swift", "sh", "sql", "toml", "yaml", "yml", "json",
+];
+
+/// Directories never descended into.
+const SKIP_DIRS: &[&str] = &["target", "node_modules", "vendor", "dist", "build"];
+
+/// Outcome of one ingestion pass.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize)]
+pub struct IngestReport {
+    pub files_scanned: usize,
+    pub files_ingested: usize,
+    pub files_unchanged: usize,
+    pub files_removed: usize,
+    pub chunks_stored: usize,
+    pub chunks_removed: usize,
+}
+
+impl IngestReport {
+    /// True when the pass changed nothing in memory.
+    pub fn is_noop(&self) -> bool {
+        self.files_ingested == 0 && self.files_removed == 0
+    }
+}
+
+/// Where an ingested chunk came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Citation {
+    pub source: String,
+    pub heading: Option<String>,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    files: BTreeMap<String, ManifestEntry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ManifestEntry {
+    hash: String,
+    chunks: usize,
+}
+
+/// The category ingested chunks are stored under.
+pub fn docs_category() -> MemoryCategory {
+    MemoryCategory::Custom(DOCS_CATEGORY.into())
+}
+
+/// Memory key for chunk `index` of `source`.
+pub fn chunk_key(source: &str, index: usize) -> String {
+    format!("{DOCS_CATEGORY}:{source}#{index}")
+}
+
+/// Source path and heading of an ingested chunk, if `entry` is one.
+pub fn citation(entry: &MemoryEntry) -> Option<Citation> {
+    if entry.category != docs_category() {
+        return None;
+    }
+    let header = entry.content.lines().next()?;
+    let inner = header.strip_prefix('[')?.strip_suffix(']')?;
+    let (source, heading) = match inner.split_once(" § ") {
+        Some((source, heading)) => (source, Some(heading.to_string())),
+        None => (inner, None),
+    };
+    Some(Citation {
+        source: source.to_string(),
+        heading,
+    })
+}
+
+/// Ingest a file or directory into memory, incrementally.
+///
+/// Sources inside the workspace are recorded relative to it. Manifest entries
+/// under `root` whose files no longer exist are forgotten.
+pub async fn ingest_path(
+    mem: &dyn Memory,
+    workspace_dir: &Path,
+    root: &Path,
+    max_tokens: usize,
+) -> Result<IngestReport> {
+    let workspace = workspace_dir
+        .canonicalize()
+        .unwrap_or_else(|_| workspace_dir.to_path_buf());
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+
+    let mut files = Vec::new();
+    collect_files(&root, &workspace.join("memory"), &mut files);
+    files.sort();
+
+    let manifest_path = manifest_path(workspace_dir);
+    let mut manifest = load_manifest(&manifest_path);
+    let mut report = IngestReport::default();
+    let mut seen = BTreeSet::new();
+
+    for path in &files {
+        report.files_scanned += 1;
+        let source = source_label(&workspace, path);
+        seen.insert(source.clone());
+
+        let Ok(bytes) = std::fs::read(path) else {
+            tracing::warn!("ingest: failed to read {}", path.display());
+            continue;
+        };
+        let hash = hex::encode(Sha256::digest(&bytes));
+        let previous = manifest.files.get(&source).map_or(0, |e| e.chunks);
+        if manifest.files.get(&source).is_some_and(|e| e.hash == hash) {
+            report.files_unchanged += 1;
+            continue;
+        }
+
+        let chunks = chunk_file(path, &bytes, max_tokens);
+        for chunk in &chunks {
+            let heading = chunk
+                .heading
+                .as_deref()
+                .map(|h| h.trim_start_matches('#').trim());
+            let header = match heading {
+                Some(heading) => format!("[{source} § {heading}]"),
+                None => format!("[{source}]"),
+            };
+            mem.store_with_metadata(
+                &chunk_key(&source, chunk.index),
+                &format!("{header}\n{}", chunk.content),
+                docs_category(),
+                None,
+                MemoryMetadata {
+                    source: Some(source.clone()),
+                    ..MemoryMetadata::default()
+                },
+            )
+            .await
+            .with_context(|| format!("Failed to store chunk {} of {source}", chunk.index))?;
+        }
+        for index in chunks.len()..previous {
+            if mem.forget(&chunk_key(&source, index)).await? {
+                report.chunks_removed += 1;
+            }
+        }
+
+        report.files_ingested += 1;
+        report.chunks_stored += chunks.len();
+        manifest.files.insert(
+            source,
+            ManifestEntry {
+                hash,
+                chunks: chunks.len(),
+            },
+        );
+    }
+
+    let deleted: Vec<(String, ManifestEntry)> = manifest
+        .files
+        .iter()
+        .filter(|(source, _)| !seen.contains(*source))
+        .filter(|(source, _)| resolve_source(&workspace, source).starts_with(&root))
+        .map(|(source, entry)| (source.clone(), entry.clone()))
+        .collect();
+    for (source, entry) in deleted {
+        for index in 0..entry.chunks {
+            if mem.forget(&chunk_key(&source, index)).await? {
+                report.chunks_removed += 1;
+            }
+        }
+        manifest.files.remove(&source);
+        report.files_removed += 1;
+    }
+
+    save_manifest(&manifest_path, &manifest)?;
+    Ok(report)
+}
+
+/// Daemon worker: re-ingest `memory.ingest_paths` every `ingest_interval_secs`.
+pub async fn run_watcher(config: Config) -> Result<()> {
+    let mem = super::create_memory(
+        &config.memory,
+        &config.workspace_dir,
+        config.api_key.as_deref(),
+        super::MemoryCipher::from_config(&config)?,
+    )?;
+    let mut interval = tokio::time::interval(Duration::from_secs(
+        config.memory.ingest_interval_secs.max(1),
+    ));
+
+    loop {
+        interval.tick().await;
+        for raw in &config.memory.ingest_paths {
+            let root = resolve_source(&config.workspace_dir, raw);
+            match ingest_path(
+                mem.as_ref(),
+                &config.workspace_dir,
+                &root,
+                config.memory.chunk_max_tokens,
+            )
+            .await
+            {
+                Ok(report) if !report.is_noop() => tracing::info!(
+                    "📚 Ingested {}: {} files updated ({} chunks), {} removed",
+                    root.display(),
+                    report.files_ingested,
+                    report.chunks_stored,
+                    report.files_removed
+                ),
+                Ok(_) => {}
+                Err(e) => tracing::warn!("ingest of {} failed: {e}", root.display()),
+            }
+        }
+        crate::health::mark_component_ok("ingest");
+    }
+}
+
+fn manifest_path(workspace_dir: &Path) -> PathBuf {
+    workspace_dir.join("memory").join(MANIFEST_FILENAME)
+}
+
+fn load_manifest(path: &Path) -> Manifest {
+    std::fs::read_to_string(path)
+        .ok()
+        .and_then(|raw| serde_json::from_str(&raw).ok())
+        .unwrap_or_default()
+}
+
+fn save_manifest(path: &Path, manifest: &Manifest) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+    std::fs::write(path, serde_json::to_string_pretty(manifest)?)?;
+    Ok(())
+}
+
+/// Workspace-relative label for files inside the workspace, absolute otherwise.
+fn source_label(workspace: &Path, path: &Path) -> String {
+    path.strip_prefix(workspace)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+fn resolve_source(workspace: &Path, source: &str) -> PathBuf {
+    let path = Path::new(source);
+    if path.is_absolute() {
+        path.to_path_buf()
+    } else {
+        workspace.join(path)
+    }
+}
+
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+}
+
+fn is_supported(path: &Path) -> bool {
+    let Some(ext) = extension(path) else {
+        return false;
+    };
+    MARKDOWN_EXTENSIONS.contains(&ext.as_str())
+        || PLAIN_EXTENSIONS.contains(&ext.as_str())
+        || (cfg!(feature = "rag-pdf") && ext == "pdf")
+}
+
+fn collect_files(path: &Path, memory_dir: &Path, out: &mut Vec<PathBuf>) {
+    if path.is_file() {
+        let small_enough = std::fs::metadata(path).is_ok_and(|m| m.len() <= MAX_FILE_BYTES);
+        if is_supported(path) && small_enough {
+            out.push(path.to_path_buf());
+        }
+        return;
+    }
+    if path == memory_dir {
+        return;
+    }
+    let Ok(entries) = std::fs::read_dir(path) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let child = entry.path();
+        if child.is_dir() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+            if name.starts_with('.') || SKIP_DIRS.contains(&name.as_ref()) {
+                continue;
+            }
+        }
+        collect_files(&child, memory_dir, out);
+    }
+}
+
+fn chunk_file(path: &Path, bytes: &[u8], max_tokens: usize) -> Vec<chunker::Chunk> {
+    let ext = extension(path).unwrap_or_default();
+    if ext == "pdf" {
+        #[cfg(feature = "rag-pdf")]
+        {
+            let text = pdf_extract::extract_text_from_mem(bytes).unwrap_or_default();
+            return chunker::chunk_plain(&text, max_tokens);
+        }
+        #[cfg(not(feature = "rag-pdf"))]
+        return Vec::new();
+    }
+
+    let text = String::from_utf8_lossy(bytes);
+    if MARKDOWN_EXTENSIONS.contains(&ext.as_str()) {
+        chunker::chunk_markdown(&text, max_tokens)
+    } else {
+        chunker::chunk_plain(&text, max_tokens)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::SqliteMemory;
+    use tempfile::TempDir;
+
+    const GUIDE: &str = "# Guide\nIntro text.\n\n## Install\nRun cargo install zeroclaw.\n";
+
+    fn write(dir: &Path, rel: &str, content: &str) -> PathBuf {
+        let path = dir.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, content).unwrap();
+        path
+    }
+
+    async fn docs(mem: &SqliteMemory) -> Vec<MemoryEntry> {
+        mem.list(Some(&docs_category()), None).await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn ingests_markdown_with_citations() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        write(tmp.path(), "kb/guide.md", GUIDE);
+
+        let report = ingest_path(&mem, tmp.path(), &tmp.path().join("kb"), 512)
+            .await
+            .unwrap();
+        assert_eq!(report.files_ingested, 1);
+        assert_eq!(report.chunks_stored, 2);
+
+        let results = mem.recall("cargo install", 5, None).await.unwrap();
+        let cite = citation(&results[0]).unwrap();
+        assert_eq!(cite.source, "kb/guide.md");
+        assert_eq!(cite.heading.as_deref(), Some("Install"));
+        assert_eq!(results[0].key, "docs:kb/guide.md#1");
+        assert_eq!(results[0].metadata.source.as_deref(), Some("kb/guide.md"));
+    }
+
+    #[tokio::test]
+    async fn reingest_skips_unchanged_and_drops_stale_chunks() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let guide = write(tmp.path(), "kb/guide.md", GUIDE);
+        let kb = tmp.path().join("kb");
+        ingest_path(&mem, tmp.path(), &kb, 512).await.unwrap();
+
+        let again = ingest_path(&mem, tmp.path(), &kb, 512).await.unwrap();
+        assert_eq!(again.files_unchanged, 1);
+        assert!(again.is_noop());
+
+        std::fs::write(&guide, "Just one paragraph now.\n").unwrap();
+        let changed = ingest_path(&mem, tmp.path(), &kb, 512).await.unwrap();
+        assert_eq!(changed.files_ingested, 1);
+        assert_eq!(changed.chunks_removed, 1);
+        let entries = docs(&mem).await;
+        assert_eq!(entries.len(), 1);
+        assert!(entries[0].content.starts_with("[kb/guide.md]\n"));
+    }
+
+    #[tokio::test]
+    async fn deleted_files_are_forgotten() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        let guide = write(tmp.path(), "kb/guide.md", GUIDE);
+        write(tmp.path(), "kb/notes.txt", "Keep this note.");
+        let kb = tmp.path().join("kb");
+        ingest_path(&mem, tmp.path(), &kb, 512).await.unwrap();
+
+        std::fs::remove_file(guide).unwrap();
+        let report = ingest_path(&mem, tmp.path(), &kb, 512).await.unwrap();
+        assert_eq!(report.files_removed, 1);
+        assert_eq!(report.chunks_removed, 2);
+
+        let entries = docs(&mem).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(entries[0].key, "docs:kb/notes.txt#0");
+    }
+
+    #[tokio::test]
+    async fn skips_memory_dir_hidden_dirs_and_unknown_files() {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        write(tmp.path(), "memory/2026-01-01.md", "daily log");
+        write(tmp.path(), ".git/HEAD.md", "ref");
+        write(tmp.path(), "image.png", "binary");
+        write(tmp.path(), "src/main.rs", "# not a heading\nfn main() {}\n");
+
+        let report = ingest_path(&mem, tmp.path(), tmp.path(), 512)
+            .await
+            .unwrap();
+        assert_eq!(report.files_scanned, 1);
+        let entries = docs(&mem).await;
+        assert_eq!(entries.len(), 1);
+        assert_eq!(citation(&entries[0]).unwrap().heading, None);
+    }
+
+    #[test]
+    fn citation_ignores_other_categories() {
+        let entry = MemoryEntry {
+            id: "1".into(),
+            key: "k".into(),
+            content: "[looks § like one]\nbody".into(),
+            category: MemoryCategory::Core,
+            timestamp: String::new(),
+            session_id: None,
+            score: None,
+            metadata: MemoryMetadata::default(),
+        };
+        assert!(citation(&entry).is_none());
+    }
+}
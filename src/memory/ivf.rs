@@ -0,0 +1,208 @@
+// Inverted-file (IVF) index — approximate nearest-neighbour recall.
+//
+// Embeddings are clustered with spherical k-means into `nlist` lists. Each
+// memory row records the list it belongs to, so a query only scans the rows
+// of the `probes` lists whose centroids are closest to it instead of the
+// whole table.
+
+/// Upper bound on the number of lists, regardless of store size.
+const MAX_LISTS: usize = 1024;
+/// Training uses at most this many sampled vectors per list.
+const MAX_TRAINING_POINTS_PER_LIST: usize = 64;
+/// Lloyd iterations per training run.
+const TRAINING_ITERATIONS: usize = 10;
+
+/// When SqliteMemory uses the IVF index instead of an exact scan.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct VectorIndexSettings {
+    /// Use the index at all (false = always exact search)
+    pub enabled: bool,
+    /// Below this many embedded memories, recall scans every vector exactly
+    pub min_rows: usize,
+    /// Number of lists scanned per query
+    pub probes: usize,
+}
+
+impl Default for VectorIndexSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            min_rows: 2_000,
+            probes: 8,
+        }
+    }
+}
+
+/// Number of lists for a store of `rows` vectors (≈ √rows).
+#[allow(
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss,
+    clippy::cast_precision_loss
+)]
+pub fn list_count(rows: usize) -> usize {
+    ((rows as f64).sqrt().round() as usize).clamp(1, MAX_LISTS)
+}
+
+/// Scale a vector to unit length. Zero vectors are returned unchanged.
+pub fn normalize(v: &[f32]) -> Vec<f32> {
+    let norm = v
+        .iter()
+        .map(|x| f64::from(*x) * f64::from(*x))
+        .sum::<f64>()
+        .sqrt();
+    if !norm.is_finite() || norm < f64::EPSILON {
+        return v.to_vec();
+    }
+    #[allow(clippy::cast_possible_truncation)]
+    v.iter().map(|x| (f64::from(*x) / norm) as f32).collect()
+}
+
+fn dot(a: &[f32], b: &[f32]) -> f32 {
+    a.iter().zip(b).map(|(x, y)| x * y).sum()
+}
+
+/// Train `nlist` unit-length centroids over `vectors` with spherical k-means.
+///
+/// Deterministic: the initial centroids are evenly spaced samples. Large
+/// inputs are subsampled so training cost stays bounded.
+pub fn train(vectors: &[Vec<f32>], nlist: usize) -> Vec<Vec<f32>> {
+    let Some(dims) = vectors.first().map(Vec::len) else {
+        return Vec::new();
+    };
+    let nlist = nlist.clamp(1, vectors.len());
+
+    let max_points = nlist * MAX_TRAINING_POINTS_PER_LIST;
+    let stride = vectors.len().div_ceil(max_points).max(1);
+    let points: Vec<Vec<f32>> = vectors
+        .iter()
+        .step_by(stride)
+        .filter(|v| v.len() == dims)
+        .map(|v| normalize(v))
+        .collect();
+    if points.is_empty() {
+        return Vec::new();
+    }
+    let nlist = nlist.min(points.len());
+
+    let mut centroids: Vec<Vec<f32>> = (0..nlist)
+        .map(|i| points[i * points.len() / nlist].clone())
+        .collect();
+
+    for _ in 0..TRAINING_ITERATIONS {
+        let mut sums = vec![vec![0.0_f32; dims]; nlist];
+        let mut counts = vec![0_usize; nlist];
+        for point in &points {
+            let list = nearest(&centroids, point, 1)[0];
+            counts[list] += 1;
+            for (s, x) in sums[list].iter_mut().zip(point) {
+                *s += x;
+            }
+        }
+
+        let mut moved = false;
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            // Empty lists keep their previous centroid
+            if count == 0 {
+                continue;
+            }
+            let next = normalize(&sum);
+            if next != *centroid {
+                *centroid = next;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+
+    centroids
+}
+
+/// Indices of the `n` centroids closest to `query`, best first.
+pub fn nearest(centroids: &[Vec<f32>], query: &[f32], n: usize) -> Vec<usize> {
+    let mut scored: Vec<(usize, f32)> = centroids
+        .iter()
+        .enumerate()
+        .filter(|(_, c)| c.len() == query.len())
+        .map(|(i, c)| (i, dot(c, query)))
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+    scored.truncate(n.max(1));
+    scored.into_iter().map(|(i, _)| i).collect()
+}
+
+#[cfg(test)]
+#[allow(clippy::cast_precision_loss)]
+mod tests {
+    use super::*;
+
+    fn clustered(n: usize) -> Vec<Vec<f32>> {
+        // Two well-separated clusters around the x and y axes
+        (0..n)
+            .map(|i| {
+                let jitter = (i % 7) as f32 * 0.01;
+                if i % 2 == 0 {
+                    vec![1.0, jitter, 0.0]
+                } else {
+                    vec![jitter, 1.0, 0.0]
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn list_count_scales_with_sqrt() {
+        assert_eq!(list_count(0), 1);
+        assert_eq!(list_count(1), 1);
+        assert_eq!(list_count(10_000), 100);
+        assert_eq!(list_count(usize::MAX / 2), MAX_LISTS);
+    }
+
+    #[test]
+    fn normalize_produces_unit_vectors() {
+        let v = normalize(&[3.0, 4.0]);
+        assert!((v[0] - 0.6).abs() < 1e-6);
+        assert!((v[1] - 0.8).abs() < 1e-6);
+        assert_eq!(normalize(&[0.0, 0.0]), vec![0.0, 0.0]);
+    }
+
+    #[test]
+    fn train_empty_input_yields_no_centroids() {
+        assert!(train(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn train_never_exceeds_input_size() {
+        let centroids = train(&clustered(3), 16);
+        assert_eq!(centroids.len(), 3);
+    }
+
+    #[test]
+    fn train_separates_clusters() {
+        let centroids = train(&clustered(100), 2);
+        assert_eq!(centroids.len(), 2);
+
+        let x = nearest(&centroids, &[1.0, 0.0, 0.0], 1)[0];
+        let y = nearest(&centroids, &[0.0, 1.0, 0.0], 1)[0];
+        assert_ne!(x, y);
+    }
+
+    #[test]
+    fn nearest_orders_by_similarity() {
+        let centroids = vec![vec![0.0, 1.0], vec![1.0, 0.0], vec![0.7, 0.7]];
+        assert_eq!(nearest(&centroids, &[1.0, 0.1], 2), vec![1, 2]);
+    }
+
+    #[test]
+    fn nearest_skips_mismatched_dimensions() {
+        let centroids = vec![vec![1.0, 0.0, 0.0], vec![0.0, 1.0]];
+        assert_eq!(nearest(&centroids, &[1.0, 0.0], 2), vec![1]);
+    }
+
+    #[test]
+    fn train_is_deterministic() {
+        let data = clustered(50);
+        assert_eq!(train(&data, 4), train(&data, 4));
+    }
+}
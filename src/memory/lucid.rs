@@ -1,5 +1,5 @@
 use super::sqlite::SqliteMemory;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 use async_trait::async_trait;
 use chrono::Local;
 use parking_lot::Mutex;
@@ -226,6 +226,10 @@ impl LucidMemory {
                 timestamp: now.clone(),
                 session_id: None,
                 score: Some((1.0 - rank as f64 * 0.05).max(0.1)),
+                metadata: MemoryMetadata {
+                    source: Some("lucid".to_string()),
+                    ..MemoryMetadata::default()
+                },
             });
         }
 
@@ -312,21 +316,56 @@ impl Memory for LucidMemory {
         content: &str,
         category: MemoryCategory,
         session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
     ) -> anyhow::Result<()> {
         self.local
-            .store(key, content, category.clone(), session_id)
+            .store_with_metadata(key, content, category.clone(), session_id, metadata)
             .await?;
         self.sync_to_lucid_async(key, content, &category).await;
         Ok(())
     }
 
+    async fn import(&self, entry: &MemoryEntry) -> anyhow::Result<()> {
+        self.local.import(entry).await?;
+        self.sync_to_lucid_async(&entry.key, &entry.content, &entry.category)
+            .await;
+        Ok(())
+    }
+
     async fn recall(
         &self,
         query: &str,
         limit: usize,
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
-        let local_results = self.local.recall(query, limit, session_id).await?;
+        self.recall_filtered(query, limit, &RecallFilter::for_session(session_id))
+            .await
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let local_results = self.local.recall_filtered(query, limit, filter).await?;
         if limit == 0
             || local_results.len() >= limit
             || local_results.len() >= self.local_hit_threshold
@@ -341,6 +380,16 @@ impl Memory for LucidMemory {
         match self.recall_from_lucid(query).await {
             Ok(lucid_results) if !lucid_results.is_empty() => {
                 self.clear_failure();
+                // Lucid results carry no session, so they are mixed in
+                // regardless of it; every other constraint still applies
+                let unscoped = RecallFilter {
+                    session_id: None,
+                    ..filter.clone()
+                };
+                let lucid_results = lucid_results
+                    .into_iter()
+                    .filter(|entry| unscoped.matches(entry))
+                    .collect();
                 Ok(Self::merge_results(local_results, lucid_results, limit))
             }
             Ok(_) => {
@@ -382,6 +431,10 @@ impl Memory for LucidMemory {
     async fn health_check(&self) -> bool {
         self.local.health_check().await
     }
+
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        self.local.reindex().await
+    }
 }
 
 #[cfg(all(test, unix))]
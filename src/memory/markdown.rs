@@ -1,6 +1,6 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{Local, Utc};
 use std::path::{Path, PathBuf};
 use tokio::fs;
 
@@ -9,10 +9,16 @@ use tokio::fs;
 /// Layout:
 ///   workspace/MEMORY.md          — curated long-term memory (core)
 ///   workspace/memory/YYYY-MM-DD.md — daily logs (append-only)
+///
+/// Metadata is kept inline as a trailing `<!-- meta: {...} -->` comment.
 pub struct MarkdownMemory {
     workspace_dir: PathBuf,
 }
 
+/// Marks the inline metadata comment at the end of an entry line
+const META_PREFIX: &str = " <!-- meta: ";
+const META_SUFFIX: &str = " -->";
+
 impl MarkdownMemory {
     pub fn new(workspace_dir: &Path) -> Self {
         Self {
@@ -63,6 +69,15 @@ impl MarkdownMemory {
         Ok(())
     }
 
+    /// Split an entry line into its text and inline metadata
+    fn split_metadata(line: &str) -> (&str, MemoryMetadata) {
+        let parsed = line
+            .strip_suffix(META_SUFFIX)
+            .and_then(|rest| rest.rsplit_once(META_PREFIX))
+            .and_then(|(text, json)| Some((text, serde_json::from_str(json).ok()?)));
+        parsed.unwrap_or((line, MemoryMetadata::default()))
+    }
+
     fn parse_entries_from_file(
         path: &Path,
         content: &str,
@@ -83,16 +98,19 @@ impl MarkdownMemory {
             .map(|(i, line)| {
                 let trimmed = line.trim();
                 let clean = trimmed.strip_prefix("- ").unwrap_or(trimmed);
+                let (text, metadata) = Self::split_metadata(clean);
                 MemoryEntry {
                     id: format!("{filename}:{i}"),
                     key: format!("{filename}:{i}"),
-                    content: clean.to_string(),
+                    content: text.to_string(),
                     category: category.clone(),
                     timestamp: filename.to_string(),
                     session_id: None,
                     score: None,
+                    metadata,
                 }
             })
+            .filter(|entry| !entry.metadata.is_expired(Utc::now()))
             .collect()
     }
 
@@ -139,13 +157,36 @@ impl Memory for MarkdownMemory {
     }
 
     async fn store(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+    ) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
+
+    async fn store_with_metadata(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         _session_id: Option<&str>,
+        metadata: MemoryMetadata,
     ) -> anyhow::Result<()> {
-        let entry = format!("- **{key}**: {content}");
+        let metadata = metadata.normalized();
+        let mut entry = format!("- **{key}**: {content}");
+        if !metadata.is_empty() {
+            let json = serde_json::to_string(&metadata)?;
+            entry = format!("{entry}{META_PREFIX}{json}{META_SUFFIX}");
+        }
         let path = match category {
             MemoryCategory::Core => self.core_path(),
             _ => self.daily_path(),
@@ -159,6 +200,22 @@ impl Memory for MarkdownMemory {
         limit: usize,
         _session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, &RecallFilter::default())
+            .await
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        // Markdown entries carry no session, so session scoping is ignored
+        // exactly as in `recall`
+        let filter = RecallFilter {
+            session_id: None,
+            ..filter.clone()
+        };
         let all = self.read_all_entries().await?;
         let query_lower = query.to_lowercase();
         let keywords: Vec<&str> = query_lower.split_whitespace().collect();
@@ -175,7 +232,7 @@ impl Memory for MarkdownMemory {
                     #[allow(clippy::cast_precision_loss)]
                     let score = matched as f64 / keywords.len() as f64;
                     entry.score = Some(score);
-                    Some(entry)
+                    filter.matches(&entry).then_some(entry)
                 } else {
                     None
                 }
@@ -353,4 +410,48 @@ mod tests {
         let (_tmp, mem) = temp_workspace();
         assert_eq!(mem.count().await.unwrap(), 0);
     }
+
+    #[tokio::test]
+    async fn markdown_metadata_roundtrips_inline() {
+        let (_tmp, mem) = temp_workspace();
+        let meta = MemoryMetadata {
+            tags: vec!["work".into()],
+            source: Some("user".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(
+            "standup",
+            "Standup at 10am",
+            MemoryCategory::Core,
+            None,
+            meta,
+        )
+        .await
+        .unwrap();
+        mem.store("lunch", "Lunch at noon", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let filter = RecallFilter {
+            tags: vec!["work".into()],
+            ..RecallFilter::default()
+        };
+        let results = mem.recall_filtered("at", 10, &filter).await.unwrap();
+        assert_eq!(results.len(), 1);
+        assert_eq!(results[0].content, "**standup**: Standup at 10am");
+        assert_eq!(results[0].metadata.source.as_deref(), Some("user"));
+    }
+
+    #[tokio::test]
+    async fn markdown_hides_expired_entries() {
+        let (_tmp, mem) = temp_workspace();
+        let expired = MemoryMetadata::default()
+            .with_ttl(chrono::Duration::seconds(-1))
+            .unwrap();
+        mem.store_with_metadata("old", "Stale note", MemoryCategory::Core, None, expired)
+            .await
+            .unwrap();
+        assert_eq!(mem.count().await.unwrap(), 0);
+        assert!(mem.recall("stale", 10, None).await.unwrap().is_empty());
+    }
 }
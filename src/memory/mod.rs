@@ -1,10 +1,16 @@
 pub mod backend;
 pub mod chunker;
+pub mod cli;
+pub mod consolidate;
 pub mod embeddings;
+pub mod encryption;
 pub mod hygiene;
+pub mod ingest;
+pub mod ivf;
 pub mod lucid;
 pub mod markdown;
 pub mod none;
+pub mod ranking;
 pub mod response_cache;
 pub mod snapshot;
 pub mod sqlite;
@@ -16,6 +22,7 @@ pub use backend::{
     classify_memory_backend, default_memory_backend_key, memory_backend_profile,
     selectable_memory_backends, MemoryBackendKind, MemoryBackendProfile,
 };
+pub use encryption::MemoryCipher;
 pub use lucid::LucidMemory;
 pub use markdown::MarkdownMemory;
 pub use none::NoneMemory;
@@ -23,7 +30,7 @@ pub use response_cache::ResponseCache;
 pub use sqlite::SqliteMemory;
 pub use traits::Memory;
 #[allow(unused_imports)]
-pub use traits::{MemoryCategory, MemoryEntry};
+pub use traits::{MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 
 use crate::config::MemoryConfig;
 use std::path::Path;
@@ -55,11 +62,15 @@ where
     }
 }
 
-/// Factory: create the right memory backend from config
+/// Factory: create the right memory backend from config.
+///
+/// `cipher` (see [`MemoryCipher::from_config`]) encrypts the SQLite store and
+/// snapshot at rest.
 pub fn create_memory(
     config: &MemoryConfig,
     workspace_dir: &Path,
     api_key: Option<&str>,
+    cipher: Option<Arc<MemoryCipher>>,
 ) -> anyhow::Result<Box<dyn Memory>> {
     // Best-effort memory hygiene/retention pass (throttled by state file).
     if let Err(e) = hygiene::run_if_due(config, workspace_dir) {
@@ -68,7 +79,7 @@ pub fn create_memory(
 
     // If snapshot_on_hygiene is enabled, export core memories during hygiene.
     if config.snapshot_enabled && config.snapshot_on_hygiene {
-        if let Err(e) = snapshot::export_snapshot(workspace_dir) {
+        if let Err(e) = snapshot::export_snapshot(workspace_dir, cipher.as_deref()) {
             tracing::warn!("memory snapshot skipped: {e}");
         }
     }
@@ -83,7 +94,7 @@ pub fn create_memory(
         && snapshot::should_hydrate(workspace_dir)
     {
         tracing::info!("🧬 Cold boot detected — hydrating from MEMORY_SNAPSHOT.md");
-        match snapshot::hydrate_from_snapshot(workspace_dir) {
+        match snapshot::hydrate_from_snapshot(workspace_dir, cipher.as_deref()) {
             Ok(count) => {
                 if count > 0 {
                     tracing::info!("🧬 Hydrated {count} core memories from snapshot");
@@ -95,10 +106,20 @@ pub fn create_memory(
         }
     }
 
+    if cipher.is_some()
+        && matches!(
+            classify_memory_backend(&config.backend),
+            MemoryBackendKind::Markdown | MemoryBackendKind::Unknown
+        )
+    {
+        tracing::warn!("memory encryption covers the sqlite and lucid backends only; markdown files stay plaintext");
+    }
+
     fn build_sqlite_memory(
         config: &MemoryConfig,
         workspace_dir: &Path,
         api_key: Option<&str>,
+        cipher: Option<&Arc<MemoryCipher>>,
     ) -> anyhow::Result<SqliteMemory> {
         let embedder: Arc<dyn embeddings::EmbeddingProvider> =
             Arc::from(embeddings::create_embedding_provider(
@@ -115,14 +136,25 @@ pub fn create_memory(
             config.vector_weight as f32,
             config.keyword_weight as f32,
             config.embedding_cache_size,
-        )?;
+        )?
+        .with_vector_index(ivf::VectorIndexSettings {
+            enabled: config.vector_index_enabled,
+            min_rows: config.vector_index_min_rows,
+            probes: config.vector_index_probes,
+        })
+        .with_ranking(ranking::RankingSettings::from_config(config));
+        let mem = match cipher {
+            Some(cipher) => mem.with_cipher(Arc::clone(cipher)),
+            None => mem,
+        };
+        mem.spawn_pending_reembed();
         Ok(mem)
     }
 
     create_memory_with_sqlite_builder(
         &config.backend,
         workspace_dir,
-        || build_sqlite_memory(config, workspace_dir, api_key),
+        || build_sqlite_memory(config, workspace_dir, api_key, cipher.as_ref()),
         "",
     )
 }
@@ -146,7 +178,11 @@ pub fn create_memory_for_migration(
 }
 
 /// Factory: create an optional response cache from config.
-pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Option<ResponseCache> {
+pub fn create_response_cache(
+    config: &MemoryConfig,
+    workspace_dir: &Path,
+    cipher: Option<Arc<MemoryCipher>>,
+) -> Option<ResponseCache> {
     if !config.response_cache_enabled {
         return None;
     }
@@ -158,11 +194,16 @@ pub fn create_response_cache(config: &MemoryConfig, workspace_dir: &Path) -> Opt
     ) {
         Ok(cache) => {
             tracing::info!(
-                "💾 Response cache enabled (TTL: {}min, max: {} entries)",
+                "💾 Response cache enabled (TTL: {}min, max: {} entries, temperature <= {})",
                 config.response_cache_ttl_minutes,
-                config.response_cache_max_entries
+                config.response_cache_max_entries,
+                config.response_cache_max_temperature
             );
-            Some(cache)
+            let cache = cache.with_max_temperature(config.response_cache_max_temperature);
+            Some(match cipher {
+                Some(cipher) => cache.with_cipher(cipher),
+                None => cache,
+            })
         }
         Err(e) => {
             tracing::warn!("Response cache disabled due to error: {e}");
@@ -183,7 +224,7 @@ mod tests {
             backend: "sqlite".into(),
             ..MemoryConfig::default()
         };
-        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        let mem = create_memory(&cfg, tmp.path(), None, None).unwrap();
         assert_eq!(mem.name(), "sqlite");
     }
 
@@ -194,7 +235,7 @@ mod tests {
             backend: "markdown".into(),
             ..MemoryConfig::default()
         };
-        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        let mem = create_memory(&cfg, tmp.path(), None, None).unwrap();
         assert_eq!(mem.name(), "markdown");
     }
 
@@ -205,7 +246,7 @@ mod tests {
             backend: "lucid".into(),
             ..MemoryConfig::default()
         };
-        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        let mem = create_memory(&cfg, tmp.path(), None, None).unwrap();
         assert_eq!(mem.name(), "lucid");
     }
 
@@ -216,7 +257,7 @@ mod tests {
             backend: "none".into(),
             ..MemoryConfig::default()
         };
-        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        let mem = create_memory(&cfg, tmp.path(), None, None).unwrap();
         assert_eq!(mem.name(), "none");
     }
 
@@ -227,7 +268,7 @@ mod tests {
             backend: "redis".into(),
             ..MemoryConfig::default()
         };
-        let mem = create_memory(&cfg, tmp.path(), None).unwrap();
+        let mem = create_memory(&cfg, tmp.path(), None, None).unwrap();
         assert_eq!(mem.name(), "markdown");
     }
 
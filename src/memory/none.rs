@@ -1,4 +1,4 @@
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 use async_trait::async_trait;
 
 /// Explicit no-op memory backend.
@@ -30,6 +30,17 @@ impl Memory for NoneMemory {
         Ok(())
     }
 
+    async fn store_with_metadata(
+        &self,
+        _key: &str,
+        _content: &str,
+        _category: MemoryCategory,
+        _session_id: Option<&str>,
+        _metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        Ok(())
+    }
+
     async fn recall(
         &self,
         _query: &str,
@@ -39,6 +50,15 @@ impl Memory for NoneMemory {
         Ok(Vec::new())
     }
 
+    async fn recall_filtered(
+        &self,
+        _query: &str,
+        _limit: usize,
+        _filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        Ok(Vec::new())
+    }
+
     async fn get(&self, _key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         Ok(None)
     }
@@ -79,6 +99,21 @@ mod tests {
 
         assert!(memory.get("k").await.unwrap().is_none());
         assert!(memory.recall("k", 10, None).await.unwrap().is_empty());
+        memory
+            .store_with_metadata(
+                "k",
+                "v",
+                MemoryCategory::Core,
+                None,
+                MemoryMetadata::default(),
+            )
+            .await
+            .unwrap();
+        assert!(memory
+            .recall_filtered("k", 10, &RecallFilter::default())
+            .await
+            .unwrap()
+            .is_empty());
         assert!(memory.list(None, None).await.unwrap().is_empty());
         assert!(!memory.forget("k").await.unwrap());
         assert_eq!(memory.count().await.unwrap(), 0);
@@ -0,0 +1,378 @@
+//! Recall ranking — order hybrid-search hits by more than text relevance.
+//!
+//! `vector::hybrid_merge` only knows how well an entry matches the query.
+//! [`rank`] re-scores its candidates with:
+//!
+//! 1. **Recency** — exponential decay with a per-category half-life, so a
+//!    months-old conversation row loses to yesterday's decision while core
+//!    facts never fade.
+//! 2. **Importance** — `metadata.importance`, set by the caller or the model
+//!    at store time (unset counts as neutral).
+//! 3. **Access frequency** — a saturating boost from how often the entry has
+//!    been returned by earlier recalls.
+//! 4. **Diversity** — maximal marginal relevance, so near-duplicates don't
+//!    crowd out everything else.
+
+use super::{vector, MemoryCategory, MemoryEntry};
+use chrono::{DateTime, Utc};
+use std::collections::HashSet;
+
+/// Importance assumed for entries stored without one.
+const NEUTRAL_IMPORTANCE: f64 = 0.5;
+/// Recall count at which the access boost reaches one half.
+const ACCESS_HALF_SATURATION: f64 = 5.0;
+
+/// Weights and decay rates for recall ranking.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RankingSettings {
+    /// Re-rank at all (false = plain hybrid-search order)
+    pub enabled: bool,
+    pub relevance_weight: f64,
+    pub recency_weight: f64,
+    pub importance_weight: f64,
+    pub access_weight: f64,
+    /// MMR trade-off: 1.0 ranks purely by score, lower values favour
+    /// results unlike those already picked
+    pub mmr_lambda: f64,
+    /// Half-lives in days per category; 0 disables decay
+    pub core_half_life_days: f64,
+    pub daily_half_life_days: f64,
+    pub conversation_half_life_days: f64,
+    /// Half-life for custom categories (`docs`, user-defined, ...)
+    pub custom_half_life_days: f64,
+}
+
+impl Default for RankingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            relevance_weight: 1.0,
+            recency_weight: 0.2,
+            importance_weight: 0.15,
+            access_weight: 0.1,
+            mmr_lambda: 0.8,
+            core_half_life_days: 0.0,
+            daily_half_life_days: 7.0,
+            conversation_half_life_days: 3.0,
+            custom_half_life_days: 30.0,
+        }
+    }
+}
+
+impl RankingSettings {
+    pub fn from_config(config: &crate::config::MemoryConfig) -> Self {
+        Self {
+            enabled: config.ranking_enabled,
+            relevance_weight: config.ranking_relevance_weight,
+            recency_weight: config.ranking_recency_weight,
+            importance_weight: config.ranking_importance_weight,
+            access_weight: config.ranking_access_weight,
+            mmr_lambda: config.ranking_mmr_lambda.clamp(0.0, 1.0),
+            core_half_life_days: config.ranking_core_half_life_days,
+            daily_half_life_days: config.ranking_daily_half_life_days,
+            conversation_half_life_days: config.ranking_conversation_half_life_days,
+            custom_half_life_days: config.ranking_custom_half_life_days,
+        }
+    }
+
+    fn half_life_days(&self, category: &MemoryCategory) -> f64 {
+        match category {
+            MemoryCategory::Core => self.core_half_life_days,
+            MemoryCategory::Daily => self.daily_half_life_days,
+            MemoryCategory::Conversation => self.conversation_half_life_days,
+            MemoryCategory::Custom(_) => self.custom_half_life_days,
+        }
+    }
+}
+
+/// One recall hit awaiting ranking.
+#[derive(Debug, Clone)]
+pub struct RankCandidate {
+    pub entry: MemoryEntry,
+    /// Hybrid-search score (any positive scale; normalized per query)
+    pub relevance: f64,
+    /// How often explicit recalls returned this entry
+    pub access_count: u32,
+    /// Stored embedding, used to measure redundancy for MMR
+    pub embedding: Option<Vec<f32>>,
+}
+
+/// Recency factor in `[0, 1]`: 1 for a fresh entry, 0.5 after one half-life.
+pub fn recency_decay(entry: &MemoryEntry, settings: &RankingSettings, now: DateTime<Utc>) -> f64 {
+    let half_life = settings.half_life_days(&entry.category);
+    if half_life <= 0.0 {
+        return 1.0;
+    }
+    let Some(created) = entry.created_at() else {
+        return 1.0;
+    };
+    #[allow(clippy::cast_precision_loss)]
+    let age_days = (now - created).num_seconds().max(0) as f64 / 86_400.0;
+    0.5_f64.powf(age_days / half_life)
+}
+
+/// Access boost in `[0, 1)`, rising quickly for the first few recalls.
+pub fn access_boost(access_count: u32) -> f64 {
+    let n = f64::from(access_count);
+    n / (n + ACCESS_HALF_SATURATION)
+}
+
+fn tokenize(text: &str) -> HashSet<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|w| w.chars().count() > 1)
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[allow(clippy::cast_precision_loss)]
+fn jaccard(a: &HashSet<String>, b: &HashSet<String>) -> f64 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    let shared = a.intersection(b).count();
+    shared as f64 / (a.len() + b.len() - shared) as f64
+}
+
+struct Scored {
+    candidate: RankCandidate,
+    score: f64,
+    tokens: HashSet<String>,
+}
+
+fn redundancy(a: &Scored, b: &Scored) -> f64 {
+    match (&a.candidate.embedding, &b.candidate.embedding) {
+        (Some(x), Some(y)) if x.len() == y.len() => f64::from(vector::cosine_similarity(x, y)),
+        _ => jaccard(&a.tokens, &b.tokens),
+    }
+}
+
+/// Score `candidates`, diversify them and return the best `limit` entries.
+/// Each entry's `score` is replaced by its blended ranking score.
+pub fn rank(
+    candidates: Vec<RankCandidate>,
+    settings: &RankingSettings,
+    now: DateTime<Utc>,
+    limit: usize,
+) -> Vec<MemoryEntry> {
+    let max_relevance = candidates
+        .iter()
+        .map(|c| c.relevance)
+        .fold(0.0_f64, f64::max);
+    let max_relevance = if max_relevance < f64::EPSILON {
+        1.0
+    } else {
+        max_relevance
+    };
+
+    let mut pool: Vec<Scored> = candidates
+        .into_iter()
+        .map(|candidate| {
+            let importance = candidate
+                .entry
+                .metadata
+                .importance
+                .unwrap_or(NEUTRAL_IMPORTANCE);
+            let score = settings.relevance_weight * (candidate.relevance / max_relevance)
+                + settings.recency_weight * recency_decay(&candidate.entry, settings, now)
+                + settings.importance_weight * importance
+                + settings.access_weight * access_boost(candidate.access_count);
+            let tokens = if settings.mmr_lambda < 1.0 {
+                tokenize(&candidate.entry.content)
+            } else {
+                HashSet::new()
+            };
+            Scored {
+                candidate,
+                score,
+                tokens,
+            }
+        })
+        .collect();
+
+    pool.sort_by(|a, b| {
+        b.score
+            .partial_cmp(&a.score)
+            .unwrap_or(std::cmp::Ordering::Equal)
+    });
+
+    // Greedy MMR: each pick trades its score against its closest match
+    // among the entries already selected
+    let mut selected: Vec<Scored> = Vec::with_capacity(limit.min(pool.len()));
+    while selected.len() < limit && !pool.is_empty() {
+        let best = if settings.mmr_lambda >= 1.0 || selected.is_empty() {
+            0
+        } else {
+            let mut best = 0;
+            let mut best_mmr = f64::NEG_INFINITY;
+            for (i, item) in pool.iter().enumerate() {
+                let closest = selected
+                    .iter()
+                    .map(|s| redundancy(item, s))
+                    .fold(0.0_f64, f64::max);
+                let mmr = settings.mmr_lambda * item.score - (1.0 - settings.mmr_lambda) * closest;
+                if mmr > best_mmr {
+                    best_mmr = mmr;
+                    best = i;
+                }
+            }
+            best
+        };
+        selected.push(pool.remove(best));
+    }
+
+    selected
+        .into_iter()
+        .map(|s| {
+            let mut entry = s.candidate.entry;
+            entry.score = Some(s.score);
+            entry
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryMetadata;
+    use chrono::Duration;
+
+    fn entry(key: &str, content: &str, category: MemoryCategory, age_days: i64) -> MemoryEntry {
+        MemoryEntry {
+            id: key.into(),
+            key: key.into(),
+            content: content.into(),
+            category,
+            timestamp: (Utc::now() - Duration::days(age_days)).to_rfc3339(),
+            session_id: None,
+            score: None,
+            metadata: MemoryMetadata::default(),
+        }
+    }
+
+    fn candidate(entry: MemoryEntry, relevance: f64) -> RankCandidate {
+        RankCandidate {
+            entry,
+            relevance,
+            access_count: 0,
+            embedding: None,
+        }
+    }
+
+    fn keys(entries: &[MemoryEntry]) -> Vec<&str> {
+        entries.iter().map(|e| e.key.as_str()).collect()
+    }
+
+    #[test]
+    fn decay_halves_per_half_life_and_spares_core() {
+        let settings = RankingSettings::default();
+        let now = Utc::now();
+        let old_chat = entry("a", "x", MemoryCategory::Conversation, 3);
+        assert!((recency_decay(&old_chat, &settings, now) - 0.5).abs() < 0.01);
+        let old_fact = entry("b", "x", MemoryCategory::Core, 365);
+        assert!((recency_decay(&old_fact, &settings, now) - 1.0).abs() < f64::EPSILON);
+    }
+
+    #[test]
+    fn access_boost_saturates() {
+        assert!(access_boost(0).abs() < f64::EPSILON);
+        assert!((access_boost(5) - 0.5).abs() < f64::EPSILON);
+        assert!(access_boost(1_000) < 1.0);
+    }
+
+    #[test]
+    fn fresh_entries_beat_stale_ones_at_equal_relevance() {
+        let settings = RankingSettings::default();
+        let ranked = rank(
+            vec![
+                candidate(
+                    entry(
+                        "stale",
+                        "deploy on fridays",
+                        MemoryCategory::Conversation,
+                        90,
+                    ),
+                    1.0,
+                ),
+                candidate(
+                    entry(
+                        "fresh",
+                        "deploy on mondays",
+                        MemoryCategory::Conversation,
+                        1,
+                    ),
+                    1.0,
+                ),
+            ],
+            &settings,
+            Utc::now(),
+            2,
+        );
+        assert_eq!(keys(&ranked), vec!["fresh", "stale"]);
+    }
+
+    #[test]
+    fn importance_and_access_lift_entries() {
+        let settings = RankingSettings {
+            mmr_lambda: 1.0,
+            ..RankingSettings::default()
+        };
+        let mut important = entry("important", "alpha", MemoryCategory::Core, 0);
+        important.metadata.importance = Some(1.0);
+        let mut popular = candidate(entry("popular", "beta", MemoryCategory::Core, 0), 0.9);
+        popular.access_count = 20;
+        let ranked = rank(
+            vec![
+                candidate(entry("plain", "gamma", MemoryCategory::Core, 0), 0.95),
+                candidate(important, 0.9),
+                popular,
+            ],
+            &settings,
+            Utc::now(),
+            3,
+        );
+        assert_eq!(ranked[2].key, "plain");
+    }
+
+    #[test]
+    fn mmr_pushes_near_duplicates_down() {
+        let settings = RankingSettings {
+            mmr_lambda: 0.5,
+            ..RankingSettings::default()
+        };
+        let ranked = rank(
+            vec![
+                candidate(
+                    entry("a", "user prefers dark mode", MemoryCategory::Core, 0),
+                    1.0,
+                ),
+                candidate(
+                    entry("b", "user prefers dark mode", MemoryCategory::Core, 0),
+                    0.99,
+                ),
+                candidate(
+                    entry("c", "project uses rust", MemoryCategory::Core, 0),
+                    0.9,
+                ),
+            ],
+            &settings,
+            Utc::now(),
+            2,
+        );
+        assert_eq!(keys(&ranked), vec!["a", "c"]);
+    }
+
+    #[test]
+    fn ranking_replaces_scores_and_respects_limit() {
+        let ranked = rank(
+            vec![
+                candidate(entry("a", "one", MemoryCategory::Core, 0), 3.0),
+                candidate(entry("b", "two", MemoryCategory::Core, 0), 1.0),
+            ],
+            &RankingSettings::default(),
+            Utc::now(),
+            1,
+        );
+        assert_eq!(ranked.len(), 1);
+        assert!(ranked[0].score.is_some());
+    }
+}
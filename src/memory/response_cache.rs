@@ -4,13 +4,21 @@
 //! `(model, system_prompt_hash, user_prompt)`. Entries expire after a
 //! configurable TTL (default: 1 hour). The cache is optional and disabled by
 //! default — users opt in via `[memory] response_cache_enabled = true`.
+//!
+//! Only near-deterministic calls are worth caching: callers check
+//! [`ResponseCache::accepts_temperature`] before looking anything up.
+//!
+//! With memory encryption on, responses are stored sealed and prompt hashes
+//! are re-keyed so cached prompts can't be confirmed by hashing guesses.
 
+use super::encryption::{self, MemoryCipher};
 use anyhow::Result;
 use chrono::{Duration, Local};
 use parking_lot::Mutex;
 use rusqlite::{params, Connection};
 use sha2::{Digest, Sha256};
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 /// Response cache backed by a dedicated SQLite database.
 ///
@@ -22,6 +30,8 @@ pub struct ResponseCache {
     db_path: PathBuf,
     ttl_minutes: i64,
     max_entries: usize,
+    max_temperature: f64,
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
 impl ResponseCache {
@@ -50,7 +60,15 @@ impl ResponseCache {
                 hit_count   INTEGER NOT NULL DEFAULT 0
             );
             CREATE INDEX IF NOT EXISTS idx_rc_accessed ON response_cache(accessed_at);
-            CREATE INDEX IF NOT EXISTS idx_rc_created ON response_cache(created_at);",
+            CREATE INDEX IF NOT EXISTS idx_rc_created ON response_cache(created_at);
+
+            -- Lookup counters survive eviction and clear()
+            CREATE TABLE IF NOT EXISTS response_cache_lookups (
+                id     INTEGER PRIMARY KEY CHECK (id = 1),
+                hits   INTEGER NOT NULL DEFAULT 0,
+                misses INTEGER NOT NULL DEFAULT 0
+            );
+            INSERT OR IGNORE INTO response_cache_lookups (id) VALUES (1);",
         )?;
 
         Ok(Self {
@@ -58,9 +76,36 @@ impl ResponseCache {
             db_path,
             ttl_minutes: i64::from(ttl_minutes),
             max_entries,
+            max_temperature: 0.0,
+            cipher: None,
         })
     }
 
+    /// Encrypt cached responses at rest.
+    pub fn with_cipher(mut self, cipher: Arc<MemoryCipher>) -> Self {
+        self.cipher = Some(cipher);
+        self
+    }
+
+    /// Row key for `key`: keyed again under encryption.
+    fn storage_key(&self, key: &str) -> String {
+        match &self.cipher {
+            Some(cipher) => cipher.keyed_hash(key),
+            None => key.to_string(),
+        }
+    }
+
+    /// Only cache calls made at or below this sampling temperature.
+    pub fn with_max_temperature(mut self, max_temperature: f64) -> Self {
+        self.max_temperature = max_temperature;
+        self
+    }
+
+    /// Whether a call at `temperature` is deterministic enough to cache.
+    pub fn accepts_temperature(&self, temperature: f64) -> bool {
+        temperature <= self.max_temperature
+    }
+
     /// Build a deterministic cache key from model + system prompt + user prompt.
     pub fn cache_key(model: &str, system_prompt: Option<&str>, user_prompt: &str) -> String {
         let mut hasher = Sha256::new();
@@ -77,6 +122,7 @@ impl ResponseCache {
 
     /// Look up a cached response. Returns `None` on miss or expired entry.
     pub fn get(&self, key: &str) -> Result<Option<String>> {
+        let key = self.storage_key(key);
         let conn = self.conn.lock();
 
         let now = Local::now();
@@ -87,7 +133,17 @@ impl ResponseCache {
              WHERE prompt_hash = ?1 AND created_at > ?2",
         )?;
 
-        let result: Option<String> = stmt.query_row(params![key, cutoff], |row| row.get(0)).ok();
+        // Entries sealed under another key (or encryption setting) are misses
+        let result: Option<String> = stmt
+            .query_row(params![key, cutoff], |row| row.get(0))
+            .ok()
+            .and_then(|value| encryption::open_text(self.cipher.as_deref(), value).ok());
+
+        let counter = if result.is_some() { "hits" } else { "misses" };
+        conn.execute(
+            &format!("UPDATE response_cache_lookups SET {counter} = {counter} + 1 WHERE id = 1"),
+            [],
+        )?;
 
         if result.is_some() {
             // Bump hit count and accessed_at
@@ -105,6 +161,8 @@ impl ResponseCache {
 
     /// Store a response in the cache.
     pub fn put(&self, key: &str, model: &str, response: &str, token_count: u32) -> Result<()> {
+        let key = self.storage_key(key);
+        let response = encryption::seal_text(self.cipher.as_deref(), response)?;
         let conn = self.conn.lock();
 
         let now = Local::now().to_rfc3339();
@@ -161,6 +219,20 @@ impl ResponseCache {
         Ok((count as usize, hits as u64, tokens_saved as u64))
     }
 
+    /// Return lifetime lookup counters: (hits, misses).
+    pub fn lookups(&self) -> Result<(u64, u64)> {
+        let conn = self.conn.lock();
+
+        let (hits, misses): (i64, i64) = conn.query_row(
+            "SELECT hits, misses FROM response_cache_lookups WHERE id = 1",
+            [],
+            |row| Ok((row.get(0)?, row.get(1)?)),
+        )?;
+
+        #[allow(clippy::cast_sign_loss)]
+        Ok((hits as u64, misses as u64))
+    }
+
     /// Wipe the entire cache (useful for `zeroclaw cache clear`).
     pub fn clear(&self) -> Result<usize> {
         let conn = self.conn.lock();
@@ -348,4 +420,29 @@ mod tests {
         let result = cache.get(&key).unwrap();
         assert_eq!(result.as_deref(), Some("はい、Rustは素晴らしい"));
     }
+
+    #[test]
+    fn lookups_count_hits_and_misses() {
+        let (_tmp, cache) = temp_cache(60);
+        let key = ResponseCache::cache_key("gpt-4", None, "hello");
+
+        let _ = cache.get(&key).unwrap();
+        cache.put(&key, "gpt-4", "Hi!", 5).unwrap();
+        let _ = cache.get(&key).unwrap();
+        let _ = cache.get(&key).unwrap();
+        cache.clear().unwrap();
+
+        assert_eq!(cache.lookups().unwrap(), (2, 1));
+    }
+
+    #[test]
+    fn temperature_threshold() {
+        let (_tmp, cache) = temp_cache(60);
+        assert!(cache.accepts_temperature(0.0));
+        assert!(!cache.accepts_temperature(0.1));
+
+        let cache = cache.with_max_temperature(0.3);
+        assert!(cache.accepts_temperature(0.3));
+        assert!(!cache.accepts_temperature(0.7));
+    }
 }
@@ -5,7 +5,11 @@
 //!
 //! **Auto-Hydration**: if `brain.db` is missing but `MEMORY_SNAPSHOT.md` exists,
 //! re-indexes all entries back into a fresh SQLite database.
+//!
+//! With memory encryption on, each entry's content is written sealed
+//! (`menc:…`); keys and timestamps stay readable.
 
+use super::encryption::{self, MemoryCipher};
 use anyhow::Result;
 use chrono::Local;
 use rusqlite::{params, Connection};
@@ -24,8 +28,9 @@ const SNAPSHOT_HEADER: &str = "# 🧠 ZeroClaw Memory Snapshot\n\n\
 
 /// Export all `Core` memories from SQLite → `MEMORY_SNAPSHOT.md`.
 ///
-/// Returns the number of entries exported.
-pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
+/// Contents are sealed with `cipher` when given, and written as plaintext
+/// otherwise. Returns the number of entries exported.
+pub fn export_snapshot(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<usize> {
     let db_path = workspace_dir.join("memory").join("brain.db");
     if !db_path.exists() {
         tracing::debug!("snapshot export skipped: brain.db does not exist");
@@ -60,14 +65,54 @@ pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
         return Ok(0);
     }
 
-    let mut output = String::with_capacity(rows.len() * 200);
+    let rows = rows
+        .into_iter()
+        .map(|(key, content, category, created_at, updated_at)| {
+            let content = match cipher {
+                Some(cipher) => cipher.encrypt(&cipher.decrypt(&content)?)?,
+                None => encryption::open_text(None, content)?,
+            };
+            Ok((key, content, category, created_at, updated_at))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let output = render_snapshot(rows.iter().map(
+        |(key, content, _category, created_at, updated_at)| {
+            (
+                key.as_str(),
+                content.as_str(),
+                created_at.as_str(),
+                updated_at.as_str(),
+            )
+        },
+    ));
+
+    let snapshot_path = snapshot_path(workspace_dir);
+    fs::write(&snapshot_path, output)?;
+
+    tracing::info!(
+        "📸 Memory snapshot exported: {} core memories → {}",
+        rows.len(),
+        snapshot_path.display()
+    );
+
+    Ok(rows.len())
+}
+
+/// Render `(key, content, created_at, updated_at)` rows in the snapshot format.
+pub fn render_snapshot<'a, I>(rows: I) -> String
+where
+    I: ExactSizeIterator<Item = (&'a str, &'a str, &'a str, &'a str)>,
+{
+    let total = rows.len();
+    let mut output = String::with_capacity(total * 200);
     output.push_str(SNAPSHOT_HEADER);
 
     let now = Local::now().format("%Y-%m-%d %H:%M:%S").to_string();
     write!(output, "**Last exported:** {now}\n\n").unwrap();
-    write!(output, "**Total core memories:** {}\n\n---\n\n", rows.len()).unwrap();
+    write!(output, "**Total core memories:** {total}\n\n---\n\n").unwrap();
 
-    for (key, content, _category, created_at, updated_at) in &rows {
+    for (key, content, created_at, updated_at) in rows {
         write!(output, "### 🔑 `{key}`\n\n").unwrap();
         write!(output, "{content}\n\n").unwrap();
         write!(
@@ -77,23 +122,15 @@ pub fn export_snapshot(workspace_dir: &Path) -> Result<usize> {
         .unwrap();
     }
 
-    let snapshot_path = snapshot_path(workspace_dir);
-    fs::write(&snapshot_path, output)?;
-
-    tracing::info!(
-        "📸 Memory snapshot exported: {} core memories → {}",
-        rows.len(),
-        snapshot_path.display()
-    );
-
-    Ok(rows.len())
+    output
 }
 
 /// Import memories from `MEMORY_SNAPSHOT.md` into SQLite.
 ///
 /// Called during cold-boot when `brain.db` doesn't exist but the snapshot does.
-/// Returns the number of entries hydrated.
-pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
+/// Entries are stored sealed (with a blind keyword index) when `cipher` is
+/// given. Returns the number of entries hydrated.
+pub fn hydrate_from_snapshot(workspace_dir: &Path, cipher: Option<&MemoryCipher>) -> Result<usize> {
     let snapshot = snapshot_path(workspace_dir);
     if !snapshot.exists() {
         return Ok(0);
@@ -123,7 +160,8 @@ pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
             category   TEXT NOT NULL DEFAULT 'core',
             embedding  BLOB,
             created_at TEXT NOT NULL,
-            updated_at TEXT NOT NULL
+            updated_at TEXT NOT NULL,
+            search_tokens TEXT
         );
         CREATE INDEX IF NOT EXISTS idx_mem_key ON memories(key);
         CREATE INDEX IF NOT EXISTS idx_mem_cat ON memories(category);
@@ -143,19 +181,30 @@ pub fn hydrate_from_snapshot(workspace_dir: &Path) -> Result<usize> {
     let mut hydrated = 0;
 
     for (key, content) in &entries {
+        let plaintext = match cipher {
+            Some(cipher) => cipher.decrypt(content)?,
+            None => encryption::open_text(None, content.clone())?,
+        };
+        let stored = encryption::seal_text(cipher, &plaintext)?;
+        let search_tokens = cipher.map(|cipher| cipher.blind_index(&plaintext));
+
         let id = uuid::Uuid::new_v4().to_string();
         let result = conn.execute(
-            "INSERT OR IGNORE INTO memories (id, key, content, category, created_at, updated_at)
-             VALUES (?1, ?2, ?3, 'core', ?4, ?5)",
-            params![id, key, content, now, now],
+            "INSERT OR IGNORE INTO memories (id, key, content, category, created_at, updated_at, search_tokens)
+             VALUES (?1, ?2, ?3, 'core', ?4, ?5, ?6)",
+            params![id, key, stored, now, now, search_tokens],
         );
 
         match result {
             Ok(changed) if changed > 0 => {
-                // Populate FTS5
+                // Populate FTS5 (blind tokens for encrypted rows)
                 let _ = conn.execute(
-                    "INSERT INTO memories_fts(key, content) VALUES (?1, ?2)",
-                    params![key, content],
+                    "INSERT INTO memories_fts(rowid, key, content) VALUES (?1, ?2, ?3)",
+                    params![
+                        conn.last_insert_rowid(),
+                        key,
+                        search_tokens.as_deref().unwrap_or(&plaintext)
+                    ],
                 );
                 hydrated += 1;
             }
@@ -204,7 +253,7 @@ fn snapshot_path(workspace_dir: &Path) -> PathBuf {
 }
 
 /// Parse the structured markdown snapshot back into (key, content) pairs.
-fn parse_snapshot(input: &str) -> Vec<(String, String)> {
+pub fn parse_snapshot(input: &str) -> Vec<(String, String)> {
     let mut entries = Vec::new();
     let mut current_key: Option<String> = None;
     let mut current_content = String::new();
@@ -330,7 +379,7 @@ Rule 3: Protect the user.
     #[test]
     fn export_no_db_returns_zero() {
         let tmp = TempDir::new().unwrap();
-        let count = export_snapshot(tmp.path()).unwrap();
+        let count = export_snapshot(tmp.path(), None).unwrap();
         assert_eq!(count, 0);
     }
 
@@ -383,7 +432,7 @@ Rule 3: Protect the user.
         drop(conn);
 
         // Export snapshot
-        let exported = export_snapshot(workspace).unwrap();
+        let exported = export_snapshot(workspace, None).unwrap();
         assert_eq!(exported, 2, "Should export only core memories");
 
         // Verify the file exists and is readable
@@ -403,7 +452,7 @@ Rule 3: Protect the user.
         assert!(should_hydrate(workspace));
 
         // Hydrate from snapshot
-        let hydrated = hydrate_from_snapshot(workspace).unwrap();
+        let hydrated = hydrate_from_snapshot(workspace, None).unwrap();
         assert_eq!(hydrated, 2, "Should hydrate both core memories");
 
         // Verify brain.db was recreated
@@ -464,7 +513,7 @@ Rule 3: Protect the user.
     #[test]
     fn hydrate_no_snapshot_returns_zero() {
         let tmp = TempDir::new().unwrap();
-        let count = hydrate_from_snapshot(tmp.path()).unwrap();
+        let count = hydrate_from_snapshot(tmp.path(), None).unwrap();
         assert_eq!(count, 0);
     }
 }
@@ -1,11 +1,15 @@
 use super::embeddings::EmbeddingProvider;
-use super::traits::{Memory, MemoryCategory, MemoryEntry};
+use super::encryption::{self, MemoryCipher};
+use super::ivf::{self, VectorIndexSettings};
+use super::ranking::{self, RankCandidate, RankingSettings};
+use super::traits::{Memory, MemoryCategory, MemoryEntry, MemoryMetadata, RecallFilter};
 use super::vector;
 use async_trait::async_trait;
-use chrono::Local;
+use chrono::{DateTime, Local, Utc};
 use parking_lot::Mutex;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
 use uuid::Uuid;
 
@@ -13,19 +17,74 @@ use uuid::Uuid;
 ///
 /// Full-stack search engine:
 /// - **Vector DB**: embeddings stored as BLOB, cosine similarity search
+/// - **IVF Index**: k-means lists for approximate recall on large stores
 /// - **Keyword Search**: FTS5 virtual table with BM25 scoring
 /// - **Hybrid Merge**: weighted fusion of vector + keyword results
 /// - **Embedding Cache**: LRU-evicted cache to avoid redundant API calls
 /// - **Safe Reindex**: temp DB → seed → sync → atomic swap → rollback
+/// - **Encryption at Rest**: optional sealed content/embeddings with a blind
+///   keyword index (see `memory::encryption`)
+/// - **Metadata & TTL**: per-entry JSON metadata; expired rows are hidden and
+///   pruned on the next write
+/// - **Ranking**: recency decay, importance, access frequency and MMR
+///   diversification on top of hybrid relevance (see `memory::ranking`)
 pub struct SqliteMemory {
-    conn: Mutex<Connection>,
+    conn: Arc<Mutex<Connection>>,
     db_path: PathBuf,
     embedder: Arc<dyn EmbeddingProvider>,
     vector_weight: f32,
     keyword_weight: f32,
     cache_max: usize,
+    vector_index: VectorIndexSettings,
+    ranking: RankingSettings,
+    /// Stored embeddings come from another provider. They are kept, and
+    /// vector recall paused, until `reindex` swaps in new ones
+    reembed_pending: Arc<AtomicBool>,
+    /// Serializes re-embedding so concurrent callers never race on it
+    reindex_lock: Arc<tokio::sync::Mutex<()>>,
+    /// Encrypts content and embeddings at rest when configured
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
+/// Retrain the IVF index once the store has grown this many times past
+/// the size it was last trained on.
+const VECTOR_INDEX_REGROW_FACTOR: usize = 4;
+
+/// Texts embedded per provider call during `reindex`.
+const REINDEX_BATCH: usize = 32;
+
+/// Columns read by `row_to_entry`, in order.
+const ENTRY_COLUMNS: &str = "id, key, content, category, created_at, session_id, metadata";
+
+/// Hides rows whose TTL has passed. `expires_at` is stored as UTC with
+/// second precision so it compares correctly as text.
+const NOT_EXPIRED: &str =
+    "(expires_at IS NULL OR expires_at > strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))";
+
+/// Candidates retrieved per requested result when recall is filtered.
+const FILTERED_RECALL_OVERSAMPLE: usize = 8;
+
+/// Candidates retrieved per requested result for the ranking pass to reorder.
+const RANKING_OVERSAMPLE: usize = 3;
+
+/// FTS5 sync triggers. The index holds the blind-index tokens of encrypted
+/// rows and the plaintext content of the rest.
+const FTS_TRIGGERS: &str = "
+    CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
+        INSERT INTO memories_fts(rowid, key, content)
+        VALUES (new.rowid, new.key, COALESCE(new.search_tokens, new.content));
+    END;
+    CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, key, content)
+        VALUES ('delete', old.rowid, old.key, COALESCE(old.search_tokens, old.content));
+    END;
+    CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
+        INSERT INTO memories_fts(memories_fts, rowid, key, content)
+        VALUES ('delete', old.rowid, old.key, COALESCE(old.search_tokens, old.content));
+        INSERT INTO memories_fts(rowid, key, content)
+        VALUES (new.rowid, new.key, COALESCE(new.search_tokens, new.content));
+    END;";
+
 impl SqliteMemory {
     pub fn new(workspace_dir: &Path) -> anyhow::Result<Self> {
         Self::with_embedder(
@@ -68,16 +127,372 @@ impl SqliteMemory {
 
         Self::init_schema(&conn)?;
 
+        // The noop embedder never writes vectors, so it must not invalidate
+        // ones produced by a real provider (e.g. during migration)
+        let reembed_pending = if embedder.dimensions() == 0 {
+            false
+        } else {
+            Self::check_embedding_fingerprint(&conn, &embedder.fingerprint())?
+        };
+
         Ok(Self {
-            conn: Mutex::new(conn),
+            conn: Arc::new(Mutex::new(conn)),
             db_path,
             embedder,
             vector_weight,
             keyword_weight,
             cache_max,
+            vector_index: VectorIndexSettings::default(),
+            ranking: RankingSettings::default(),
+            reembed_pending: Arc::new(AtomicBool::new(reembed_pending)),
+            reindex_lock: Arc::new(tokio::sync::Mutex::new(())),
+            cipher: None,
+        })
+    }
+
+    /// Compare the embedder fingerprint with the stored one. On a mismatch
+    /// the stored vectors belong to another vector space; they are left in
+    /// place (a switch back finds them intact) and `true` is returned so
+    /// `reindex` replaces them. An empty store adopts the new fingerprint.
+    fn check_embedding_fingerprint(conn: &Connection, fingerprint: &str) -> anyhow::Result<bool> {
+        let stored: Option<String> = conn
+            .query_row(
+                "SELECT fingerprint FROM embedding_meta WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        match stored.as_deref() {
+            Some(stored) if stored == fingerprint => Ok(false),
+            Some(stored) => {
+                let rows: i64 =
+                    conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+                if rows > 0 {
+                    tracing::warn!(
+                        "memory: embedding provider changed ({stored} → {fingerprint}); \
+                         keeping stored vectors until they are re-embedded"
+                    );
+                    return Ok(true);
+                }
+                let tx = conn.unchecked_transaction()?;
+                tx.execute_batch(
+                    "DELETE FROM embedding_cache;
+                     DELETE FROM vector_centroids;
+                     DELETE FROM vector_index_state;",
+                )?;
+                tx.execute(
+                    "UPDATE embedding_meta SET fingerprint = ?1 WHERE id = 1",
+                    params![fingerprint],
+                )?;
+                tx.commit()?;
+                Ok(false)
+            }
+            None => {
+                conn.execute(
+                    "INSERT INTO embedding_meta (id, fingerprint) VALUES (1, ?1)",
+                    params![fingerprint],
+                )?;
+                Ok(false)
+            }
+        }
+    }
+
+    /// True while vectors from a previous embedding provider await re-embedding
+    pub fn reembed_pending(&self) -> bool {
+        self.reembed_pending.load(Ordering::Acquire)
+    }
+
+    /// Re-embed in the background after a provider change, so neither
+    /// startup nor recall waits on it. Without a tokio runtime, or if it
+    /// fails, the old vectors stay and `zeroclaw memory reindex` retries.
+    pub fn spawn_pending_reembed(&self) {
+        if !self.reembed_pending() {
+            return;
+        }
+        let Ok(runtime) = tokio::runtime::Handle::try_current() else {
+            return;
+        };
+        let job = self.reindex_job();
+        runtime.spawn(async move {
+            let _guard = job.lock.lock().await;
+            if !job.pending.load(Ordering::Acquire) {
+                return;
+            }
+            match job.run_locked().await {
+                Ok(count) => tracing::info!(
+                    "memory: re-embedded {count} memories for the new embedding provider"
+                ),
+                Err(e) => tracing::warn!(
+                    "memory: re-embedding failed, keeping previous vectors \
+                     (run `zeroclaw memory reindex` to retry): {e}"
+                ),
+            }
+        });
+    }
+
+    fn reindex_job(&self) -> ReindexJob {
+        ReindexJob {
+            conn: Arc::clone(&self.conn),
+            embedder: Arc::clone(&self.embedder),
+            cipher: self.cipher.clone(),
+            vector_index: self.vector_index,
+            pending: Arc::clone(&self.reembed_pending),
+            lock: Arc::clone(&self.reindex_lock),
+        }
+    }
+
+    /// Configure when vector recall switches from an exact scan to the IVF index
+    pub fn with_vector_index(mut self, settings: VectorIndexSettings) -> Self {
+        self.vector_index = settings;
+        self
+    }
+
+    /// Configure how recall results are re-ranked after hybrid search
+    pub fn with_ranking(mut self, settings: RankingSettings) -> Self {
+        self.ranking = settings;
+        self
+    }
+
+    /// Encrypt content and embeddings written from now on, and decrypt them on read.
+    /// Also turns on `secure_delete` so overwritten rows don't linger in free pages.
+    pub fn with_cipher(mut self, cipher: Arc<MemoryCipher>) -> Self {
+        if let Err(e) = encryption::enable_secure_delete(&self.conn.lock()) {
+            tracing::warn!("Failed to enable secure_delete on {:?}: {e}", self.db_path);
+        }
+        self.cipher = Some(cipher);
+        self
+    }
+
+    fn cipher(&self) -> Option<&MemoryCipher> {
+        self.cipher.as_deref()
+    }
+
+    /// Decrypt a `content` column inside a row mapper
+    fn open_content(&self, index: usize, value: String) -> rusqlite::Result<String> {
+        encryption::open_text(self.cipher(), value).map_err(|e| encryption::column_error(index, e))
+    }
+
+    /// Insert or update the entry under `key`. `restored` carries an exported
+    /// entry's id and creation time; such a write replaces any row with the
+    /// same key or id instead of updating it in place.
+    async fn write_entry(
+        &self,
+        key: &str,
+        content: &str,
+        category: &MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+        restored: Option<(&str, &str)>,
+    ) -> anyhow::Result<()> {
+        // Compute embedding (async, before lock)
+        let embedding = self.get_or_compute_embedding(content).await?;
+        let embedding_bytes = embedding
+            .as_deref()
+            .map(|emb| Self::encode_vector(self.cipher(), emb))
+            .transpose()?;
+        let stored_content = encryption::seal_text(self.cipher(), content)?;
+        let search_tokens = self.cipher().map(|cipher| cipher.blind_index(content));
+        let metadata = metadata.normalized();
+        let expires_at = metadata.expires_at.map(Self::expiry_to_str);
+        let stored_metadata = self.seal_metadata(&metadata)?;
+
+        let conn = self.conn.lock();
+        let now = Local::now().to_rfc3339();
+        let (id, created_at, updated_at) = match restored {
+            Some((id, timestamp)) => (id.to_string(), timestamp, timestamp),
+            None => (Uuid::new_v4().to_string(), now.as_str(), now.as_str()),
+        };
+        let cat = Self::category_to_str(category);
+        let vector_list = match embedding.as_deref() {
+            Some(emb) if self.vector_index.enabled => {
+                Self::assign_vector_list(&conn, emb, self.cipher())?
+            }
+            _ => None,
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        Self::prune_expired(&tx)?;
+        if restored.is_some() {
+            tx.execute(
+                "DELETE FROM memories WHERE key = ?1 OR id = ?2",
+                params![key, id],
+            )?;
+        }
+        tx.execute(
+            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id, vector_list, search_tokens, metadata, expires_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)
+             ON CONFLICT(key) DO UPDATE SET
+                content = excluded.content,
+                category = excluded.category,
+                embedding = excluded.embedding,
+                updated_at = excluded.updated_at,
+                session_id = excluded.session_id,
+                vector_list = excluded.vector_list,
+                search_tokens = excluded.search_tokens,
+                metadata = excluded.metadata,
+                expires_at = excluded.expires_at",
+            params![
+                id,
+                key,
+                stored_content,
+                cat,
+                embedding_bytes,
+                created_at,
+                updated_at,
+                session_id,
+                vector_list,
+                search_tokens,
+                stored_metadata,
+                expires_at
+            ],
+        )?;
+        tx.commit()?;
+
+        if let Some(emb) = embedding.as_deref() {
+            if Self::vector_index_stale(&conn, emb.len(), &self.vector_index)? {
+                Self::rebuild_vector_index(&conn, &self.vector_index, self.cipher())?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Build a `MemoryEntry` from a row selected with `ENTRY_COLUMNS`
+    fn row_to_entry(
+        &self,
+        row: &rusqlite::Row,
+        score: Option<f64>,
+    ) -> rusqlite::Result<MemoryEntry> {
+        let metadata = match row.get::<_, Option<String>>(6)? {
+            Some(raw) => serde_json::from_str(&self.open_content(6, raw)?).unwrap_or_default(),
+            None => MemoryMetadata::default(),
+        };
+        Ok(MemoryEntry {
+            id: row.get(0)?,
+            key: row.get(1)?,
+            content: self.open_content(2, row.get(2)?)?,
+            category: Self::str_to_category(&row.get::<_, String>(3)?),
+            timestamp: row.get(4)?,
+            session_id: row.get(5)?,
+            score,
+            metadata,
         })
     }
 
+    /// Serialize metadata for the `metadata` column (NULL when empty)
+    fn seal_metadata(&self, metadata: &MemoryMetadata) -> anyhow::Result<Option<String>> {
+        if metadata.is_empty() {
+            return Ok(None);
+        }
+        let json = serde_json::to_string(metadata)?;
+        Ok(Some(encryption::seal_text(self.cipher(), &json)?))
+    }
+
+    fn expiry_to_str(at: DateTime<Utc>) -> String {
+        at.format("%Y-%m-%dT%H:%M:%SZ").to_string()
+    }
+
+    /// Delete rows whose TTL has passed
+    fn prune_expired(conn: &Connection) -> anyhow::Result<usize> {
+        Ok(conn.execute(
+            "DELETE FROM memories
+             WHERE expires_at IS NOT NULL AND expires_at <= strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+            [],
+        )?)
+    }
+
+    /// Decode a stored embedding or centroid, decrypting it if needed
+    fn decode_vector(cipher: Option<&MemoryCipher>, blob: Vec<u8>) -> anyhow::Result<Vec<f32>> {
+        Ok(vector::bytes_to_vec(&encryption::open_bytes(cipher, blob)?))
+    }
+
+    /// Encode an embedding or centroid for storage, encrypting it if configured
+    fn encode_vector(cipher: Option<&MemoryCipher>, v: &[f32]) -> anyhow::Result<Vec<u8>> {
+        encryption::seal_bytes(cipher, vector::vec_to_bytes(v))
+    }
+
+    /// Recall counts for `ids`; memories never recalled are absent
+    fn access_counts(
+        conn: &Connection,
+        ids: &[&str],
+    ) -> anyhow::Result<std::collections::HashMap<String, u32>> {
+        let mut stmt =
+            conn.prepare("SELECT access_count FROM memory_access WHERE memory_id = ?1")?;
+        let mut counts = std::collections::HashMap::new();
+        for id in ids {
+            if let Some(count) = stmt
+                .query_row(params![id], |row| row.get::<_, u32>(0))
+                .optional()?
+            {
+                counts.insert((*id).to_string(), count);
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Count one more recall for each of `ids`
+    fn record_access(conn: &Connection, ids: &[&str]) -> anyhow::Result<()> {
+        let now = Local::now().to_rfc3339();
+        let mut stmt = conn.prepare(
+            "INSERT INTO memory_access (memory_id, access_count, last_accessed_at)
+             VALUES (?1, 1, ?2)
+             ON CONFLICT(memory_id) DO UPDATE SET
+                access_count = access_count + 1,
+                last_accessed_at = excluded.last_accessed_at",
+        )?;
+        for id in ids {
+            stmt.execute(params![id, now])?;
+        }
+        Ok(())
+    }
+
+    /// Stored embeddings for `ids`, for measuring redundancy between results
+    fn load_embeddings(
+        conn: &Connection,
+        ids: &[&str],
+        cipher: Option<&MemoryCipher>,
+    ) -> anyhow::Result<std::collections::HashMap<String, Vec<f32>>> {
+        let mut stmt =
+            conn.prepare("SELECT embedding FROM memories WHERE id = ?1 AND embedding IS NOT NULL")?;
+        let mut embeddings = std::collections::HashMap::new();
+        for id in ids {
+            if let Some(blob) = stmt
+                .query_row(params![id], |row| row.get::<_, Vec<u8>>(0))
+                .optional()?
+            {
+                embeddings.insert((*id).to_string(), Self::decode_vector(cipher, blob)?);
+            }
+        }
+        Ok(embeddings)
+    }
+
+    /// Reorder recall hits with the ranking pipeline and keep the best `limit`
+    fn rank_results(
+        &self,
+        conn: &Connection,
+        results: Vec<MemoryEntry>,
+        limit: usize,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let ids: Vec<&str> = results.iter().map(|e| e.id.as_str()).collect();
+        let mut access = Self::access_counts(conn, &ids)?;
+        let mut embeddings = if self.ranking.mmr_lambda < 1.0 {
+            Self::load_embeddings(conn, &ids, self.cipher())?
+        } else {
+            std::collections::HashMap::new()
+        };
+        let candidates = results
+            .into_iter()
+            .map(|entry| RankCandidate {
+                relevance: entry.score.unwrap_or(0.0),
+                access_count: access.remove(&entry.id).unwrap_or(0),
+                embedding: embeddings.remove(&entry.id),
+                entry,
+            })
+            .collect();
+        Ok(ranking::rank(candidates, &self.ranking, Utc::now(), limit))
+    }
+
     /// Initialize all tables: memories, FTS5, `embedding_cache`
     fn init_schema(conn: &Connection) -> anyhow::Result<()> {
         conn.execute_batch(
@@ -99,22 +514,6 @@ impl SqliteMemory {
                 key, content, content=memories, content_rowid=rowid
             );
 
-            -- FTS5 triggers: keep in sync with memories table
-            CREATE TRIGGER IF NOT EXISTS memories_ai AFTER INSERT ON memories BEGIN
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_ad AFTER DELETE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-            END;
-            CREATE TRIGGER IF NOT EXISTS memories_au AFTER UPDATE ON memories BEGIN
-                INSERT INTO memories_fts(memories_fts, rowid, key, content)
-                VALUES ('delete', old.rowid, old.key, old.content);
-                INSERT INTO memories_fts(rowid, key, content)
-                VALUES (new.rowid, new.key, new.content);
-            END;
-
             -- Embedding cache with LRU eviction
             CREATE TABLE IF NOT EXISTS embedding_cache (
                 content_hash TEXT PRIMARY KEY,
@@ -122,21 +521,108 @@ impl SqliteMemory {
                 created_at   TEXT NOT NULL,
                 accessed_at  TEXT NOT NULL
             );
-            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);",
+            CREATE INDEX IF NOT EXISTS idx_cache_accessed ON embedding_cache(accessed_at);
+
+            -- IVF vector index: one centroid per list (see memory::ivf)
+            CREATE TABLE IF NOT EXISTS vector_centroids (
+                list_id  INTEGER PRIMARY KEY,
+                centroid BLOB NOT NULL
+            );
+            CREATE TABLE IF NOT EXISTS vector_index_state (
+                id           INTEGER PRIMARY KEY CHECK (id = 1),
+                dims         INTEGER NOT NULL,
+                trained_rows INTEGER NOT NULL
+            );
+
+            -- Originals folded into a consolidated memory (provenance + archive)
+            CREATE TABLE IF NOT EXISTS memory_archive (
+                id                TEXT PRIMARY KEY,
+                key               TEXT NOT NULL,
+                content           TEXT NOT NULL,
+                category          TEXT NOT NULL,
+                session_id        TEXT,
+                created_at        TEXT NOT NULL,
+                updated_at        TEXT NOT NULL,
+                consolidated_into TEXT NOT NULL,
+                archived_at       TEXT NOT NULL
+            );
+            CREATE INDEX IF NOT EXISTS idx_archive_consolidated
+                ON memory_archive(consolidated_into);
+
+            -- Which embedding provider produced the stored vectors
+            CREATE TABLE IF NOT EXISTS embedding_meta (
+                id          INTEGER PRIMARY KEY CHECK (id = 1),
+                fingerprint TEXT NOT NULL
+            );
+
+            -- How often recall returned each memory (ranking access boost).
+            -- Kept apart from `memories` so bumps don't churn the FTS index.
+            CREATE TABLE IF NOT EXISTS memory_access (
+                memory_id        TEXT PRIMARY KEY,
+                access_count     INTEGER NOT NULL DEFAULT 0,
+                last_accessed_at TEXT NOT NULL
+            );
+            CREATE TRIGGER IF NOT EXISTS memories_access_ad AFTER DELETE ON memories BEGIN
+                DELETE FROM memory_access WHERE memory_id = old.id;
+            END;",
         )?;
 
-        // Migration: add session_id column if not present (safe to run repeatedly)
-        let has_session_id: bool = conn
+        let memories_sql: String = conn
             .prepare("SELECT sql FROM sqlite_master WHERE type='table' AND name='memories'")?
-            .query_row([], |row| row.get::<_, String>(0))?
-            .contains("session_id");
-        if !has_session_id {
+            .query_row([], |row| row.get::<_, String>(0))?;
+
+        // Migration: add session_id column if not present (safe to run repeatedly)
+        if !memories_sql.contains("session_id") {
             conn.execute_batch(
                 "ALTER TABLE memories ADD COLUMN session_id TEXT;
                  CREATE INDEX IF NOT EXISTS idx_memories_session ON memories(session_id);",
             )?;
         }
 
+        // Migration: add the IVF list assignment column
+        if !memories_sql.contains("vector_list") {
+            conn.execute_batch("ALTER TABLE memories ADD COLUMN vector_list INTEGER;")?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_memories_vector_list ON memories(vector_list);
+             CREATE INDEX IF NOT EXISTS idx_memories_embedded ON memories(id)
+                 WHERE embedding IS NOT NULL;",
+        )?;
+
+        // Migration: blind-index tokens of encrypted content
+        if !memories_sql.contains("search_tokens") {
+            conn.execute_batch("ALTER TABLE memories ADD COLUMN search_tokens TEXT;")?;
+        }
+
+        // Migration: structured metadata (JSON) and TTL
+        if !memories_sql.contains("metadata") {
+            conn.execute_batch("ALTER TABLE memories ADD COLUMN metadata TEXT;")?;
+        }
+        if !memories_sql.contains("expires_at") {
+            conn.execute_batch("ALTER TABLE memories ADD COLUMN expires_at TEXT;")?;
+        }
+        conn.execute_batch(
+            "CREATE INDEX IF NOT EXISTS idx_memories_expires ON memories(expires_at)
+                 WHERE expires_at IS NOT NULL;",
+        )?;
+
+        // FTS5 triggers: keep in sync with memories table. Older versions
+        // indexed `content` unconditionally, so replace those triggers.
+        let current_triggers: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM sqlite_master
+             WHERE type = 'trigger' AND name = 'memories_ai' AND sql LIKE '%search_tokens%'",
+            [],
+            |row| row.get(0),
+        )?;
+        if current_triggers == 0 {
+            conn.execute_batch(
+                "DROP TRIGGER IF EXISTS memories_ai;
+                 DROP TRIGGER IF EXISTS memories_ad;
+                 DROP TRIGGER IF EXISTS memories_au;",
+            )?;
+        }
+        conn.execute_batch(FTS_TRIGGERS)?;
+
         Ok(())
     }
 
@@ -180,8 +666,18 @@ impl SqliteMemory {
         if self.embedder.dimensions() == 0 {
             return Ok(None); // Noop embedder
         }
+        // New vectors wouldn't be comparable with the stored ones (nor would
+        // cached ones with the new provider); `reindex` fills these rows in
+        if self.reembed_pending() {
+            return Ok(None);
+        }
 
-        let hash = Self::content_hash(text);
+        // Under encryption the cache key is keyed too, so equal texts can't
+        // be confirmed by hashing guesses
+        let hash = match self.cipher() {
+            Some(cipher) => cipher.keyed_hash(text),
+            None => Self::content_hash(text),
+        };
         let now = Local::now().to_rfc3339();
 
         // Check cache
@@ -198,13 +694,13 @@ impl SqliteMemory {
                     "UPDATE embedding_cache SET accessed_at = ?1 WHERE content_hash = ?2",
                     params![now, hash],
                 )?;
-                return Ok(Some(vector::bytes_to_vec(&bytes)));
+                return Ok(Some(Self::decode_vector(self.cipher(), bytes)?));
             }
         }
 
         // Compute embedding
         let embedding = self.embedder.embed_one(text).await?;
-        let bytes = vector::vec_to_bytes(&embedding);
+        let bytes = Self::encode_vector(self.cipher(), &embedding)?;
 
         // Store in cache + LRU eviction
         {
@@ -232,16 +728,24 @@ impl SqliteMemory {
         Ok(Some(embedding))
     }
 
-    /// FTS5 BM25 keyword search
+    /// FTS5 BM25 keyword search. With a cipher, each word also matches its
+    /// blind-index tokens so encrypted content stays searchable.
     fn fts5_search(
         conn: &Connection,
         query: &str,
         limit: usize,
+        cipher: Option<&MemoryCipher>,
     ) -> anyhow::Result<Vec<(String, f32)>> {
         // Escape FTS5 special chars and build query
         let fts_query: String = query
             .split_whitespace()
-            .map(|w| format!("\"{w}\""))
+            .flat_map(|w| {
+                let mut terms = vec![format!("\"{w}\"")];
+                if let Some(cipher) = cipher {
+                    terms.extend(cipher.blind_terms(w).iter().map(|t| format!("\"{t}\"")));
+                }
+                terms
+            })
             .collect::<Vec<_>>()
             .join(" OR ");
 
@@ -275,14 +779,33 @@ impl SqliteMemory {
         Ok(results)
     }
 
-    /// Vector similarity search: scan embeddings and compute cosine similarity
+    /// Vector similarity search: scan embeddings and compute cosine similarity.
+    ///
+    /// With a trained IVF index only the rows of the lists nearest to the query
+    /// (plus rows not yet assigned to a list) are scanned; otherwise all of them.
     fn vector_search(
         conn: &Connection,
         query_embedding: &[f32],
         limit: usize,
+        settings: &VectorIndexSettings,
+        cipher: Option<&MemoryCipher>,
     ) -> anyhow::Result<Vec<(String, f32)>> {
-        let mut stmt =
-            conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+        let sql = match Self::probe_lists(conn, query_embedding, settings, cipher)? {
+            Some(lists) => {
+                let lists = lists
+                    .iter()
+                    .map(usize::to_string)
+                    .collect::<Vec<_>>()
+                    .join(",");
+                format!(
+                    "SELECT id, embedding FROM memories
+                     WHERE embedding IS NOT NULL
+                       AND (vector_list IN ({lists}) OR vector_list IS NULL)"
+                )
+            }
+            None => "SELECT id, embedding FROM memories WHERE embedding IS NOT NULL".to_string(),
+        };
+        let mut stmt = conn.prepare(&sql)?;
 
         let rows = stmt.query_map([], |row| {
             let id: String = row.get(0)?;
@@ -293,7 +816,7 @@ impl SqliteMemory {
         let mut scored: Vec<(String, f32)> = Vec::new();
         for row in rows {
             let (id, blob) = row?;
-            let emb = vector::bytes_to_vec(&blob);
+            let emb = Self::decode_vector(cipher, blob)?;
             let sim = vector::cosine_similarity(query_embedding, &emb);
             if sim > 0.0 {
                 scored.push((id, sim));
@@ -305,86 +828,474 @@ impl SqliteMemory {
         Ok(scored)
     }
 
-    /// Safe reindex: rebuild FTS5 + embeddings with rollback on failure
+    /// `(dims, trained_rows)` of the current IVF index, if one is trained
+    fn vector_index_state(conn: &Connection) -> anyhow::Result<Option<(usize, usize)>> {
+        let state = conn
+            .query_row(
+                "SELECT dims, trained_rows FROM vector_index_state WHERE id = 1",
+                [],
+                |row| Ok((row.get::<_, i64>(0)?, row.get::<_, i64>(1)?)),
+            )
+            .optional()?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        Ok(state.map(|(dims, rows)| (dims as usize, rows as usize)))
+    }
+
+    /// Centroids of the IVF index, if it is trained for `dims`-wide vectors
+    fn load_centroids(
+        conn: &Connection,
+        dims: usize,
+        cipher: Option<&MemoryCipher>,
+    ) -> anyhow::Result<Option<Vec<Vec<f32>>>> {
+        match Self::vector_index_state(conn)? {
+            Some((trained_dims, _)) if trained_dims == dims => {}
+            _ => return Ok(None),
+        }
+        let mut stmt = conn.prepare("SELECT centroid FROM vector_centroids ORDER BY list_id")?;
+        let rows = stmt.query_map([], |row| row.get::<_, Vec<u8>>(0))?;
+        let mut centroids = Vec::new();
+        for row in rows {
+            centroids.push(Self::decode_vector(cipher, row?)?);
+        }
+        Ok((!centroids.is_empty()).then_some(centroids))
+    }
+
+    /// IVF lists to scan for `query`, or `None` when recall should be exact
+    fn probe_lists(
+        conn: &Connection,
+        query: &[f32],
+        settings: &VectorIndexSettings,
+        cipher: Option<&MemoryCipher>,
+    ) -> anyhow::Result<Option<Vec<usize>>> {
+        if !settings.enabled {
+            return Ok(None);
+        }
+        // Small stores (or an index trained before min_rows was raised) scan exactly
+        match Self::vector_index_state(conn)? {
+            Some((_, trained_rows)) if trained_rows >= settings.min_rows => {}
+            _ => return Ok(None),
+        }
+        Ok(Self::load_centroids(conn, query.len(), cipher)?
+            .map(|centroids| ivf::nearest(&centroids, query, settings.probes)))
+    }
+
+    /// List a new embedding belongs to under the current IVF index
+    fn assign_vector_list(
+        conn: &Connection,
+        embedding: &[f32],
+        cipher: Option<&MemoryCipher>,
+    ) -> anyhow::Result<Option<i64>> {
+        #[allow(clippy::cast_possible_wrap)]
+        Ok(Self::load_centroids(conn, embedding.len(), cipher)?
+            .and_then(|centroids| ivf::nearest(&centroids, embedding, 1).first().copied())
+            .map(|list| list as i64))
+    }
+
+    /// Whether the IVF index is missing, built for other dimensions, or trained
+    /// on a store much smaller than the current one
+    fn vector_index_stale(
+        conn: &Connection,
+        dims: usize,
+        settings: &VectorIndexSettings,
+    ) -> anyhow::Result<bool> {
+        if !settings.enabled {
+            return Ok(false);
+        }
+        let embedded: i64 = conn.query_row(
+            "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL",
+            [],
+            |row| row.get(0),
+        )?;
+        #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
+        let embedded = embedded as usize;
+        if embedded < settings.min_rows.max(1) {
+            return Ok(false);
+        }
+        Ok(match Self::vector_index_state(conn)? {
+            Some((trained_dims, trained_rows)) => {
+                trained_dims != dims || embedded >= trained_rows * VECTOR_INDEX_REGROW_FACTOR
+            }
+            None => true,
+        })
+    }
+
+    /// Retrain the IVF index over all stored embeddings and reassign every row.
+    /// Clears the index when indexing is off or the store is below `min_rows`.
+    /// Returns the number of lists.
+    fn rebuild_vector_index(
+        conn: &Connection,
+        settings: &VectorIndexSettings,
+        cipher: Option<&MemoryCipher>,
+    ) -> anyhow::Result<usize> {
+        let (ids, vectors): (Vec<String>, Vec<Vec<f32>>) = {
+            let mut stmt =
+                conn.prepare("SELECT id, embedding FROM memories WHERE embedding IS NOT NULL")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?))
+            })?;
+            let mut ids = Vec::new();
+            let mut vectors = Vec::new();
+            for row in rows {
+                let (id, blob) = row?;
+                ids.push(id);
+                vectors.push(Self::decode_vector(cipher, blob)?);
+            }
+            (ids, vectors)
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        tx.execute_batch(
+            "DELETE FROM vector_centroids;
+             DELETE FROM vector_index_state;
+             UPDATE memories SET vector_list = NULL WHERE vector_list IS NOT NULL;",
+        )?;
+
+        if !settings.enabled || vectors.len() < settings.min_rows.max(1) {
+            tx.commit()?;
+            return Ok(0);
+        }
+
+        let dims = vectors[0].len();
+        let centroids = ivf::train(&vectors, ivf::list_count(vectors.len()));
+        for (list_id, centroid) in centroids.iter().enumerate() {
+            #[allow(clippy::cast_possible_wrap)]
+            tx.execute(
+                "INSERT INTO vector_centroids (list_id, centroid) VALUES (?1, ?2)",
+                params![list_id as i64, Self::encode_vector(cipher, centroid)?],
+            )?;
+        }
+        #[allow(clippy::cast_possible_wrap)]
+        tx.execute(
+            "INSERT INTO vector_index_state (id, dims, trained_rows) VALUES (1, ?1, ?2)",
+            params![dims as i64, vectors.len() as i64],
+        )?;
+
+        {
+            let mut update = tx.prepare("UPDATE memories SET vector_list = ?1 WHERE id = ?2")?;
+            for (id, vector) in ids.iter().zip(&vectors) {
+                if let Some(&list) = ivf::nearest(&centroids, vector, 1).first() {
+                    #[allow(clippy::cast_possible_wrap)]
+                    update.execute(params![list as i64, id])?;
+                }
+            }
+        }
+        tx.commit()?;
+
+        tracing::debug!(
+            "memory: trained IVF index with {} lists over {} embeddings",
+            centroids.len(),
+            vectors.len()
+        );
+        Ok(centroids.len())
+    }
+
+    /// Safe reindex: rebuild FTS5 + embeddings + IVF index with rollback on failure
     #[allow(dead_code)]
     pub async fn reindex(&self) -> anyhow::Result<usize> {
+        let job = self.reindex_job();
+        let _guard = job.lock.lock().await;
+        job.run_locked().await
+    }
+
+    /// Encrypt (or, with `encrypt = false`, decrypt) `brain.db` in place:
+    /// content and its blind index, embeddings, IVF centroids and archived
+    /// originals. The embedding cache is cleared because its keys are hashed
+    /// differently under encryption. Returns the memory and archive rows
+    /// rewritten; a missing database is a no-op.
+    ///
+    /// Afterwards the FTS index is optimized, the WAL truncated and the file
+    /// vacuumed so no old values survive in free pages or stale segments.
+    pub fn migrate_encryption(
+        workspace_dir: &Path,
+        cipher: &MemoryCipher,
+        encrypt: bool,
+    ) -> anyhow::Result<usize> {
+        let db_path = workspace_dir.join("memory").join("brain.db");
+        if !db_path.exists() {
+            return Ok(0);
+        }
+        let conn = Connection::open(&db_path)?;
+        conn.execute_batch("PRAGMA journal_mode = WAL; PRAGMA synchronous = NORMAL;")?;
+        encryption::enable_secure_delete(&conn)?;
+        Self::init_schema(&conn)?;
+        let target = encrypt.then_some(cipher);
+
+        type MemoryRow = (String, String, Option<Vec<u8>>, Option<String>);
+        let memories: Vec<MemoryRow> = {
+            let mut stmt = conn.prepare("SELECT id, content, embedding, metadata FROM memories")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let archived: Vec<(String, String)> = {
+            let mut stmt = conn.prepare("SELECT id, content FROM memory_archive")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+        let centroids: Vec<(i64, Vec<u8>)> = {
+            let mut stmt = conn.prepare("SELECT list_id, centroid FROM vector_centroids")?;
+            let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.collect::<Result<_, _>>()?
+        };
+
+        let tx = conn.unchecked_transaction()?;
+        for (id, content, embedding, metadata) in &memories {
+            let plaintext = cipher.decrypt(content)?;
+            let embedding = embedding
+                .as_deref()
+                .map(|blob| encryption::reseal_bytes(cipher, blob, encrypt))
+                .transpose()?;
+            let metadata = metadata
+                .as_deref()
+                .map(|raw| encryption::reseal_text(cipher, raw, encrypt))
+                .transpose()?;
+            tx.execute(
+                "UPDATE memories SET content = ?1, search_tokens = ?2, embedding = ?3, metadata = ?4
+                 WHERE id = ?5",
+                params![
+                    encryption::reseal_text(cipher, content, encrypt)?,
+                    target.map(|cipher| cipher.blind_index(&plaintext)),
+                    embedding,
+                    metadata,
+                    id
+                ],
+            )?;
+        }
+        for (id, content) in &archived {
+            tx.execute(
+                "UPDATE memory_archive SET content = ?1 WHERE id = ?2",
+                params![encryption::reseal_text(cipher, content, encrypt)?, id],
+            )?;
+        }
+        for (list_id, blob) in &centroids {
+            tx.execute(
+                "UPDATE vector_centroids SET centroid = ?1 WHERE list_id = ?2",
+                params![encryption::reseal_bytes(cipher, blob, encrypt)?, list_id],
+            )?;
+        }
+        tx.execute("DELETE FROM embedding_cache", [])?;
+        tx.commit()?;
+        encryption::scrub_database(&conn, &["memories_fts"])?;
+
+        Ok(memories.len() + archived.len())
+    }
+}
+
+/// What `reindex` works on, owned so a pending re-embed can run as a
+/// background task
+struct ReindexJob {
+    conn: Arc<Mutex<Connection>>,
+    embedder: Arc<dyn EmbeddingProvider>,
+    cipher: Option<Arc<MemoryCipher>>,
+    vector_index: VectorIndexSettings,
+    pending: Arc<AtomicBool>,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+
+impl ReindexJob {
+    fn cipher(&self) -> Option<&MemoryCipher> {
+        self.cipher.as_deref()
+    }
+
+    /// `reindex` body; callers must hold `lock`
+    async fn run_locked(&self) -> anyhow::Result<usize> {
         // Step 1: Rebuild FTS5
         {
             let conn = self.conn.lock();
 
-            conn.execute_batch("INSERT INTO memories_fts(memories_fts) VALUES('rebuild');")?;
+            // Not FTS5 'rebuild': that would index the (possibly encrypted)
+            // content column instead of the blind index
+            conn.execute_batch(
+                "INSERT INTO memories_fts(memories_fts) VALUES('delete-all');
+                 INSERT INTO memories_fts(rowid, key, content)
+                 SELECT rowid, key, COALESCE(search_tokens, content) FROM memories;",
+            )?;
         }
 
-        // Step 2: Re-embed all memories that lack embeddings
         if self.embedder.dimensions() == 0 {
             return Ok(0);
         }
 
+        // Step 2: After a provider change, replace every vector at once
+        let mut count = 0;
+        if self.pending.load(Ordering::Acquire) {
+            count += self.swap_embeddings().await?;
+            self.pending.store(false, Ordering::Release);
+        }
+
+        // Step 3: Embed memories that lack embeddings, in batches
         let entries: Vec<(String, String)> = {
             let conn = self.conn.lock();
 
             let mut stmt =
                 conn.prepare("SELECT id, content FROM memories WHERE embedding IS NULL")?;
             let rows = stmt.query_map([], |row| {
-                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+                let content = encryption::open_text(self.cipher(), row.get(1)?)
+                    .map_err(|e| encryption::column_error(1, e))?;
+                Ok((row.get::<_, String>(0)?, content))
             })?;
             rows.filter_map(std::result::Result::ok).collect()
         };
 
-        let mut count = 0;
-        for (id, content) in &entries {
-            if let Ok(Some(emb)) = self.get_or_compute_embedding(content).await {
-                let bytes = vector::vec_to_bytes(&emb);
-                let conn = self.conn.lock();
+        for batch in entries.chunks(REINDEX_BATCH) {
+            let texts: Vec<&str> = batch.iter().map(|(_, content)| content.as_str()).collect();
+            // A failed batch aborts the pass; rows embedded so far are kept
+            // and the rest are picked up by the next reindex
+            let embeddings = self.embedder.embed(&texts).await?;
+
+            let conn = self.conn.lock();
+            for ((id, _), emb) in batch.iter().zip(&embeddings) {
+                if emb.is_empty() {
+                    continue;
+                }
                 conn.execute(
                     "UPDATE memories SET embedding = ?1 WHERE id = ?2",
-                    params![bytes, id],
+                    params![SqliteMemory::encode_vector(self.cipher(), emb)?, id],
                 )?;
                 count += 1;
             }
         }
 
-        Ok(count)
-    }
-}
+        // Step 4: Retrain the vector index over the refreshed embeddings
+        {
+            let conn = self.conn.lock();
+            SqliteMemory::rebuild_vector_index(&conn, &self.vector_index, self.cipher())?;
+        }
 
-#[async_trait]
-impl Memory for SqliteMemory {
-    fn name(&self) -> &str {
-        "sqlite"
+        Ok(count)
     }
 
-    async fn store(
+    /// Embed every memory with the current provider, then swap the vectors
+    /// and fingerprint in one transaction. Any failure before the commit
+    /// leaves the previous vectors, cache and IVF index untouched.
+    async fn swap_embeddings(&self) -> anyhow::Result<usize> {
+        let (previous, rows) = {
+            let conn = self.conn.lock();
+            let previous: Option<String> = conn
+                .query_row(
+                    "SELECT fingerprint FROM embedding_meta WHERE id = 1",
+                    [],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let mut stmt = conn.prepare("SELECT id, content FROM memories")?;
+            let rows = stmt.query_map([], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?;
+            (previous, rows.collect::<Result<Vec<_>, _>>()?)
+        };
+
+        let mut embedded = Vec::with_capacity(rows.len());
+        for batch in rows.chunks(REINDEX_BATCH) {
+            let texts = batch
+                .iter()
+                .map(|(_, stored)| encryption::open_text(self.cipher(), stored.clone()))
+                .collect::<anyhow::Result<Vec<_>>>()?;
+            let texts: Vec<&str> = texts.iter().map(String::as_str).collect();
+            let embeddings = self.embedder.embed(&texts).await?;
+            anyhow::ensure!(
+                embeddings.len() == batch.len(),
+                "embedding provider returned {} vectors for {} texts",
+                embeddings.len(),
+                batch.len()
+            );
+            embedded.extend(batch.iter().zip(embeddings));
+        }
+
+        let conn = self.conn.lock();
+        let tx = conn.unchecked_transaction()?;
+        let current: Option<String> = tx
+            .query_row(
+                "SELECT fingerprint FROM embedding_meta WHERE id = 1",
+                [],
+                |row| row.get(0),
+            )
+            .optional()?;
+        anyhow::ensure!(
+            current == previous,
+            "embedding provider changed again during re-embedding"
+        );
+
+        // Rows edited meanwhile keep NULL and are embedded by the next step
+        tx.execute_batch(
+            "UPDATE memories SET embedding = NULL, vector_list = NULL;
+             DELETE FROM embedding_cache;
+             DELETE FROM vector_centroids;
+             DELETE FROM vector_index_state;",
+        )?;
+        let mut count = 0;
+        {
+            let mut update =
+                tx.prepare("UPDATE memories SET embedding = ?1 WHERE id = ?2 AND content = ?3")?;
+            for ((id, stored), emb) in &embedded {
+                if emb.is_empty() {
+                    continue;
+                }
+                count += update.execute(params![
+                    SqliteMemory::encode_vector(self.cipher(), emb)?,
+                    id,
+                    stored
+                ])?;
+            }
+        }
+        tx.execute(
+            "INSERT INTO embedding_meta (id, fingerprint) VALUES (1, ?1)
+             ON CONFLICT(id) DO UPDATE SET fingerprint = excluded.fingerprint",
+            params![self.embedder.fingerprint()],
+        )?;
+        tx.commit()?;
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl Memory for SqliteMemory {
+    fn name(&self) -> &str {
+        "sqlite"
+    }
+
+    async fn store(
         &self,
         key: &str,
         content: &str,
         category: MemoryCategory,
         session_id: Option<&str>,
     ) -> anyhow::Result<()> {
-        // Compute embedding (async, before lock)
-        let embedding_bytes = self
-            .get_or_compute_embedding(content)
-            .await?
-            .map(|emb| vector::vec_to_bytes(&emb));
-
-        let conn = self.conn.lock();
-        let now = Local::now().to_rfc3339();
-        let cat = Self::category_to_str(&category);
-        let id = Uuid::new_v4().to_string();
+        self.store_with_metadata(
+            key,
+            content,
+            category,
+            session_id,
+            MemoryMetadata::default(),
+        )
+        .await
+    }
 
-        conn.execute(
-            "INSERT INTO memories (id, key, content, category, embedding, created_at, updated_at, session_id)
-             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)
-             ON CONFLICT(key) DO UPDATE SET
-                content = excluded.content,
-                category = excluded.category,
-                embedding = excluded.embedding,
-                updated_at = excluded.updated_at,
-                session_id = excluded.session_id",
-            params![id, key, content, cat, embedding_bytes, now, now, session_id],
-        )?;
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        self.write_entry(key, content, &category, session_id, metadata, None)
+            .await
+    }
 
-        Ok(())
+    async fn import(&self, entry: &MemoryEntry) -> anyhow::Result<()> {
+        let restored = (!entry.id.is_empty() && !entry.timestamp.is_empty())
+            .then_some((entry.id.as_str(), entry.timestamp.as_str()));
+        self.write_entry(
+            &entry.key,
+            &entry.content,
+            &entry.category,
+            entry.session_id.as_deref(),
+            entry.metadata.clone(),
+            restored,
+        )
+        .await
     }
 
     async fn recall(
@@ -392,22 +1303,45 @@ impl Memory for SqliteMemory {
         query: &str,
         limit: usize,
         session_id: Option<&str>,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        self.recall_filtered(query, limit, &RecallFilter::for_session(session_id))
+            .await
+    }
+
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
     ) -> anyhow::Result<Vec<MemoryEntry>> {
         if query.trim().is_empty() {
             return Ok(Vec::new());
         }
 
+        // Filters beyond the session drop candidates after retrieval, so
+        // widen the candidate pool to still fill `limit`
+        let mut pool = if filter.is_session_only() {
+            limit
+        } else {
+            limit.saturating_mul(FILTERED_RECALL_OVERSAMPLE)
+        };
+        if self.ranking.enabled {
+            pool = pool.max(limit.saturating_mul(RANKING_OVERSAMPLE));
+        }
+
         // Compute query embedding (async, before lock)
         let query_embedding = self.get_or_compute_embedding(query).await?;
 
         let conn = self.conn.lock();
 
         // FTS5 BM25 keyword search
-        let keyword_results = Self::fts5_search(&conn, query, limit * 2).unwrap_or_default();
+        let keyword_results =
+            Self::fts5_search(&conn, query, pool * 2, self.cipher()).unwrap_or_default();
 
         // Vector similarity search (if embeddings available)
         let vector_results = if let Some(ref qe) = query_embedding {
-            Self::vector_search(&conn, qe, limit * 2).unwrap_or_default()
+            Self::vector_search(&conn, qe, pool * 2, &self.vector_index, self.cipher())
+                .unwrap_or_default()
         } else {
             Vec::new()
         };
@@ -430,34 +1364,22 @@ impl Memory for SqliteMemory {
                 &keyword_results,
                 self.vector_weight,
                 self.keyword_weight,
-                limit,
+                pool,
             )
         };
 
         // Fetch full entries for merged results
         let mut results = Vec::new();
         for scored in &merged {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories WHERE id = ?1",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories WHERE id = ?1 AND {NOT_EXPIRED}"
+            ))?;
             if let Ok(entry) = stmt.query_row(params![scored.id], |row| {
-                Ok(MemoryEntry {
-                    id: row.get(0)?,
-                    key: row.get(1)?,
-                    content: row.get(2)?,
-                    category: Self::str_to_category(&row.get::<_, String>(3)?),
-                    timestamp: row.get(4)?,
-                    session_id: row.get(5)?,
-                    score: Some(f64::from(scored.final_score)),
-                })
+                self.row_to_entry(row, Some(f64::from(scored.final_score)))
             }) {
-                // Filter by session_id if requested
-                if let Some(sid) = session_id {
-                    if entry.session_id.as_deref() != Some(sid) {
-                        continue;
-                    }
+                if filter.matches(&entry) {
+                    results.push(entry);
                 }
-                results.push(entry);
             }
         }
 
@@ -466,17 +1388,27 @@ impl Memory for SqliteMemory {
             let keywords: Vec<String> =
                 query.split_whitespace().map(|w| format!("%{w}%")).collect();
             if !keywords.is_empty() {
+                // Encrypted content is only searchable through the blind index
+                let content_column = if self.cipher.is_some() {
+                    "key"
+                } else {
+                    "content"
+                };
                 let conditions: Vec<String> = keywords
                     .iter()
                     .enumerate()
                     .map(|(i, _)| {
-                        format!("(content LIKE ?{} OR key LIKE ?{})", i * 2 + 1, i * 2 + 2)
+                        format!(
+                            "({content_column} LIKE ?{} OR key LIKE ?{})",
+                            i * 2 + 1,
+                            i * 2 + 2
+                        )
                     })
                     .collect();
                 let where_clause = conditions.join(" OR ");
                 let sql = format!(
-                    "SELECT id, key, content, category, created_at, session_id FROM memories
-                     WHERE {where_clause}
+                    "SELECT {ENTRY_COLUMNS} FROM memories
+                     WHERE ({where_clause}) AND {NOT_EXPIRED}
                      ORDER BY updated_at DESC
                      LIMIT ?{}",
                     keywords.len() * 2 + 1
@@ -488,58 +1420,50 @@ impl Memory for SqliteMemory {
                     param_values.push(Box::new(kw.clone()));
                 }
                 #[allow(clippy::cast_possible_wrap)]
-                param_values.push(Box::new(limit as i64));
+                param_values.push(Box::new(pool as i64));
                 let params_ref: Vec<&dyn rusqlite::types::ToSql> =
                     param_values.iter().map(AsRef::as_ref).collect();
                 let rows = stmt.query_map(params_ref.as_slice(), |row| {
-                    Ok(MemoryEntry {
-                        id: row.get(0)?,
-                        key: row.get(1)?,
-                        content: row.get(2)?,
-                        category: Self::str_to_category(&row.get::<_, String>(3)?),
-                        timestamp: row.get(4)?,
-                        session_id: row.get(5)?,
-                        score: Some(1.0),
-                    })
+                    self.row_to_entry(row, Some(1.0))
                 })?;
                 for row in rows {
                     let entry = row?;
-                    if let Some(sid) = session_id {
-                        if entry.session_id.as_deref() != Some(sid) {
-                            continue;
-                        }
+                    if filter.matches(&entry) {
+                        results.push(entry);
                     }
-                    results.push(entry);
                 }
             }
         }
 
+        let mut results = if self.ranking.enabled {
+            self.rank_results(&conn, results, limit)?
+        } else {
+            results
+        };
         results.truncate(limit);
+
+        if filter.record_access {
+            let ids: Vec<&str> = results.iter().map(|e| e.id.as_str()).collect();
+            if let Err(e) = Self::record_access(&conn, &ids) {
+                tracing::debug!("memory: failed to record recall access: {e}");
+            }
+        }
         Ok(results)
     }
 
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>> {
         let conn = self.conn.lock();
 
-        let mut stmt = conn.prepare(
-            "SELECT id, key, content, category, created_at, session_id FROM memories WHERE key = ?1",
-        )?;
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {ENTRY_COLUMNS} FROM memories WHERE key = ?1 AND {NOT_EXPIRED}"
+        ))?;
 
-        let mut rows = stmt.query_map(params![key], |row| {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: row.get(5)?,
-                score: None,
-            })
-        })?;
+        let mut rows = stmt.query_map(params![key], |row| self.row_to_entry(row, None))?;
 
         match rows.next() {
             Some(Ok(entry)) => Ok(Some(entry)),
-            _ => Ok(None),
+            Some(Err(e)) => Err(e.into()),
+            None => Ok(None),
         }
     }
 
@@ -552,24 +1476,14 @@ impl Memory for SqliteMemory {
 
         let mut results = Vec::new();
 
-        let row_mapper = |row: &rusqlite::Row| -> rusqlite::Result<MemoryEntry> {
-            Ok(MemoryEntry {
-                id: row.get(0)?,
-                key: row.get(1)?,
-                content: row.get(2)?,
-                category: Self::str_to_category(&row.get::<_, String>(3)?),
-                timestamp: row.get(4)?,
-                session_id: row.get(5)?,
-                score: None,
-            })
-        };
+        let row_mapper = |row: &rusqlite::Row| self.row_to_entry(row, None);
 
         if let Some(cat) = category {
             let cat_str = Self::category_to_str(cat);
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories
-                 WHERE category = ?1 ORDER BY updated_at DESC",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE category = ?1 AND {NOT_EXPIRED} ORDER BY updated_at DESC"
+            ))?;
             let rows = stmt.query_map(params![cat_str], row_mapper)?;
             for row in rows {
                 let entry = row?;
@@ -581,10 +1495,10 @@ impl Memory for SqliteMemory {
                 results.push(entry);
             }
         } else {
-            let mut stmt = conn.prepare(
-                "SELECT id, key, content, category, created_at, session_id FROM memories
-                 ORDER BY updated_at DESC",
-            )?;
+            let mut stmt = conn.prepare(&format!(
+                "SELECT {ENTRY_COLUMNS} FROM memories
+                 WHERE {NOT_EXPIRED} ORDER BY updated_at DESC"
+            ))?;
             let rows = stmt.query_map([], row_mapper)?;
             for row in rows {
                 let entry = row?;
@@ -608,7 +1522,11 @@ impl Memory for SqliteMemory {
 
     async fn count(&self) -> anyhow::Result<usize> {
         let conn = self.conn.lock();
-        let count: i64 = conn.query_row("SELECT COUNT(*) FROM memories", [], |row| row.get(0))?;
+        let count: i64 = conn.query_row(
+            &format!("SELECT COUNT(*) FROM memories WHERE {NOT_EXPIRED}"),
+            [],
+            |row| row.get(0),
+        )?;
         #[allow(clippy::cast_sign_loss, clippy::cast_possible_truncation)]
         Ok(count as usize)
     }
@@ -616,6 +1534,10 @@ impl Memory for SqliteMemory {
     async fn health_check(&self) -> bool {
         self.conn.lock().execute_batch("SELECT 1").is_ok()
     }
+
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        SqliteMemory::reindex(self).await
+    }
 }
 
 #[cfg(test)]
@@ -1362,6 +2284,97 @@ mod tests {
         assert_eq!(mem.count().await.unwrap(), 1);
     }
 
+    // ── Ranking: access tracking ─────────────────────────────────
+
+    fn access_count(mem: &SqliteMemory, key: &str) -> Option<u32> {
+        let conn = mem.conn.lock();
+        conn.query_row(
+            "SELECT a.access_count FROM memory_access a
+             JOIN memories m ON m.id = a.memory_id WHERE m.key = ?1",
+            params![key],
+            |row| row.get(0),
+        )
+        .optional()
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn recall_records_access_counts() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("a", "rust ownership rules", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "python packaging notes", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        let explicit = RecallFilter {
+            record_access: true,
+            ..RecallFilter::default()
+        };
+        mem.recall_filtered("rust", 5, &explicit).await.unwrap();
+        mem.recall_filtered("rust ownership", 5, &explicit)
+            .await
+            .unwrap();
+        assert_eq!(access_count(&mem, "a"), Some(2));
+        assert_eq!(access_count(&mem, "b"), None);
+    }
+
+    #[tokio::test]
+    async fn automatic_recall_does_not_record_access() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("a", "rust ownership rules", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+
+        mem.recall("rust", 5, None).await.unwrap();
+        assert_eq!(access_count(&mem, "a"), None);
+    }
+
+    #[tokio::test]
+    async fn forget_drops_access_counts() {
+        let (_tmp, mem) = temp_sqlite();
+        mem.store("a", "rust ownership rules", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let explicit = RecallFilter {
+            record_access: true,
+            ..RecallFilter::default()
+        };
+        mem.recall_filtered("rust", 5, &explicit).await.unwrap();
+        assert_eq!(access_count(&mem, "a"), Some(1));
+        mem.forget("a").await.unwrap();
+
+        let orphans: i64 = mem
+            .conn
+            .lock()
+            .query_row("SELECT COUNT(*) FROM memory_access", [], |row| row.get(0))
+            .unwrap();
+        assert_eq!(orphans, 0);
+    }
+
+    #[tokio::test]
+    async fn ranking_prefers_important_memories() {
+        let (_tmp, mem) = temp_sqlite();
+        for (key, importance) in [("minor", 0.0), ("major", 1.0)] {
+            let metadata = MemoryMetadata {
+                importance: Some(importance),
+                ..MemoryMetadata::default()
+            };
+            mem.store_with_metadata(
+                key,
+                "server runs on port 8080",
+                MemoryCategory::Core,
+                None,
+                metadata,
+            )
+            .await
+            .unwrap();
+        }
+        let top = mem.recall("server port", 1, None).await.unwrap();
+        assert_eq!(top[0].key, "major");
+    }
+
     // ── Edge cases: reindex ──────────────────────────────────────
 
     #[tokio::test]
@@ -1583,4 +2596,619 @@ mod tests {
             assert_eq!(results[0].session_id.as_deref(), Some("sess-x"));
         }
     }
+
+    // ── IVF vector index ─────────────────────────────────────────
+
+    /// Deterministic 8-dim embedding: byte sums bucketed by position
+    struct ByteEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for ByteEmbedding {
+        fn name(&self) -> &str {
+            "bytes"
+        }
+
+        fn dimensions(&self) -> usize {
+            8
+        }
+
+        async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            Ok(texts
+                .iter()
+                .map(|text| {
+                    let mut v = vec![0.0_f32; 8];
+                    for (i, b) in text.bytes().enumerate() {
+                        v[i % 8] += f32::from(b);
+                    }
+                    v
+                })
+                .collect())
+        }
+    }
+
+    fn indexed_sqlite(min_rows: usize) -> (TempDir, SqliteMemory) {
+        let tmp = TempDir::new().unwrap();
+        let mem = SqliteMemory::with_embedder(tmp.path(), Arc::new(ByteEmbedding), 1.0, 0.0, 1000)
+            .unwrap()
+            .with_vector_index(VectorIndexSettings {
+                enabled: true,
+                min_rows,
+                probes: 1,
+            });
+        (tmp, mem)
+    }
+
+    fn centroid_count(mem: &SqliteMemory) -> i64 {
+        mem.conn
+            .lock()
+            .query_row("SELECT COUNT(*) FROM vector_centroids", [], |r| r.get(0))
+            .unwrap()
+    }
+
+    fn unassigned_count(mem: &SqliteMemory) -> i64 {
+        mem.conn
+            .lock()
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL AND vector_list IS NULL",
+                [],
+                |r| r.get(0),
+            )
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn schema_has_vector_index_tables() {
+        let (_tmp, mem) = temp_sqlite();
+        let conn = mem.conn.lock();
+        for table in ["vector_centroids", "vector_index_state"] {
+            let count: i64 = conn
+                .query_row(
+                    "SELECT COUNT(*) FROM sqlite_master WHERE type='table' AND name=?1",
+                    params![table],
+                    |r| r.get(0),
+                )
+                .unwrap();
+            assert_eq!(count, 1, "missing table {table}");
+        }
+    }
+
+    #[tokio::test]
+    async fn vector_index_untrained_below_min_rows() {
+        let (_tmp, mem) = indexed_sqlite(50);
+        for i in 0..10 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("note {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        assert_eq!(centroid_count(&mem), 0);
+
+        let results = mem.recall("note 3", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "k3");
+    }
+
+    #[tokio::test]
+    async fn vector_index_trains_once_min_rows_reached() {
+        let (_tmp, mem) = indexed_sqlite(20);
+        for i in 0..40 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("entry number {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        assert!(centroid_count(&mem) > 1);
+        assert_eq!(unassigned_count(&mem), 0);
+
+        // An exact copy of a stored text lands in that text's list
+        let results = mem.recall("entry number 27", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "k27");
+    }
+
+    #[tokio::test]
+    async fn vector_index_forget_drops_row_from_results() {
+        let (_tmp, mem) = indexed_sqlite(10);
+        for i in 0..20 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("item {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        assert!(mem.forget("k7").await.unwrap());
+
+        let results = mem.recall("item 7", 5, None).await.unwrap();
+        assert!(results.iter().all(|e| e.key != "k7"));
+    }
+
+    #[tokio::test]
+    async fn reindex_rebuilds_vector_index() {
+        let (_tmp, mem) = indexed_sqlite(10);
+        for i in 0..20 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("fact {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        mem.conn
+            .lock()
+            .execute_batch("DELETE FROM vector_centroids; DELETE FROM vector_index_state;")
+            .unwrap();
+
+        mem.reindex().await.unwrap();
+        assert!(centroid_count(&mem) > 0);
+        assert_eq!(unassigned_count(&mem), 0);
+    }
+
+    #[tokio::test]
+    async fn reindex_clears_vector_index_when_disabled() {
+        let (tmp, mem) = indexed_sqlite(10);
+        for i in 0..20 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("fact {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        assert!(centroid_count(&mem) > 0);
+        drop(mem);
+
+        let mem = SqliteMemory::with_embedder(tmp.path(), Arc::new(ByteEmbedding), 1.0, 0.0, 1000)
+            .unwrap()
+            .with_vector_index(VectorIndexSettings {
+                enabled: false,
+                ..VectorIndexSettings::default()
+            });
+        mem.reindex().await.unwrap();
+        assert_eq!(centroid_count(&mem), 0);
+
+        let results = mem.recall("fact 4", 1, None).await.unwrap();
+        assert_eq!(results[0].key, "k4");
+    }
+
+    // ── Embedding provider changes ───────────────────────────────
+
+    fn embedded_count(mem: &SqliteMemory) -> i64 {
+        mem.conn
+            .lock()
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE embedding IS NOT NULL",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap()
+    }
+
+    fn hashed_sqlite(tmp: &TempDir, dims: usize) -> SqliteMemory {
+        SqliteMemory::with_embedder(
+            tmp.path(),
+            Arc::new(super::super::embeddings::HashEmbedding::new(dims)),
+            0.7,
+            0.3,
+            1000,
+        )
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn same_provider_keeps_embeddings() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        mem.store("a", "alpha fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let mem = hashed_sqlite(&tmp, 16);
+        assert!(!mem.reembed_pending());
+        assert_eq!(embedded_count(&mem), 1);
+    }
+
+    fn stored_dims(mem: &SqliteMemory) -> usize {
+        mem.conn
+            .lock()
+            .query_row("SELECT embedding FROM memories LIMIT 1", [], |row| {
+                row.get::<_, Vec<u8>>(0)
+            })
+            .map(|bytes| vector::bytes_to_vec(&bytes).len())
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn provider_change_keeps_vectors_until_reindex() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        for i in 0..40 {
+            mem.store(
+                &format!("k{i}"),
+                &format!("stored fact number {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        drop(mem);
+
+        let mem = hashed_sqlite(&tmp, 32);
+        assert!(mem.reembed_pending());
+        assert_eq!(embedded_count(&mem), 40);
+
+        // Recall falls back to keywords and leaves the old vectors alone
+        let results = mem.recall("fact number 7", 3, None).await.unwrap();
+        assert!(!results.is_empty());
+        assert!(mem.reembed_pending());
+        assert_eq!(stored_dims(&mem), 16);
+
+        assert_eq!(mem.reindex().await.unwrap(), 40);
+        assert!(!mem.reembed_pending());
+        assert_eq!(embedded_count(&mem), 40);
+        assert_eq!(stored_dims(&mem), 32);
+    }
+
+    /// Claims 32 dimensions but is unreachable
+    struct DownEmbedding;
+
+    #[async_trait]
+    impl EmbeddingProvider for DownEmbedding {
+        fn name(&self) -> &str {
+            "down"
+        }
+
+        fn dimensions(&self) -> usize {
+            32
+        }
+
+        async fn embed(&self, _texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+            anyhow::bail!("connection refused")
+        }
+    }
+
+    #[tokio::test]
+    async fn failed_reembed_keeps_previous_vectors() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        mem.store("a", "alpha fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("b", "beta fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let down = SqliteMemory::with_embedder(tmp.path(), Arc::new(DownEmbedding), 0.7, 0.3, 1000)
+            .unwrap();
+        assert!(down.reembed_pending());
+        assert!(down.reindex().await.is_err());
+        assert!(down.reembed_pending());
+        assert_eq!(embedded_count(&down), 2);
+        drop(down);
+
+        // Switching back finds the original vectors untouched
+        let mem = hashed_sqlite(&tmp, 16);
+        assert!(!mem.reembed_pending());
+        assert_eq!(embedded_count(&mem), 2);
+        assert_eq!(stored_dims(&mem), 16);
+    }
+
+    #[tokio::test]
+    async fn noop_embedder_does_not_invalidate_vectors() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        mem.store("a", "alpha fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let noop = SqliteMemory::new(tmp.path()).unwrap();
+        assert!(!noop.reembed_pending());
+        drop(noop);
+
+        let mem = hashed_sqlite(&tmp, 16);
+        assert!(!mem.reembed_pending());
+        assert_eq!(embedded_count(&mem), 1);
+    }
+
+    #[tokio::test]
+    async fn reembed_clears_embedding_cache() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        mem.store("a", "alpha fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let mem = hashed_sqlite(&tmp, 8);
+        let cached = |mem: &SqliteMemory| -> i64 {
+            mem.conn
+                .lock()
+                .query_row("SELECT COUNT(*) FROM embedding_cache", [], |row| row.get(0))
+                .unwrap()
+        };
+        assert_eq!(cached(&mem), 1);
+        mem.reindex().await.unwrap();
+        assert_eq!(cached(&mem), 0);
+    }
+
+    #[tokio::test]
+    async fn explicit_reindex_clears_pending_flag() {
+        let tmp = TempDir::new().unwrap();
+        let mem = hashed_sqlite(&tmp, 16);
+        mem.store("a", "alpha fact", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        let mem = hashed_sqlite(&tmp, 24);
+        assert!(mem.reembed_pending());
+        assert_eq!(mem.reindex().await.unwrap(), 1);
+        assert!(!mem.reembed_pending());
+    }
+
+    fn raw_contents(tmp: &TempDir) -> Vec<String> {
+        let conn = Connection::open(tmp.path().join("memory").join("brain.db")).unwrap();
+        let mut stmt = conn.prepare("SELECT content FROM memories").unwrap();
+        stmt.query_map([], |row| row.get(0))
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn encrypted_store_hides_content_but_stays_searchable() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = Arc::new(MemoryCipher::for_config_dir(&tmp.path().join("cfg")).unwrap());
+        let mem = SqliteMemory::new(tmp.path()).unwrap().with_cipher(cipher);
+        mem.store(
+            "lang",
+            "Prefers Rust for systems work",
+            MemoryCategory::Core,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let raw = raw_contents(&tmp);
+        assert!(raw.iter().all(|c| !c.contains("Rust")));
+
+        let hits = mem.recall("rust", 5, None).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].content, "Prefers Rust for systems work");
+        let entry = mem.get("lang").await.unwrap().unwrap();
+        assert_eq!(entry.content, "Prefers Rust for systems work");
+    }
+
+    #[tokio::test]
+    async fn migrate_encryption_roundtrips_existing_rows() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::for_config_dir(&tmp.path().join("cfg")).unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        mem.store("lang", "Prefers Rust", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        drop(mem);
+
+        assert_eq!(
+            SqliteMemory::migrate_encryption(tmp.path(), &cipher, true).unwrap(),
+            1
+        );
+        assert!(raw_contents(&tmp)
+            .iter()
+            .all(|c| MemoryCipher::is_encrypted(c)));
+
+        // Reading without the key fails loudly rather than returning ciphertext
+        let plain = SqliteMemory::new(tmp.path()).unwrap();
+        assert!(plain.get("lang").await.is_err());
+        drop(plain);
+
+        SqliteMemory::migrate_encryption(tmp.path(), &cipher, false).unwrap();
+        assert_eq!(raw_contents(&tmp), vec!["Prefers Rust".to_string()]);
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        assert_eq!(mem.recall("rust", 5, None).await.unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn migrate_encryption_leaves_no_plaintext_on_disk() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = MemoryCipher::for_config_dir(&tmp.path().join("cfg")).unwrap();
+        let mem = SqliteMemory::new(tmp.path()).unwrap();
+        for i in 0..20 {
+            mem.store(
+                &format!("note_{i}"),
+                &format!("Vault combination is xylophonequokka {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+        }
+        drop(mem);
+
+        SqliteMemory::migrate_encryption(tmp.path(), &cipher, true).unwrap();
+
+        let dir = tmp.path().join("memory");
+        for file in ["brain.db", "brain.db-wal"] {
+            let Ok(bytes) = std::fs::read(dir.join(file)) else {
+                continue;
+            };
+            let needle = b"xylophonequokka";
+            assert!(
+                !bytes.windows(needle.len()).any(|w| w == needle),
+                "plaintext found in {file}"
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn metadata_roundtrips_through_get_and_list() {
+        let (_tmp, mem) = temp_sqlite();
+        let meta = MemoryMetadata {
+            tags: vec!["rust".into(), "Rust".into()],
+            source: Some("user".into()),
+            importance: Some(0.9),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("lang", "Prefers Rust", MemoryCategory::Core, None, meta)
+            .await
+            .unwrap();
+
+        let entry = mem.get("lang").await.unwrap().unwrap();
+        assert_eq!(entry.metadata.tags, vec!["rust".to_string()]);
+        assert_eq!(entry.metadata.source.as_deref(), Some("user"));
+        assert_eq!(entry.metadata.importance, Some(0.9));
+        assert_eq!(
+            mem.list(None, None).await.unwrap()[0].metadata,
+            entry.metadata
+        );
+
+        // A plain store replaces the entry, metadata included
+        mem.store("lang", "Prefers Go", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert!(mem.get("lang").await.unwrap().unwrap().metadata.is_empty());
+    }
+
+    #[tokio::test]
+    async fn expired_entries_are_hidden_and_pruned() {
+        let (_tmp, mem) = temp_sqlite();
+        let expired = MemoryMetadata::default()
+            .with_ttl(chrono::Duration::seconds(-1))
+            .unwrap();
+        mem.store_with_metadata("otp", "code 1234", MemoryCategory::Core, None, expired)
+            .await
+            .unwrap();
+        let live = MemoryMetadata::default()
+            .with_ttl(chrono::Duration::hours(1))
+            .unwrap();
+        mem.store_with_metadata(
+            "meeting",
+            "meeting code room",
+            MemoryCategory::Core,
+            None,
+            live,
+        )
+        .await
+        .unwrap();
+
+        assert!(mem.get("otp").await.unwrap().is_none());
+        assert_eq!(mem.count().await.unwrap(), 1);
+        let hits = mem.recall("code", 10, None).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "meeting");
+
+        // The next write deletes expired rows outright
+        mem.store("other", "anything", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let rows: i64 = mem
+            .conn
+            .lock()
+            .query_row(
+                "SELECT COUNT(*) FROM memories WHERE key = 'otp'",
+                [],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(rows, 0);
+    }
+
+    #[tokio::test]
+    async fn recall_filtered_by_tags_category_source_and_time() {
+        let (_tmp, mem) = temp_sqlite();
+        let work = MemoryMetadata {
+            tags: vec!["work".into()],
+            source: Some("user".into()),
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("a", "deploy notes", MemoryCategory::Core, None, work)
+            .await
+            .unwrap();
+        mem.store("b", "deploy checklist", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+
+        let by_tag = RecallFilter {
+            tags: vec!["WORK".into()],
+            ..RecallFilter::default()
+        };
+        let hits = mem.recall_filtered("deploy", 10, &by_tag).await.unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "a");
+
+        let by_category = RecallFilter {
+            categories: vec![MemoryCategory::Daily],
+            ..RecallFilter::default()
+        };
+        let hits = mem
+            .recall_filtered("deploy", 10, &by_category)
+            .await
+            .unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].key, "b");
+
+        let by_source = RecallFilter {
+            source: Some("agent".into()),
+            ..RecallFilter::default()
+        };
+        assert!(mem
+            .recall_filtered("deploy", 10, &by_source)
+            .await
+            .unwrap()
+            .is_empty());
+
+        let future = RecallFilter {
+            since: Some(Utc::now() + chrono::Duration::days(1)),
+            ..RecallFilter::default()
+        };
+        assert!(mem
+            .recall_filtered("deploy", 10, &future)
+            .await
+            .unwrap()
+            .is_empty());
+    }
+
+    #[tokio::test]
+    async fn encrypted_metadata_is_sealed() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = Arc::new(MemoryCipher::for_config_dir(&tmp.path().join("cfg")).unwrap());
+        let mem = SqliteMemory::new(tmp.path()).unwrap().with_cipher(cipher);
+        let meta = MemoryMetadata {
+            tags: vec!["medical".into()],
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("k", "private", MemoryCategory::Core, None, meta)
+            .await
+            .unwrap();
+
+        let raw: String = mem
+            .conn
+            .lock()
+            .query_row("SELECT metadata FROM memories", [], |row| row.get(0))
+            .unwrap();
+        assert!(!raw.contains("medical"));
+        assert!(mem
+            .get("k")
+            .await
+            .unwrap()
+            .unwrap()
+            .metadata
+            .has_tag("medical"));
+    }
 }
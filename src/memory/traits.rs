@@ -1,6 +1,11 @@
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
 
+/// How many candidates per requested result the default `recall_filtered`
+/// pulls before filtering
+const FILTER_OVERSAMPLE: usize = 4;
+
 /// A single memory entry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MemoryEntry {
@@ -11,6 +16,174 @@ pub struct MemoryEntry {
     pub timestamp: String,
     pub session_id: Option<String>,
     pub score: Option<f64>,
+    #[serde(default, skip_serializing_if = "MemoryMetadata::is_empty")]
+    pub metadata: MemoryMetadata,
+}
+
+impl MemoryEntry {
+    /// When the entry was written, if its timestamp is RFC 3339 or a plain date
+    pub fn created_at(&self) -> Option<DateTime<Utc>> {
+        if let Ok(ts) = DateTime::parse_from_rfc3339(&self.timestamp) {
+            return Some(ts.with_timezone(&Utc));
+        }
+        NaiveDate::parse_from_str(&self.timestamp, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc())
+    }
+}
+
+/// Structured, optional metadata attached to a memory entry
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct MemoryMetadata {
+    /// Free-form labels, compared case-insensitively
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Where the memory came from (e.g. "user", "agent", "docs", a URL)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    /// Relative importance in `[0, 1]`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub importance: Option<f64>,
+    /// After this instant the entry is no longer returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<DateTime<Utc>>,
+    /// Any other caller-defined fields
+    #[serde(default, flatten)]
+    pub extra: serde_json::Map<String, serde_json::Value>,
+}
+
+impl MemoryMetadata {
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Set `expires_at` to `ttl` from now. Fails when that instant is out of
+    /// the representable date range.
+    pub fn with_ttl(mut self, ttl: chrono::TimeDelta) -> anyhow::Result<Self> {
+        let expires_at = Utc::now()
+            .checked_add_signed(ttl)
+            .ok_or_else(|| anyhow::anyhow!("TTL is too large"))?;
+        self.expires_at = Some(expires_at);
+        Ok(self)
+    }
+
+    pub fn is_expired(&self, now: DateTime<Utc>) -> bool {
+        self.expires_at.is_some_and(|at| at <= now)
+    }
+
+    pub fn has_tag(&self, tag: &str) -> bool {
+        self.tags.iter().any(|t| t.eq_ignore_ascii_case(tag))
+    }
+
+    /// Trim and de-duplicate tags, clamp importance to `[0, 1]`
+    #[must_use]
+    pub fn normalized(mut self) -> Self {
+        let mut tags: Vec<String> = Vec::with_capacity(self.tags.len());
+        for tag in self.tags.iter().map(|t| t.trim()).filter(|t| !t.is_empty()) {
+            if !tags.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                tags.push(tag.to_string());
+            }
+        }
+        self.tags = tags;
+        self.importance = self.importance.map(|i| i.clamp(0.0, 1.0));
+        self
+    }
+}
+
+/// Constraints for `Memory::recall_filtered`. Empty fields match everything.
+#[derive(Debug, Clone, Default)]
+pub struct RecallFilter {
+    pub session_id: Option<String>,
+    /// Entry category must be one of these
+    pub categories: Vec<MemoryCategory>,
+    /// Entry must carry every one of these tags
+    pub tags: Vec<String>,
+    /// Written at or after this instant
+    pub since: Option<DateTime<Utc>>,
+    /// Written at or before this instant
+    pub until: Option<DateTime<Utc>>,
+    /// Drop results scoring below this
+    pub min_score: Option<f64>,
+    /// `metadata.source` must equal this (case-insensitive)
+    pub source: Option<String>,
+    /// Count the results as accessed for ranking. Set only for explicit
+    /// recalls (the `memory_recall` tool, the CLI); automatic context recall
+    /// leaves it off so the access boost doesn't feed on its own output.
+    pub record_access: bool,
+}
+
+impl RecallFilter {
+    /// A filter that only scopes recall to a session, like `Memory::recall`
+    pub fn for_session(session_id: Option<&str>) -> Self {
+        Self {
+            session_id: session_id.map(str::to_string),
+            ..Self::default()
+        }
+    }
+
+    /// True when only the session (if any) constrains results
+    pub fn is_session_only(&self) -> bool {
+        self.categories.is_empty()
+            && self.tags.is_empty()
+            && self.since.is_none()
+            && self.until.is_none()
+            && self.min_score.is_none()
+            && self.source.is_none()
+    }
+
+    /// Whether `entry` passes every constraint. Expired entries never match.
+    pub fn matches(&self, entry: &MemoryEntry) -> bool {
+        if entry.metadata.is_expired(Utc::now()) {
+            return false;
+        }
+        if let Some(sid) = self.session_id.as_deref() {
+            if entry.session_id.as_deref() != Some(sid) {
+                return false;
+            }
+        }
+        if !self.categories.is_empty() && !self.categories.contains(&entry.category) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| entry.metadata.has_tag(tag)) {
+            return false;
+        }
+        if let Some(source) = self.source.as_deref() {
+            if !entry
+                .metadata
+                .source
+                .as_deref()
+                .is_some_and(|s| s.eq_ignore_ascii_case(source))
+            {
+                return false;
+            }
+        }
+        if let Some(min) = self.min_score {
+            if entry.score.is_some_and(|s| s < min) {
+                return false;
+            }
+        }
+        if self.since.is_some() || self.until.is_some() {
+            let Some(created) = entry.created_at() else {
+                return false;
+            };
+            if self.since.is_some_and(|since| created < since)
+                || self.until.is_some_and(|until| created > until)
+            {
+                return false;
+            }
+        }
+        true
+    }
+
+    /// Keep the first `limit` entries that match
+    pub fn apply(&self, entries: Vec<MemoryEntry>, limit: usize) -> Vec<MemoryEntry> {
+        entries
+            .into_iter()
+            .filter(|e| self.matches(e))
+            .take(limit)
+            .collect()
+    }
 }
 
 /// Memory categories for organization
@@ -53,6 +226,33 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<()>;
 
+    /// Store a memory entry together with structured metadata (tags, source,
+    /// importance, expiry). Backends without metadata support drop it.
+    async fn store_with_metadata(
+        &self,
+        key: &str,
+        content: &str,
+        category: MemoryCategory,
+        session_id: Option<&str>,
+        metadata: MemoryMetadata,
+    ) -> anyhow::Result<()> {
+        let _ = metadata;
+        self.store(key, content, category, session_id).await
+    }
+
+    /// Restore an exported entry, keeping its id and timestamp where the
+    /// backend stores them. The default writes it as a new entry.
+    async fn import(&self, entry: &MemoryEntry) -> anyhow::Result<()> {
+        self.store_with_metadata(
+            &entry.key,
+            &entry.content,
+            entry.category.clone(),
+            entry.session_id.as_deref(),
+            entry.metadata.clone(),
+        )
+        .await
+    }
+
     /// Recall memories matching a query (keyword search), optionally scoped to a session
     async fn recall(
         &self,
@@ -61,6 +261,24 @@ pub trait Memory: Send + Sync {
         session_id: Option<&str>,
     ) -> anyhow::Result<Vec<MemoryEntry>>;
 
+    /// Recall memories matching a query, constrained by `filter`.
+    /// The default over-fetches through `recall` and filters the results.
+    async fn recall_filtered(
+        &self,
+        query: &str,
+        limit: usize,
+        filter: &RecallFilter,
+    ) -> anyhow::Result<Vec<MemoryEntry>> {
+        let candidates = self
+            .recall(
+                query,
+                limit.saturating_mul(FILTER_OVERSAMPLE),
+                filter.session_id.as_deref(),
+            )
+            .await?;
+        Ok(filter.apply(candidates, limit))
+    }
+
     /// Get a specific memory by key
     async fn get(&self, key: &str) -> anyhow::Result<Option<MemoryEntry>>;
 
@@ -79,6 +297,12 @@ pub trait Memory: Send + Sync {
 
     /// Health check
     async fn health_check(&self) -> bool;
+
+    /// Rebuild derived indexes (full-text, embeddings); returns entries re-embedded.
+    /// Backends without derived indexes have nothing to rebuild.
+    async fn reindex(&self) -> anyhow::Result<usize> {
+        Ok(0)
+    }
 }
 
 #[cfg(test)]
@@ -117,6 +341,7 @@ mod tests {
             timestamp: "2026-02-16T00:00:00Z".into(),
             session_id: Some("session-abc".into()),
             score: Some(0.98),
+            metadata: MemoryMetadata::default(),
         };
 
         let json = serde_json::to_string(&entry).unwrap();
@@ -129,4 +354,118 @@ mod tests {
         assert_eq!(parsed.session_id.as_deref(), Some("session-abc"));
         assert_eq!(parsed.score, Some(0.98));
     }
+
+    fn entry(category: MemoryCategory, timestamp: &str, metadata: MemoryMetadata) -> MemoryEntry {
+        MemoryEntry {
+            id: "id".into(),
+            key: "key".into(),
+            content: "content".into(),
+            category,
+            timestamp: timestamp.into(),
+            session_id: None,
+            score: Some(0.5),
+            metadata,
+        }
+    }
+
+    #[test]
+    fn metadata_roundtrips_and_keeps_extra_fields() {
+        let raw = r#"{"tags":["rust"],"source":"user","importance":0.8,"project":"zeroclaw"}"#;
+        let meta: MemoryMetadata = serde_json::from_str(raw).unwrap();
+        assert!(meta.has_tag("RUST"));
+        assert_eq!(meta.source.as_deref(), Some("user"));
+        assert_eq!(meta.extra["project"], "zeroclaw");
+
+        let back: MemoryMetadata =
+            serde_json::from_str(&serde_json::to_string(&meta).unwrap()).unwrap();
+        assert_eq!(back, meta);
+        assert!(MemoryMetadata::default().is_empty());
+    }
+
+    #[test]
+    fn metadata_normalizes_tags_and_importance() {
+        let meta = MemoryMetadata {
+            tags: vec![" Rust ".into(), "rust".into(), String::new(), "cli".into()],
+            importance: Some(3.0),
+            ..MemoryMetadata::default()
+        }
+        .normalized();
+        assert_eq!(meta.tags, vec!["Rust".to_string(), "cli".to_string()]);
+        assert_eq!(meta.importance, Some(1.0));
+    }
+
+    #[test]
+    fn entry_without_metadata_deserializes() {
+        let json = r#"{"id":"1","key":"k","content":"c","category":"core",
+            "timestamp":"2026-02-16T00:00:00Z","session_id":null,"score":null}"#;
+        let entry: MemoryEntry = serde_json::from_str(json).unwrap();
+        assert!(entry.metadata.is_empty());
+        assert!(!serde_json::to_string(&entry).unwrap().contains("metadata"));
+    }
+
+    #[test]
+    fn filter_matches_tags_source_and_category() {
+        let meta = MemoryMetadata {
+            tags: vec!["rust".into(), "work".into()],
+            source: Some("user".into()),
+            ..MemoryMetadata::default()
+        };
+        let e = entry(MemoryCategory::Core, "2026-02-16T00:00:00Z", meta);
+
+        assert!(RecallFilter::default().matches(&e));
+        let tagged = RecallFilter {
+            tags: vec!["Work".into()],
+            source: Some("USER".into()),
+            categories: vec![MemoryCategory::Core, MemoryCategory::Daily],
+            ..RecallFilter::default()
+        };
+        assert!(tagged.matches(&e));
+        let missing_tag = RecallFilter {
+            tags: vec!["rust".into(), "home".into()],
+            ..RecallFilter::default()
+        };
+        assert!(!missing_tag.matches(&e));
+        let other_category = RecallFilter {
+            categories: vec![MemoryCategory::Conversation],
+            ..RecallFilter::default()
+        };
+        assert!(!other_category.matches(&e));
+    }
+
+    #[test]
+    fn filter_applies_time_range_score_and_expiry() {
+        let e = entry(
+            MemoryCategory::Daily,
+            "2026-02-16",
+            MemoryMetadata::default(),
+        );
+        let day = |d: &str| DateTime::parse_from_rfc3339(d).unwrap().with_timezone(&Utc);
+
+        let in_range = RecallFilter {
+            since: Some(day("2026-02-15T00:00:00Z")),
+            until: Some(day("2026-02-17T00:00:00Z")),
+            min_score: Some(0.4),
+            ..RecallFilter::default()
+        };
+        assert!(in_range.matches(&e));
+        let too_late = RecallFilter {
+            since: Some(day("2026-02-16T12:00:00Z")),
+            ..RecallFilter::default()
+        };
+        assert!(!too_late.matches(&e));
+        let high_bar = RecallFilter {
+            min_score: Some(0.9),
+            ..RecallFilter::default()
+        };
+        assert!(!high_bar.matches(&e));
+
+        let expired = entry(
+            MemoryCategory::Core,
+            "2026-02-16T00:00:00Z",
+            MemoryMetadata::default()
+                .with_ttl(chrono::Duration::seconds(-1))
+                .unwrap(),
+        );
+        assert!(!RecallFilter::default().matches(&expired));
+    }
 }
@@ -67,6 +67,9 @@ impl Observer for LogObserver {
             ObserverEvent::TurnComplete => {
                 info!("turn.complete");
             }
+            ObserverEvent::ResponseCacheLookup { hit } => {
+                info!(hit = hit, "response_cache.lookup");
+            }
             ObserverEvent::ChannelMessage { channel, direction } => {
                 info!(channel = %channel, direction = %direction, "channel.message");
             }
@@ -150,6 +153,7 @@ mod tests {
             success: false,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
+        obs.record_event(&ObserverEvent::ResponseCacheLookup { hit: true });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "outbound".into(),
@@ -11,7 +11,7 @@ pub use self::log::LogObserver;
 pub use self::multi::MultiObserver;
 pub use noop::NoopObserver;
 pub use otel::OtelObserver;
-pub use traits::{Observer, ObserverEvent};
+pub use traits::{Observer, ObserverEvent, ObserverMetric};
 #[allow(unused_imports)]
 pub use verbose::VerboseObserver;
 
@@ -21,6 +21,7 @@ pub struct OtelObserver {
     tool_duration: Histogram<f64>,
     channel_messages: Counter<u64>,
     heartbeat_ticks: Counter<u64>,
+    response_cache_lookups: Counter<u64>,
     errors: Counter<u64>,
     request_latency: Histogram<f64>,
     tokens_used: Counter<u64>,
@@ -123,6 +124,11 @@ impl OtelObserver {
             .with_description("Total heartbeat ticks")
             .build();
 
+        let response_cache_lookups = meter
+            .u64_counter("zeroclaw.response_cache.lookups")
+            .with_description("LLM response cache lookups by hit/miss")
+            .build();
+
         let errors = meter
             .u64_counter("zeroclaw.errors")
             .with_description("Total errors by component")
@@ -160,6 +166,7 @@ impl OtelObserver {
             tool_duration,
             channel_messages,
             heartbeat_ticks,
+            response_cache_lookups,
             errors,
             request_latency,
             tokens_used,
@@ -302,6 +309,10 @@ impl Observer for OtelObserver {
             ObserverEvent::HeartbeatTick => {
                 self.heartbeat_ticks.add(1, &[]);
             }
+            ObserverEvent::ResponseCacheLookup { hit } => {
+                self.response_cache_lookups
+                    .add(1, &[KeyValue::new("hit", hit.to_string())]);
+            }
             ObserverEvent::Error { component, message } => {
                 // Create an error span for visibility in trace backends
                 let mut span = tracer.build(
@@ -419,6 +430,7 @@ mod tests {
             success: false,
         });
         obs.record_event(&ObserverEvent::TurnComplete);
+        obs.record_event(&ObserverEvent::ResponseCacheLookup { hit: false });
         obs.record_event(&ObserverEvent::ChannelMessage {
             channel: "telegram".into(),
             direction: "inbound".into(),
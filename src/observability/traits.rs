@@ -40,6 +40,10 @@ pub enum ObserverEvent {
     },
     /// The agent produced a final answer for the current user message.
     TurnComplete,
+    /// The LLM response cache was consulted for a tool-free turn.
+    ResponseCacheLookup {
+        hit: bool,
+    },
     ChannelMessage {
         channel: String,
         direction: String,
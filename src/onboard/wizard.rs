@@ -1,8 +1,9 @@
 use crate::config::schema::{DingTalkConfig, IrcConfig, QQConfig, WhatsAppConfig};
 use crate::config::{
-    AutonomyConfig, BrowserConfig, ChannelsConfig, ComposioConfig, Config, DiscordConfig,
-    HeartbeatConfig, IMessageConfig, MatrixConfig, MemoryConfig, ObservabilityConfig,
-    RuntimeConfig, SecretsConfig, SlackConfig, TelegramConfig, WebhookConfig,
+    AutonomyConfig, BrowserConfig, ChannelSessionConfig, ChannelTenantConfig, ChannelsConfig,
+    ComposioConfig, Config, DiscordConfig, HeartbeatConfig, IMessageConfig, MatrixConfig,
+    MemoryConfig, ObservabilityConfig, RuntimeConfig, SecretsConfig, SlackConfig, TelegramConfig,
+    WebhookConfig,
 };
 use crate::hardware::{self, HardwareConfig};
 use crate::memory::{
@@ -114,6 +115,7 @@ pub fn run_wizard() -> Result<Config> {
         default_provider: Some(provider),
         default_model: Some(model),
         default_temperature: 0.7,
+        default_model_vision: false,
         observability: ObservabilityConfig::default(),
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
@@ -282,9 +284,29 @@ fn memory_config_defaults_for_backend(backend: &str) -> MemoryConfig {
             0
         },
         chunk_max_tokens: 512,
+        vector_index_enabled: true,
+        vector_index_min_rows: 2_000,
+        vector_index_probes: 8,
+        ranking_enabled: true,
+        ranking_relevance_weight: 1.0,
+        ranking_recency_weight: 0.2,
+        ranking_importance_weight: 0.15,
+        ranking_access_weight: 0.1,
+        ranking_mmr_lambda: 0.8,
+        ranking_core_half_life_days: 0.0,
+        ranking_daily_half_life_days: 7.0,
+        ranking_conversation_half_life_days: 3.0,
+        ranking_custom_half_life_days: 30.0,
         response_cache_enabled: false,
         response_cache_ttl_minutes: 60,
         response_cache_max_entries: 5_000,
+        response_cache_max_temperature: 0.2,
+        consolidation_enabled: false,
+        consolidation_similarity: 0.85,
+        consolidation_token_budget: 20_000,
+        encryption_enabled: false,
+        ingest_paths: Vec::new(),
+        ingest_interval_secs: 300,
         snapshot_enabled: false,
         snapshot_on_hygiene: false,
         auto_hydrate: true,
@@ -332,6 +354,7 @@ pub fn run_quick_setup(
         default_provider: Some(provider_name.clone()),
         default_model: Some(model.clone()),
         default_temperature: 0.7,
+        default_model_vision: false,
         observability: ObservabilityConfig::default(),
         autonomy: AutonomyConfig::default(),
         runtime: RuntimeConfig::default(),
@@ -2431,6 +2454,8 @@ fn setup_channels() -> Result<ChannelsConfig> {
         lark: None,
         dingtalk: None,
         qq: None,
+        sessions: ChannelSessionConfig::default(),
+        tenants: ChannelTenantConfig::default(),
     };
 
     loop {
@@ -1,9 +1,12 @@
+use crate::providers::streaming::{error_stream, request_stream, sse_data, StreamParser};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    ContentPart, MediaSource, Provider, Reasoning, ReasoningOptions, StreamChunk, StreamError,
+    StreamOptions, StreamResult, ToolCall as ProviderToolCall, ToolCallDelta, Usage,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -47,11 +50,57 @@ struct NativeChatRequest {
     model: String,
     max_tokens: u32,
     #[serde(skip_serializing_if = "Option::is_none")]
-    system: Option<String>,
+    system: Option<Vec<SystemBlock>>,
     messages: Vec<NativeMessage>,
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tool_choice: Option<NativeToolChoice>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thinking: Option<ThinkingConfig>,
+}
+
+/// Forces a call to the named tool; how a response format is enforced.
+#[derive(Debug, Serialize)]
+struct NativeToolChoice {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    name: String,
+}
+
+/// Extended thinking. The budget counts against `max_tokens`.
+#[derive(Debug, Serialize)]
+struct ThinkingConfig {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    budget_tokens: u32,
+}
+
+/// Smallest thinking budget the API accepts.
+const MIN_THINKING_BUDGET: u32 = 1024;
+
+/// Prompt-caching breakpoint: everything up to and including the marked
+/// block is cached and billed at the cache-read rate on the next request.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+struct CacheControl {
+    #[serde(rename = "type")]
+    kind: &'static str,
+}
+
+impl CacheControl {
+    const EPHEMERAL: Self = Self { kind: "ephemeral" };
+}
+
+#[derive(Debug, Serialize)]
+struct SystemBlock {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    text: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
 }
 
 #[derive(Debug, Serialize)]
@@ -64,18 +113,93 @@ struct NativeMessage {
 #[serde(tag = "type")]
 enum NativeContentOut {
     #[serde(rename = "text")]
-    Text { text: String },
+    Text {
+        text: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
+    #[serde(rename = "image")]
+    Image {
+        source: NativeImageSource,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
+    },
     #[serde(rename = "tool_use")]
     ToolUse {
         id: String,
         name: String,
         input: serde_json::Value,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
     #[serde(rename = "tool_result")]
     ToolResult {
         tool_use_id: String,
         content: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        cache_control: Option<CacheControl>,
     },
+    /// Thinking replayed verbatim; the signature proves it is unmodified.
+    #[serde(rename = "thinking")]
+    Thinking { thinking: String, signature: String },
+    #[serde(rename = "redacted_thinking")]
+    RedactedThinking { data: String },
+}
+
+impl NativeContentOut {
+    fn text(text: impl Into<String>) -> Self {
+        Self::Text {
+            text: text.into(),
+            cache_control: None,
+        }
+    }
+
+    fn set_cache_control(&mut self) {
+        match self {
+            Self::Text { cache_control, .. }
+            | Self::Image { cache_control, .. }
+            | Self::ToolUse { cache_control, .. }
+            | Self::ToolResult { cache_control, .. } => {
+                *cache_control = Some(CacheControl::EPHEMERAL);
+            }
+            // Thinking blocks cannot carry a breakpoint
+            Self::Thinking { .. } | Self::RedactedThinking { .. } => {}
+        }
+    }
+
+    /// Rebuild thinking blocks from [`Reasoning::replay`], which holds them
+    /// as the API returned them.
+    fn from_replay(replay: &serde_json::Value) -> Vec<Self> {
+        let Some(blocks) = replay.as_array() else {
+            return Vec::new();
+        };
+        let field = |block: &serde_json::Value, name: &str| {
+            block
+                .get(name)
+                .and_then(serde_json::Value::as_str)
+                .map(ToString::to_string)
+        };
+        blocks
+            .iter()
+            .filter_map(|block| match block.get("type")?.as_str()? {
+                "thinking" => Some(Self::Thinking {
+                    thinking: field(block, "thinking")?,
+                    signature: field(block, "signature")?,
+                }),
+                "redacted_thinking" => Some(Self::RedactedThinking {
+                    data: field(block, "data")?,
+                }),
+                _ => None,
+            })
+            .collect()
+    }
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum NativeImageSource {
+    Base64 { media_type: String, data: String },
+    Url { url: String },
 }
 
 #[derive(Debug, Serialize)]
@@ -83,12 +207,43 @@ struct NativeToolSpec {
     name: String,
     description: String,
     input_schema: serde_json::Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cache_control: Option<CacheControl>,
 }
 
 #[derive(Debug, Deserialize)]
 struct NativeChatResponse {
     #[serde(default)]
     content: Vec<NativeContentIn>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeUsage {
+    #[serde(default)]
+    input_tokens: u64,
+    #[serde(default)]
+    output_tokens: u64,
+    #[serde(default)]
+    cache_creation_input_tokens: u64,
+    #[serde(default)]
+    cache_read_input_tokens: u64,
+}
+
+/// Anthropic reports cached prompt tokens separately from `input_tokens`;
+/// fold them back in so `input_tokens` is the whole prompt.
+impl From<NativeUsage> for Usage {
+    fn from(usage: NativeUsage) -> Self {
+        Usage::new(
+            usage.input_tokens + usage.cache_read_input_tokens + usage.cache_creation_input_tokens,
+            usage.output_tokens,
+        )
+        .with_cache(
+            usage.cache_read_input_tokens,
+            usage.cache_creation_input_tokens,
+        )
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -103,6 +258,205 @@ struct NativeContentIn {
     name: Option<String>,
     #[serde(default)]
     input: Option<serde_json::Value>,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    signature: Option<String>,
+    #[serde(default)]
+    data: Option<String>,
+}
+
+impl NativeContentIn {
+    /// The block in replay form, if it is a thinking block.
+    fn thinking_replay(&self) -> Option<serde_json::Value> {
+        match self.kind.as_str() {
+            "thinking" => Some(serde_json::json!({
+                "type": "thinking",
+                "thinking": self.thinking.clone().unwrap_or_default(),
+                "signature": self.signature.clone().unwrap_or_default(),
+            })),
+            "redacted_thinking" => Some(serde_json::json!({
+                "type": "redacted_thinking",
+                "data": self.data.clone().unwrap_or_default(),
+            })),
+            _ => None,
+        }
+    }
+}
+
+/// One server-sent event from a streaming Messages API response.
+#[derive(Debug, Deserialize)]
+struct StreamEvent {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    message: Option<StreamMessage>,
+    #[serde(default)]
+    content_block: Option<NativeContentIn>,
+    #[serde(default)]
+    delta: Option<StreamDelta>,
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+    #[serde(default)]
+    error: Option<StreamErrorBody>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamMessage {
+    #[serde(default)]
+    usage: Option<NativeUsage>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamDelta {
+    #[serde(rename = "type", default)]
+    kind: String,
+    #[serde(default)]
+    text: Option<String>,
+    #[serde(default)]
+    partial_json: Option<String>,
+    #[serde(default)]
+    thinking: Option<String>,
+    #[serde(default)]
+    signature: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamErrorBody {
+    message: String,
+}
+
+/// Parses Messages API SSE events. Tool-use blocks are keyed by their
+/// content-block index; input tokens arrive in `message_start` and output
+/// tokens in `message_delta`. Thinking blocks are streamed as reasoning and
+/// reassembled, signatures included, for replay.
+#[derive(Default)]
+pub(crate) struct AnthropicStreamParser {
+    usage: Option<Usage>,
+    thinking: Vec<(usize, serde_json::Value)>,
+    stopped: bool,
+}
+
+impl AnthropicStreamParser {
+    fn thinking_block(&mut self, index: usize) -> Option<&mut serde_json::Value> {
+        self.thinking
+            .iter_mut()
+            .find(|(i, _)| *i == index)
+            .map(|(_, block)| block)
+    }
+}
+
+impl StreamParser for AnthropicStreamParser {
+    fn parse_line(&mut self, line: &str) -> StreamResult<Option<StreamChunk>> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+        let event: StreamEvent = serde_json::from_str(data).map_err(StreamError::Json)?;
+
+        match event.kind.as_str() {
+            "message_start" => {
+                if let Some(usage) = event.message.and_then(|m| m.usage) {
+                    self.usage = Some(usage.into());
+                }
+                Ok(None)
+            }
+            "message_delta" => {
+                if let Some(reported) = event.usage {
+                    let output_tokens = reported.output_tokens;
+                    let usage = self.usage.get_or_insert_with(Usage::default);
+                    // Later API versions repeat the prompt counts here
+                    if reported.input_tokens > 0 {
+                        *usage = reported.into();
+                    }
+                    usage.output_tokens = output_tokens;
+                }
+                Ok(None)
+            }
+            "content_block_start" => {
+                let Some(block) = event.content_block else {
+                    return Ok(None);
+                };
+                if let Some(replay) = block.thinking_replay() {
+                    self.thinking.push((event.index, replay));
+                    return Ok(None);
+                }
+                Ok((block.kind == "tool_use").then(|| {
+                    StreamChunk::delta("").with_tool_calls(vec![ToolCallDelta {
+                        index: event.index,
+                        id: block.id,
+                        name: block.name,
+                        arguments: String::new(),
+                    }])
+                }))
+            }
+            "content_block_delta" => {
+                let Some(delta) = event.delta else {
+                    return Ok(None);
+                };
+                Ok(match delta.kind.as_str() {
+                    "text_delta" => delta.text.filter(|t| !t.is_empty()).map(StreamChunk::delta),
+                    "thinking_delta" => {
+                        let text = delta.thinking.filter(|t| !t.is_empty());
+                        if let (Some(text), Some(block)) = (&text, self.thinking_block(event.index))
+                        {
+                            let mut thinking = block["thinking"].as_str().unwrap_or("").to_string();
+                            thinking.push_str(text);
+                            block["thinking"] = serde_json::Value::String(thinking);
+                        }
+                        text.map(StreamChunk::reasoning)
+                    }
+                    "signature_delta" => {
+                        if let (Some(signature), Some(block)) =
+                            (delta.signature, self.thinking_block(event.index))
+                        {
+                            block["signature"] = serde_json::Value::String(signature);
+                        }
+                        None
+                    }
+                    "input_json_delta" => {
+                        delta
+                            .partial_json
+                            .filter(|json| !json.is_empty())
+                            .map(|arguments| {
+                                StreamChunk::delta("").with_tool_calls(vec![ToolCallDelta {
+                                    index: event.index,
+                                    arguments,
+                                    ..ToolCallDelta::default()
+                                }])
+                            })
+                    }
+                    _ => None,
+                })
+            }
+            "message_stop" => {
+                self.stopped = true;
+                Ok(None)
+            }
+            "error" => Err(StreamError::Provider(format!(
+                "Anthropic stream error: {}",
+                event
+                    .error
+                    .map_or_else(|| "unknown error".to_string(), |e| e.message)
+            ))),
+            _ => Ok(None),
+        }
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    fn finished(&self) -> bool {
+        self.stopped
+    }
+
+    fn reasoning_replay(&self) -> Option<serde_json::Value> {
+        (!self.thinking.is_empty()).then(|| {
+            serde_json::Value::Array(self.thinking.iter().map(|(_, b)| b.clone()).collect())
+        })
+    }
 }
 
 impl AnthropicProvider {
@@ -159,27 +513,203 @@ impl AnthropicProvider {
                     name: tool.name.clone(),
                     description: tool.description.clone(),
                     input_schema: tool.parameters.clone(),
+                    cache_control: None,
                 })
                 .collect(),
         )
     }
 
+    /// Convert OpenAI-format tool definitions (as built by the agent loop).
+    fn tools_from_openai_format(tools: &[serde_json::Value]) -> Option<Vec<NativeToolSpec>> {
+        let specs: Vec<NativeToolSpec> = tools
+            .iter()
+            .filter_map(|t| {
+                let func = t.get("function")?;
+                Some(NativeToolSpec {
+                    name: func.get("name")?.as_str()?.to_string(),
+                    description: func
+                        .get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or("")
+                        .to_string(),
+                    input_schema: func
+                        .get("parameters")
+                        .cloned()
+                        .unwrap_or(serde_json::json!({"type": "object"})),
+                    cache_control: None,
+                })
+            })
+            .collect();
+        (!specs.is_empty()).then_some(specs)
+    }
+
+    fn stream_native(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<Vec<NativeToolSpec>>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.as_ref() else {
+            return error_stream(
+                "Anthropic credentials not set. Set ANTHROPIC_API_KEY or ANTHROPIC_OAUTH_TOKEN (setup-token).",
+            );
+        };
+
+        let native_request = Self::build_native_request(
+            messages,
+            tools,
+            model,
+            temperature,
+            Some(true),
+            options.reasoning,
+        );
+
+        let req = self
+            .client
+            .post(format!("{}/v1/messages", self.base_url))
+            .header("anthropic-version", "2023-06-01")
+            .header("content-type", "application/json")
+            .json(&native_request);
+
+        request_stream(
+            self.apply_auth(req, credential),
+            "Anthropic",
+            AnthropicStreamParser::default(),
+            options.count_tokens,
+        )
+    }
+
+    fn build_native_request(
+        messages: &[ChatMessage],
+        tools: Option<Vec<NativeToolSpec>>,
+        model: &str,
+        temperature: f64,
+        stream: Option<bool>,
+        reasoning: Option<ReasoningOptions>,
+    ) -> NativeChatRequest {
+        let (system, messages) = Self::convert_messages(messages);
+        let thinking = reasoning.map(|reasoning| ThinkingConfig {
+            kind: "enabled",
+            budget_tokens: reasoning.budget_tokens().max(MIN_THINKING_BUDGET),
+        });
+        let mut request = NativeChatRequest {
+            model: model.to_string(),
+            // The thinking budget comes out of max_tokens; keep room for the answer
+            max_tokens: 4096 + thinking.as_ref().map_or(0, |t| t.budget_tokens),
+            system,
+            messages,
+            // Thinking only accepts the default temperature
+            temperature: if thinking.is_some() { 1.0 } else { temperature },
+            tools,
+            tool_choice: None,
+            stream,
+            thinking,
+        };
+        Self::apply_cache_breakpoints(&mut request);
+        request
+    }
+
+    /// Native request for [`Provider::chat`]. A response format becomes an
+    /// extra tool the model is forced to call, with the schema as its input.
+    fn build_chat_request(
+        request: &ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> NativeChatRequest {
+        let Some(format) = request.response_format else {
+            return Self::build_native_request(
+                request.messages,
+                Self::convert_tools(request.tools),
+                model,
+                temperature,
+                None,
+                request.reasoning,
+            );
+        };
+        let mut tools = Self::convert_tools(request.tools).unwrap_or_default();
+        tools.push(NativeToolSpec {
+            name: format.name.clone(),
+            description: "Give your final answer by calling this tool; its input is the answer."
+                .to_string(),
+            input_schema: format.schema.clone(),
+            cache_control: None,
+        });
+        // Thinking cannot be combined with a forced tool choice
+        let mut native = Self::build_native_request(
+            request.messages,
+            Some(tools),
+            model,
+            temperature,
+            None,
+            None,
+        );
+        native.tool_choice = Some(NativeToolChoice {
+            kind: "tool",
+            name: format.name.clone(),
+        });
+        native
+    }
+
+    /// Move the forced format call's input into the reply text.
+    fn take_format_reply(response: &mut ProviderChatResponse, format_name: &str) {
+        if let Some(pos) = response
+            .tool_calls
+            .iter()
+            .position(|call| call.name == format_name)
+        {
+            response.text = Some(response.tool_calls.remove(pos).arguments);
+        }
+    }
+
+    /// Mark the stable prefix for prompt caching. The API allows four
+    /// breakpoints: the tool list, the system prompt, the newest message
+    /// (so the next turn reads the whole conversation from cache) and the
+    /// previous user turn (still a hit while a tool loop grows the tail).
+    fn apply_cache_breakpoints(request: &mut NativeChatRequest) {
+        if let Some(tool) = request.tools.as_mut().and_then(|t| t.last_mut()) {
+            tool.cache_control = Some(CacheControl::EPHEMERAL);
+        }
+        if let Some(block) = request.system.as_mut().and_then(|s| s.last_mut()) {
+            block.cache_control = Some(CacheControl::EPHEMERAL);
+        }
+        let Some((last, earlier)) = request.messages.split_last_mut() else {
+            return;
+        };
+        if let Some(block) = last.content.last_mut() {
+            block.set_cache_control();
+        }
+        if let Some(block) = earlier
+            .iter_mut()
+            .rev()
+            .find(|m| m.role == "user")
+            .and_then(|m| m.content.last_mut())
+        {
+            block.set_cache_control();
+        }
+    }
+
     fn parse_assistant_tool_call_message(content: &str) -> Option<Vec<NativeContentOut>> {
         let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
         let tool_calls = value
             .get("tool_calls")
             .and_then(|v| serde_json::from_value::<Vec<ProviderToolCall>>(v.clone()).ok())?;
 
-        let mut blocks = Vec::new();
+        // Thinking must precede the tool calls it led to, unmodified
+        let mut blocks = value
+            .get("reasoning")
+            .and_then(|r| serde_json::from_value::<Reasoning>(r.clone()).ok())
+            .and_then(|r| r.replay)
+            .map(|replay| NativeContentOut::from_replay(&replay))
+            .unwrap_or_default();
         if let Some(text) = value
             .get("content")
             .and_then(serde_json::Value::as_str)
             .map(str::trim)
             .filter(|t| !t.is_empty())
         {
-            blocks.push(NativeContentOut::Text {
-                text: text.to_string(),
-            });
+            blocks.push(NativeContentOut::text(text));
         }
         for call in tool_calls {
             let input = serde_json::from_str::<serde_json::Value>(&call.arguments)
@@ -188,6 +718,7 @@ impl AnthropicProvider {
                 id: call.id,
                 name: call.name,
                 input,
+                cache_control: None,
             });
         }
         Some(blocks)
@@ -209,20 +740,25 @@ impl AnthropicProvider {
             content: vec![NativeContentOut::ToolResult {
                 tool_use_id,
                 content: result,
+                cache_control: None,
             }],
         })
     }
 
-    fn convert_messages(messages: &[ChatMessage]) -> (Option<String>, Vec<NativeMessage>) {
-        let mut system_prompt = None;
+    fn convert_messages(
+        messages: &[ChatMessage],
+    ) -> (Option<Vec<SystemBlock>>, Vec<NativeMessage>) {
+        let mut system_blocks = Vec::new();
         let mut native_messages = Vec::new();
 
         for msg in messages {
             match msg.role.as_str() {
                 "system" => {
-                    if system_prompt.is_none() {
-                        system_prompt = Some(msg.content.clone());
-                    }
+                    system_blocks.push(SystemBlock {
+                        kind: "text",
+                        text: msg.content.clone(),
+                        cache_control: None,
+                    });
                 }
                 "assistant" => {
                     if let Some(blocks) = Self::parse_assistant_tool_call_message(&msg.content) {
@@ -233,9 +769,7 @@ impl AnthropicProvider {
                     } else {
                         native_messages.push(NativeMessage {
                             role: "assistant".to_string(),
-                            content: vec![NativeContentOut::Text {
-                                text: msg.content.clone(),
-                            }],
+                            content: vec![NativeContentOut::text(msg.content.clone())],
                         });
                     }
                 }
@@ -245,24 +779,47 @@ impl AnthropicProvider {
                     } else {
                         native_messages.push(NativeMessage {
                             role: "user".to_string(),
-                            content: vec![NativeContentOut::Text {
-                                text: msg.content.clone(),
-                            }],
+                            content: vec![NativeContentOut::text(msg.content.clone())],
                         });
                     }
                 }
                 _ => {
                     native_messages.push(NativeMessage {
                         role: "user".to_string(),
-                        content: vec![NativeContentOut::Text {
-                            text: msg.content.clone(),
-                        }],
+                        content: Self::user_content_blocks(msg),
                     });
                 }
             }
         }
 
-        (system_prompt, native_messages)
+        let system = (!system_blocks.is_empty()).then_some(system_blocks);
+        (system, native_messages)
+    }
+
+    /// Map a user message to content blocks. Anthropic has no audio input,
+    /// so audio parts become text placeholders.
+    fn user_content_blocks(msg: &ChatMessage) -> Vec<NativeContentOut> {
+        if !msg.has_media() {
+            return vec![NativeContentOut::text(msg.content.clone())];
+        }
+        msg.parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Image { source } => NativeContentOut::Image {
+                    source: match source {
+                        MediaSource::Base64 { mime_type, data } => NativeImageSource::Base64 {
+                            media_type: mime_type.clone(),
+                            data: data.clone(),
+                        },
+                        MediaSource::Url { url } => NativeImageSource::Url { url: url.clone() },
+                    },
+                    cache_control: None,
+                },
+                ContentPart::Text { .. } | ContentPart::Audio { .. } => {
+                    NativeContentOut::text(part.to_text())
+                }
+            })
+            .collect()
     }
 
     fn parse_text_response(response: ChatResponse) -> anyhow::Result<String> {
@@ -277,8 +834,15 @@ impl AnthropicProvider {
     fn parse_native_response(response: NativeChatResponse) -> ProviderChatResponse {
         let mut text_parts = Vec::new();
         let mut tool_calls = Vec::new();
+        let mut thinking = Vec::new();
+        let mut replay = Vec::new();
 
         for block in response.content {
+            if let Some(raw) = block.thinking_replay() {
+                thinking.extend(block.thinking.filter(|t| !t.trim().is_empty()));
+                replay.push(raw);
+                continue;
+            }
             match block.kind.as_str() {
                 "text" => {
                     if let Some(text) = block.text.map(|t| t.trim().to_string()) {
@@ -312,6 +876,11 @@ impl AnthropicProvider {
                 Some(text_parts.join("\n"))
             },
             tool_calls,
+            usage: response.usage.map(Usage::from),
+            reasoning: Reasoning::from_parts(
+                thinking.join("\n"),
+                (!replay.is_empty()).then_some(serde_json::Value::Array(replay)),
+            ),
         }
     }
 }
@@ -373,15 +942,7 @@ impl Provider for AnthropicProvider {
             )
         })?;
 
-        let (system_prompt, messages) = Self::convert_messages(request.messages);
-        let native_request = NativeChatRequest {
-            model: model.to_string(),
-            max_tokens: 4096,
-            system: system_prompt,
-            messages,
-            temperature,
-            tools: Self::convert_tools(request.tools),
-        };
+        let native_request = Self::build_chat_request(&request, model, temperature);
 
         let req = self
             .client
@@ -396,17 +957,93 @@ impl Provider for AnthropicProvider {
         }
 
         let native_response: NativeChatResponse = response.json().await?;
-        Ok(Self::parse_native_response(native_response))
+        let mut parsed = Self::parse_native_response(native_response);
+        if let Some(format) = request.response_format {
+            Self::take_format_reply(&mut parsed, &format.name);
+        }
+        Ok(parsed)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let response = self
+            .chat(
+                ProviderChatRequest {
+                    messages,
+                    tools: None,
+                    reasoning: None,
+                    response_format: None,
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        Ok(response.text.unwrap_or_default())
     }
 
     fn supports_native_tools(&self) -> bool {
         true
     }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
+        }
+        messages.push(ChatMessage::user(message));
+        self.stream_native(&messages, None, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_native(messages, None, model, temperature, options)
+    }
+
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let tools = Self::tools_from_openai_format(tools);
+        self.stream_native(messages, tools, model, temperature, options)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::ResponseFormat;
 
     #[test]
     fn creates_with_key() {
@@ -583,6 +1220,15 @@ mod tests {
         assert_eq!(resp.content[0].text.as_deref(), Some("Hello there!"));
     }
 
+    #[test]
+    fn native_response_reports_usage() {
+        let json = r#"{"content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":12,"output_tokens":3}}"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = AnthropicProvider::parse_native_response(resp);
+        assert_eq!(parsed.text.as_deref(), Some("Hi"));
+        assert_eq!(parsed.usage, Some(Usage::new(12, 3)));
+    }
+
     #[test]
     fn chat_response_empty_content() {
         let json = r#"{"content":[]}"#;
@@ -614,4 +1260,226 @@ mod tests {
             assert!(json.contains(&format!("{temp}")));
         }
     }
+
+    #[test]
+    fn convert_messages_emits_image_blocks() {
+        let messages = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user_with_parts(vec![
+                ContentPart::text("describe"),
+                ContentPart::image(MediaSource::from_bytes("image/jpeg", b"jpg")),
+                ContentPart::image(MediaSource::Url {
+                    url: "https://example.com/x.png".into(),
+                }),
+                ContentPart::audio(MediaSource::from_bytes("audio/ogg", b"ogg")),
+            ]),
+        ];
+        let (system, native) = AnthropicProvider::convert_messages(&messages);
+        assert_eq!(system.unwrap()[0].text, "sys");
+
+        let json = serde_json::to_value(&native).unwrap();
+        let blocks = &json[0]["content"];
+        assert_eq!(blocks[0]["type"], "text");
+        assert_eq!(blocks[1]["type"], "image");
+        assert_eq!(blocks[1]["source"]["type"], "base64");
+        assert_eq!(blocks[1]["source"]["media_type"], "image/jpeg");
+        assert_eq!(blocks[2]["source"]["type"], "url");
+        assert_eq!(blocks[3]["type"], "text");
+        assert!(blocks[3]["text"].as_str().unwrap().contains("audio/ogg"));
+    }
+
+    #[test]
+    fn native_request_marks_cache_breakpoints() {
+        let messages = vec![
+            ChatMessage::system("You are ZeroClaw"),
+            ChatMessage::system("Workspace: /tmp"),
+            ChatMessage::user("first question"),
+            ChatMessage::assistant("first answer"),
+            ChatMessage::user("second question"),
+        ];
+        let tools = vec![
+            ToolSpec {
+                name: "shell".into(),
+                description: "Run a command".into(),
+                parameters: serde_json::json!({"type": "object"}),
+            },
+            ToolSpec {
+                name: "file_read".into(),
+                description: "Read a file".into(),
+                parameters: serde_json::json!({"type": "object"}),
+            },
+        ];
+        let request = AnthropicProvider::build_native_request(
+            &messages,
+            AnthropicProvider::convert_tools(Some(&tools)),
+            "claude-sonnet-4",
+            0.7,
+            None,
+            None,
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        let ephemeral = serde_json::json!({"type": "ephemeral"});
+
+        assert!(json["tools"][0].get("cache_control").is_none());
+        assert_eq!(json["tools"][1]["cache_control"], ephemeral);
+        // Every system message is kept; only the last one is marked
+        assert_eq!(json["system"][0]["text"], "You are ZeroClaw");
+        assert!(json["system"][0].get("cache_control").is_none());
+        assert_eq!(json["system"][1]["cache_control"], ephemeral);
+        // Newest message and the previous user turn are marked
+        let marked: Vec<bool> = json["messages"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|m| m["content"][0].get("cache_control").is_some())
+            .collect();
+        assert_eq!(marked, vec![true, false, true]);
+    }
+
+    #[test]
+    fn native_usage_includes_cached_prompt_tokens() {
+        let json = r#"{"content":[{"type":"text","text":"Hi"}],"usage":{"input_tokens":20,"cache_creation_input_tokens":300,"cache_read_input_tokens":1500,"output_tokens":5}}"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let usage = AnthropicProvider::parse_native_response(resp)
+            .usage
+            .unwrap();
+        assert_eq!(usage, Usage::new(1820, 5).with_cache(1500, 300));
+    }
+
+    #[test]
+    fn stream_parser_reports_cache_usage() {
+        let mut parser = AnthropicStreamParser::default();
+        parser
+            .parse_line(r#"data: {"type":"message_start","message":{"usage":{"input_tokens":10,"cache_read_input_tokens":900,"output_tokens":1}}}"#)
+            .unwrap();
+        parser
+            .parse_line(r#"data: {"type":"message_delta","usage":{"output_tokens":42}}"#)
+            .unwrap();
+        assert_eq!(parser.usage(), Some(Usage::new(910, 42).with_cache(900, 0)));
+    }
+
+    #[test]
+    fn native_request_enables_thinking_with_budget() {
+        let messages = vec![ChatMessage::user("plan the migration")];
+        let reasoning = ReasoningOptions {
+            budget_tokens: Some(8_000),
+            ..ReasoningOptions::default()
+        };
+        let request = AnthropicProvider::build_native_request(
+            &messages,
+            None,
+            "claude-sonnet-4",
+            0.2,
+            None,
+            Some(reasoning),
+        );
+        let json = serde_json::to_value(&request).unwrap();
+        assert_eq!(
+            json["thinking"],
+            serde_json::json!({"type": "enabled", "budget_tokens": 8000})
+        );
+        assert_eq!(json["max_tokens"], 12_096);
+        assert_eq!(json["temperature"], 1.0);
+
+        let plain =
+            AnthropicProvider::build_native_request(&messages, None, "claude", 0.2, None, None);
+        assert!(serde_json::to_value(&plain)
+            .unwrap()
+            .get("thinking")
+            .is_none());
+    }
+
+    #[test]
+    fn thinking_blocks_are_returned_and_replayed_before_tool_use() {
+        let json = r#"{"content":[
+            {"type":"thinking","thinking":"Need the date.","signature":"sig-1"},
+            {"type":"redacted_thinking","data":"opaque"},
+            {"type":"tool_use","id":"toolu_1","name":"shell","input":{"command":"date"}}
+        ]}"#;
+        let resp: NativeChatResponse = serde_json::from_str(json).unwrap();
+        let parsed = AnthropicProvider::parse_native_response(resp);
+        let reasoning = parsed.reasoning.expect("thinking is surfaced");
+        assert_eq!(reasoning.text, "Need the date.");
+        assert!(parsed.text.is_none());
+
+        let history = serde_json::json!({
+            "content": null,
+            "tool_calls": parsed.tool_calls,
+            "reasoning": reasoning,
+        });
+        let (_, messages) =
+            AnthropicProvider::convert_messages(&[ChatMessage::assistant(history.to_string())]);
+        let blocks = serde_json::to_value(&messages[0].content).unwrap();
+        assert_eq!(blocks[0]["type"], "thinking");
+        assert_eq!(blocks[0]["signature"], "sig-1");
+        assert_eq!(
+            blocks[1],
+            serde_json::json!({"type": "redacted_thinking", "data": "opaque"})
+        );
+        assert_eq!(blocks[2]["type"], "tool_use");
+    }
+
+    #[test]
+    fn stream_parser_separates_thinking_and_collects_replay() {
+        let mut parser = AnthropicStreamParser::default();
+        let lines = [
+            r#"data: {"type":"content_block_start","index":0,"content_block":{"type":"thinking","thinking":""}}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"Check "}}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"thinking_delta","thinking":"the clock."}}"#,
+            r#"data: {"type":"content_block_delta","index":0,"delta":{"type":"signature_delta","signature":"sig-2"}}"#,
+            r#"data: {"type":"content_block_start","index":1,"content_block":{"type":"text","text":""}}"#,
+            r#"data: {"type":"content_block_delta","index":1,"delta":{"type":"text_delta","text":"Noon."}}"#,
+        ];
+        let chunks: Vec<StreamChunk> = lines
+            .iter()
+            .filter_map(|line| parser.parse_line(line).unwrap())
+            .collect();
+        let reasoning: String = chunks.iter().map(|c| c.reasoning.as_str()).collect();
+        let text: String = chunks.iter().map(|c| c.delta.as_str()).collect();
+        assert_eq!(reasoning, "Check the clock.");
+        assert_eq!(text, "Noon.");
+        assert_eq!(
+            parser.reasoning_replay(),
+            Some(serde_json::json!([{
+                "type": "thinking",
+                "thinking": "Check the clock.",
+                "signature": "sig-2",
+            }]))
+        );
+    }
+
+    #[test]
+    fn response_format_forces_schema_tool_and_becomes_reply_text() {
+        let messages = [ChatMessage::user("grade this")];
+        let format = ResponseFormat::json_schema(
+            "grade",
+            serde_json::json!({"type": "object", "required": ["score"]}),
+        );
+        let request = ProviderChatRequest {
+            messages: &messages,
+            tools: None,
+            reasoning: Some(ReasoningOptions::default()),
+            response_format: Some(&format),
+        };
+        let json = serde_json::to_value(AnthropicProvider::build_chat_request(
+            &request, "claude", 0.0,
+        ))
+        .unwrap();
+        assert_eq!(json["tools"][0]["name"], "grade");
+        assert_eq!(json["tools"][0]["input_schema"]["required"][0], "score");
+        assert_eq!(
+            json["tool_choice"],
+            serde_json::json!({"type": "tool", "name": "grade"})
+        );
+        assert!(json.get("thinking").is_none());
+
+        let body = r#"{"content":[{"type":"tool_use","id":"toolu_1","name":"grade","input":{"score":7}}]}"#;
+        let mut parsed =
+            AnthropicProvider::parse_native_response(serde_json::from_str(body).unwrap());
+        AnthropicProvider::take_format_reply(&mut parsed, "grade");
+        assert!(parsed.tool_calls.is_empty());
+        let reply: serde_json::Value =
+            serde_json::from_str(parsed.text.as_deref().unwrap()).unwrap();
+        assert_eq!(reply["score"], 7);
+    }
 }
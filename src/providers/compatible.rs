@@ -2,12 +2,14 @@
 //! Most LLM APIs follow the same `/v1/chat/completions` format.
 //! This module provides a single implementation that works for all of them.
 
+use crate::providers::streaming::{error_stream, request_stream, StreamParser};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, StreamChunk, StreamError, StreamOptions, StreamResult, ToolCall as ProviderToolCall,
+    ContentPart, MediaSource, Provider, Reasoning, ReasoningOptions, StreamChunk, StreamError,
+    StreamOptions, StreamResult, ToolCall as ProviderToolCall, ToolCallDelta, Usage,
 };
 use async_trait::async_trait;
-use futures_util::{stream, StreamExt};
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -22,6 +24,9 @@ pub struct OpenAiCompatibleProvider {
     /// When false, do not fall back to /v1/responses on chat completions 404.
     /// GLM/Zhipu does not support the responses API.
     supports_responses_fallback: bool,
+    /// Whether the configured model accepts image parts. Most compatible
+    /// backends serve text-only models, so this is opt-in.
+    vision: bool,
     client: Client,
 }
 
@@ -49,6 +54,7 @@ impl OpenAiCompatibleProvider {
             credential: credential.map(ToString::to_string),
             auth_header: auth_style,
             supports_responses_fallback: true,
+            vision: false,
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(120))
                 .connect_timeout(std::time::Duration::from_secs(10))
@@ -71,6 +77,7 @@ impl OpenAiCompatibleProvider {
             credential: credential.map(ToString::to_string),
             auth_header: auth_style,
             supports_responses_fallback: false,
+            vision: false,
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(120))
                 .connect_timeout(std::time::Duration::from_secs(10))
@@ -79,6 +86,13 @@ impl OpenAiCompatibleProvider {
         }
     }
 
+    /// Declare that the configured model accepts image input. Without it,
+    /// media parts are sent as their text placeholders.
+    pub fn with_vision(mut self, vision: bool) -> Self {
+        self.vision = vision;
+        self
+    }
+
     /// Build the full URL for chat completions, detecting if base_url already includes the path.
     /// This allows custom providers with non-standard endpoints (e.g., VolcEngine ARK uses
     /// `/api/coding/v3/chat/completions` instead of `/v1/chat/completions`).
@@ -149,17 +163,135 @@ struct ChatRequest {
     temperature: f64,
     #[serde(skip_serializing_if = "Option::is_none")]
     stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<serde_json::Value>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
 }
 
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
-    content: String,
+    content: MessageContent,
+}
+
+/// OpenAI chat message content: a plain string, or an array of typed parts
+/// when the message carries images or audio. Shared with `openai.rs`.
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub(crate) enum MessageContent {
+    Text(String),
+    Parts(Vec<MessagePart>),
+}
+
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub(crate) enum MessagePart {
+    Text { text: String },
+    ImageUrl { image_url: ImageUrl },
+    InputAudio { input_audio: InputAudio },
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct ImageUrl {
+    url: String,
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct InputAudio {
+    data: String,
+    format: String,
+}
+
+impl From<String> for MessageContent {
+    fn from(text: String) -> Self {
+        Self::Text(text)
+    }
+}
+
+impl MessageContent {
+    /// Build wire content for a chat message, expanding media parts.
+    ///
+    /// Audio is only accepted inline as wav or mp3; anything else degrades to
+    /// a text placeholder.
+    pub(crate) fn from_chat_message(message: &ChatMessage) -> Self {
+        if !message.has_media() {
+            return Self::Text(message.content.clone());
+        }
+        let parts = message
+            .parts
+            .iter()
+            .map(|part| match part {
+                ContentPart::Text { text } => MessagePart::Text { text: text.clone() },
+                ContentPart::Image { source } => MessagePart::ImageUrl {
+                    image_url: ImageUrl {
+                        url: source.to_url(),
+                    },
+                },
+                ContentPart::Audio {
+                    source: MediaSource::Base64 { mime_type, data },
+                } if openai_audio_format(mime_type).is_some() => MessagePart::InputAudio {
+                    input_audio: InputAudio {
+                        data: data.clone(),
+                        format: openai_audio_format(mime_type).unwrap_or("wav").to_string(),
+                    },
+                },
+                ContentPart::Audio { .. } => MessagePart::Text {
+                    text: part.to_text(),
+                },
+            })
+            .collect();
+        Self::Parts(parts)
+    }
+}
+
+fn openai_audio_format(mime_type: &str) -> Option<&'static str> {
+    match mime_type {
+        "audio/wav" | "audio/x-wav" | "audio/wave" => Some("wav"),
+        "audio/mpeg" | "audio/mp3" => Some("mp3"),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Deserialize)]
 struct ApiChatResponse {
     choices: Vec<Choice>,
+    #[serde(default)]
+    usage: Option<ApiUsage>,
+}
+
+/// The `usage` block shared by OpenAI-style chat completion responses.
+#[derive(Debug, Deserialize)]
+pub(crate) struct ApiUsage {
+    #[serde(default)]
+    prompt_tokens: u64,
+    #[serde(default)]
+    completion_tokens: u64,
+    /// OpenAI-style automatic prefix caching report
+    #[serde(default)]
+    prompt_tokens_details: Option<PromptTokensDetails>,
+    /// DeepSeek reports cache hits at the top level instead
+    #[serde(default)]
+    prompt_cache_hit_tokens: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptTokensDetails {
+    #[serde(default)]
+    cached_tokens: u64,
+}
+
+impl From<ApiUsage> for Usage {
+    fn from(usage: ApiUsage) -> Self {
+        // Automatic prefix caching has no separate write charge, so only
+        // reads are reported
+        let cache_read = usage
+            .prompt_tokens_details
+            .map(|details| details.cached_tokens)
+            .or(usage.prompt_cache_hit_tokens)
+            .unwrap_or(0);
+        Usage::new(usage.prompt_tokens, usage.completion_tokens).with_cache(cache_read, 0)
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -173,6 +305,23 @@ struct ResponseMessage {
     content: Option<String>,
     #[serde(default)]
     tool_calls: Option<Vec<ToolCall>>,
+    /// Reasoning text: `reasoning_content` (DeepSeek, Qwen, xAI, Moonshot)
+    /// or `reasoning` (OpenRouter, Groq). Never echoed back to the API.
+    #[serde(default, skip_serializing)]
+    reasoning_content: Option<String>,
+    #[serde(default, skip_serializing)]
+    reasoning: Option<String>,
+}
+
+impl ResponseMessage {
+    fn take_reasoning(&mut self) -> Option<Reasoning> {
+        let text = self
+            .reasoning_content
+            .take()
+            .filter(|t| !t.trim().is_empty())
+            .or_else(|| self.reasoning.take())?;
+        Reasoning::from_parts(text, None)
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize)]
@@ -232,7 +381,10 @@ struct ResponsesContent {
 /// Server-Sent Event stream chunk for OpenAI-compatible streaming.
 #[derive(Debug, Deserialize)]
 struct StreamChunkResponse {
+    #[serde(default)]
     choices: Vec<StreamChoice>,
+    #[serde(default)]
+    usage: Option<ApiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -245,11 +397,27 @@ struct StreamChoice {
 struct StreamDelta {
     #[serde(default)]
     content: Option<String>,
+    #[serde(default)]
+    tool_calls: Option<Vec<StreamToolCall>>,
+    #[serde(default)]
+    reasoning_content: Option<String>,
+    #[serde(default)]
+    reasoning: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StreamToolCall {
+    #[serde(default)]
+    index: usize,
+    #[serde(default)]
+    id: Option<String>,
+    #[serde(default)]
+    function: Option<Function>,
 }
 
 /// Parse SSE (Server-Sent Events) stream from OpenAI-compatible providers.
 /// Handles the `data: {...}` format and `[DONE]` sentinel.
-fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
+fn parse_sse_line(line: &str) -> StreamResult<Option<StreamChunk>> {
     let line = line.trim();
 
     // Skip empty lines and comments
@@ -268,99 +436,88 @@ fn parse_sse_line(line: &str) -> StreamResult<Option<String>> {
 
         // Parse JSON delta
         let chunk: StreamChunkResponse = serde_json::from_str(data).map_err(StreamError::Json)?;
+        let usage = chunk.usage.map(Usage::from);
+
+        let (content, reasoning, tool_calls) = match chunk.choices.into_iter().next() {
+            Some(choice) => (
+                choice.delta.content.unwrap_or_default(),
+                choice
+                    .delta
+                    .reasoning_content
+                    .or(choice.delta.reasoning)
+                    .unwrap_or_default(),
+                choice
+                    .delta
+                    .tool_calls
+                    .unwrap_or_default()
+                    .into_iter()
+                    .map(|call| {
+                        let function = call.function.unwrap_or(Function {
+                            name: None,
+                            arguments: None,
+                        });
+                        ToolCallDelta {
+                            index: call.index,
+                            id: call.id,
+                            name: function.name,
+                            arguments: function.arguments.unwrap_or_default(),
+                        }
+                    })
+                    .collect::<Vec<_>>(),
+            ),
+            None => (String::new(), String::new(), Vec::new()),
+        };
 
-        // Extract content from delta
-        if let Some(choice) = chunk.choices.first() {
-            if let Some(content) = &choice.delta.content {
-                return Ok(Some(content.clone()));
-            }
+        if content.is_empty() && reasoning.is_empty() && tool_calls.is_empty() && usage.is_none() {
+            return Ok(None);
         }
+
+        let mut stream_chunk = StreamChunk::delta(content).with_tool_calls(tool_calls);
+        stream_chunk.reasoning = reasoning;
+        if let Some(usage) = usage {
+            stream_chunk = stream_chunk.with_usage(usage);
+        }
+        return Ok(Some(stream_chunk));
     }
 
     Ok(None)
 }
 
-/// Convert SSE byte stream to text chunks.
-fn sse_bytes_to_chunks(
-    response: reqwest::Response,
-    count_tokens: bool,
-) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
-    // Create a channel to send chunks
-    let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
-
-    tokio::spawn(async move {
-        // Buffer for incomplete lines
-        let mut buffer = String::new();
-
-        // Get response body as bytes stream
-        match response.error_for_status_ref() {
-            Ok(_) => {}
-            Err(e) => {
-                let _ = tx.send(Err(StreamError::Http(e))).await;
-                return;
-            }
-        }
+/// Stream parser for OpenAI-style chat completions, shared with
+/// [`OpenAiProvider`](super::openai::OpenAiProvider). Usage (sent on a
+/// trailing chunk when `stream_options.include_usage` is set) is held back
+/// for the final chunk.
+#[derive(Default)]
+pub(crate) struct OpenAiSseParser {
+    usage: Option<Usage>,
+    done: bool,
+}
 
-        let mut bytes_stream = response.bytes_stream();
-
-        while let Some(item) = bytes_stream.next().await {
-            match item {
-                Ok(bytes) => {
-                    // Convert bytes to string and process line by line
-                    let text = match String::from_utf8(bytes.to_vec()) {
-                        Ok(t) => t,
-                        Err(e) => {
-                            let _ = tx
-                                .send(Err(StreamError::InvalidSse(format!(
-                                    "Invalid UTF-8: {}",
-                                    e
-                                ))))
-                                .await;
-                            break;
-                        }
-                    };
-
-                    buffer.push_str(&text);
-
-                    // Process complete lines
-                    while let Some(pos) = buffer.find('\n') {
-                        let line = buffer.drain(..=pos).collect::<String>();
-                        buffer = buffer[pos + 1..].to_string();
-
-                        match parse_sse_line(&line) {
-                            Ok(Some(content)) => {
-                                let mut chunk = StreamChunk::delta(content);
-                                if count_tokens {
-                                    chunk = chunk.with_token_estimate();
-                                }
-                                if tx.send(Ok(chunk)).await.is_err() {
-                                    return; // Receiver dropped
-                                }
-                            }
-                            Ok(None) => {}
-                            Err(e) => {
-                                let _ = tx.send(Err(e)).await;
-                                return;
-                            }
-                        }
-                    }
-                }
-                Err(e) => {
-                    let _ = tx.send(Err(StreamError::Http(e))).await;
-                    break;
-                }
-            }
+impl StreamParser for OpenAiSseParser {
+    fn parse_line(&mut self, line: &str) -> StreamResult<Option<StreamChunk>> {
+        if line.trim().strip_prefix("data:").map(str::trim) == Some("[DONE]") {
+            self.done = true;
+            return Ok(None);
+        }
+        let Some(mut chunk) = parse_sse_line(line)? else {
+            return Ok(None);
+        };
+        if let Some(usage) = chunk.usage.take() {
+            self.usage = Some(usage);
+        }
+        if chunk.delta.is_empty() && chunk.reasoning.is_empty() && chunk.tool_calls.is_empty() {
+            return Ok(None);
         }
+        Ok(Some(chunk))
+    }
 
-        // Send final chunk
-        let _ = tx.send(Ok(StreamChunk::final_chunk())).await;
-    });
+    fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
 
-    // Convert channel receiver to stream
-    stream::unfold(rx, |mut rx| async {
-        rx.recv().await.map(|chunk| (chunk, rx))
-    })
-    .boxed()
+    fn finished(&self) -> bool {
+        self.done
+    }
 }
 
 fn first_nonempty(text: Option<&str>) -> Option<String> {
@@ -447,17 +604,65 @@ impl OpenAiCompatibleProvider {
         extract_responses_text(responses)
             .ok_or_else(|| anyhow::anyhow!("No response from {} Responses API", self.name))
     }
-}
 
-#[async_trait]
-impl Provider for OpenAiCompatibleProvider {
-    async fn chat_with_system(
+    fn wire_messages(&self, messages: &[ChatMessage]) -> Vec<Message> {
+        messages
+            .iter()
+            .map(|m| Message {
+                role: m.role.clone(),
+                content: if self.vision {
+                    MessageContent::from_chat_message(m)
+                } else {
+                    MessageContent::from_chat_message(&m.text_fallback())
+                },
+            })
+            .collect()
+    }
+
+    /// Stream a chat completion for already-converted wire messages.
+    fn stream_messages(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
+        messages: Vec<Message>,
+        tools: Option<Vec<serde_json::Value>>,
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<String> {
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.as_ref() else {
+            return error_stream(format!("{} API key not set", self.name));
+        };
+
+        let request = ChatRequest {
+            model: model.to_string(),
+            messages,
+            temperature,
+            stream: Some(options.enabled),
+            tools,
+            reasoning_effort: options.reasoning.map(|r| r.effort().as_str()),
+        };
+
+        let url = self.chat_completions_url();
+        let req = self
+            .apply_auth_header(self.client.post(&url).json(&request), credential)
+            .header("Accept", "text/event-stream");
+
+        request_stream(
+            req,
+            self.name.clone(),
+            OpenAiSseParser::default(),
+            options.count_tokens,
+        )
+    }
+
+    /// Multi-turn chat completion, returning the reply text alongside the
+    /// token usage and reasoning the API reported.
+    async fn chat_history_with_usage(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        reasoning: Option<ReasoningOptions>,
+    ) -> anyhow::Result<(String, Option<Usage>, Option<Reasoning>)> {
         let credential = self.credential.as_ref().ok_or_else(|| {
             anyhow::anyhow!(
                 "{} API key not set. Run `zeroclaw onboard` or set the appropriate env var.",
@@ -465,29 +670,18 @@ impl Provider for OpenAiCompatibleProvider {
             )
         })?;
 
-        let mut messages = Vec::new();
-
-        if let Some(sys) = system_prompt {
-            messages.push(Message {
-                role: "system".to_string(),
-                content: sys.to_string(),
-            });
-        }
-
-        messages.push(Message {
-            role: "user".to_string(),
-            content: message.to_string(),
-        });
+        let api_messages = self.wire_messages(messages);
 
         let request = ChatRequest {
             model: model.to_string(),
-            messages,
+            messages: api_messages,
             temperature,
             stream: Some(false),
+            tools: None,
+            reasoning_effort: reasoning.map(|r| r.effort().as_str()),
         };
 
         let url = self.chat_completions_url();
-
         let response = self
             .apply_auth_header(self.client.post(&url).json(&request), credential)
             .send()
@@ -495,34 +689,46 @@ impl Provider for OpenAiCompatibleProvider {
 
         if !response.status().is_success() {
             let status = response.status();
-            let error = response.text().await?;
-            let sanitized = super::sanitize_api_error(&error);
 
+            // Mirror chat_with_system: 404 may mean this provider uses the Responses API
             if status == reqwest::StatusCode::NOT_FOUND && self.supports_responses_fallback {
-                return self
-                    .chat_via_responses(credential, system_prompt, message, model)
-                    .await
-                    .map_err(|responses_err| {
-                        anyhow::anyhow!(
-                            "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
-                            self.name
+                // Extract system prompt and last user message for responses fallback
+                let system = messages.iter().find(|m| m.role == "system");
+                let last_user = messages.iter().rfind(|m| m.role == "user");
+                if let Some(user_msg) = last_user {
+                    return self
+                        .chat_via_responses(
+                            credential,
+                            system.map(|m| m.content.as_str()),
+                            &user_msg.content,
+                            model,
                         )
-                    });
+                        .await
+                        .map(|text| (text, None, None))
+                        .map_err(|responses_err| {
+                            anyhow::anyhow!(
+                                "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
+                                self.name
+                            )
+                        });
+                }
             }
 
-            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
+            return Err(super::api_error(&self.name, response).await);
         }
 
         let chat_response: ApiChatResponse = response.json().await?;
+        let usage = chat_response.usage.map(Usage::from);
 
         chat_response
             .choices
             .into_iter()
             .next()
-            .map(|c| {
+            .map(|mut c| {
+                let reasoning = c.message.take_reasoning();
                 // If tool_calls are present, serialize the full message as JSON
                 // so parse_tool_calls can handle the OpenAI-style format
-                if c.message.tool_calls.is_some()
+                let text = if c.message.tool_calls.is_some()
                     && c.message
                         .tool_calls
                         .as_ref()
@@ -533,14 +739,19 @@ impl Provider for OpenAiCompatibleProvider {
                 } else {
                     // No tool calls, return content as-is
                     c.message.content.unwrap_or_default()
-                }
+                };
+                (text, usage, reasoning)
             })
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))
     }
+}
 
-    async fn chat_with_history(
+#[async_trait]
+impl Provider for OpenAiCompatibleProvider {
+    async fn chat_with_system(
         &self,
-        messages: &[ChatMessage],
+        system_prompt: Option<&str>,
+        message: &str,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
@@ -551,22 +762,31 @@ impl Provider for OpenAiCompatibleProvider {
             )
         })?;
 
-        let api_messages: Vec<Message> = messages
-            .iter()
-            .map(|m| Message {
-                role: m.role.clone(),
-                content: m.content.clone(),
-            })
-            .collect();
+        let mut messages = Vec::new();
+
+        if let Some(sys) = system_prompt {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: sys.to_string().into(),
+            });
+        }
+
+        messages.push(Message {
+            role: "user".to_string(),
+            content: message.to_string().into(),
+        });
 
         let request = ChatRequest {
             model: model.to_string(),
-            messages: api_messages,
+            messages,
             temperature,
             stream: Some(false),
+            tools: None,
+            reasoning_effort: None,
         };
 
         let url = self.chat_completions_url();
+
         let response = self
             .apply_auth_header(self.client.post(&url).json(&request), credential)
             .send()
@@ -574,31 +794,22 @@ impl Provider for OpenAiCompatibleProvider {
 
         if !response.status().is_success() {
             let status = response.status();
+            let error = response.text().await?;
+            let sanitized = super::sanitize_api_error(&error);
 
-            // Mirror chat_with_system: 404 may mean this provider uses the Responses API
             if status == reqwest::StatusCode::NOT_FOUND && self.supports_responses_fallback {
-                // Extract system prompt and last user message for responses fallback
-                let system = messages.iter().find(|m| m.role == "system");
-                let last_user = messages.iter().rfind(|m| m.role == "user");
-                if let Some(user_msg) = last_user {
-                    return self
-                        .chat_via_responses(
-                            credential,
-                            system.map(|m| m.content.as_str()),
-                            &user_msg.content,
-                            model,
+                return self
+                    .chat_via_responses(credential, system_prompt, message, model)
+                    .await
+                    .map_err(|responses_err| {
+                        anyhow::anyhow!(
+                            "{} API error ({status}): {sanitized} (chat completions unavailable; responses fallback failed: {responses_err})",
+                            self.name
                         )
-                        .await
-                        .map_err(|responses_err| {
-                            anyhow::anyhow!(
-                                "{} API error (chat completions unavailable; responses fallback failed: {responses_err})",
-                                self.name
-                            )
-                        });
-                }
+                    });
             }
 
-            return Err(super::api_error(&self.name, response).await);
+            anyhow::bail!("{} API error ({status}): {sanitized}", self.name);
         }
 
         let chat_response: ApiChatResponse = response.json().await?;
@@ -626,14 +837,25 @@ impl Provider for OpenAiCompatibleProvider {
             .ok_or_else(|| anyhow::anyhow!("No response from {}", self.name))
     }
 
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.chat_history_with_usage(messages, model, temperature, None)
+            .await
+            .map(|(text, _, _)| text)
+    }
+
     async fn chat(
         &self,
         request: ProviderChatRequest<'_>,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ProviderChatResponse> {
-        let text = self
-            .chat_with_history(request.messages, model, temperature)
+        let (text, usage, reasoning) = self
+            .chat_history_with_usage(request.messages, model, temperature, request.reasoning)
             .await?;
 
         // Backward compatible path: chat_with_history may serialize tool_calls JSON into content.
@@ -657,12 +879,16 @@ impl Provider for OpenAiCompatibleProvider {
             return Ok(ProviderChatResponse {
                 text: message.content,
                 tool_calls,
+                usage,
+                reasoning,
             });
         }
 
         Ok(ProviderChatResponse {
             text: Some(text),
             tool_calls: vec![],
+            usage,
+            reasoning,
         })
     }
 
@@ -670,6 +896,10 @@ impl Provider for OpenAiCompatibleProvider {
         true
     }
 
+    fn supports_vision(&self) -> bool {
+        self.vision
+    }
+
     fn supports_streaming(&self) -> bool {
         true
     }
@@ -682,98 +912,53 @@ impl Provider for OpenAiCompatibleProvider {
         temperature: f64,
         options: StreamOptions,
     ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
-        let credential = match self.credential.as_ref() {
-            Some(value) => value.clone(),
-            None => {
-                let provider_name = self.name.clone();
-                return stream::once(async move {
-                    Err(StreamError::Provider(format!(
-                        "{} API key not set",
-                        provider_name
-                    )))
-                })
-                .boxed();
-            }
-        };
-
         let mut messages = Vec::new();
         if let Some(sys) = system_prompt {
             messages.push(Message {
                 role: "system".to_string(),
-                content: sys.to_string(),
+                content: sys.to_string().into(),
             });
         }
         messages.push(Message {
             role: "user".to_string(),
-            content: message.to_string(),
+            content: message.to_string().into(),
         });
 
-        let request = ChatRequest {
-            model: model.to_string(),
-            messages,
-            temperature,
-            stream: Some(options.enabled),
-        };
-
-        let url = self.chat_completions_url();
-        let client = self.client.clone();
-        let auth_header = self.auth_header.clone();
-
-        // Use a channel to bridge the async HTTP response to the stream
-        let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
-
-        tokio::spawn(async move {
-            // Build request with auth
-            let mut req_builder = client.post(&url).json(&request);
-
-            // Apply auth header
-            req_builder = match &auth_header {
-                AuthStyle::Bearer => {
-                    req_builder.header("Authorization", format!("Bearer {}", credential))
-                }
-                AuthStyle::XApiKey => req_builder.header("x-api-key", &credential),
-                AuthStyle::Custom(header) => req_builder.header(header, &credential),
-            };
-
-            // Set accept header for streaming
-            req_builder = req_builder.header("Accept", "text/event-stream");
-
-            // Send request
-            let response = match req_builder.send().await {
-                Ok(r) => r,
-                Err(e) => {
-                    let _ = tx.send(Err(StreamError::Http(e))).await;
-                    return;
-                }
-            };
-
-            // Check status
-            if !response.status().is_success() {
-                let status = response.status();
-                let error = match response.text().await {
-                    Ok(e) => e,
-                    Err(_) => format!("HTTP error: {}", status),
-                };
-                let _ = tx
-                    .send(Err(StreamError::Provider(format!("{}: {}", status, error))))
-                    .await;
-                return;
-            }
+        self.stream_messages(messages, None, model, temperature, options)
+    }
 
-            // Convert to chunk stream and forward to channel
-            let mut chunk_stream = sse_bytes_to_chunks(response, options.count_tokens);
-            while let Some(chunk) = chunk_stream.next().await {
-                if tx.send(chunk).await.is_err() {
-                    break; // Receiver dropped
-                }
-            }
-        });
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_messages(
+            self.wire_messages(messages),
+            None,
+            model,
+            temperature,
+            options,
+        )
+    }
 
-        // Convert channel receiver to stream
-        stream::unfold(rx, |mut rx| async move {
-            rx.recv().await.map(|chunk| (chunk, rx))
-        })
-        .boxed()
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let tools = (!tools.is_empty()).then(|| tools.to_vec());
+        self.stream_messages(
+            self.wire_messages(messages),
+            tools,
+            model,
+            temperature,
+            options,
+        )
     }
 }
 
@@ -829,15 +1014,17 @@ mod tests {
             messages: vec![
                 Message {
                     role: "system".to_string(),
-                    content: "You are ZeroClaw".to_string(),
+                    content: "You are ZeroClaw".to_string().into(),
                 },
                 Message {
                     role: "user".to_string(),
-                    content: "hello".to_string(),
+                    content: "hello".to_string().into(),
                 },
             ],
             temperature: 0.4,
             stream: Some(false),
+            tools: None,
+            reasoning_effort: None,
         };
         let json = serde_json::to_string(&req).unwrap();
         assert!(json.contains("llama-3.3-70b"));
@@ -855,6 +1042,94 @@ mod tests {
         );
     }
 
+    #[test]
+    fn response_usage_maps_prompt_and_completion_tokens() {
+        let json = r#"{"choices":[{"message":{"content":"Hi"}}],"usage":{"prompt_tokens":42,"completion_tokens":7,"total_tokens":49}}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.usage.map(Usage::from), Some(Usage::new(42, 7)));
+
+        let without: ApiChatResponse = serde_json::from_str(r#"{"choices":[]}"#).unwrap();
+        assert!(without.usage.is_none());
+    }
+
+    #[test]
+    fn usage_reports_cached_prompt_tokens() {
+        let json = r#"{"choices":[],"usage":{"prompt_tokens":2048,"completion_tokens":10,"prompt_tokens_details":{"cached_tokens":1920}}}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(
+            resp.usage.map(Usage::from),
+            Some(Usage::new(2048, 10).with_cache(1920, 0))
+        );
+
+        let json = r#"{"choices":[],"usage":{"prompt_tokens":500,"completion_tokens":5,"prompt_cache_hit_tokens":448,"prompt_cache_miss_tokens":52}}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(Usage::from(resp.usage.unwrap()).cache_read_tokens, 448);
+    }
+
+    #[test]
+    fn parse_sse_line_extracts_content_delta() {
+        let chunk = parse_sse_line(r#"data: {"choices":[{"delta":{"content":"Hel"}}]}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.delta, "Hel");
+        assert!(!chunk.is_final);
+        assert!(chunk.tool_calls.is_empty());
+
+        assert!(parse_sse_line("data: [DONE]").unwrap().is_none());
+        assert!(parse_sse_line(": keep-alive").unwrap().is_none());
+        assert!(parse_sse_line("").unwrap().is_none());
+    }
+
+    #[test]
+    fn parse_sse_line_extracts_tool_call_deltas_and_usage() {
+        let chunk = parse_sse_line(
+            r#"data: {"choices":[{"delta":{"tool_calls":[{"index":0,"id":"call_1","type":"function","function":{"name":"shell","arguments":"{\"comm"}}]}}]}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(chunk.delta, "");
+        assert_eq!(
+            chunk.tool_calls,
+            vec![ToolCallDelta {
+                index: 0,
+                id: Some("call_1".into()),
+                name: Some("shell".into()),
+                arguments: r#"{"comm"#.into(),
+            }]
+        );
+
+        let usage_chunk = parse_sse_line(
+            r#"data: {"choices":[],"usage":{"prompt_tokens":10,"completion_tokens":3}}"#,
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(usage_chunk.usage, Some(Usage::new(10, 3)));
+    }
+
+    #[test]
+    fn parse_sse_line_rejects_malformed_json() {
+        assert!(parse_sse_line("data: {not json").is_err());
+    }
+
+    #[test]
+    fn reasoning_content_is_kept_out_of_the_reply() {
+        let json = r#"{"choices":[{"message":{"content":"42","reasoning_content":"6 times 7."}}]}"#;
+        let mut resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        let message = &mut resp.choices[0].message;
+        assert_eq!(message.take_reasoning().unwrap().text, "6 times 7.");
+        assert_eq!(message.content.as_deref(), Some("42"));
+        // Never echoed back when the message is re-serialized
+        assert!(!serde_json::to_string(message)
+            .unwrap()
+            .contains("reasoning"));
+
+        let chunk = parse_sse_line(r#"data: {"choices":[{"delta":{"reasoning":"Hmm"}}]}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.reasoning, "Hmm");
+        assert!(chunk.delta.is_empty());
+    }
+
     #[test]
     fn response_empty_choices() {
         let json = r#"{"choices":[]}"#;
@@ -1129,4 +1404,50 @@ mod tests {
             "https://opencode.ai/zen/v1/chat/completions"
         );
     }
+
+    #[test]
+    fn message_content_expands_media_parts() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("listen"),
+            ContentPart::audio(MediaSource::from_bytes("audio/mpeg", b"mp3")),
+            ContentPart::audio(MediaSource::from_bytes("audio/ogg", b"ogg")),
+            ContentPart::image(MediaSource::Url {
+                url: "https://example.com/a.png".into(),
+            }),
+        ]);
+        let json = serde_json::to_value(MessageContent::from_chat_message(&msg)).unwrap();
+
+        assert_eq!(json[0]["type"], "text");
+        assert_eq!(json[1]["type"], "input_audio");
+        assert_eq!(json[1]["input_audio"]["format"], "mp3");
+        assert_eq!(json[2]["type"], "text");
+        assert!(json[2]["text"].as_str().unwrap().contains("audio/ogg"));
+        assert_eq!(json[3]["image_url"]["url"], "https://example.com/a.png");
+
+        let plain =
+            serde_json::to_value(MessageContent::from_chat_message(&ChatMessage::user("hi")))
+                .unwrap();
+        assert_eq!(plain, "hi");
+    }
+
+    #[test]
+    fn text_only_models_get_media_placeholders() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("what is this?"),
+            ContentPart::image(MediaSource::from_bytes("image/png", b"png")),
+        ]);
+        let provider = make_provider("test", "https://example.com", None);
+        assert!(!provider.supports_vision());
+
+        let json =
+            serde_json::to_value(&provider.wire_messages(std::slice::from_ref(&msg))[0]).unwrap();
+        let content = json["content"].as_str().unwrap();
+        assert!(content.starts_with("what is this?"));
+        assert!(content.contains("image/png"));
+
+        let provider = provider.with_vision(true);
+        assert!(provider.supports_vision());
+        let json = serde_json::to_value(&provider.wire_messages(&[msg])[0]).unwrap();
+        assert_eq!(json["content"][1]["type"], "image_url");
+    }
 }
@@ -3,11 +3,19 @@
 //! - Gemini CLI OAuth tokens (reuse existing ~/.gemini/ authentication)
 //! - Google Cloud ADC (`GOOGLE_APPLICATION_CREDENTIALS`)
 
-use crate::providers::traits::Provider;
+use crate::providers::streaming::{error_stream, request_stream, sse_data, StreamParser};
+use crate::providers::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ContentPart, MediaSource, Provider,
+    ProviderCapabilities, Reasoning, ReasoningOptions, ResponseFormat, StreamChunk, StreamError,
+    StreamOptions, StreamResult, ToolCall, ToolCallDelta, ToolsPayload, Usage,
+};
+use crate::tools::ToolSpec;
 use async_trait::async_trait;
 use directories::UserDirs;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
 use std::path::PathBuf;
 
 /// Gemini provider supporting multiple authentication methods.
@@ -59,6 +67,8 @@ struct GenerateContentRequest {
     contents: Vec<Content>,
     #[serde(skip_serializing_if = "Option::is_none")]
     system_instruction: Option<Content>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    tools: Option<Vec<GeminiTools>>,
     #[serde(rename = "generationConfig")]
     generation_config: GenerationConfig,
 }
@@ -71,8 +81,150 @@ struct Content {
 }
 
 #[derive(Debug, Serialize)]
-struct Part {
-    text: String,
+#[serde(untagged)]
+enum Part {
+    Text {
+        text: String,
+    },
+    InlineData {
+        #[serde(rename = "inlineData")]
+        inline_data: Blob,
+    },
+    FileData {
+        #[serde(rename = "fileData")]
+        file_data: FileData,
+    },
+    FunctionCall {
+        #[serde(rename = "functionCall")]
+        function_call: FunctionCall,
+        /// Returned with the call when thinking is on; must be sent back
+        #[serde(rename = "thoughtSignature", skip_serializing_if = "Option::is_none")]
+        thought_signature: Option<String>,
+    },
+    FunctionResponse {
+        #[serde(rename = "functionResponse")]
+        function_response: FunctionResponse,
+    },
+}
+
+#[derive(Debug, Serialize)]
+struct Blob {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    data: String,
+}
+
+#[derive(Debug, Serialize)]
+struct FileData {
+    #[serde(rename = "mimeType")]
+    mime_type: String,
+    #[serde(rename = "fileUri")]
+    file_uri: String,
+}
+
+/// A function invocation: sent back in `model` turns and parsed from
+/// responses.
+#[derive(Debug, Serialize, Deserialize)]
+struct FunctionCall {
+    name: String,
+    #[serde(default)]
+    args: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct FunctionResponse {
+    name: String,
+    response: serde_json::Value,
+}
+
+#[derive(Debug, Serialize)]
+struct GeminiTools {
+    #[serde(rename = "functionDeclarations")]
+    function_declarations: Vec<serde_json::Value>,
+}
+
+/// JSON Schema keywords Gemini's OpenAPI-subset schema rejects.
+const UNSUPPORTED_SCHEMA_KEYS: &[&str] = &["$schema", "additionalProperties"];
+
+/// Strip schema keywords Gemini rejects, leaving property names alone.
+fn sanitize_schema(schema: &serde_json::Value) -> serde_json::Value {
+    match schema {
+        serde_json::Value::Object(map) => map
+            .iter()
+            .filter(|(key, _)| !UNSUPPORTED_SCHEMA_KEYS.contains(&key.as_str()))
+            .map(|(key, value)| {
+                let value = match (key.as_str(), value) {
+                    ("properties", serde_json::Value::Object(props)) => props
+                        .iter()
+                        .map(|(name, prop)| (name.clone(), sanitize_schema(prop)))
+                        .collect(),
+                    _ => sanitize_schema(value),
+                };
+                (key.clone(), value)
+            })
+            .collect(),
+        serde_json::Value::Array(items) => items.iter().map(sanitize_schema).collect(),
+        other => other.clone(),
+    }
+}
+
+fn function_declaration(
+    name: &str,
+    description: &str,
+    parameters: &serde_json::Value,
+) -> serde_json::Value {
+    serde_json::json!({
+        "name": name,
+        "description": description,
+        "parameters": sanitize_schema(parameters),
+    })
+}
+
+impl Part {
+    fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    /// Convert a content part. Gemini takes images and audio inline; URL
+    /// sources need a MIME type, so unknown extensions degrade to text.
+    fn from_content_part(part: &ContentPart) -> Self {
+        let source = match part {
+            ContentPart::Text { text } => return Self::text(text.clone()),
+            ContentPart::Image { source } | ContentPart::Audio { source } => source,
+        };
+        match source {
+            MediaSource::Base64 { mime_type, data } => Self::InlineData {
+                inline_data: Blob {
+                    mime_type: mime_type.clone(),
+                    data: data.clone(),
+                },
+            },
+            MediaSource::Url { url } => match guess_mime_from_url(url) {
+                Some(mime_type) => Self::FileData {
+                    file_data: FileData {
+                        mime_type: mime_type.to_string(),
+                        file_uri: url.clone(),
+                    },
+                },
+                None => Self::text(part.to_text()),
+            },
+        }
+    }
+}
+
+fn guess_mime_from_url(url: &str) -> Option<&'static str> {
+    let path = url.split(['?', '#']).next().unwrap_or(url);
+    let ext = path.rsplit_once('.')?.1.to_ascii_lowercase();
+    match ext.as_str() {
+        "png" => Some("image/png"),
+        "jpg" | "jpeg" => Some("image/jpeg"),
+        "webp" => Some("image/webp"),
+        "gif" => Some("image/gif"),
+        "mp3" => Some("audio/mpeg"),
+        "wav" => Some("audio/wav"),
+        "ogg" | "oga" => Some("audio/ogg"),
+        _ => None,
+    }
 }
 
 #[derive(Debug, Serialize)]
@@ -80,27 +232,101 @@ struct GenerationConfig {
     temperature: f64,
     #[serde(rename = "maxOutputTokens")]
     max_output_tokens: u32,
+    #[serde(rename = "thinkingConfig", skip_serializing_if = "Option::is_none")]
+    thinking_config: Option<ThinkingConfig>,
+    #[serde(rename = "responseMimeType", skip_serializing_if = "Option::is_none")]
+    response_mime_type: Option<&'static str>,
+    #[serde(rename = "responseSchema", skip_serializing_if = "Option::is_none")]
+    response_schema: Option<serde_json::Value>,
+}
+
+impl GenerationConfig {
+    /// Output budget for the answer; a thinking budget is added on top.
+    const MAX_OUTPUT_TOKENS: u32 = 8192;
+
+    fn new(temperature: f64, reasoning: Option<ReasoningOptions>) -> Self {
+        let thinking_config = reasoning.map(|r| ThinkingConfig {
+            thinking_budget: r.budget_tokens(),
+            include_thoughts: true,
+        });
+        Self {
+            temperature,
+            max_output_tokens: Self::MAX_OUTPUT_TOKENS
+                + thinking_config.as_ref().map_or(0, |t| t.thinking_budget),
+            thinking_config,
+            response_mime_type: None,
+            response_schema: None,
+        }
+    }
+
+    /// Constrain the reply to JSON matching the format's schema.
+    fn with_response_format(mut self, format: Option<&ResponseFormat>) -> Self {
+        if let Some(format) = format {
+            self.response_mime_type = Some("application/json");
+            self.response_schema = Some(sanitize_schema(&format.schema));
+        }
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct ThinkingConfig {
+    #[serde(rename = "thinkingBudget")]
+    thinking_budget: u32,
+    /// Return thought summaries as `thought: true` parts
+    #[serde(rename = "includeThoughts")]
+    include_thoughts: bool,
 }
 
 #[derive(Debug, Deserialize)]
 struct GenerateContentResponse {
     candidates: Option<Vec<Candidate>>,
     error: Option<ApiError>,
+    #[serde(rename = "usageMetadata")]
+    usage_metadata: Option<UsageMetadata>,
+    #[serde(rename = "promptFeedback")]
+    prompt_feedback: Option<PromptFeedback>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PromptFeedback {
+    #[serde(rename = "blockReason")]
+    block_reason: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct UsageMetadata {
+    #[serde(rename = "promptTokenCount", default)]
+    prompt_token_count: u64,
+    #[serde(rename = "candidatesTokenCount", default)]
+    candidates_token_count: u64,
 }
 
 #[derive(Debug, Deserialize)]
 struct Candidate {
+    /// Absent when the candidate was blocked before producing output
+    #[serde(default)]
     content: CandidateContent,
+    #[serde(rename = "finishReason")]
+    finish_reason: Option<String>,
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Default, Deserialize)]
 struct CandidateContent {
+    #[serde(default)]
     parts: Vec<ResponsePart>,
 }
 
 #[derive(Debug, Deserialize)]
 struct ResponsePart {
     text: Option<String>,
+    #[serde(rename = "functionCall")]
+    function_call: Option<FunctionCall>,
+    /// The text is a thought summary, not part of the answer
+    #[serde(default)]
+    thought: bool,
+    #[serde(rename = "thoughtSignature")]
+    thought_signature: Option<String>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -119,6 +345,88 @@ struct GeminiCliOAuthCreds {
     expiry: Option<String>,
 }
 
+/// Parses `streamGenerateContent?alt=sse` events, each a partial
+/// `GenerateContentResponse`. Function calls arrive whole, one per part;
+/// usage metadata is cumulative, so the latest report wins.
+#[derive(Default)]
+pub(crate) struct GeminiStreamParser {
+    usage: Option<Usage>,
+    next_call_index: usize,
+    /// Thought signature of each function call, by call index
+    signatures: Vec<Option<String>>,
+    finish_reason_seen: bool,
+}
+
+impl StreamParser for GeminiStreamParser {
+    fn parse_line(&mut self, line: &str) -> StreamResult<Option<StreamChunk>> {
+        let Some(data) = sse_data(line) else {
+            return Ok(None);
+        };
+        let event: GenerateContentResponse =
+            serde_json::from_str(data).map_err(StreamError::Json)?;
+        if let Some(message) = GeminiProvider::response_error(&event) {
+            return Err(StreamError::Provider(message));
+        }
+        if let Some(u) = &event.usage_metadata {
+            self.usage = Some(Usage::new(u.prompt_token_count, u.candidates_token_count));
+        }
+
+        let Some(candidate) = event.candidates.and_then(|c| c.into_iter().next()) else {
+            return Ok(None);
+        };
+        if let Some(err) = candidate
+            .finish_reason
+            .as_deref()
+            .and_then(GeminiProvider::finish_reason_error)
+        {
+            return Err(StreamError::Provider(err.to_string()));
+        }
+        self.finish_reason_seen |= candidate.finish_reason.is_some();
+
+        let mut text = String::new();
+        let mut thoughts = String::new();
+        let mut tool_calls = Vec::new();
+        for part in candidate.content.parts {
+            if let Some(delta) = part.text {
+                if part.thought {
+                    thoughts.push_str(&delta);
+                } else {
+                    text.push_str(&delta);
+                }
+            }
+            if let Some(call) = part.function_call.filter(|c| !c.name.is_empty()) {
+                tool_calls.push(ToolCallDelta {
+                    index: self.next_call_index,
+                    id: Some(uuid::Uuid::new_v4().to_string()),
+                    name: Some(call.name),
+                    arguments: GeminiProvider::call_arguments(call.args),
+                });
+                self.signatures.push(part.thought_signature);
+                self.next_call_index += 1;
+            }
+        }
+
+        if text.is_empty() && thoughts.is_empty() && tool_calls.is_empty() {
+            return Ok(None);
+        }
+        let mut chunk = StreamChunk::delta(text).with_tool_calls(tool_calls);
+        chunk.reasoning = thoughts;
+        Ok(Some(chunk))
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    fn finished(&self) -> bool {
+        self.finish_reason_seen
+    }
+
+    fn reasoning_replay(&self) -> Option<serde_json::Value> {
+        GeminiProvider::signature_replay(&self.signatures)
+    }
+}
+
 impl GeminiProvider {
     /// Create a new Gemini provider.
     ///
@@ -226,18 +534,391 @@ impl GeminiProvider {
     }
 
     fn build_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+        Self::build_url(model, auth, "generateContent")
+    }
+
+    /// `streamGenerateContent` as server-sent events rather than one JSON
+    /// array.
+    fn build_stream_generate_content_url(model: &str, auth: &GeminiAuth) -> String {
+        Self::build_url(model, auth, "streamGenerateContent?alt=sse")
+    }
+
+    fn build_url(model: &str, auth: &GeminiAuth, method: &str) -> String {
         let model_name = Self::format_model_name(model);
-        let base_url = format!(
-            "https://generativelanguage.googleapis.com/v1beta/{model_name}:generateContent"
-        );
+        let base_url =
+            format!("https://generativelanguage.googleapis.com/v1beta/{model_name}:{method}");
 
         if auth.is_api_key() {
-            format!("{base_url}?key={}", auth.credential())
+            let separator = if base_url.contains('?') { '&' } else { '?' };
+            format!("{base_url}{separator}key={}", auth.credential())
         } else {
             base_url
         }
     }
 
+    fn require_auth(&self) -> anyhow::Result<&GeminiAuth> {
+        self.auth.as_ref().ok_or_else(|| {
+            anyhow::anyhow!(
+                "Gemini API key not found. Options:\n\
+                 1. Set GEMINI_API_KEY env var\n\
+                 2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
+                 3. Get an API key from https://aistudio.google.com/app/apikey\n\
+                 4. Run `zeroclaw onboard` to configure"
+            )
+        })
+    }
+
+    /// Map chat history to Gemini `contents`: system messages become the
+    /// system instruction, `assistant` turns use Gemini's `model` role and
+    /// native tool calls/results become `functionCall`/`functionResponse`
+    /// parts.
+    fn convert_messages(messages: &[ChatMessage]) -> (Option<Content>, Vec<Content>) {
+        let system_texts: Vec<&str> = messages
+            .iter()
+            .filter(|m| m.role == "system")
+            .map(|m| m.content.as_str())
+            .collect();
+        let system_instruction = (!system_texts.is_empty()).then(|| Content {
+            role: None,
+            parts: vec![Part::text(system_texts.join("\n\n"))],
+        });
+
+        // Gemini matches responses to calls by function name, so remember
+        // which name each call id belongs to
+        let mut call_names: HashMap<String, String> = HashMap::new();
+        let mut contents: Vec<Content> = Vec::new();
+
+        for msg in messages.iter().filter(|m| m.role != "system") {
+            match msg.role.as_str() {
+                "assistant" => {
+                    let parts =
+                        Self::parse_assistant_tool_call_message(&msg.content, &mut call_names)
+                            .unwrap_or_else(|| vec![Part::text(msg.content.clone())]);
+                    contents.push(Content {
+                        role: Some("model".to_string()),
+                        parts,
+                    });
+                }
+                "tool" => {
+                    let Some(part) = Self::parse_tool_result_message(&msg.content, &call_names)
+                    else {
+                        contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![Part::text(msg.content.clone())],
+                        });
+                        continue;
+                    };
+                    // All responses to one model turn belong in a single
+                    // content
+                    match contents.last_mut() {
+                        Some(last)
+                            if last.role.as_deref() == Some("user")
+                                && last
+                                    .parts
+                                    .iter()
+                                    .all(|p| matches!(p, Part::FunctionResponse { .. })) =>
+                        {
+                            last.parts.push(part);
+                        }
+                        _ => contents.push(Content {
+                            role: Some("user".to_string()),
+                            parts: vec![part],
+                        }),
+                    }
+                }
+                _ => {
+                    let parts = if msg.parts.is_empty() {
+                        vec![Part::text(msg.content.clone())]
+                    } else {
+                        msg.parts.iter().map(Part::from_content_part).collect()
+                    };
+                    contents.push(Content {
+                        role: Some("user".to_string()),
+                        parts,
+                    });
+                }
+            }
+        }
+
+        (system_instruction, contents)
+    }
+
+    /// Parse an assistant turn stored as `{"content", "tool_calls"}` JSON.
+    /// Thought signatures from its `reasoning` go back on their calls.
+    fn parse_assistant_tool_call_message(
+        content: &str,
+        call_names: &mut HashMap<String, String>,
+    ) -> Option<Vec<Part>> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let tool_calls = value
+            .get("tool_calls")
+            .and_then(|v| serde_json::from_value::<Vec<ToolCall>>(v.clone()).ok())?;
+        let signatures: Vec<Option<String>> = value
+            .get("reasoning")
+            .and_then(|r| serde_json::from_value::<Reasoning>(r.clone()).ok())
+            .and_then(|r| r.replay)
+            .and_then(|replay| serde_json::from_value(replay).ok())
+            .unwrap_or_default();
+
+        let mut parts = Vec::new();
+        if let Some(text) = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+        {
+            parts.push(Part::text(text));
+        }
+        for (i, call) in tool_calls.into_iter().enumerate() {
+            let args = serde_json::from_str::<serde_json::Value>(&call.arguments)
+                .unwrap_or_else(|_| serde_json::Value::Object(serde_json::Map::new()));
+            call_names.insert(call.id, call.name.clone());
+            parts.push(Part::FunctionCall {
+                function_call: FunctionCall {
+                    name: call.name,
+                    args,
+                },
+                thought_signature: signatures.get(i).cloned().flatten(),
+            });
+        }
+        Some(parts)
+    }
+
+    /// Replay data for per-call thought signatures, if any were returned.
+    fn signature_replay(signatures: &[Option<String>]) -> Option<serde_json::Value> {
+        signatures
+            .iter()
+            .any(Option::is_some)
+            .then(|| serde_json::json!(signatures))
+    }
+
+    /// Parse a tool turn stored as `{"tool_call_id", "content"}` JSON.
+    fn parse_tool_result_message(
+        content: &str,
+        call_names: &HashMap<String, String>,
+    ) -> Option<Part> {
+        let value = serde_json::from_str::<serde_json::Value>(content).ok()?;
+        let name = value
+            .get("tool_call_id")
+            .and_then(serde_json::Value::as_str)
+            .and_then(|id| call_names.get(id))?;
+        let result = value
+            .get("content")
+            .and_then(serde_json::Value::as_str)
+            .unwrap_or("");
+        Some(Part::FunctionResponse {
+            function_response: FunctionResponse {
+                name: name.clone(),
+                response: serde_json::json!({ "content": result }),
+            },
+        })
+    }
+
+    /// Turn OpenAI-format tool definitions (as built by the agent loop)
+    /// into Gemini function declarations.
+    fn declarations_from_openai_tools(tools: &[serde_json::Value]) -> Vec<serde_json::Value> {
+        tools
+            .iter()
+            .filter_map(|t| {
+                let func = t.get("function")?;
+                Some(function_declaration(
+                    func.get("name")?.as_str()?,
+                    func.get("description")
+                        .and_then(|d| d.as_str())
+                        .unwrap_or(""),
+                    func.get("parameters")
+                        .unwrap_or(&serde_json::json!({"type": "object"})),
+                ))
+            })
+            .collect()
+    }
+
+    fn tools_field(function_declarations: Vec<serde_json::Value>) -> Option<Vec<GeminiTools>> {
+        (!function_declarations.is_empty()).then(|| {
+            vec![GeminiTools {
+                function_declarations,
+            }]
+        })
+    }
+
+    /// Explain why Gemini refused or cut off a response, if it did.
+    fn finish_reason_error(reason: &str) -> Option<anyhow::Error> {
+        let message = match reason {
+            "SAFETY" | "IMAGE_SAFETY" => {
+                format!("Gemini blocked the response for safety reasons ({reason})")
+            }
+            "RECITATION" => {
+                "Gemini blocked the response because it recited copyrighted material".to_string()
+            }
+            "BLOCKLIST" | "PROHIBITED_CONTENT" | "SPII" => {
+                format!("Gemini blocked the response under its content policy ({reason})")
+            }
+            "MALFORMED_FUNCTION_CALL" => {
+                "Gemini produced a malformed function call; try again or simplify the tool schemas"
+                    .to_string()
+            }
+            _ => return None,
+        };
+        Some(anyhow::anyhow!(message))
+    }
+
+    /// An API error or blocked prompt reported in a response body.
+    fn response_error(result: &GenerateContentResponse) -> Option<String> {
+        if let Some(err) = &result.error {
+            return Some(format!("Gemini API error: {}", err.message));
+        }
+        result
+            .prompt_feedback
+            .as_ref()
+            .and_then(|f| f.block_reason.as_deref())
+            .map(|reason| format!("Gemini blocked the prompt ({reason})"))
+    }
+
+    fn call_arguments(args: serde_json::Value) -> String {
+        if args.is_null() {
+            "{}".to_string()
+        } else {
+            args.to_string()
+        }
+    }
+
+    fn parse_response(result: GenerateContentResponse) -> anyhow::Result<ChatResponse> {
+        if let Some(message) = Self::response_error(&result) {
+            anyhow::bail!(message);
+        }
+
+        let usage = result
+            .usage_metadata
+            .map(|u| Usage::new(u.prompt_token_count, u.candidates_token_count));
+
+        let candidate = result
+            .candidates
+            .and_then(|c| c.into_iter().next())
+            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))?;
+        if let Some(err) = candidate
+            .finish_reason
+            .as_deref()
+            .and_then(Self::finish_reason_error)
+        {
+            return Err(err);
+        }
+
+        let mut text_parts = Vec::new();
+        let mut thoughts = Vec::new();
+        let mut tool_calls = Vec::new();
+        let mut signatures = Vec::new();
+        for part in candidate.content.parts {
+            if let Some(text) = part.text.filter(|t| !t.is_empty()) {
+                if part.thought {
+                    thoughts.push(text);
+                } else {
+                    text_parts.push(text);
+                }
+            }
+            if let Some(call) = part.function_call.filter(|c| !c.name.is_empty()) {
+                tool_calls.push(ToolCall {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    name: call.name,
+                    arguments: Self::call_arguments(call.args),
+                });
+                signatures.push(part.thought_signature);
+            }
+        }
+
+        if text_parts.is_empty() && tool_calls.is_empty() {
+            if candidate.finish_reason.as_deref() == Some("MAX_TOKENS") {
+                anyhow::bail!("Gemini hit the output token limit before producing a response");
+            }
+            anyhow::bail!("No response from Gemini");
+        }
+
+        Ok(ChatResponse {
+            text: (!text_parts.is_empty()).then(|| text_parts.concat()),
+            tool_calls,
+            usage,
+            reasoning: Reasoning::from_parts(
+                thoughts.concat(),
+                Self::signature_replay(&signatures),
+            ),
+        })
+    }
+
+    async fn generate_content(
+        &self,
+        auth: &GeminiAuth,
+        model: &str,
+        request: &GenerateContentRequest,
+    ) -> anyhow::Result<ChatResponse> {
+        let url = Self::build_generate_content_url(model, auth);
+
+        let response = self
+            .build_generate_content_request(auth, &url, request)
+            .send()
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let error_text = response.text().await.unwrap_or_default();
+            anyhow::bail!("Gemini API error ({status}): {error_text}");
+        }
+
+        let result: GenerateContentResponse = response.json().await?;
+        Self::parse_response(result)
+    }
+
+    async fn send_history(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<Vec<GeminiTools>>,
+        model: &str,
+        temperature: f64,
+        reasoning: Option<ReasoningOptions>,
+        response_format: Option<&ResponseFormat>,
+    ) -> anyhow::Result<ChatResponse> {
+        let auth = self.require_auth()?;
+        let (system_instruction, contents) = Self::convert_messages(messages);
+
+        let request = GenerateContentRequest {
+            contents,
+            system_instruction,
+            tools,
+            generation_config: GenerationConfig::new(temperature, reasoning)
+                .with_response_format(response_format),
+        };
+
+        self.generate_content(auth, model, &request).await
+    }
+
+    fn stream_history(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<Vec<GeminiTools>>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let auth = match self.require_auth() {
+            Ok(auth) => auth,
+            Err(e) => return error_stream(e.to_string()),
+        };
+        let (system_instruction, contents) = Self::convert_messages(messages);
+
+        let request = GenerateContentRequest {
+            contents,
+            system_instruction,
+            tools,
+            generation_config: GenerationConfig::new(temperature, options.reasoning),
+        };
+
+        let url = Self::build_stream_generate_content_url(model, auth);
+        request_stream(
+            self.build_generate_content_request(auth, &url, &request),
+            "Gemini",
+            GeminiStreamParser::default(),
+            options.count_tokens,
+        )
+    }
+
     fn build_generate_content_request(
         &self,
         auth: &GeminiAuth,
@@ -254,6 +935,23 @@ impl GeminiProvider {
 
 #[async_trait]
 impl Provider for GeminiProvider {
+    fn capabilities(&self) -> ProviderCapabilities {
+        ProviderCapabilities {
+            native_tool_calling: true,
+            vision: true,
+            structured_output: true,
+        }
+    }
+
+    fn convert_tools(&self, tools: &[ToolSpec]) -> ToolsPayload {
+        ToolsPayload::Gemini {
+            function_declarations: tools
+                .iter()
+                .map(|tool| function_declaration(&tool.name, &tool.description, &tool.parameters))
+                .collect(),
+        }
+    }
+
     async fn chat_with_system(
         &self,
         system_prompt: Option<&str>,
@@ -261,65 +959,114 @@ impl Provider for GeminiProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
-        let auth = self.auth.as_ref().ok_or_else(|| {
-            anyhow::anyhow!(
-                "Gemini API key not found. Options:\n\
-                 1. Set GEMINI_API_KEY env var\n\
-                 2. Run `gemini` CLI to authenticate (tokens will be reused)\n\
-                 3. Get an API key from https://aistudio.google.com/app/apikey\n\
-                 4. Run `zeroclaw onboard` to configure"
-            )
-        })?;
+        let auth = self.require_auth()?;
 
-        // Build request
         let system_instruction = system_prompt.map(|sys| Content {
             role: None,
-            parts: vec![Part {
-                text: sys.to_string(),
-            }],
+            parts: vec![Part::text(sys)],
         });
 
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
-                    text: message.to_string(),
-                }],
+                parts: vec![Part::text(message)],
             }],
             system_instruction,
-            generation_config: GenerationConfig {
-                temperature,
-                max_output_tokens: 8192,
-            },
+            tools: None,
+            generation_config: GenerationConfig::new(temperature, None),
         };
 
-        let url = Self::build_generate_content_url(model, auth);
+        self.generate_content(auth, model, &request)
+            .await
+            .map(|response| response.text.unwrap_or_default())
+    }
 
-        let response = self
-            .build_generate_content_request(auth, &url, &request)
-            .send()
-            .await?;
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.send_history(messages, None, model, temperature, None, None)
+            .await
+            .map(|response| response.text.unwrap_or_default())
+    }
 
-        if !response.status().is_success() {
-            let status = response.status();
-            let error_text = response.text().await.unwrap_or_default();
-            anyhow::bail!("Gemini API error ({status}): {error_text}");
-        }
+    async fn chat(
+        &self,
+        request: ChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let tools = match request.tools.map(|tools| self.convert_tools(tools)) {
+            Some(ToolsPayload::Gemini {
+                function_declarations,
+            }) => Self::tools_field(function_declarations),
+            _ => None,
+        };
+        self.send_history(
+            request.messages,
+            tools,
+            model,
+            temperature,
+            request.reasoning,
+            request.response_format,
+        )
+        .await
+    }
 
-        let result: GenerateContentResponse = response.json().await?;
+    async fn chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ChatResponse> {
+        let tools = Self::tools_field(Self::declarations_from_openai_tools(tools));
+        self.send_history(messages, tools, model, temperature, None, None)
+            .await
+    }
 
-        // Check for API error in response body
-        if let Some(err) = result.error {
-            anyhow::bail!("Gemini API error: {}", err.message);
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
         }
+        messages.push(ChatMessage::user(message));
+        self.stream_history(&messages, None, model, temperature, options)
+    }
 
-        // Extract text from response
-        result
-            .candidates
-            .and_then(|c| c.into_iter().next())
-            .and_then(|c| c.content.parts.into_iter().next())
-            .and_then(|p| p.text)
-            .ok_or_else(|| anyhow::anyhow!("No response from Gemini"))
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_history(messages, None, model, temperature, options)
+    }
+
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let tools = Self::tools_field(Self::declarations_from_openai_tools(tools));
+        self.stream_history(messages, tools, model, temperature, options)
     }
 }
 
@@ -416,6 +1163,17 @@ mod tests {
         assert!(url.contains(":generateContent?key=api-key-123"));
     }
 
+    #[test]
+    fn stream_url_requests_sse_and_keeps_key() {
+        let auth = GeminiAuth::ExplicitKey("api-key-123".into());
+        let url = GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &auth);
+        assert!(url.ends_with(":streamGenerateContent?alt=sse&key=api-key-123"));
+
+        let auth = GeminiAuth::OAuthToken("ya29.test-token".into());
+        let url = GeminiProvider::build_stream_generate_content_url("gemini-2.0-flash", &auth);
+        assert!(url.ends_with(":streamGenerateContent?alt=sse"));
+    }
+
     #[test]
     fn oauth_url_omits_key_query_param() {
         let auth = GeminiAuth::OAuthToken("ya29.test-token".into());
@@ -435,14 +1193,16 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
-                    text: "hello".into(),
-                }],
+                parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                thinking_config: None,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -471,14 +1231,16 @@ mod tests {
         let body = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".into()),
-                parts: vec![Part {
-                    text: "hello".into(),
-                }],
+                parts: vec![Part::text("hello")],
             }],
             system_instruction: None,
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                thinking_config: None,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -495,19 +1257,19 @@ mod tests {
         let request = GenerateContentRequest {
             contents: vec![Content {
                 role: Some("user".to_string()),
-                parts: vec![Part {
-                    text: "Hello".to_string(),
-                }],
+                parts: vec![Part::text("Hello")],
             }],
             system_instruction: Some(Content {
                 role: None,
-                parts: vec![Part {
-                    text: "You are helpful".to_string(),
-                }],
+                parts: vec![Part::text("You are helpful")],
             }),
+            tools: None,
             generation_config: GenerationConfig {
                 temperature: 0.7,
                 max_output_tokens: 8192,
+                thinking_config: None,
+                response_mime_type: None,
+                response_schema: None,
             },
         };
 
@@ -545,6 +1307,19 @@ mod tests {
         assert_eq!(text, Some("Hello there!".to_string()));
     }
 
+    #[test]
+    fn response_usage_metadata_deserializes() {
+        let json = r#"{
+            "candidates": [{"content": {"parts": [{"text": "Hi"}]}}],
+            "usageMetadata": {"promptTokenCount": 20, "candidatesTokenCount": 5, "totalTokenCount": 25}
+        }"#;
+
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let usage = response.usage_metadata.unwrap();
+        assert_eq!(usage.prompt_token_count, 20);
+        assert_eq!(usage.candidates_token_count, 5);
+    }
+
     #[test]
     fn error_response_deserialization() {
         let json = r#"{
@@ -557,4 +1332,317 @@ mod tests {
         assert!(response.error.is_some());
         assert_eq!(response.error.unwrap().message, "Invalid API key");
     }
+
+    #[test]
+    fn convert_messages_maps_roles_and_media() {
+        let messages = vec![
+            ChatMessage::system("Be brief"),
+            ChatMessage::user("hi"),
+            ChatMessage::assistant("hello"),
+            ChatMessage::user_with_parts(vec![
+                ContentPart::text("what's this?"),
+                ContentPart::image(MediaSource::from_bytes("image/png", b"png")),
+                ContentPart::image(MediaSource::Url {
+                    url: "https://example.com/cat.JPG?size=large".into(),
+                }),
+                ContentPart::audio(MediaSource::Url {
+                    url: "https://example.com/clip".into(),
+                }),
+            ]),
+        ];
+
+        let (system, contents) = GeminiProvider::convert_messages(&messages);
+        let system = serde_json::to_value(system).unwrap();
+        assert_eq!(system["parts"][0]["text"], "Be brief");
+
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[0]["role"], "user");
+        assert_eq!(json[1]["role"], "model");
+        let parts = &json[2]["parts"];
+        assert_eq!(parts[0]["text"], "what's this?");
+        assert_eq!(parts[1]["inlineData"]["mimeType"], "image/png");
+        assert_eq!(parts[1]["inlineData"]["data"], "cG5n");
+        assert_eq!(parts[2]["fileData"]["mimeType"], "image/jpeg");
+        assert!(parts[3]["text"]
+            .as_str()
+            .unwrap()
+            .contains("https://example.com/clip"));
+    }
+
+    #[test]
+    fn convert_messages_maps_native_tool_turns() {
+        let messages = vec![
+            ChatMessage::user("list files"),
+            ChatMessage::assistant(
+                serde_json::json!({
+                    "content": "Checking.",
+                    "tool_calls": [
+                        {"id": "c1", "name": "shell", "arguments": "{\"command\":\"ls\"}"},
+                        {"id": "c2", "name": "file_read", "arguments": "{\"path\":\"a\"}"}
+                    ]
+                })
+                .to_string(),
+            ),
+            ChatMessage::tool(r#"{"tool_call_id":"c1","content":"a b"}"#),
+            ChatMessage::tool(r#"{"tool_call_id":"c2","content":"hello"}"#),
+            ChatMessage::assistant("Done."),
+        ];
+
+        let (_, contents) = GeminiProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 4);
+
+        assert_eq!(json[1]["role"], "model");
+        assert_eq!(json[1]["parts"][0]["text"], "Checking.");
+        assert_eq!(json[1]["parts"][1]["functionCall"]["name"], "shell");
+        assert_eq!(json[1]["parts"][1]["functionCall"]["args"]["command"], "ls");
+
+        assert_eq!(json[2]["role"], "user");
+        let responses = json[2]["parts"].as_array().unwrap();
+        assert_eq!(responses.len(), 2);
+        assert_eq!(responses[0]["functionResponse"]["name"], "shell");
+        assert_eq!(
+            responses[0]["functionResponse"]["response"]["content"],
+            "a b"
+        );
+        assert_eq!(responses[1]["functionResponse"]["name"], "file_read");
+
+        assert_eq!(json[3]["role"], "model");
+        assert_eq!(json[3]["parts"][0]["text"], "Done.");
+    }
+
+    #[test]
+    fn convert_messages_falls_back_to_text_for_unknown_tool_results() {
+        let messages = vec![ChatMessage::tool(
+            r#"{"tool_call_id":"nope","content":"x"}"#,
+        )];
+        let (_, contents) = GeminiProvider::convert_messages(&messages);
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json[0]["role"], "user");
+        assert!(json[0]["parts"][0]["text"]
+            .as_str()
+            .unwrap()
+            .contains("nope"));
+    }
+
+    #[test]
+    fn convert_tools_emits_sanitized_function_declarations() {
+        let provider = GeminiProvider::new(Some("key"));
+        let payload = provider.convert_tools(&[ToolSpec {
+            name: "shell".into(),
+            description: "Run a command".into(),
+            parameters: serde_json::json!({
+                "$schema": "http://json-schema.org/draft-07/schema#",
+                "type": "object",
+                "additionalProperties": false,
+                "properties": {
+                    "additionalProperties": {"type": "string"},
+                    "env": {"type": "object", "additionalProperties": {"type": "string"}}
+                }
+            }),
+        }]);
+
+        let ToolsPayload::Gemini {
+            function_declarations,
+        } = payload
+        else {
+            panic!("expected Gemini payload");
+        };
+        let params = &function_declarations[0]["parameters"];
+        assert_eq!(function_declarations[0]["name"], "shell");
+        assert!(params.get("$schema").is_none());
+        assert!(params.get("additionalProperties").is_none());
+        assert!(params["properties"].get("additionalProperties").is_some());
+        assert!(params["properties"]["env"]
+            .get("additionalProperties")
+            .is_none());
+        assert!(provider.supports_native_tools());
+        assert!(provider.supports_vision());
+    }
+
+    #[test]
+    fn openai_tools_become_declarations_in_request() {
+        let tools = vec![serde_json::json!({
+            "type": "function",
+            "function": {
+                "name": "shell",
+                "description": "Run a shell command",
+                "parameters": {"type": "object", "properties": {"command": {"type": "string"}}}
+            }
+        })];
+        let request = GenerateContentRequest {
+            contents: Vec::new(),
+            system_instruction: None,
+            tools: GeminiProvider::tools_field(GeminiProvider::declarations_from_openai_tools(
+                &tools,
+            )),
+            generation_config: GenerationConfig {
+                temperature: 0.7,
+                max_output_tokens: 8192,
+                thinking_config: None,
+                response_mime_type: None,
+                response_schema: None,
+            },
+        };
+        let json = serde_json::to_value(&request).unwrap();
+        let decl = &json["tools"][0]["functionDeclarations"][0];
+        assert_eq!(decl["name"], "shell");
+        assert_eq!(
+            decl["parameters"]["properties"]["command"]["type"],
+            "string"
+        );
+
+        assert!(GeminiProvider::tools_field(Vec::new()).is_none());
+    }
+
+    #[test]
+    fn parse_response_collects_text_and_function_calls() {
+        let json = r#"{
+            "candidates": [{
+                "content": {"parts": [
+                    {"text": "Let me check. "},
+                    {"functionCall": {"name": "shell", "args": {"command": "date"}}},
+                    {"text": "One moment."}
+                ]},
+                "finishReason": "STOP"
+            }],
+            "usageMetadata": {"promptTokenCount": 12, "candidatesTokenCount": 4}
+        }"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = GeminiProvider::parse_response(response).unwrap();
+
+        assert_eq!(parsed.text.as_deref(), Some("Let me check. One moment."));
+        assert_eq!(parsed.tool_calls.len(), 1);
+        assert_eq!(parsed.tool_calls[0].name, "shell");
+        assert!(!parsed.tool_calls[0].id.is_empty());
+        let args: serde_json::Value =
+            serde_json::from_str(&parsed.tool_calls[0].arguments).unwrap();
+        assert_eq!(args["command"], "date");
+        assert_eq!(parsed.usage.unwrap().input_tokens, 12);
+    }
+
+    #[test]
+    fn thoughts_and_signatures_survive_a_tool_round() {
+        let json = r#"{"candidates": [{"content": {"parts": [
+            {"text": "Need the time.", "thought": true},
+            {"functionCall": {"name": "shell", "args": {"command": "date"}}, "thoughtSignature": "c2ln"},
+            {"text": "Checking."}
+        ]}}]}"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = GeminiProvider::parse_response(response).unwrap();
+        assert_eq!(parsed.text.as_deref(), Some("Checking."));
+        let reasoning = parsed.reasoning.expect("thought summary is surfaced");
+        assert_eq!(reasoning.text, "Need the time.");
+
+        let history = serde_json::json!({
+            "content": parsed.text,
+            "tool_calls": parsed.tool_calls,
+            "reasoning": reasoning,
+        });
+        let (_, contents) =
+            GeminiProvider::convert_messages(&[ChatMessage::assistant(history.to_string())]);
+        let json = serde_json::to_value(&contents).unwrap();
+        assert_eq!(json[0]["parts"][1]["thoughtSignature"], "c2ln");
+    }
+
+    #[test]
+    fn generation_config_adds_thinking_budget() {
+        let reasoning = ReasoningOptions {
+            effort: Some(crate::providers::ReasoningEffort::Low),
+            ..ReasoningOptions::default()
+        };
+        let json = serde_json::to_value(GenerationConfig::new(0.5, Some(reasoning))).unwrap();
+        assert_eq!(json["thinkingConfig"]["thinkingBudget"], 2048);
+        assert_eq!(json["thinkingConfig"]["includeThoughts"], true);
+        assert_eq!(json["maxOutputTokens"], 8192 + 2048);
+
+        let plain = serde_json::to_value(GenerationConfig::new(0.5, None)).unwrap();
+        assert!(plain.get("thinkingConfig").is_none());
+        assert!(plain.get("responseSchema").is_none());
+    }
+
+    #[test]
+    fn response_format_sets_sanitized_response_schema() {
+        let format = ResponseFormat::json_schema(
+            "verdict",
+            serde_json::json!({
+                "type": "object",
+                "properties": {"pass": {"type": "boolean"}},
+                "additionalProperties": false
+            }),
+        );
+        let json = serde_json::to_value(
+            GenerationConfig::new(0.0, None).with_response_format(Some(&format)),
+        )
+        .unwrap();
+        assert_eq!(json["responseMimeType"], "application/json");
+        assert_eq!(
+            json["responseSchema"]["properties"]["pass"]["type"],
+            "boolean"
+        );
+        assert!(json["responseSchema"].get("additionalProperties").is_none());
+    }
+
+    #[test]
+    fn parse_response_accepts_function_call_without_text() {
+        let json = r#"{"candidates": [{"content": {"parts": [
+            {"functionCall": {"name": "memory_recall"}}
+        ]}}]}"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = GeminiProvider::parse_response(response).unwrap();
+        assert!(parsed.text.is_none());
+        assert_eq!(parsed.tool_calls[0].arguments, "{}");
+    }
+
+    #[test]
+    fn parse_response_maps_blocks_and_finish_reasons_to_errors() {
+        let cases = [
+            (
+                r#"{"promptFeedback": {"blockReason": "SAFETY"}}"#,
+                "blocked the prompt (SAFETY)",
+            ),
+            (
+                r#"{"candidates": [{"finishReason": "SAFETY"}]}"#,
+                "safety reasons",
+            ),
+            (
+                r#"{"candidates": [{"content": {"parts": []}, "finishReason": "RECITATION"}]}"#,
+                "recited",
+            ),
+            (
+                r#"{"candidates": [{"finishReason": "PROHIBITED_CONTENT"}]}"#,
+                "content policy (PROHIBITED_CONTENT)",
+            ),
+            (
+                r#"{"candidates": [{"finishReason": "MALFORMED_FUNCTION_CALL"}]}"#,
+                "malformed function call",
+            ),
+            (
+                r#"{"candidates": [{"content": {"parts": []}, "finishReason": "MAX_TOKENS"}]}"#,
+                "output token limit",
+            ),
+            (r#"{"candidates": []}"#, "No response from Gemini"),
+        ];
+
+        for (json, expected) in cases {
+            let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+            let err = GeminiProvider::parse_response(response).unwrap_err();
+            assert!(
+                err.to_string().contains(expected),
+                "{json}: unexpected error {err}"
+            );
+        }
+    }
+
+    #[test]
+    fn parse_response_keeps_truncated_text() {
+        let json = r#"{"candidates": [{
+            "content": {"parts": [{"text": "partial"}]},
+            "finishReason": "MAX_TOKENS"
+        }]}"#;
+        let response: GenerateContentResponse = serde_json::from_str(json).unwrap();
+        let parsed = GeminiProvider::parse_response(response).unwrap();
+        assert_eq!(parsed.text.as_deref(), Some("partial"));
+    }
 }
@@ -7,12 +7,15 @@ pub mod openai;
 pub mod openrouter;
 pub mod reliable;
 pub mod router;
+pub(crate) mod streaming;
+pub mod structured;
 pub mod traits;
 
 #[allow(unused_imports)]
 pub use traits::{
-    ChatMessage, ChatRequest, ChatResponse, ConversationMessage, Provider, ToolCall,
-    ToolResultMessage,
+    ChatMessage, ChatRequest, ChatResponse, ContentPart, ConversationMessage, MediaSource,
+    Provider, Reasoning, ReasoningEffort, ReasoningOptions, ResponseFormat, ToolCall,
+    ToolResultMessage, Usage,
 };
 
 use compatible::{AuthStyle, OpenAiCompatibleProvider};
@@ -359,52 +362,69 @@ pub fn create_provider(name: &str, api_key: Option<&str>) -> anyhow::Result<Box<
 }
 
 /// Factory: create the right provider from config with optional custom base URL
-#[allow(clippy::too_many_lines)]
 pub fn create_provider_with_url(
     name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
+) -> anyhow::Result<Box<dyn Provider>> {
+    create_provider_with_vision(name, api_key, api_url, false)
+}
+
+/// Factory: like [`create_provider_with_url`], declaring whether the model
+/// accepts images. Only OpenAI-compatible and Ollama backends read `vision`;
+/// native providers know their own models.
+#[allow(clippy::too_many_lines)]
+pub fn create_provider_with_vision(
+    name: &str,
+    api_key: Option<&str>,
+    api_url: Option<&str>,
+    vision: bool,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let resolved_credential = resolve_provider_credential(name, api_key);
     #[allow(clippy::option_as_ref_deref)]
     let key = resolved_credential.as_ref().map(String::as_str);
+    let compatible = |name: &str, base_url: &str, key: Option<&str>, auth_style: AuthStyle| {
+        OpenAiCompatibleProvider::new(name, base_url, key, auth_style).with_vision(vision)
+    };
     match name {
         // ── Primary providers (custom implementations) ───────
         "openrouter" => Ok(Box::new(openrouter::OpenRouterProvider::new(key))),
         "anthropic" => Ok(Box::new(anthropic::AnthropicProvider::new(key))),
         "openai" => Ok(Box::new(openai::OpenAiProvider::new(key))),
         // Ollama uses api_url for custom base URL (e.g. remote Ollama instance)
-        "ollama" => Ok(Box::new(ollama::OllamaProvider::new(api_url, key))),
+        "ollama" => Ok(Box::new(
+            ollama::OllamaProvider::new(api_url, key).with_vision(vision),
+        )),
         "gemini" | "google" | "google-gemini" => {
             Ok(Box::new(gemini::GeminiProvider::new(key)))
         }
 
         // ── OpenAI-compatible providers ──────────────────────
-        "venice" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "venice" => Ok(Box::new(compatible(
             "Venice", "https://api.venice.ai", key, AuthStyle::Bearer,
         ))),
-        "vercel" | "vercel-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "vercel" | "vercel-ai" => Ok(Box::new(compatible(
             "Vercel AI Gateway", "https://api.vercel.ai", key, AuthStyle::Bearer,
         ))),
-        "cloudflare" | "cloudflare-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "cloudflare" | "cloudflare-ai" => Ok(Box::new(compatible(
             "Cloudflare AI Gateway",
             "https://gateway.ai.cloudflare.com/v1",
             key,
             AuthStyle::Bearer,
         ))),
-        name if moonshot_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if moonshot_base_url(name).is_some() => Ok(Box::new(compatible(
             "Moonshot",
             moonshot_base_url(name).expect("checked in guard"),
             key,
             AuthStyle::Bearer,
         ))),
-        "synthetic" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "synthetic" => Ok(Box::new(compatible(
             "Synthetic", "https://api.synthetic.com", key, AuthStyle::Bearer,
         ))),
-        "opencode" | "opencode-zen" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "opencode" | "opencode-zen" => Ok(Box::new(compatible(
             "OpenCode Zen", "https://opencode.ai/zen/v1", key, AuthStyle::Bearer,
         ))),
-        name if zai_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if zai_base_url(name).is_some() => Ok(Box::new(compatible(
             "Z.AI",
             zai_base_url(name).expect("checked in guard"),
             key,
@@ -416,24 +436,25 @@ pub fn create_provider_with_url(
                 glm_base_url(name).expect("checked in guard"),
                 key,
                 AuthStyle::Bearer,
-            )))
+            )
+            .with_vision(vision)))
         }
-        name if minimax_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if minimax_base_url(name).is_some() => Ok(Box::new(compatible(
             "MiniMax",
             minimax_base_url(name).expect("checked in guard"),
             key,
             AuthStyle::Bearer,
         ))),
-        "bedrock" | "aws-bedrock" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "bedrock" | "aws-bedrock" => Ok(Box::new(compatible(
             "Amazon Bedrock",
             "https://bedrock-runtime.us-east-1.amazonaws.com",
             key,
             AuthStyle::Bearer,
         ))),
-        name if is_qianfan_alias(name) => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if is_qianfan_alias(name) => Ok(Box::new(compatible(
             "Qianfan", "https://aip.baidubce.com", key, AuthStyle::Bearer,
         ))),
-        name if qwen_base_url(name).is_some() => Ok(Box::new(OpenAiCompatibleProvider::new(
+        name if qwen_base_url(name).is_some() => Ok(Box::new(compatible(
             "Qwen",
             qwen_base_url(name).expect("checked in guard"),
             key,
@@ -441,28 +462,28 @@ pub fn create_provider_with_url(
         ))),
 
         // ── Extended ecosystem (community favorites) ─────────
-        "groq" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "groq" => Ok(Box::new(compatible(
             "Groq", "https://api.groq.com/openai/v1", key, AuthStyle::Bearer,
         ))),
-        "mistral" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "mistral" => Ok(Box::new(compatible(
             "Mistral", "https://api.mistral.ai/v1", key, AuthStyle::Bearer,
         ))),
-        "xai" | "grok" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "xai" | "grok" => Ok(Box::new(compatible(
             "xAI", "https://api.x.ai", key, AuthStyle::Bearer,
         ))),
-        "deepseek" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "deepseek" => Ok(Box::new(compatible(
             "DeepSeek", "https://api.deepseek.com", key, AuthStyle::Bearer,
         ))),
-        "together" | "together-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "together" | "together-ai" => Ok(Box::new(compatible(
             "Together AI", "https://api.together.xyz", key, AuthStyle::Bearer,
         ))),
-        "fireworks" | "fireworks-ai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "fireworks" | "fireworks-ai" => Ok(Box::new(compatible(
             "Fireworks AI", "https://api.fireworks.ai/inference/v1", key, AuthStyle::Bearer,
         ))),
-        "perplexity" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "perplexity" => Ok(Box::new(compatible(
             "Perplexity", "https://api.perplexity.ai", key, AuthStyle::Bearer,
         ))),
-        "cohere" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "cohere" => Ok(Box::new(compatible(
             "Cohere", "https://api.cohere.com/compatibility", key, AuthStyle::Bearer,
         ))),
         "copilot" | "github-copilot" => {
@@ -473,7 +494,7 @@ pub fn create_provider_with_url(
                 .map(str::trim)
                 .filter(|value| !value.is_empty())
                 .unwrap_or("lm-studio");
-            Ok(Box::new(OpenAiCompatibleProvider::new(
+            Ok(Box::new(compatible(
                 "LM Studio",
                 "http://localhost:1234/v1",
                 Some(lm_studio_key),
@@ -481,7 +502,7 @@ pub fn create_provider_with_url(
             )))
         }
         "nvidia" | "nvidia-nim" | "build.nvidia.com" => Ok(Box::new(
-            OpenAiCompatibleProvider::new(
+            compatible(
                 "NVIDIA NIM",
                 "https://integrate.api.nvidia.com/v1",
                 key,
@@ -490,7 +511,7 @@ pub fn create_provider_with_url(
         )),
 
         // ── AI inference routers ─────────────────────────────
-        "astrai" => Ok(Box::new(OpenAiCompatibleProvider::new(
+        "astrai" => Ok(Box::new(compatible(
             "Astrai", "https://as-trai.com/v1", key, AuthStyle::Bearer,
         ))),
 
@@ -502,7 +523,7 @@ pub fn create_provider_with_url(
                 "Custom provider",
                 "custom:https://your-api.com",
             )?;
-            Ok(Box::new(OpenAiCompatibleProvider::new(
+            Ok(Box::new(compatible(
                 "Custom",
                 &base_url,
                 key,
@@ -533,17 +554,21 @@ pub fn create_provider_with_url(
 }
 
 /// Create provider chain with retry and fallback behavior.
+///
+/// `vision` declares whether the model accepts images; it applies to the
+/// primary only, so fallbacks send media as text placeholders.
 pub fn create_resilient_provider(
     primary_name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
+    vision: bool,
     reliability: &crate::config::ReliabilityConfig,
 ) -> anyhow::Result<Box<dyn Provider>> {
     let mut providers: Vec<(String, Box<dyn Provider>)> = Vec::new();
 
     providers.push((
         primary_name.to_string(),
-        create_provider_with_url(primary_name, api_key, api_url)?,
+        create_provider_with_vision(primary_name, api_key, api_url, vision)?,
     ));
 
     for fallback in &reliability.fallback_providers {
@@ -581,12 +606,13 @@ pub fn create_routed_provider(
     primary_name: &str,
     api_key: Option<&str>,
     api_url: Option<&str>,
+    vision: bool,
     reliability: &crate::config::ReliabilityConfig,
     model_routes: &[crate::config::ModelRouteConfig],
     default_model: &str,
 ) -> anyhow::Result<Box<dyn Provider>> {
     if model_routes.is_empty() {
-        return create_resilient_provider(primary_name, api_key, api_url, reliability);
+        return create_resilient_provider(primary_name, api_key, api_url, vision, reliability);
     }
 
     // Collect unique provider names needed
@@ -612,7 +638,14 @@ pub fn create_routed_provider(
         let key = routed_credential.or(api_key);
         // Only use api_url for the primary provider
         let url = if name == primary_name { api_url } else { None };
-        match create_resilient_provider(name, key, url, reliability) {
+        // One provider serves the default model and every route naming it,
+        // so it only accepts images when all of those models do.
+        let provider_vision = (name != primary_name || vision)
+            && model_routes
+                .iter()
+                .filter(|r| &r.provider == name)
+                .all(|r| r.vision);
+        match create_resilient_provider(name, key, url, provider_vision, reliability) {
             Ok(provider) => providers.push((name.clone(), provider)),
             Err(e) => {
                 if name == primary_name {
@@ -635,6 +668,7 @@ pub fn create_routed_provider(
                 router::Route {
                     provider_name: r.provider.clone(),
                     model: r.model.clone(),
+                    reasoning: r.reasoning,
                 },
             )
         })
@@ -1278,11 +1312,45 @@ mod tests {
             "openrouter",
             Some("provider-test-credential"),
             None,
+            false,
             &reliability,
         );
         assert!(provider.is_ok());
     }
 
+    #[test]
+    fn vision_comes_from_config_for_compatible_backends() {
+        let reliability = crate::config::ReliabilityConfig::default();
+        let text_only =
+            create_resilient_provider("ollama", None, None, false, &reliability).unwrap();
+        assert!(!text_only.supports_vision());
+        let vision = create_resilient_provider("ollama", None, None, true, &reliability).unwrap();
+        assert!(vision.supports_vision());
+
+        let route = |vision| crate::config::ModelRouteConfig {
+            hint: "fast".into(),
+            provider: "groq".into(),
+            model: "llama-3.3-70b-versatile".into(),
+            api_key: Some("provider-test-credential".into()),
+            reasoning: None,
+            vision,
+        };
+        let routed = |route_vision| {
+            create_routed_provider(
+                "ollama",
+                None,
+                None,
+                true,
+                &reliability,
+                &[route(route_vision)],
+                "llava",
+            )
+            .unwrap()
+        };
+        assert!(!routed(false).supports_vision());
+        assert!(routed(true).supports_vision());
+    }
+
     #[test]
     fn resilient_provider_errors_for_invalid_primary() {
         let reliability = crate::config::ReliabilityConfig::default();
@@ -1290,6 +1358,7 @@ mod tests {
             "totally-invalid",
             Some("provider-test-credential"),
             None,
+            false,
             &reliability,
         );
         assert!(provider.is_err());
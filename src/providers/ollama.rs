@@ -1,11 +1,21 @@
-use crate::providers::traits::Provider;
+use crate::providers::streaming::{error_stream, request_stream, StreamParser};
+use crate::providers::traits::{
+    build_tool_instructions_text, with_tool_instructions, ChatMessage,
+    ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse, ContentPart,
+    MediaSource, Provider, Reasoning, ReasoningOptions, ResponseFormat, StreamChunk, StreamError,
+    StreamOptions, StreamResult, ToolCallDelta, Usage,
+};
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
 pub struct OllamaProvider {
     base_url: String,
     api_key: Option<String>,
+    /// Whether the configured model accepts images (llava, llama3.2-vision,
+    /// ...). Most Ollama models are text-only, so this is opt-in.
+    vision: bool,
     client: Client,
 }
 
@@ -17,12 +27,22 @@ struct ChatRequest {
     messages: Vec<Message>,
     stream: bool,
     options: Options,
+    /// Ask thinking models to reason before answering; Ollama has no
+    /// budget or effort knob, so any reasoning option just turns it on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    think: Option<bool>,
+    /// JSON Schema the reply must match (structured outputs)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    format: Option<serde_json::Value>,
 }
 
 #[derive(Debug, Serialize)]
 struct Message {
     role: String,
     content: String,
+    /// Base64 images for vision models (llava, llama3.2-vision, ...).
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    images: Vec<String>,
 }
 
 #[derive(Debug, Serialize)]
@@ -35,6 +55,39 @@ struct Options {
 #[derive(Debug, Deserialize)]
 struct ApiChatResponse {
     message: ResponseMessage,
+    /// Prompt tokens evaluated (absent when the prompt was fully cached).
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    /// Tokens generated for the response.
+    #[serde(default)]
+    eval_count: Option<u64>,
+}
+
+impl ApiChatResponse {
+    fn usage(&self) -> Option<Usage> {
+        if self.prompt_eval_count.is_none() && self.eval_count.is_none() {
+            return None;
+        }
+        Some(Usage::new(
+            self.prompt_eval_count.unwrap_or(0),
+            self.eval_count.unwrap_or(0),
+        ))
+    }
+}
+
+/// One NDJSON line of a streamed `/api/chat` response.
+#[derive(Debug, Deserialize)]
+struct StreamLine {
+    #[serde(default)]
+    message: Option<ResponseMessage>,
+    #[serde(default)]
+    done: bool,
+    #[serde(default)]
+    error: Option<String>,
+    #[serde(default)]
+    prompt_eval_count: Option<u64>,
+    #[serde(default)]
+    eval_count: Option<u64>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -43,7 +96,7 @@ struct ResponseMessage {
     content: String,
     #[serde(default)]
     tool_calls: Vec<OllamaToolCall>,
-    /// Some models return a "thinking" field with internal reasoning
+    /// Thinking models return their reasoning here, separate from `content`
     #[serde(default)]
     thinking: Option<String>,
 }
@@ -61,6 +114,72 @@ struct OllamaFunction {
     arguments: serde_json::Value,
 }
 
+// ─── Streaming ────────────────────────────────────────────────────────────────
+
+/// Parses `/api/chat` NDJSON. Tool calls arrive whole, so each becomes a
+/// single fragment; token counts come on the `done` line.
+#[derive(Default)]
+pub(crate) struct OllamaStreamParser {
+    usage: Option<Usage>,
+    next_call_index: usize,
+    done: bool,
+}
+
+impl StreamParser for OllamaStreamParser {
+    fn parse_line(&mut self, line: &str) -> StreamResult<Option<StreamChunk>> {
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+        let parsed: StreamLine = serde_json::from_str(line).map_err(StreamError::Json)?;
+        if let Some(error) = parsed.error {
+            return Err(StreamError::Provider(format!("Ollama error: {error}")));
+        }
+        self.done |= parsed.done;
+        if parsed.done && (parsed.prompt_eval_count.is_some() || parsed.eval_count.is_some()) {
+            self.usage = Some(Usage::new(
+                parsed.prompt_eval_count.unwrap_or(0),
+                parsed.eval_count.unwrap_or(0),
+            ));
+        }
+
+        let Some(message) = parsed.message else {
+            return Ok(None);
+        };
+        let tool_calls: Vec<ToolCallDelta> = message
+            .tool_calls
+            .iter()
+            .map(|tc| {
+                let (name, args) = OllamaProvider::extract_tool_name_and_args(tc);
+                let delta = ToolCallDelta {
+                    index: self.next_call_index,
+                    id: tc.id.clone(),
+                    name: Some(name),
+                    arguments: args.to_string(),
+                };
+                self.next_call_index += 1;
+                delta
+            })
+            .collect();
+
+        let thinking = message.thinking.unwrap_or_default();
+        if message.content.is_empty() && tool_calls.is_empty() && thinking.is_empty() {
+            return Ok(None);
+        }
+        let mut chunk = StreamChunk::delta(message.content).with_tool_calls(tool_calls);
+        chunk.reasoning = thinking;
+        Ok(Some(chunk))
+    }
+
+    fn usage(&self) -> Option<Usage> {
+        self.usage
+    }
+
+    fn finished(&self) -> bool {
+        self.done
+    }
+}
+
 // ─── Implementation ───────────────────────────────────────────────────────────
 
 impl OllamaProvider {
@@ -76,6 +195,7 @@ impl OllamaProvider {
                 .trim_end_matches('/')
                 .to_string(),
             api_key,
+            vision: false,
             client: Client::builder()
                 .timeout(std::time::Duration::from_secs(300))
                 .connect_timeout(std::time::Duration::from_secs(10))
@@ -84,6 +204,13 @@ impl OllamaProvider {
         }
     }
 
+    /// Declare that the configured model accepts image input. Without it,
+    /// media parts are sent as their text placeholders.
+    pub fn with_vision(mut self, vision: bool) -> Self {
+        self.vision = vision;
+        self
+    }
+
     fn is_local_endpoint(&self) -> bool {
         reqwest::Url::parse(&self.base_url)
             .ok()
@@ -114,6 +241,45 @@ impl OllamaProvider {
         Ok((normalized_model, should_auth))
     }
 
+    fn stream_messages(
+        &self,
+        messages: Vec<Message>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let (normalized_model, should_auth) = match self.resolve_request_details(model) {
+            Ok(details) => details,
+            Err(e) => return error_stream(e.to_string()),
+        };
+
+        let request = ChatRequest {
+            model: normalized_model,
+            messages,
+            stream: true,
+            options: Options { temperature },
+            think: options.reasoning.map(|_| true),
+            format: None,
+        };
+
+        let mut request_builder = self
+            .client
+            .post(format!("{}/api/chat", self.base_url))
+            .json(&request);
+        if should_auth {
+            if let Some(key) = self.api_key.as_ref() {
+                request_builder = request_builder.bearer_auth(key);
+            }
+        }
+
+        request_stream(
+            request_builder,
+            "Ollama",
+            OllamaStreamParser::default(),
+            options.count_tokens,
+        )
+    }
+
     /// Send a request to Ollama and get the parsed response
     async fn send_request(
         &self,
@@ -121,12 +287,16 @@ impl OllamaProvider {
         model: &str,
         temperature: f64,
         should_auth: bool,
+        reasoning: Option<ReasoningOptions>,
+        response_format: Option<&ResponseFormat>,
     ) -> anyhow::Result<ApiChatResponse> {
         let request = ChatRequest {
             model: model.to_string(),
             messages,
             stream: false,
             options: Options { temperature },
+            think: reasoning.map(|_| true),
+            format: response_format.map(|f| f.schema.clone()),
         };
 
         let url = format!("{}/api/chat", self.base_url);
@@ -185,6 +355,50 @@ impl OllamaProvider {
         Ok(chat_response)
     }
 
+    /// Convert a chat message. Ollama takes inline base64 images only, so URL
+    /// images and audio are described in the text instead.
+    fn convert_message(message: &ChatMessage) -> Message {
+        if !message.has_media() {
+            return Message {
+                role: message.role.clone(),
+                content: message.content.clone(),
+                images: Vec::new(),
+            };
+        }
+
+        let mut texts = Vec::new();
+        let mut images = Vec::new();
+        for part in &message.parts {
+            match part {
+                ContentPart::Image {
+                    source: MediaSource::Base64 { data, .. },
+                } => images.push(data.clone()),
+                _ => texts.push(part.to_text()),
+            }
+        }
+
+        Message {
+            role: message.role.clone(),
+            content: texts.join("\n"),
+            images,
+        }
+    }
+
+    /// Convert a conversation, replacing media with text placeholders when
+    /// the model has no vision.
+    fn wire_messages(&self, messages: &[ChatMessage]) -> Vec<Message> {
+        messages
+            .iter()
+            .map(|m| {
+                if self.vision {
+                    Self::convert_message(m)
+                } else {
+                    Self::convert_message(&m.text_fallback())
+                }
+            })
+            .collect()
+    }
+
     /// Convert Ollama tool calls to the JSON format expected by parse_tool_calls in loop_.rs
     ///
     /// Handles quirky model behavior where tool calls are wrapped:
@@ -194,7 +408,7 @@ impl OllamaProvider {
         let formatted_calls: Vec<serde_json::Value> = tool_calls
             .iter()
             .map(|tc| {
-                let (tool_name, tool_args) = self.extract_tool_name_and_args(tc);
+                let (tool_name, tool_args) = Self::extract_tool_name_and_args(tc);
 
                 // Arguments must be a JSON string for parse_tool_calls compatibility
                 let args_str =
@@ -219,7 +433,7 @@ impl OllamaProvider {
     }
 
     /// Extract the actual tool name and arguments from potentially nested structures
-    fn extract_tool_name_and_args(&self, tc: &OllamaToolCall) -> (String, serde_json::Value) {
+    fn extract_tool_name_and_args(tc: &OllamaToolCall) -> (String, serde_json::Value) {
         let name = &tc.function.name;
         let args = &tc.function.arguments;
 
@@ -255,36 +469,37 @@ impl OllamaProvider {
         // Pattern 3: Normal tool call
         (name.clone(), args.clone())
     }
-}
 
-#[async_trait]
-impl Provider for OllamaProvider {
-    async fn chat_with_system(
+    /// Multi-turn chat, returning the reply text alongside the token counts
+    /// Ollama reported and any thinking the model did.
+    async fn chat_history_with_usage(
         &self,
-        system_prompt: Option<&str>,
-        message: &str,
+        messages: &[ChatMessage],
         model: &str,
         temperature: f64,
-    ) -> anyhow::Result<String> {
+        reasoning: Option<ReasoningOptions>,
+        response_format: Option<&ResponseFormat>,
+    ) -> anyhow::Result<(String, Option<Usage>, Option<Reasoning>)> {
         let (normalized_model, should_auth) = self.resolve_request_details(model)?;
 
-        let mut messages = Vec::new();
-
-        if let Some(sys) = system_prompt {
-            messages.push(Message {
-                role: "system".to_string(),
-                content: sys.to_string(),
-            });
-        }
-
-        messages.push(Message {
-            role: "user".to_string(),
-            content: message.to_string(),
-        });
+        let api_messages = self.wire_messages(messages);
 
         let response = self
-            .send_request(messages, &normalized_model, temperature, should_auth)
+            .send_request(
+                api_messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                reasoning,
+                response_format,
+            )
             .await?;
+        let usage = response.usage();
+        let thinking = response
+            .message
+            .thinking
+            .clone()
+            .and_then(|text| Reasoning::from_parts(text, None));
 
         // If model returned tool calls, format them for loop_.rs's parse_tool_calls
         if !response.message.tool_calls.is_empty() {
@@ -292,48 +507,77 @@ impl Provider for OllamaProvider {
                 "Ollama returned {} tool call(s), formatting for loop parser",
                 response.message.tool_calls.len()
             );
-            return Ok(self.format_tool_calls_for_loop(&response.message.tool_calls));
+            return Ok((
+                self.format_tool_calls_for_loop(&response.message.tool_calls),
+                usage,
+                thinking,
+            ));
         }
 
         // Plain text response
         let content = response.message.content;
 
         // Handle edge case: model returned only "thinking" with no content or tool calls
+        // This is a model quirk - it stopped after reasoning without producing output
         if content.is_empty() {
             if let Some(thinking) = &response.message.thinking {
                 tracing::warn!(
                     "Ollama returned empty content with only thinking: '{}'. Model may have stopped prematurely.",
                     if thinking.len() > 100 { &thinking[..100] } else { thinking }
                 );
-                return Ok(format!(
-                    "I was thinking about this: {}... but I didn't complete my response. Could you try asking again?",
-                    if thinking.len() > 200 { &thinking[..200] } else { thinking }
+                // Return a message indicating the model's thought process but no action
+                return Ok((
+                    format!(
+                        "I was thinking about this: {}... but I didn't complete my response. Could you try asking again?",
+                        if thinking.len() > 200 { &thinking[..200] } else { thinking }
+                    ),
+                    usage,
+                    None,
                 ));
             }
             tracing::warn!("Ollama returned empty content with no tool calls");
         }
 
-        Ok(content)
+        Ok((content, usage, thinking))
     }
+}
 
-    async fn chat_with_history(
+#[async_trait]
+impl Provider for OllamaProvider {
+    async fn chat_with_system(
         &self,
-        messages: &[crate::providers::ChatMessage],
+        system_prompt: Option<&str>,
+        message: &str,
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<String> {
         let (normalized_model, should_auth) = self.resolve_request_details(model)?;
 
-        let api_messages: Vec<Message> = messages
-            .iter()
-            .map(|m| Message {
-                role: m.role.clone(),
-                content: m.content.clone(),
-            })
-            .collect();
+        let mut messages = Vec::new();
+
+        if let Some(sys) = system_prompt {
+            messages.push(Message {
+                role: "system".to_string(),
+                content: sys.to_string(),
+                images: Vec::new(),
+            });
+        }
+
+        messages.push(Message {
+            role: "user".to_string(),
+            content: message.to_string(),
+            images: Vec::new(),
+        });
 
         let response = self
-            .send_request(api_messages, &normalized_model, temperature, should_auth)
+            .send_request(
+                messages,
+                &normalized_model,
+                temperature,
+                should_auth,
+                None,
+                None,
+            )
             .await?;
 
         // If model returned tool calls, format them for loop_.rs's parse_tool_calls
@@ -349,14 +593,12 @@ impl Provider for OllamaProvider {
         let content = response.message.content;
 
         // Handle edge case: model returned only "thinking" with no content or tool calls
-        // This is a model quirk - it stopped after reasoning without producing output
         if content.is_empty() {
             if let Some(thinking) = &response.message.thinking {
                 tracing::warn!(
                     "Ollama returned empty content with only thinking: '{}'. Model may have stopped prematurely.",
                     if thinking.len() > 100 { &thinking[..100] } else { thinking }
                 );
-                // Return a message indicating the model's thought process but no action
                 return Ok(format!(
                     "I was thinking about this: {}... but I didn't complete my response. Could you try asking again?",
                     if thinking.len() > 200 { &thinking[..200] } else { thinking }
@@ -368,12 +610,93 @@ impl Provider for OllamaProvider {
         Ok(content)
     }
 
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        self.chat_history_with_usage(messages, model, temperature, None, None)
+            .await
+            .map(|(text, _, _)| text)
+    }
+
+    async fn chat(
+        &self,
+        request: ProviderChatRequest<'_>,
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<ProviderChatResponse> {
+        // Tool calls are parsed from the text by the agent loop, so tools
+        // are described in the system prompt rather than sent natively.
+        let messages = match request.tools {
+            Some(tools) if !tools.is_empty() => {
+                with_tool_instructions(request.messages, &build_tool_instructions_text(tools))
+            }
+            _ => request.messages.to_vec(),
+        };
+        let (text, usage, reasoning) = self
+            .chat_history_with_usage(
+                &messages,
+                model,
+                temperature,
+                request.reasoning,
+                request.response_format,
+            )
+            .await?;
+        Ok(ProviderChatResponse {
+            text: Some(text),
+            tool_calls: Vec::new(),
+            usage,
+            reasoning,
+        })
+    }
+
     fn supports_native_tools(&self) -> bool {
         // Return false since loop_.rs uses XML-style tool parsing via system prompt
         // The model may return native tool_calls but we convert them to JSON format
         // that parse_tool_calls() understands
         false
     }
+
+    fn supports_vision(&self) -> bool {
+        self.vision
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
+        }
+        messages.push(ChatMessage::user(message));
+        self.stream_chat_with_history(&messages, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let api_messages = self.wire_messages(messages);
+        self.stream_messages(api_messages, model, temperature, options)
+    }
 }
 
 // ─── Tests ────────────────────────────────────────────────────────────────────
@@ -457,6 +780,17 @@ mod tests {
         assert_eq!(resp.message.content, "Hello from Ollama!");
     }
 
+    #[test]
+    fn response_usage_uses_eval_counts() {
+        let json = r#"{"message":{"role":"assistant","content":"hi"},"prompt_eval_count":30,"eval_count":4}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        assert_eq!(resp.usage(), Some(Usage::new(30, 4)));
+
+        let json = r#"{"message":{"role":"assistant","content":"hi"}}"#;
+        let resp: ApiChatResponse = serde_json::from_str(json).unwrap();
+        assert!(resp.usage().is_none());
+    }
+
     #[test]
     fn response_with_empty_content() {
         let json = r#"{"message":{"role":"assistant","content":""}}"#;
@@ -479,6 +813,55 @@ mod tests {
         assert_eq!(resp.message.content, "hello");
     }
 
+    #[test]
+    fn think_flag_sent_only_when_reasoning_requested() {
+        let request = |think| ChatRequest {
+            model: "qwen3".into(),
+            messages: Vec::new(),
+            stream: false,
+            options: Options { temperature: 0.7 },
+            think,
+            format: None,
+        };
+        let plain = serde_json::to_value(request(None)).unwrap();
+        assert!(plain.get("think").is_none());
+        let thinking = serde_json::to_value(request(Some(true))).unwrap();
+        assert_eq!(thinking["think"], true);
+    }
+
+    #[test]
+    fn response_format_is_sent_as_format_schema() {
+        let request = ChatRequest {
+            model: "llama3.2".into(),
+            messages: Vec::new(),
+            stream: false,
+            options: Options { temperature: 0.0 },
+            think: None,
+            format: Some(serde_json::json!({"type": "object"})),
+        };
+        let json = serde_json::to_value(request).unwrap();
+        assert_eq!(json["format"]["type"], "object");
+    }
+
+    #[test]
+    fn stream_parser_separates_thinking_from_content() {
+        let mut parser = OllamaStreamParser::default();
+        let chunk = parser
+            .parse_line(
+                r#"{"message":{"role":"assistant","content":"","thinking":"Hmm, "},"done":false}"#,
+            )
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.reasoning, "Hmm, ");
+        assert!(chunk.delta.is_empty());
+        let chunk = parser
+            .parse_line(r#"{"message":{"role":"assistant","content":"Hi"},"done":false}"#)
+            .unwrap()
+            .unwrap();
+        assert_eq!(chunk.delta, "Hi");
+        assert!(chunk.reasoning.is_empty());
+    }
+
     #[test]
     fn response_with_tool_calls_parses_correctly() {
         let json = r#"{"message":{"role":"assistant","content":"","tool_calls":[{"id":"call_123","function":{"name":"shell","arguments":{"command":"date"}}}]}}"#;
@@ -490,7 +873,6 @@ mod tests {
 
     #[test]
     fn extract_tool_name_handles_nested_tool_call() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -501,14 +883,13 @@ mod tests {
                 }),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "shell");
         assert_eq!(args.get("command").unwrap(), "date");
     }
 
     #[test]
     fn extract_tool_name_handles_prefixed_name() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -516,14 +897,13 @@ mod tests {
                 arguments: serde_json::json!({"command": "ls"}),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "shell");
         assert_eq!(args.get("command").unwrap(), "ls");
     }
 
     #[test]
     fn extract_tool_name_handles_normal_call() {
-        let provider = OllamaProvider::new(None, None);
         let tc = OllamaToolCall {
             id: Some("call_123".into()),
             function: OllamaFunction {
@@ -531,7 +911,7 @@ mod tests {
                 arguments: serde_json::json!({"path": "/tmp/test"}),
             },
         };
-        let (name, args) = provider.extract_tool_name_and_args(&tc);
+        let (name, args) = OllamaProvider::extract_tool_name_and_args(&tc);
         assert_eq!(name, "file_read");
         assert_eq!(args.get("path").unwrap(), "/tmp/test");
     }
@@ -559,4 +939,44 @@ mod tests {
         // arguments should be a string (JSON-encoded)
         assert!(func.get("arguments").unwrap().is_string());
     }
+
+    #[test]
+    fn convert_message_moves_inline_images_to_images_field() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("what's in the photo?"),
+            ContentPart::image(MediaSource::from_bytes("image/jpeg", b"jpg")),
+            ContentPart::image(MediaSource::Url {
+                url: "https://example.com/cat.png".into(),
+            }),
+        ]);
+
+        let json = serde_json::to_value(OllamaProvider::convert_message(&msg)).unwrap();
+        assert_eq!(json["images"], serde_json::json!(["anBn"]));
+        let content = json["content"].as_str().unwrap();
+        assert!(content.starts_with("what's in the photo?"));
+        assert!(content.contains("https://example.com/cat.png"));
+
+        let plain = serde_json::to_value(OllamaProvider::convert_message(&ChatMessage::user("hi")))
+            .unwrap();
+        assert!(plain.get("images").is_none());
+    }
+
+    #[test]
+    fn text_only_models_get_no_images() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("what's in the photo?"),
+            ContentPart::image(MediaSource::from_bytes("image/jpeg", b"jpg")),
+        ]);
+        let provider = OllamaProvider::new(None, None);
+        assert!(!provider.supports_vision());
+        let json =
+            serde_json::to_value(&provider.wire_messages(std::slice::from_ref(&msg))[0]).unwrap();
+        assert!(json.get("images").is_none());
+        assert!(json["content"].as_str().unwrap().contains("image/jpeg"));
+
+        let provider = provider.with_vision(true);
+        assert!(provider.supports_vision());
+        let json = serde_json::to_value(&provider.wire_messages(&[msg])[0]).unwrap();
+        assert_eq!(json["images"], serde_json::json!(["anBn"]));
+    }
 }
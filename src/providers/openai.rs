@@ -1,9 +1,13 @@
+use crate::providers::compatible::{ApiUsage, MessageContent, OpenAiSseParser};
+use crate::providers::streaming::{error_stream, request_stream};
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
-    Provider, ToolCall as ProviderToolCall,
+    Provider, ReasoningOptions, ResponseFormat, StreamChunk, StreamOptions, StreamResult,
+    ToolCall as ProviderToolCall,
 };
 use crate::tools::ToolSpec;
 use async_trait::async_trait;
+use futures_util::stream;
 use reqwest::Client;
 use serde::{Deserialize, Serialize};
 
@@ -44,18 +48,62 @@ struct ResponseMessage {
 struct NativeChatRequest {
     model: String,
     messages: Vec<NativeMessage>,
-    temperature: f64,
+    /// Omitted for reasoning requests: reasoning models only take the default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    temperature: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasoning_effort: Option<&'static str>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tools: Option<Vec<NativeToolSpec>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_choice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    response_format: Option<NativeResponseFormat>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stream_options: Option<NativeStreamOptions>,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeResponseFormat {
+    #[serde(rename = "type")]
+    kind: &'static str,
+    json_schema: NativeJsonSchema,
+}
+
+#[derive(Debug, Serialize)]
+struct NativeJsonSchema {
+    name: String,
+    schema: serde_json::Value,
+    /// Enforce the schema instead of treating it as a hint
+    strict: bool,
+}
+
+impl From<&ResponseFormat> for NativeResponseFormat {
+    fn from(format: &ResponseFormat) -> Self {
+        Self {
+            kind: "json_schema",
+            json_schema: NativeJsonSchema {
+                name: format.name.clone(),
+                schema: format.schema.clone(),
+                strict: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+struct NativeStreamOptions {
+    /// Ask for a trailing chunk with token usage
+    include_usage: bool,
 }
 
 #[derive(Debug, Serialize)]
 struct NativeMessage {
     role: String,
     #[serde(skip_serializing_if = "Option::is_none")]
-    content: Option<String>,
+    content: Option<MessageContent>,
     #[serde(skip_serializing_if = "Option::is_none")]
     tool_call_id: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -94,6 +142,8 @@ struct NativeFunctionCall {
 #[derive(Debug, Deserialize)]
 struct NativeChatResponse {
     choices: Vec<NativeChoice>,
+    #[serde(default)]
+    usage: Option<ApiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -137,6 +187,96 @@ impl OpenAiProvider {
         })
     }
 
+    /// Keep the function definitions from OpenAI-format tool values (as
+    /// built by the agent loop), dropping anything malformed.
+    fn tools_from_values(tools: &[serde_json::Value]) -> Option<Vec<NativeToolSpec>> {
+        let specs: Vec<NativeToolSpec> = tools
+            .iter()
+            .filter_map(|t| {
+                let func = t.get("function")?;
+                Some(NativeToolSpec {
+                    kind: "function".to_string(),
+                    function: NativeToolFunctionSpec {
+                        name: func.get("name")?.as_str()?.to_string(),
+                        description: func
+                            .get("description")
+                            .and_then(|d| d.as_str())
+                            .unwrap_or("")
+                            .to_string(),
+                        parameters: func
+                            .get("parameters")
+                            .cloned()
+                            .unwrap_or(serde_json::json!({})),
+                    },
+                })
+            })
+            .collect();
+        (!specs.is_empty()).then_some(specs)
+    }
+
+    fn build_native_request(
+        messages: &[ChatMessage],
+        tools: Option<Vec<NativeToolSpec>>,
+        model: &str,
+        temperature: f64,
+        reasoning: Option<ReasoningOptions>,
+        response_format: Option<&ResponseFormat>,
+    ) -> NativeChatRequest {
+        NativeChatRequest {
+            model: model.to_string(),
+            messages: Self::convert_messages(messages),
+            temperature: reasoning.is_none().then_some(temperature),
+            reasoning_effort: reasoning.map(|r| r.effort().as_str()),
+            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+            tools,
+            response_format: response_format.map(Into::into),
+            stream: None,
+            stream_options: None,
+        }
+    }
+
+    fn stream_native(
+        &self,
+        messages: &[ChatMessage],
+        tools: Option<Vec<NativeToolSpec>>,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let Some(credential) = self.credential.as_ref() else {
+            return error_stream("OpenAI API key not set. Set OPENAI_API_KEY or edit config.toml.");
+        };
+
+        let native_request = NativeChatRequest {
+            stream: Some(true),
+            stream_options: Some(NativeStreamOptions {
+                include_usage: true,
+            }),
+            ..Self::build_native_request(
+                messages,
+                tools,
+                model,
+                temperature,
+                options.reasoning,
+                None,
+            )
+        };
+
+        let req = self
+            .client
+            .post("https://api.openai.com/v1/chat/completions")
+            .header("Authorization", format!("Bearer {credential}"))
+            .header("Accept", "text/event-stream")
+            .json(&native_request);
+
+        request_stream(
+            req,
+            "OpenAI",
+            OpenAiSseParser::default(),
+            options.count_tokens,
+        )
+    }
+
     fn convert_messages(messages: &[ChatMessage]) -> Vec<NativeMessage> {
         messages
             .iter()
@@ -163,7 +303,7 @@ impl OpenAiProvider {
                                 let content = value
                                     .get("content")
                                     .and_then(serde_json::Value::as_str)
-                                    .map(ToString::to_string);
+                                    .map(|text| MessageContent::Text(text.to_string()));
                                 return NativeMessage {
                                     role: "assistant".to_string(),
                                     content,
@@ -184,7 +324,7 @@ impl OpenAiProvider {
                         let content = value
                             .get("content")
                             .and_then(serde_json::Value::as_str)
-                            .map(ToString::to_string);
+                            .map(|text| MessageContent::Text(text.to_string()));
                         return NativeMessage {
                             role: "tool".to_string(),
                             content,
@@ -196,7 +336,7 @@ impl OpenAiProvider {
 
                 NativeMessage {
                     role: m.role.clone(),
-                    content: Some(m.content.clone()),
+                    content: Some(MessageContent::from_chat_message(m)),
                     tool_call_id: None,
                     tool_calls: None,
                 }
@@ -219,6 +359,8 @@ impl OpenAiProvider {
         ProviderChatResponse {
             text: message.content,
             tool_calls,
+            usage: None,
+            reasoning: None,
         }
     }
 }
@@ -289,13 +431,14 @@ impl Provider for OpenAiProvider {
         })?;
 
         let tools = Self::convert_tools(request.tools);
-        let native_request = NativeChatRequest {
-            model: model.to_string(),
-            messages: Self::convert_messages(request.messages),
-            temperature,
-            tool_choice: tools.as_ref().map(|_| "auto".to_string()),
+        let native_request = Self::build_native_request(
+            request.messages,
             tools,
-        };
+            model,
+            temperature,
+            request.reasoning,
+            request.response_format,
+        );
 
         let response = self
             .client
@@ -316,17 +459,91 @@ impl Provider for OpenAiProvider {
             .next()
             .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenAI"))?;
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = native_response.usage.map(Into::into);
+        Ok(parsed)
+    }
+
+    async fn chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+    ) -> anyhow::Result<String> {
+        let response = self
+            .chat(
+                ProviderChatRequest {
+                    messages,
+                    tools: None,
+                    reasoning: None,
+                    response_format: None,
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        Ok(response.text.unwrap_or_default())
     }
 
     fn supports_native_tools(&self) -> bool {
         true
     }
+
+    fn supports_vision(&self) -> bool {
+        true
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        true
+    }
+
+    fn supports_streaming(&self) -> bool {
+        true
+    }
+
+    fn stream_chat_with_system(
+        &self,
+        system_prompt: Option<&str>,
+        message: &str,
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let mut messages = Vec::new();
+        if let Some(sys) = system_prompt {
+            messages.push(ChatMessage::system(sys));
+        }
+        messages.push(ChatMessage::user(message));
+        self.stream_native(&messages, None, model, temperature, options)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_native(messages, None, model, temperature, options)
+    }
+
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let tools = Self::tools_from_values(tools);
+        self.stream_native(messages, tools, model, temperature, options)
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::ReasoningEffort;
 
     #[test]
     fn creates_with_key() {
@@ -437,4 +654,78 @@ mod tests {
         let resp: ChatResponse = serde_json::from_str(&json).unwrap();
         assert_eq!(resp.choices[0].message.content.len(), 100_000);
     }
+
+    #[test]
+    fn convert_messages_serializes_image_parts() {
+        use crate::providers::traits::{ContentPart, MediaSource};
+
+        let messages = vec![
+            ChatMessage::system("sys"),
+            ChatMessage::user_with_parts(vec![
+                ContentPart::text("what is this?"),
+                ContentPart::image(MediaSource::from_bytes("image/png", b"png")),
+            ]),
+        ];
+        let json = serde_json::to_value(OpenAiProvider::convert_messages(&messages)).unwrap();
+
+        assert_eq!(json[0]["content"], "sys");
+        assert_eq!(json[1]["content"][0]["type"], "text");
+        assert_eq!(json[1]["content"][0]["text"], "what is this?");
+        assert_eq!(json[1]["content"][1]["type"], "image_url");
+        assert_eq!(
+            json[1]["content"][1]["image_url"]["url"],
+            "data:image/png;base64,cG5n"
+        );
+    }
+
+    #[test]
+    fn reasoning_request_sends_effort_instead_of_temperature() {
+        let messages = vec![ChatMessage::user("hi")];
+        let reasoning = ReasoningOptions {
+            effort: Some(ReasoningEffort::High),
+            ..ReasoningOptions::default()
+        };
+        let json = serde_json::to_value(OpenAiProvider::build_native_request(
+            &messages,
+            None,
+            "o3",
+            0.7,
+            Some(reasoning),
+            None,
+        ))
+        .unwrap();
+        assert_eq!(json["reasoning_effort"], "high");
+        assert!(json.get("temperature").is_none());
+
+        let plain = serde_json::to_value(OpenAiProvider::build_native_request(
+            &messages, None, "gpt-4o", 0.7, None, None,
+        ))
+        .unwrap();
+        assert_eq!(plain["temperature"], 0.7);
+        assert!(plain.get("reasoning_effort").is_none());
+    }
+
+    #[test]
+    fn response_format_maps_to_json_schema() {
+        let format = ResponseFormat::json_schema(
+            "plan",
+            serde_json::json!({"type": "object", "required": ["steps"]}),
+        );
+        let json = serde_json::to_value(OpenAiProvider::build_native_request(
+            &[ChatMessage::user("plan it")],
+            None,
+            "gpt-4o",
+            0.2,
+            None,
+            Some(&format),
+        ))
+        .unwrap();
+        assert_eq!(json["response_format"]["type"], "json_schema");
+        assert_eq!(json["response_format"]["json_schema"]["name"], "plan");
+        assert_eq!(json["response_format"]["json_schema"]["strict"], true);
+        assert_eq!(
+            json["response_format"]["json_schema"]["schema"]["required"][0],
+            "steps"
+        );
+    }
 }
@@ -1,3 +1,4 @@
+use crate::providers::compatible::ApiUsage;
 use crate::providers::traits::{
     ChatMessage, ChatRequest as ProviderChatRequest, ChatResponse as ProviderChatResponse,
     Provider, ToolCall as ProviderToolCall,
@@ -94,6 +95,8 @@ struct NativeFunctionCall {
 #[derive(Debug, Deserialize)]
 struct NativeChatResponse {
     choices: Vec<NativeChoice>,
+    #[serde(default)]
+    usage: Option<ApiUsage>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -223,6 +226,8 @@ impl OpenRouterProvider {
         ProviderChatResponse {
             text: message.content,
             tool_calls,
+            usage: None,
+            reasoning: None,
         }
     }
 }
@@ -395,7 +400,9 @@ impl Provider for OpenRouterProvider {
             .next()
             .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))?;
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = native_response.usage.map(Into::into);
+        Ok(parsed)
     }
 
     fn supports_native_tools(&self) -> bool {
@@ -483,7 +490,9 @@ impl Provider for OpenRouterProvider {
             .next()
             .map(|c| c.message)
             .ok_or_else(|| anyhow::anyhow!("No response from OpenRouter"))?;
-        Ok(Self::parse_native_response(message))
+        let mut parsed = Self::parse_native_response(message);
+        parsed.usage = native_response.usage.map(Into::into);
+        Ok(parsed)
     }
 }
 
@@ -532,10 +541,12 @@ mod tests {
             ChatMessage {
                 role: "system".into(),
                 content: "be concise".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "hello".into(),
+                parts: Vec::new(),
             },
         ];
 
@@ -578,10 +589,12 @@ mod tests {
             ChatMessage {
                 role: "assistant".into(),
                 content: "Previous answer".into(),
+                parts: Vec::new(),
             },
             ChatMessage {
                 role: "user".into(),
                 content: "Follow-up".into(),
+                parts: Vec::new(),
             },
         ];
 
@@ -628,6 +641,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "user".into(),
             content: "What is the date?".into(),
+            parts: Vec::new(),
         }];
         let tools = vec![serde_json::json!({
             "type": "function",
@@ -722,6 +736,7 @@ mod tests {
             role: "assistant".into(),
             content: r#"{"content":"Using tool","tool_calls":[{"id":"call_abc","name":"shell","arguments":"{\"command\":\"pwd\"}"}]}"#
                 .into(),
+            parts: Vec::new(),
         }];
 
         let converted = OpenRouterProvider::convert_messages(&messages);
@@ -741,6 +756,7 @@ mod tests {
         let messages = vec![ChatMessage {
             role: "tool".into(),
             content: r#"{"tool_call_id":"call_xyz","content":"done"}"#.into(),
+            parts: Vec::new(),
         }];
 
         let converted = OpenRouterProvider::convert_messages(&messages);
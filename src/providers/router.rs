@@ -1,6 +1,10 @@
-use super::traits::{ChatMessage, ChatRequest, ChatResponse};
+use super::traits::{
+    ChatMessage, ChatRequest, ChatResponse, ReasoningOptions, StreamChunk, StreamOptions,
+    StreamResult,
+};
 use super::Provider;
 use async_trait::async_trait;
+use futures_util::stream;
 use std::collections::HashMap;
 
 /// A single route: maps a task hint to a provider + model combo.
@@ -8,6 +12,17 @@ use std::collections::HashMap;
 pub struct Route {
     pub provider_name: String,
     pub model: String,
+    /// Extended thinking for requests on this route, unless the request
+    /// sets its own.
+    pub reasoning: Option<ReasoningOptions>,
+}
+
+/// A route with its provider looked up.
+#[derive(Debug, Clone)]
+struct ResolvedRoute {
+    provider_index: usize,
+    model: String,
+    reasoning: Option<ReasoningOptions>,
 }
 
 /// Multi-model router — routes requests to different provider+model combos
@@ -15,11 +30,12 @@ pub struct Route {
 ///
 /// The model parameter can be:
 /// - A regular model name (e.g. "anthropic/claude-sonnet-4") → uses default provider
-/// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table
+/// - A hint-prefixed string (e.g. "hint:reasoning") → resolves via route table,
+///   which may also set the request's reasoning options
 ///
 /// This wraps multiple pre-created providers and selects the right one per request.
 pub struct RouterProvider {
-    routes: HashMap<String, (usize, String)>, // hint → (provider_index, model)
+    routes: HashMap<String, ResolvedRoute>,
     providers: Vec<(String, Box<dyn Provider>)>,
     default_index: usize,
     default_model: String,
@@ -43,12 +59,19 @@ impl RouterProvider {
             .collect();
 
         // Resolve routes to provider indices
-        let resolved_routes: HashMap<String, (usize, String)> = routes
+        let resolved_routes: HashMap<String, ResolvedRoute> = routes
             .into_iter()
             .filter_map(|(hint, route)| {
                 let index = name_to_index.get(route.provider_name.as_str()).copied();
                 match index {
-                    Some(i) => Some((hint, (i, route.model))),
+                    Some(i) => Some((
+                        hint,
+                        ResolvedRoute {
+                            provider_index: i,
+                            model: route.model,
+                            reasoning: route.reasoning,
+                        },
+                    )),
                     None => {
                         tracing::warn!(
                             hint = hint,
@@ -69,15 +92,20 @@ impl RouterProvider {
         }
     }
 
-    /// Resolve a model parameter to a (provider, actual_model) pair.
+    /// Resolve a model parameter to a (provider_index, actual_model) pair.
     ///
     /// If the model starts with "hint:", look up the hint in the route table.
     /// Otherwise, use the default provider with the given model name.
-    /// Resolve a model parameter to a (provider_index, actual_model) pair.
     fn resolve(&self, model: &str) -> (usize, String) {
+        let (index, model, _) = self.resolve_route(model);
+        (index, model)
+    }
+
+    /// Like [`Self::resolve`], also returning the route's reasoning options.
+    fn resolve_route(&self, model: &str) -> (usize, String, Option<ReasoningOptions>) {
         if let Some(hint) = model.strip_prefix("hint:") {
-            if let Some((idx, resolved_model)) = self.routes.get(hint) {
-                return (*idx, resolved_model.clone());
+            if let Some(route) = self.routes.get(hint) {
+                return (route.provider_index, route.model.clone(), route.reasoning);
             }
             tracing::warn!(
                 hint = hint,
@@ -86,7 +114,7 @@ impl RouterProvider {
         }
 
         // Not a hint or hint not found — use default provider with the model as-is
-        (self.default_index, model.to_string())
+        (self.default_index, model.to_string(), None)
     }
 }
 
@@ -132,8 +160,12 @@ impl Provider for RouterProvider {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let (provider_idx, resolved_model) = self.resolve(model);
+        let (provider_idx, resolved_model, reasoning) = self.resolve_route(model);
         let (_, provider) = &self.providers[provider_idx];
+        let request = ChatRequest {
+            reasoning: request.reasoning.or(reasoning),
+            ..request
+        };
         provider.chat(request, &resolved_model, temperature).await
     }
 
@@ -144,6 +176,65 @@ impl Provider for RouterProvider {
             .unwrap_or(false)
     }
 
+    fn supports_vision(&self) -> bool {
+        // Media is kept or replaced before the route is resolved, so a
+        // text-only route would receive images; require vision on all of them.
+        std::iter::once(self.default_index)
+            .chain(self.routes.values().map(|route| route.provider_index))
+            .all(|index| {
+                self.providers
+                    .get(index)
+                    .is_some_and(|(_, p)| p.supports_vision())
+            })
+    }
+
+    fn supports_structured_output(&self) -> bool {
+        // The request's model (and so its route) isn't known here. A routed
+        // provider without native support would never see the schema, so
+        // only claim it when the default and every route target enforce it.
+        std::iter::once(self.default_index)
+            .chain(self.routes.values().map(|route| route.provider_index))
+            .all(|index| {
+                self.providers
+                    .get(index)
+                    .is_some_and(|(_, p)| p.supports_structured_output())
+            })
+    }
+
+    fn supports_streaming(&self) -> bool {
+        self.providers
+            .get(self.default_index)
+            .map(|(_, p)| p.supports_streaming())
+            .unwrap_or(false)
+    }
+
+    fn stream_chat_with_history(
+        &self,
+        messages: &[ChatMessage],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let (provider_idx, resolved_model, reasoning) = self.resolve_route(model);
+        let (_, provider) = &self.providers[provider_idx];
+        let options = options.with_reasoning(options.reasoning.or(reasoning));
+        provider.stream_chat_with_history(messages, &resolved_model, temperature, options)
+    }
+
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        let (provider_idx, resolved_model, reasoning) = self.resolve_route(model);
+        let (_, provider) = &self.providers[provider_idx];
+        let options = options.with_reasoning(options.reasoning.or(reasoning));
+        provider.stream_chat_with_tools(messages, tools, &resolved_model, temperature, options)
+    }
+
     async fn warmup(&self) -> anyhow::Result<()> {
         for (name, provider) in &self.providers {
             tracing::info!(provider = name, "Warming up routed provider");
@@ -158,6 +249,7 @@ impl Provider for RouterProvider {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::providers::ReasoningEffort;
     use std::sync::atomic::{AtomicUsize, Ordering};
     use std::sync::Arc;
 
@@ -228,6 +320,7 @@ mod tests {
                     Route {
                         provider_name: provider_name.to_string(),
                         model: model.to_string(),
+                        reasoning: None,
                     },
                 )
             })
@@ -344,6 +437,96 @@ mod tests {
         assert_eq!(model, "claude-opus");
     }
 
+    #[test]
+    fn structured_output_requires_every_routed_provider() {
+        struct Structured;
+
+        #[async_trait]
+        impl Provider for Structured {
+            fn supports_structured_output(&self) -> bool {
+                true
+            }
+
+            async fn chat_with_system(
+                &self,
+                _system_prompt: Option<&str>,
+                _message: &str,
+                _model: &str,
+                _temperature: f64,
+            ) -> anyhow::Result<String> {
+                Ok("{}".into())
+            }
+        }
+
+        let route = |provider: &str| {
+            vec![(
+                "cheap".to_string(),
+                Route {
+                    provider_name: provider.to_string(),
+                    model: "small".to_string(),
+                    reasoning: None,
+                },
+            )]
+        };
+        let providers = || -> Vec<(String, Box<dyn Provider>)> {
+            vec![
+                ("native".to_string(), Box::new(Structured)),
+                ("plain".to_string(), Box::new(MockProvider::new("ok"))),
+            ]
+        };
+
+        let all_native = RouterProvider::new(providers(), route("native"), "m".into());
+        assert!(all_native.supports_structured_output());
+
+        let mixed = RouterProvider::new(providers(), route("plain"), "m".into());
+        assert!(!mixed.supports_structured_output());
+    }
+
+    #[test]
+    fn vision_requires_every_routed_provider() {
+        struct Vision;
+
+        #[async_trait]
+        impl Provider for Vision {
+            fn supports_vision(&self) -> bool {
+                true
+            }
+
+            async fn chat_with_system(
+                &self,
+                _system_prompt: Option<&str>,
+                _message: &str,
+                _model: &str,
+                _temperature: f64,
+            ) -> anyhow::Result<String> {
+                Ok("a cat".into())
+            }
+        }
+
+        let route = |provider: &str| {
+            vec![(
+                "cheap".to_string(),
+                Route {
+                    provider_name: provider.to_string(),
+                    model: "small".to_string(),
+                    reasoning: None,
+                },
+            )]
+        };
+        let providers = || -> Vec<(String, Box<dyn Provider>)> {
+            vec![
+                ("vision".to_string(), Box::new(Vision)),
+                ("text".to_string(), Box::new(MockProvider::new("ok"))),
+            ]
+        };
+
+        let all_vision = RouterProvider::new(providers(), route("vision"), "m".into());
+        assert!(all_vision.supports_vision());
+
+        let mixed = RouterProvider::new(providers(), route("text"), "m".into());
+        assert!(!mixed.supports_vision());
+    }
+
     #[test]
     fn skips_routes_with_unknown_provider() {
         let (router, _) = make_router(
@@ -382,4 +565,112 @@ mod tests {
         assert_eq!(result, "response");
         assert_eq!(mock.call_count(), 1);
     }
+
+    /// Records the reasoning options each `chat` call receives.
+    #[derive(Default)]
+    struct ReasoningProbe {
+        seen: parking_lot::Mutex<Vec<Option<ReasoningOptions>>>,
+    }
+
+    #[async_trait]
+    impl Provider for ReasoningProbe {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            Ok(String::new())
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.seen.lock().push(request.reasoning);
+            Ok(ChatResponse {
+                text: Some(String::new()),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning: None,
+            })
+        }
+    }
+
+    #[async_trait]
+    impl Provider for Arc<ReasoningProbe> {
+        async fn chat_with_system(
+            &self,
+            system_prompt: Option<&str>,
+            message: &str,
+            model: &str,
+            temperature: f64,
+        ) -> anyhow::Result<String> {
+            self.as_ref()
+                .chat_with_system(system_prompt, message, model, temperature)
+                .await
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            model: &str,
+            temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.as_ref().chat(request, model, temperature).await
+        }
+    }
+
+    #[tokio::test]
+    async fn route_reasoning_applies_unless_request_sets_its_own() {
+        let probe = Arc::new(ReasoningProbe::default());
+        let route_reasoning = ReasoningOptions {
+            budget_tokens: Some(8000),
+            ..ReasoningOptions::default()
+        };
+        let router = RouterProvider::new(
+            vec![(
+                "smart".to_string(),
+                Box::new(Arc::clone(&probe)) as Box<dyn Provider>,
+            )],
+            vec![(
+                "think".to_string(),
+                Route {
+                    provider_name: "smart".to_string(),
+                    model: "claude-opus".to_string(),
+                    reasoning: Some(route_reasoning),
+                },
+            )],
+            "default-model".to_string(),
+        );
+        let messages = [ChatMessage::user("hi")];
+        let request = |reasoning| ChatRequest {
+            messages: &messages,
+            tools: None,
+            reasoning,
+            response_format: None,
+        };
+        let own = ReasoningOptions {
+            effort: Some(ReasoningEffort::Low),
+            ..ReasoningOptions::default()
+        };
+
+        router.chat(request(None), "hint:think", 0.5).await.unwrap();
+        router
+            .chat(request(Some(own)), "hint:think", 0.5)
+            .await
+            .unwrap();
+        router
+            .chat(request(None), "default-model", 0.5)
+            .await
+            .unwrap();
+
+        assert_eq!(
+            *probe.seen.lock(),
+            vec![Some(route_reasoning), Some(own), None]
+        );
+    }
 }
@@ -0,0 +1,372 @@
+//! Shared plumbing for native streaming.
+//!
+//! A provider builds its HTTP request and hands it to [`request_stream`]
+//! together with a [`StreamParser`] for its wire format: SSE for Anthropic,
+//! OpenAI and Gemini, NDJSON for Ollama. The body is split into lines,
+//! each line becomes at most one [`StreamChunk`], and the stream ends with a
+//! final chunk carrying whatever token usage (and reasoning replay data) was
+//! reported. A body that closes before the format's terminal event was cut
+//! off, so the stream ends with an error instead.
+
+use super::traits::{StreamChunk, StreamError, StreamResult, Usage};
+use futures_util::{stream, StreamExt};
+
+/// Turns the lines of a streamed response body into chunks.
+pub(crate) trait StreamParser: Send + 'static {
+    /// Parse one line of the body (without its trailing newline).
+    fn parse_line(&mut self, line: &str) -> StreamResult<Option<StreamChunk>>;
+
+    /// Token usage reported so far, attached to the final chunk.
+    fn usage(&self) -> Option<Usage>;
+
+    /// Whether the terminal event (`message_stop`, `[DONE]`, a finish
+    /// reason, `"done": true`) has been seen.
+    fn finished(&self) -> bool;
+
+    /// Reasoning replay data collected from the stream, attached to the
+    /// final chunk (see [`Reasoning::replay`](super::traits::Reasoning)).
+    fn reasoning_replay(&self) -> Option<serde_json::Value> {
+        None
+    }
+}
+
+/// Payload of an SSE `data:` line. Event names, comments, blank lines and
+/// the OpenAI `[DONE]` sentinel carry nothing and yield `None`.
+pub(crate) fn sse_data(line: &str) -> Option<&str> {
+    let data = line.trim().strip_prefix("data:")?.trim();
+    (!data.is_empty() && data != "[DONE]").then_some(data)
+}
+
+/// Reassembles lines from network reads; kept as bytes so multi-byte
+/// characters split across reads come out intact.
+#[derive(Default)]
+struct LineBuffer {
+    buffer: Vec<u8>,
+}
+
+impl LineBuffer {
+    fn push(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    fn next_line(&mut self) -> Option<StreamResult<String>> {
+        let pos = self.buffer.iter().position(|&b| b == b'\n')?;
+        let line: Vec<u8> = self.buffer.drain(..=pos).collect();
+        Some(Self::decode(line))
+    }
+
+    /// Whatever is left once the body ends (NDJSON may lack a final newline).
+    fn take_rest(&mut self) -> Option<StreamResult<String>> {
+        if self.buffer.iter().all(u8::is_ascii_whitespace) {
+            return None;
+        }
+        Some(Self::decode(std::mem::take(&mut self.buffer)))
+    }
+
+    fn decode(line: Vec<u8>) -> StreamResult<String> {
+        String::from_utf8(line)
+            .map(|line| line.trim_end_matches(['\r', '\n']).to_string())
+            .map_err(|e| StreamError::InvalidSse(format!("Invalid UTF-8: {e}")))
+    }
+}
+
+fn parse_with<P: StreamParser>(
+    parser: &mut P,
+    line: StreamResult<String>,
+    count_tokens: bool,
+) -> StreamResult<Option<StreamChunk>> {
+    let chunk = parser.parse_line(&line?)?;
+    Ok(chunk.map(|chunk| {
+        if count_tokens {
+            chunk.with_token_estimate()
+        } else {
+            chunk
+        }
+    }))
+}
+
+/// The closing chunk, or an error when the body ended early.
+fn final_chunk<P: StreamParser>(parser: &P) -> StreamResult<StreamChunk> {
+    if !parser.finished() {
+        return Err(StreamError::InvalidSse(
+            "stream ended before its terminal event (connection dropped?)".into(),
+        ));
+    }
+    let mut chunk = StreamChunk::final_chunk();
+    chunk.usage = parser.usage();
+    chunk.reasoning_replay = parser.reasoning_replay();
+    Ok(chunk)
+}
+
+/// A stream that fails immediately, for problems found before sending
+/// (missing credentials, invalid model routing, ...).
+pub(crate) fn error_stream(
+    message: impl Into<String>,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+    let message = message.into();
+    stream::once(async move { Err(StreamError::Provider(message)) }).boxed()
+}
+
+/// Send `request` and stream its response body through `parser`.
+pub(crate) fn request_stream<P: StreamParser>(
+    request: reqwest::RequestBuilder,
+    provider: impl Into<String>,
+    mut parser: P,
+    count_tokens: bool,
+) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+    let provider = provider.into();
+    let (tx, rx) = tokio::sync::mpsc::channel::<StreamResult<StreamChunk>>(100);
+
+    tokio::spawn(async move {
+        let response = match request.send().await {
+            Ok(response) => response,
+            Err(e) => {
+                let _ = tx.send(Err(StreamError::Http(e))).await;
+                return;
+            }
+        };
+
+        if !response.status().is_success() {
+            let error = super::api_error(&provider, response).await;
+            let _ = tx.send(Err(StreamError::Provider(error.to_string()))).await;
+            return;
+        }
+
+        let mut lines = LineBuffer::default();
+        let mut body = response.bytes_stream();
+        loop {
+            let line = match lines.next_line() {
+                Some(line) => line,
+                None => match body.next().await {
+                    Some(Ok(bytes)) => {
+                        lines.push(&bytes);
+                        continue;
+                    }
+                    Some(Err(e)) => {
+                        let _ = tx.send(Err(StreamError::Http(e))).await;
+                        return;
+                    }
+                    None => match lines.take_rest() {
+                        Some(line) => line,
+                        None => break,
+                    },
+                },
+            };
+            match parse_with(&mut parser, line, count_tokens) {
+                Ok(Some(chunk)) => {
+                    if tx.send(Ok(chunk)).await.is_err() {
+                        return; // Receiver dropped
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    let _ = tx.send(Err(e)).await;
+                    return;
+                }
+            }
+        }
+
+        let _ = tx.send(final_chunk(&parser)).await;
+    });
+
+    stream::unfold(rx, |mut rx| async move {
+        rx.recv().await.map(|chunk| (chunk, rx))
+    })
+    .boxed()
+}
+
+/// Run a recorded response body through `parser` as [`request_stream`]
+/// would, delivering it in small reads to exercise line reassembly.
+#[cfg(test)]
+pub(crate) fn parse_recorded<P: StreamParser>(
+    mut parser: P,
+    body: &str,
+) -> StreamResult<Vec<StreamChunk>> {
+    let mut lines = LineBuffer::default();
+    let mut chunks = Vec::new();
+    for read in body.as_bytes().chunks(7) {
+        lines.push(read);
+        while let Some(line) = lines.next_line() {
+            chunks.extend(parse_with(&mut parser, line, false)?);
+        }
+    }
+    if let Some(line) = lines.take_rest() {
+        chunks.extend(parse_with(&mut parser, line, false)?);
+    }
+    chunks.push(final_chunk(&parser)?);
+    Ok(chunks)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::anthropic::AnthropicStreamParser;
+    use crate::providers::compatible::OpenAiSseParser;
+    use crate::providers::gemini::GeminiStreamParser;
+    use crate::providers::ollama::OllamaStreamParser;
+    use crate::providers::traits::collect_tool_call_deltas;
+
+    /// Concatenated text, assembled tool calls and final usage of a stream.
+    fn summarize(chunks: &[StreamChunk]) -> (String, Vec<(String, serde_json::Value)>, Usage) {
+        let text = chunks.iter().map(|c| c.delta.as_str()).collect();
+        let deltas: Vec<_> = chunks.iter().flat_map(|c| c.tool_calls.clone()).collect();
+        let calls = collect_tool_call_deltas(&deltas)
+            .into_iter()
+            .map(|call| (call.name, serde_json::from_str(&call.arguments).unwrap()))
+            .collect();
+        let last = chunks.last().unwrap();
+        assert!(last.is_final);
+        assert!(chunks[..chunks.len() - 1].iter().all(|c| !c.is_final));
+        (text, calls, last.usage.expect("final chunk carries usage"))
+    }
+
+    #[test]
+    fn sse_data_skips_non_data_lines() {
+        assert_eq!(sse_data("data: {\"a\":1}"), Some("{\"a\":1}"));
+        assert_eq!(sse_data("data:{}\r"), Some("{}"));
+        assert_eq!(sse_data("event: ping"), None);
+        assert_eq!(sse_data(": keep-alive"), None);
+        assert_eq!(sse_data("data: [DONE]"), None);
+        assert_eq!(sse_data(""), None);
+    }
+
+    #[test]
+    fn line_buffer_reassembles_split_characters() {
+        let mut lines = LineBuffer::default();
+        let bytes = "héllo\nwörld".as_bytes();
+        lines.push(&bytes[..2]);
+        assert!(lines.next_line().is_none());
+        lines.push(&bytes[2..]);
+        assert_eq!(lines.next_line().unwrap().unwrap(), "héllo");
+        assert!(lines.next_line().is_none());
+        assert_eq!(lines.take_rest().unwrap().unwrap(), "wörld");
+        assert!(lines.take_rest().is_none());
+    }
+
+    #[test]
+    fn anthropic_fixture_streams_text_tool_calls_and_usage() {
+        let chunks = parse_recorded(
+            AnthropicStreamParser::default(),
+            include_str!("../../tests/fixtures/streams/anthropic.sse"),
+        )
+        .unwrap();
+        let (text, calls, usage) = summarize(&chunks);
+        assert_eq!(text, "Let me check the date.");
+        assert_eq!(
+            calls,
+            vec![("shell".to_string(), serde_json::json!({"command": "date"}))]
+        );
+        assert_eq!(usage, Usage::new(412, 61));
+        let first_call = chunks.iter().find(|c| !c.tool_calls.is_empty()).unwrap();
+        assert_eq!(
+            first_call.tool_calls[0].id.as_deref(),
+            Some("toolu_01A09q90qw90lq917835lq9")
+        );
+    }
+
+    #[test]
+    fn anthropic_error_event_fails_the_stream() {
+        let err = parse_recorded(
+            AnthropicStreamParser::default(),
+            "event: error\ndata: {\"type\":\"error\",\"error\":{\"type\":\"overloaded_error\",\"message\":\"Overloaded\"}}\n\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("Overloaded"));
+    }
+
+    #[test]
+    fn openai_fixture_streams_text_tool_calls_and_usage() {
+        let chunks = parse_recorded(
+            OpenAiSseParser::default(),
+            include_str!("../../tests/fixtures/streams/openai.sse"),
+        )
+        .unwrap();
+        let (text, calls, usage) = summarize(&chunks);
+        assert_eq!(text, "Checking both.");
+        assert_eq!(
+            calls,
+            vec![
+                ("shell".to_string(), serde_json::json!({"command": "date"})),
+                (
+                    "file_read".to_string(),
+                    serde_json::json!({"path": "notes.md"})
+                ),
+            ]
+        );
+        assert_eq!(usage, Usage::new(95, 42));
+        // Usage moves to the final chunk instead of an empty one of its own
+        assert!(chunks[..chunks.len() - 1].iter().all(|c| c.usage.is_none()));
+    }
+
+    #[test]
+    fn gemini_fixture_streams_text_function_calls_and_usage() {
+        let chunks = parse_recorded(
+            GeminiStreamParser::default(),
+            include_str!("../../tests/fixtures/streams/gemini.sse"),
+        )
+        .unwrap();
+        let (text, calls, usage) = summarize(&chunks);
+        assert_eq!(text, "Sure — checking the weather in Zürich.");
+        assert_eq!(
+            calls,
+            vec![("weather".to_string(), serde_json::json!({"city": "Zürich"}))]
+        );
+        assert_eq!(usage, Usage::new(38, 17));
+    }
+
+    #[test]
+    fn gemini_safety_stop_fails_the_stream() {
+        let err = parse_recorded(
+            GeminiStreamParser::default(),
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hm\"}]}}]}\r\n\r\ndata: {\"candidates\":[{\"finishReason\":\"SAFETY\"}]}\r\n\r\n",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("safety"));
+    }
+
+    #[test]
+    fn ollama_fixture_streams_text_tool_calls_and_usage() {
+        let chunks = parse_recorded(
+            OllamaStreamParser::default(),
+            include_str!("../../tests/fixtures/streams/ollama.ndjson"),
+        )
+        .unwrap();
+        let (text, calls, usage) = summarize(&chunks);
+        assert_eq!(text, "Running it now.");
+        assert_eq!(
+            calls,
+            vec![("shell".to_string(), serde_json::json!({"command": "ls"}))]
+        );
+        assert_eq!(usage, Usage::new(26, 12));
+    }
+
+    #[test]
+    fn truncated_streams_fail() {
+        let anthropic = include_str!("../../tests/fixtures/streams/anthropic.sse");
+        let cut = anthropic.find("event: message_stop").unwrap();
+        let err = parse_recorded(AnthropicStreamParser::default(), &anthropic[..cut]).unwrap_err();
+        assert!(err.to_string().contains("terminal event"));
+
+        let openai = include_str!("../../tests/fixtures/streams/openai.sse");
+        let cut = openai.find("data: [DONE]").unwrap();
+        assert!(parse_recorded(OpenAiSseParser::default(), &openai[..cut]).is_err());
+
+        let gemini =
+            "data: {\"candidates\":[{\"content\":{\"parts\":[{\"text\":\"Hm\"}]}}]}\r\n\r\n";
+        assert!(parse_recorded(GeminiStreamParser::default(), gemini).is_err());
+
+        let ollama = include_str!("../../tests/fixtures/streams/ollama.ndjson");
+        let cut = ollama.rfind("{\"model\"").unwrap();
+        assert!(parse_recorded(OllamaStreamParser::default(), &ollama[..cut]).is_err());
+    }
+
+    #[test]
+    fn ollama_error_line_fails_the_stream() {
+        let err = parse_recorded(
+            OllamaStreamParser::default(),
+            "{\"error\":\"model 'nope' not found\"}",
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("not found"));
+    }
+}
@@ -0,0 +1,469 @@
+//! Structured output — replies that are JSON matching a schema.
+//!
+//! [`chat_json`] asks any provider for a reply matching a [`ResponseFormat`].
+//! Providers that enforce schemas natively receive it as
+//! [`ChatRequest::response_format`]; for the rest the schema is described in
+//! the system prompt. Either way the reply is parsed and validated, and a
+//! reply that fails is sent back with the problem for up to
+//! [`MAX_REPAIR_ATTEMPTS`] corrections. [`chat_typed`] additionally
+//! deserializes the result into a Rust type.
+//!
+//! Validation covers the keywords structured-output APIs accept: `type`,
+//! `enum`, `const`, `properties`, `required`, `additionalProperties`,
+//! `items`, `minItems`/`maxItems` and `anyOf`/`oneOf` (both treated as
+//! "matches at least one"). Other keywords are ignored.
+
+use super::traits::{with_tool_instructions, ChatMessage, ChatRequest, Provider, ResponseFormat};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+/// Corrections requested after the first reply before giving up.
+pub const MAX_REPAIR_ATTEMPTS: usize = 2;
+
+/// Ask for a reply matching `format` and return it as validated JSON.
+pub async fn chat_json(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<Value> {
+    request_valid(provider, messages, format, model, temperature, |value| {
+        validate(&format.schema, &value).map(|()| value)
+    })
+    .await
+}
+
+/// Ask for a reply matching `format` and deserialize it into `T`. A reply
+/// that validates but does not fit `T` is repaired like a schema mismatch.
+pub async fn chat_typed<T: DeserializeOwned>(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+) -> anyhow::Result<T> {
+    request_valid(provider, messages, format, model, temperature, |value| {
+        validate(&format.schema, &value)?;
+        serde_json::from_value(value).map_err(|e| e.to_string())
+    })
+    .await
+}
+
+async fn request_valid<R>(
+    provider: &dyn Provider,
+    messages: &[ChatMessage],
+    format: &ResponseFormat,
+    model: &str,
+    temperature: f64,
+    mut check: impl FnMut(Value) -> Result<R, String>,
+) -> anyhow::Result<R> {
+    let mut history = if provider.supports_structured_output() {
+        messages.to_vec()
+    } else {
+        with_tool_instructions(messages, &schema_instructions(format))
+    };
+
+    let mut problem = String::new();
+    for _ in 0..=MAX_REPAIR_ATTEMPTS {
+        let response = provider
+            .chat(
+                ChatRequest {
+                    messages: &history,
+                    tools: None,
+                    reasoning: None,
+                    response_format: Some(format),
+                },
+                model,
+                temperature,
+            )
+            .await?;
+        let text = response.text.unwrap_or_default();
+        let checked = extract_json(&text)
+            .ok_or_else(|| "the reply is not valid JSON".to_string())
+            .and_then(&mut check);
+        match checked {
+            Ok(result) => return Ok(result),
+            Err(e) => {
+                tracing::debug!("Structured reply for '{}' rejected: {e}", format.name);
+                problem = e;
+            }
+        }
+        history.push(ChatMessage::assistant(text));
+        history.push(ChatMessage::user(format!(
+            "That reply does not match the required JSON schema: {problem}. \
+             Reply again with only the corrected JSON."
+        )));
+    }
+
+    anyhow::bail!(
+        "Structured reply for '{}' still invalid after {} attempts: {problem}",
+        format.name,
+        MAX_REPAIR_ATTEMPTS + 1
+    )
+}
+
+/// System-prompt text describing the schema, for providers that cannot
+/// enforce it.
+fn schema_instructions(format: &ResponseFormat) -> String {
+    let schema = serde_json::to_string_pretty(&format.schema).unwrap_or_default();
+    format!(
+        "## Response Format\n\n\
+         Reply with only a JSON value (no prose, no code fences) matching this JSON Schema:\n\n\
+         {schema}\n"
+    )
+}
+
+/// Parse the JSON in a reply, tolerating code fences and surrounding prose.
+fn extract_json(text: &str) -> Option<Value> {
+    let text = text.trim();
+    if let Ok(value) = serde_json::from_str(text) {
+        return Some(value);
+    }
+    let unfenced = text
+        .strip_prefix("```json")
+        .or_else(|| text.strip_prefix("```"))
+        .and_then(|rest| rest.trim_end().strip_suffix("```"));
+    if let Some(value) = unfenced.and_then(|inner| serde_json::from_str(inner.trim()).ok()) {
+        return Some(value);
+    }
+    let start = text.find(['{', '['])?;
+    let end = text.rfind(['}', ']'])?;
+    (start < end)
+        .then(|| serde_json::from_str(&text[start..=end]).ok())
+        .flatten()
+}
+
+/// Check `value` against `schema`, naming the first mismatch and where it is.
+pub fn validate(schema: &Value, value: &Value) -> Result<(), String> {
+    validate_at(schema, value, "$")
+}
+
+fn validate_at(schema: &Value, value: &Value, path: &str) -> Result<(), String> {
+    let Some(schema) = schema.as_object() else {
+        // `true`, `{}` and anything unrecognised accept every value
+        return Ok(());
+    };
+
+    if let Some(types) = schema.get("type") {
+        let allowed: Vec<&str> = match types {
+            Value::String(t) => vec![t.as_str()],
+            Value::Array(ts) => ts.iter().filter_map(Value::as_str).collect(),
+            _ => Vec::new(),
+        };
+        if !allowed.is_empty() && !allowed.iter().any(|t| has_type(value, t)) {
+            return Err(format!(
+                "{path}: expected {}, got {}",
+                allowed.join(" or "),
+                type_name(value)
+            ));
+        }
+    }
+    if let Some(options) = schema.get("enum").and_then(Value::as_array) {
+        if !options.contains(value) {
+            return Err(format!(
+                "{path}: {value} is not one of {}",
+                Value::from(options.clone())
+            ));
+        }
+    }
+    if let Some(expected) = schema.get("const") {
+        if expected != value {
+            return Err(format!("{path}: expected {expected}, got {value}"));
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(branches) = schema.get(key).and_then(Value::as_array) {
+            if !branches.iter().any(|b| validate_at(b, value, path).is_ok()) {
+                return Err(format!("{path}: matches none of the allowed schemas"));
+            }
+        }
+    }
+
+    match value {
+        Value::Object(object) => {
+            let properties = schema.get("properties").and_then(Value::as_object);
+            for name in schema
+                .get("required")
+                .and_then(Value::as_array)
+                .into_iter()
+                .flatten()
+                .filter_map(Value::as_str)
+            {
+                if !object.contains_key(name) {
+                    return Err(format!("{path}: missing required property `{name}`"));
+                }
+            }
+            for (name, item) in object {
+                let item_path = format!("{path}.{name}");
+                match properties.and_then(|p| p.get(name)) {
+                    Some(item_schema) => validate_at(item_schema, item, &item_path)?,
+                    None => match schema.get("additionalProperties") {
+                        Some(Value::Bool(false)) => {
+                            return Err(format!("{path}: unexpected property `{name}`"));
+                        }
+                        Some(extra) => validate_at(extra, item, &item_path)?,
+                        None => {}
+                    },
+                }
+            }
+        }
+        Value::Array(items) => {
+            let count = items.len() as u64;
+            if let Some(min) = schema.get("minItems").and_then(Value::as_u64) {
+                if count < min {
+                    return Err(format!(
+                        "{path}: expected at least {min} items, got {count}"
+                    ));
+                }
+            }
+            if let Some(max) = schema.get("maxItems").and_then(Value::as_u64) {
+                if count > max {
+                    return Err(format!("{path}: expected at most {max} items, got {count}"));
+                }
+            }
+            if let Some(item_schema) = schema.get("items") {
+                for (i, item) in items.iter().enumerate() {
+                    validate_at(item_schema, item, &format!("{path}[{i}]"))?;
+                }
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+fn has_type(value: &Value, expected: &str) -> bool {
+    match expected {
+        "object" => value.is_object(),
+        "array" => value.is_array(),
+        "string" => value.is_string(),
+        "number" => value.is_number(),
+        "integer" => {
+            value.is_i64() || value.is_u64() || value.as_f64().is_some_and(|n| n.fract() == 0.0)
+        }
+        "boolean" => value.is_boolean(),
+        "null" => value.is_null(),
+        _ => true,
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Object(_) => "object",
+        Value::Array(_) => "array",
+        Value::String(_) => "string",
+        Value::Number(_) => "number",
+        Value::Bool(_) => "boolean",
+        Value::Null => "null",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::providers::ChatResponse;
+    use async_trait::async_trait;
+    use serde::Deserialize;
+
+    /// Replies from a script and records every request it receives.
+    struct ScriptedProvider {
+        native: bool,
+        replies: parking_lot::Mutex<Vec<&'static str>>,
+        requests: parking_lot::Mutex<Vec<(Vec<ChatMessage>, bool)>>,
+    }
+
+    impl ScriptedProvider {
+        fn new(native: bool, replies: &[&'static str]) -> Self {
+            Self {
+                native,
+                replies: parking_lot::Mutex::new(replies.iter().rev().copied().collect()),
+                requests: parking_lot::Mutex::new(Vec::new()),
+            }
+        }
+    }
+
+    #[async_trait]
+    impl Provider for ScriptedProvider {
+        async fn chat_with_system(
+            &self,
+            _system_prompt: Option<&str>,
+            _message: &str,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<String> {
+            unreachable!("structured requests go through chat")
+        }
+
+        async fn chat(
+            &self,
+            request: ChatRequest<'_>,
+            _model: &str,
+            _temperature: f64,
+        ) -> anyhow::Result<ChatResponse> {
+            self.requests
+                .lock()
+                .push((request.messages.to_vec(), request.response_format.is_some()));
+            Ok(ChatResponse {
+                text: self.replies.lock().pop().map(ToString::to_string),
+                tool_calls: Vec::new(),
+                usage: None,
+                reasoning: None,
+            })
+        }
+
+        fn supports_structured_output(&self) -> bool {
+            self.native
+        }
+    }
+
+    fn plan_format() -> ResponseFormat {
+        ResponseFormat::json_schema(
+            "plan",
+            serde_json::json!({
+                "type": "object",
+                "properties": {
+                    "title": {"type": "string"},
+                    "steps": {"type": "array", "items": {"type": "string"}, "minItems": 1}
+                },
+                "required": ["title", "steps"],
+                "additionalProperties": false
+            }),
+        )
+    }
+
+    #[test]
+    fn validate_reports_first_mismatch_with_path() {
+        let schema = plan_format().schema;
+        assert!(validate(&schema, &serde_json::json!({"title": "t", "steps": ["a"]})).is_ok());
+
+        let err = validate(
+            &schema,
+            &serde_json::json!({"title": "t", "steps": ["a", 2]}),
+        )
+        .unwrap_err();
+        assert_eq!(err, "$.steps[1]: expected string, got number");
+        let err = validate(&schema, &serde_json::json!({"steps": ["a"]})).unwrap_err();
+        assert!(err.contains("missing required property `title`"));
+        let err = validate(&schema, &serde_json::json!({"title": "t", "steps": []})).unwrap_err();
+        assert_eq!(err, "$.steps: expected at least 1 items, got 0");
+        let err = validate(
+            &schema,
+            &serde_json::json!({"title": "t", "steps": ["a"], "x": 1}),
+        )
+        .unwrap_err();
+        assert_eq!(err, "$: unexpected property `x`");
+    }
+
+    #[test]
+    fn validate_handles_enum_union_types_and_integers() {
+        let schema = serde_json::json!({
+            "type": "object",
+            "properties": {
+                "level": {"enum": ["low", "high"]},
+                "count": {"type": "integer"},
+                "note": {"type": ["string", "null"]}
+            }
+        });
+        let ok = serde_json::json!({"level": "low", "count": 3.0, "note": null});
+        assert!(validate(&schema, &ok).is_ok());
+        assert!(validate(&schema, &serde_json::json!({"level": "mid"})).is_err());
+        assert!(validate(&schema, &serde_json::json!({"count": 1.5})).is_err());
+    }
+
+    #[test]
+    fn extract_json_tolerates_fences_and_prose() {
+        let expected = serde_json::json!({"a": 1});
+        assert_eq!(extract_json("{\"a\": 1}"), Some(expected.clone()));
+        assert_eq!(
+            extract_json("```json\n{\"a\": 1}\n```"),
+            Some(expected.clone())
+        );
+        assert_eq!(
+            extract_json("Here you go: {\"a\": 1} Hope that helps."),
+            Some(expected)
+        );
+        assert_eq!(extract_json("no json here"), None);
+    }
+
+    #[tokio::test]
+    async fn fallback_describes_schema_and_repairs_invalid_reply() {
+        let provider = ScriptedProvider::new(
+            false,
+            &[
+                "Sure! Title: Ship it",
+                r#"{"title": "Ship it", "steps": ["build", "deploy"]}"#,
+            ],
+        );
+        let format = plan_format();
+        let value = chat_json(
+            &provider,
+            &[ChatMessage::user("plan the release")],
+            &format,
+            "model",
+            0.0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(value["steps"][1], "deploy");
+
+        let requests = provider.requests.lock();
+        assert_eq!(requests.len(), 2);
+        assert!(requests[0].0[0].content.contains("## Response Format"));
+        let repair = requests[1].0.last().unwrap();
+        assert_eq!(repair.role, "user");
+        assert!(repair.content.contains("not valid JSON"));
+    }
+
+    #[tokio::test]
+    async fn native_provider_gets_format_without_prompt_instructions() {
+        let provider = ScriptedProvider::new(true, &[r#"{"title": "t", "steps": ["a"]}"#]);
+        chat_json(
+            &provider,
+            &[ChatMessage::user("plan")],
+            &plan_format(),
+            "m",
+            0.0,
+        )
+        .await
+        .unwrap();
+        let requests = provider.requests.lock();
+        assert!(requests[0].1);
+        assert_eq!(requests[0].0.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn chat_typed_deserializes_and_gives_up_after_repairs() {
+        #[derive(Debug, Deserialize)]
+        struct Plan {
+            title: String,
+            steps: Vec<String>,
+        }
+
+        let provider = ScriptedProvider::new(true, &[r#"{"title": "t", "steps": ["a", "b"]}"#]);
+        let plan: Plan = chat_typed(
+            &provider,
+            &[ChatMessage::user("plan")],
+            &plan_format(),
+            "m",
+            0.0,
+        )
+        .await
+        .unwrap();
+        assert_eq!(plan.title, "t");
+        assert_eq!(plan.steps.len(), 2);
+
+        let stubborn = ScriptedProvider::new(true, &["{}", "{}", "{}"]);
+        let err = chat_typed::<Plan>(
+            &stubborn,
+            &[ChatMessage::user("plan")],
+            &plan_format(),
+            "m",
+            0.0,
+        )
+        .await
+        .unwrap_err();
+        assert!(err.to_string().contains("after 3 attempts"));
+        assert_eq!(stubborn.requests.lock().len(), MAX_REPAIR_ATTEMPTS + 1);
+    }
+}
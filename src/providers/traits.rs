@@ -5,10 +5,17 @@ use serde::{Deserialize, Serialize};
 use std::fmt::Write;
 
 /// A single message in a conversation.
+///
+/// `content` always carries the plain-text form of the message. When `parts`
+/// is non-empty it is the authoritative, ordered content (text interleaved
+/// with images or audio) and `content` mirrors its text for consumers that
+/// only deal in strings (history trimming, memory, logs).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChatMessage {
     pub role: String,
     pub content: String,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parts: Vec<ContentPart>,
 }
 
 impl ChatMessage {
@@ -16,6 +23,7 @@ impl ChatMessage {
         Self {
             role: "system".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -23,6 +31,24 @@ impl ChatMessage {
         Self {
             role: "user".into(),
             content: content.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// A user message made of typed parts (text, images, audio).
+    pub fn user_with_parts(parts: Vec<ContentPart>) -> Self {
+        let content = parts
+            .iter()
+            .filter_map(|part| match part {
+                ContentPart::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            role: "user".into(),
+            content,
+            parts,
         }
     }
 
@@ -30,6 +56,7 @@ impl ChatMessage {
         Self {
             role: "assistant".into(),
             content: content.into(),
+            parts: Vec::new(),
         }
     }
 
@@ -37,6 +64,118 @@ impl ChatMessage {
         Self {
             role: "tool".into(),
             content: content.into(),
+            parts: Vec::new(),
+        }
+    }
+
+    /// True when the message carries images or audio.
+    pub fn has_media(&self) -> bool {
+        self.parts.iter().any(ContentPart::is_media)
+    }
+
+    /// Text-only rendering for providers that cannot accept media: each
+    /// image or audio part is replaced by a short bracketed placeholder.
+    pub fn text_fallback(&self) -> Self {
+        if !self.has_media() {
+            return Self {
+                role: self.role.clone(),
+                content: self.content.clone(),
+                parts: Vec::new(),
+            };
+        }
+        let content = self
+            .parts
+            .iter()
+            .map(ContentPart::to_text)
+            .collect::<Vec<_>>()
+            .join("\n");
+        Self {
+            role: self.role.clone(),
+            content,
+            parts: Vec::new(),
+        }
+    }
+}
+
+/// Where the bytes of an image or audio part come from.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum MediaSource {
+    /// Publicly reachable URL the provider fetches itself.
+    Url { url: String },
+    /// Inline base64-encoded bytes.
+    Base64 { mime_type: String, data: String },
+}
+
+impl MediaSource {
+    /// Encode raw bytes as an inline source.
+    pub fn from_bytes(mime_type: impl Into<String>, bytes: &[u8]) -> Self {
+        use base64::Engine;
+        Self::Base64 {
+            mime_type: mime_type.into(),
+            data: base64::engine::general_purpose::STANDARD.encode(bytes),
+        }
+    }
+
+    /// Declared MIME type, when known.
+    pub fn mime_type(&self) -> Option<&str> {
+        match self {
+            Self::Url { .. } => None,
+            Self::Base64 { mime_type, .. } => Some(mime_type),
+        }
+    }
+
+    /// The URL itself, or a `data:` URL for inline bytes.
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Url { url } => url.clone(),
+            Self::Base64 { mime_type, data } => format!("data:{mime_type};base64,{data}"),
+        }
+    }
+}
+
+/// One piece of multimodal message content.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ContentPart {
+    Text { text: String },
+    Image { source: MediaSource },
+    Audio { source: MediaSource },
+}
+
+impl ContentPart {
+    pub fn text(text: impl Into<String>) -> Self {
+        Self::Text { text: text.into() }
+    }
+
+    pub fn image(source: MediaSource) -> Self {
+        Self::Image { source }
+    }
+
+    pub fn audio(source: MediaSource) -> Self {
+        Self::Audio { source }
+    }
+
+    /// True for image and audio parts.
+    pub fn is_media(&self) -> bool {
+        !matches!(self, Self::Text { .. })
+    }
+
+    /// Text for this part, with a placeholder standing in for media.
+    pub fn to_text(&self) -> String {
+        match self {
+            Self::Text { text } => text.clone(),
+            Self::Image { source } => media_placeholder("image", source),
+            Self::Audio { source } => media_placeholder("audio", source),
+        }
+    }
+}
+
+fn media_placeholder(kind: &str, source: &MediaSource) -> String {
+    match source {
+        MediaSource::Url { url } => format!("[{kind} attached: {url}]"),
+        MediaSource::Base64 { mime_type, .. } => {
+            format!("[{kind} attached ({mime_type}) — this model cannot view it]")
         }
     }
 }
@@ -56,6 +195,118 @@ pub struct ChatResponse {
     pub text: Option<String>,
     /// Tool calls requested by the LLM.
     pub tool_calls: Vec<ToolCall>,
+    /// Token counts reported by the provider, when its API returns them.
+    pub usage: Option<Usage>,
+    /// Reasoning the model produced before answering, kept apart from
+    /// `text` so it is never mistaken for (or delivered as) the reply.
+    pub reasoning: Option<Reasoning>,
+}
+
+/// Reasoning ("thinking") content returned by a reasoning model.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct Reasoning {
+    /// Readable reasoning text or summary.
+    pub text: String,
+    /// Opaque provider data that must accompany the reasoning when the turn
+    /// is sent back, e.g. Anthropic's signed thinking blocks or Gemini's
+    /// thought signatures. Required by those APIs across tool-call turns.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay: Option<serde_json::Value>,
+}
+
+impl Reasoning {
+    /// Build from streamed or collected parts; `None` when there is nothing
+    /// to show or send back.
+    pub fn from_parts(text: String, replay: Option<serde_json::Value>) -> Option<Self> {
+        (!text.trim().is_empty() || replay.is_some()).then_some(Self { text, replay })
+    }
+}
+
+/// How much a reasoning model should think before answering.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReasoningEffort {
+    Low,
+    Medium,
+    High,
+}
+
+impl ReasoningEffort {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Low => "low",
+            Self::Medium => "medium",
+            Self::High => "high",
+        }
+    }
+}
+
+/// Per-request reasoning (extended thinking) settings.
+///
+/// Set either field; providers use the form their API takes (a token
+/// budget for Anthropic and Gemini, an effort level for OpenAI-style APIs)
+/// and derive it from the other when it is missing.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ReasoningOptions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub effort: Option<ReasoningEffort>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub budget_tokens: Option<u32>,
+}
+
+impl ReasoningOptions {
+    pub fn effort(&self) -> ReasoningEffort {
+        self.effort.unwrap_or(match self.budget_tokens {
+            Some(budget) if budget <= 4_096 => ReasoningEffort::Low,
+            Some(budget) if budget > 16_384 => ReasoningEffort::High,
+            _ => ReasoningEffort::Medium,
+        })
+    }
+
+    pub fn budget_tokens(&self) -> u32 {
+        self.budget_tokens.unwrap_or(match self.effort() {
+            ReasoningEffort::Low => 2_048,
+            ReasoningEffort::Medium => 8_192,
+            ReasoningEffort::High => 24_576,
+        })
+    }
+}
+
+/// Prompt/completion token counts parsed from a provider's usage block.
+///
+/// `input_tokens` covers the whole prompt, including the parts served from
+/// (`cache_read_tokens`) or written to (`cache_write_tokens`) the
+/// provider's prompt cache, which are billed at their own rates.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Usage {
+    pub input_tokens: u64,
+    pub output_tokens: u64,
+    #[serde(default)]
+    pub cache_read_tokens: u64,
+    #[serde(default)]
+    pub cache_write_tokens: u64,
+}
+
+impl Usage {
+    pub fn new(input_tokens: u64, output_tokens: u64) -> Self {
+        Self {
+            input_tokens,
+            output_tokens,
+            cache_read_tokens: 0,
+            cache_write_tokens: 0,
+        }
+    }
+
+    /// Record how much of the prompt hit or populated the prompt cache.
+    pub fn with_cache(mut self, cache_read_tokens: u64, cache_write_tokens: u64) -> Self {
+        self.cache_read_tokens = cache_read_tokens;
+        self.cache_write_tokens = cache_write_tokens;
+        self
+    }
+
+    pub fn total_tokens(&self) -> u64 {
+        self.input_tokens.saturating_add(self.output_tokens)
+    }
 }
 
 impl ChatResponse {
@@ -75,6 +326,37 @@ impl ChatResponse {
 pub struct ChatRequest<'a> {
     pub messages: &'a [ChatMessage],
     pub tools: Option<&'a [ToolSpec]>,
+    /// Ask for extended thinking; `None` leaves the model's default.
+    pub reasoning: Option<ReasoningOptions>,
+    /// Constrain the reply to JSON matching a schema (see [`ResponseFormat`]).
+    pub response_format: Option<&'a ResponseFormat>,
+}
+
+/// A JSON Schema the reply must satisfy.
+///
+/// Providers that report [`Provider::supports_structured_output`] enforce it
+/// natively (OpenAI strict `json_schema`, Gemini `responseSchema`, Anthropic
+/// forced tool use, Ollama `format`); others ignore it. Use
+/// [`structured::chat_json`](super::structured::chat_json) to get validated
+/// JSON from any provider.
+///
+/// OpenAI's strict mode only accepts schemas whose objects set
+/// `"additionalProperties": false` and list every property in `required`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ResponseFormat {
+    /// Schema name (letters, digits, `_` and `-`); doubles as the tool name
+    /// when a provider enforces the schema through forced tool use.
+    pub name: String,
+    pub schema: serde_json::Value,
+}
+
+impl ResponseFormat {
+    pub fn json_schema(name: impl Into<String>, schema: serde_json::Value) -> Self {
+        Self {
+            name: name.into(),
+            schema,
+        }
+    }
 }
 
 /// A tool result to feed back to the LLM.
@@ -94,6 +376,9 @@ pub enum ConversationMessage {
     AssistantToolCalls {
         text: Option<String>,
         tool_calls: Vec<ToolCall>,
+        /// Reasoning that led to the calls, replayed where the API requires it.
+        #[serde(default)]
+        reasoning: Option<Reasoning>,
     },
     /// Results of tool executions, fed back to the LLM.
     ToolResults(Vec<ToolResultMessage>),
@@ -108,6 +393,14 @@ pub struct StreamChunk {
     pub is_final: bool,
     /// Approximate token count for this chunk (estimated).
     pub token_count: usize,
+    /// Fragments of native tool calls carried by this chunk.
+    pub tool_calls: Vec<ToolCallDelta>,
+    /// Token usage, when the provider reports it (usually on the last chunk).
+    pub usage: Option<Usage>,
+    /// Reasoning text delta, streamed separately from the reply.
+    pub reasoning: String,
+    /// Reasoning replay data (see [`Reasoning::replay`]), on the last chunk.
+    pub reasoning_replay: Option<serde_json::Value>,
 }
 
 impl StreamChunk {
@@ -117,6 +410,10 @@ impl StreamChunk {
             delta: text.into(),
             is_final: false,
             token_count: 0,
+            tool_calls: Vec::new(),
+            usage: None,
+            reasoning: String::new(),
+            reasoning_replay: None,
         }
     }
 
@@ -126,6 +423,10 @@ impl StreamChunk {
             delta: String::new(),
             is_final: true,
             token_count: 0,
+            tool_calls: Vec::new(),
+            usage: None,
+            reasoning: String::new(),
+            reasoning_replay: None,
         }
     }
 
@@ -135,6 +436,10 @@ impl StreamChunk {
             delta: message.into(),
             is_final: true,
             token_count: 0,
+            tool_calls: Vec::new(),
+            usage: None,
+            reasoning: String::new(),
+            reasoning_replay: None,
         }
     }
 
@@ -143,6 +448,80 @@ impl StreamChunk {
         self.token_count = self.delta.len().div_ceil(4);
         self
     }
+
+    /// Attach native tool-call fragments.
+    pub fn with_tool_calls(mut self, tool_calls: Vec<ToolCallDelta>) -> Self {
+        self.tool_calls = tool_calls;
+        self
+    }
+
+    /// Attach reported token usage.
+    pub fn with_usage(mut self, usage: Usage) -> Self {
+        self.usage = Some(usage);
+        self
+    }
+
+    /// Create a non-final chunk carrying only reasoning text.
+    pub fn reasoning(text: impl Into<String>) -> Self {
+        let mut chunk = Self::delta("");
+        chunk.reasoning = text.into();
+        chunk
+    }
+
+    /// Attach reasoning replay data.
+    pub fn with_reasoning_replay(mut self, replay: serde_json::Value) -> Self {
+        self.reasoning_replay = Some(replay);
+        self
+    }
+}
+
+/// A fragment of a native tool call streamed across several chunks.
+///
+/// Fragments sharing an `index` belong to the same call: `id` and `name`
+/// usually arrive once, `arguments` is split into pieces to concatenate.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ToolCallDelta {
+    pub index: usize,
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub arguments: String,
+}
+
+/// Assemble streamed tool-call fragments into complete calls, in index order.
+///
+/// Calls without a name are dropped; missing ids get a fresh UUID.
+pub fn collect_tool_call_deltas(deltas: &[ToolCallDelta]) -> Vec<ToolCall> {
+    let mut calls: std::collections::BTreeMap<usize, ToolCallDelta> =
+        std::collections::BTreeMap::new();
+    for delta in deltas {
+        let call = calls.entry(delta.index).or_insert_with(|| ToolCallDelta {
+            index: delta.index,
+            ..ToolCallDelta::default()
+        });
+        if delta.id.is_some() {
+            call.id.clone_from(&delta.id);
+        }
+        if let Some(name) = &delta.name {
+            call.name.get_or_insert_with(String::new).push_str(name);
+        }
+        call.arguments.push_str(&delta.arguments);
+    }
+
+    calls
+        .into_values()
+        .filter_map(|call| {
+            let name = call.name.filter(|name| !name.trim().is_empty())?;
+            Some(ToolCall {
+                id: call.id.unwrap_or_else(|| uuid::Uuid::new_v4().to_string()),
+                name,
+                arguments: if call.arguments.trim().is_empty() {
+                    "{}".to_string()
+                } else {
+                    call.arguments
+                },
+            })
+        })
+        .collect()
 }
 
 /// Options for streaming chat requests.
@@ -152,6 +531,8 @@ pub struct StreamOptions {
     pub enabled: bool,
     /// Whether to include token counts in chunks.
     pub count_tokens: bool,
+    /// Ask for extended thinking; `None` leaves the model's default.
+    pub reasoning: Option<ReasoningOptions>,
 }
 
 impl StreamOptions {
@@ -160,9 +541,16 @@ impl StreamOptions {
         Self {
             enabled,
             count_tokens: false,
+            reasoning: None,
         }
     }
 
+    /// Request extended thinking.
+    pub fn with_reasoning(mut self, reasoning: Option<ReasoningOptions>) -> Self {
+        self.reasoning = reasoning;
+        self
+    }
+
     /// Enable token counting.
     pub fn with_token_count(mut self) -> Self {
         self.count_tokens = true;
@@ -205,6 +593,18 @@ pub struct ProviderCapabilities {
     ///
     /// When `false`, tools must be injected via system prompt as text.
     pub native_tool_calling: bool,
+
+    /// Whether the provider accepts image parts in messages.
+    ///
+    /// When `false`, the agent loop replaces images and audio with text
+    /// placeholders (see [`ChatMessage::text_fallback`]) before sending.
+    pub vision: bool,
+
+    /// Whether the provider can constrain replies to a JSON schema.
+    ///
+    /// When `false`, [`ChatRequest::response_format`] is ignored and the
+    /// structured-output helpers describe the schema in the prompt instead.
+    pub structured_output: bool,
 }
 
 /// Provider-specific tool payload formats.
@@ -312,20 +712,8 @@ pub trait Provider: Send + Sync {
                         )
                     }
                 };
-                let mut modified_messages = request.messages.to_vec();
-
-                // Inject tool instructions into an existing system message.
-                // If none exists, prepend one to the conversation.
-                if let Some(system_message) =
-                    modified_messages.iter_mut().find(|m| m.role == "system")
-                {
-                    if !system_message.content.is_empty() {
-                        system_message.content.push_str("\n\n");
-                    }
-                    system_message.content.push_str(&tool_instructions);
-                } else {
-                    modified_messages.insert(0, ChatMessage::system(tool_instructions));
-                }
+                let modified_messages =
+                    with_tool_instructions(request.messages, &tool_instructions);
 
                 let text = self
                     .chat_with_history(&modified_messages, model, temperature)
@@ -333,6 +721,8 @@ pub trait Provider: Send + Sync {
                 return Ok(ChatResponse {
                     text: Some(text),
                     tool_calls: Vec::new(),
+                    usage: None,
+                    reasoning: None,
                 });
             }
         }
@@ -343,6 +733,8 @@ pub trait Provider: Send + Sync {
         Ok(ChatResponse {
             text: Some(text),
             tool_calls: Vec::new(),
+            usage: None,
+            reasoning: None,
         })
     }
 
@@ -351,6 +743,16 @@ pub trait Provider: Send + Sync {
         self.capabilities().native_tool_calling
     }
 
+    /// Whether provider accepts image content parts.
+    fn supports_vision(&self) -> bool {
+        self.capabilities().vision
+    }
+
+    /// Whether provider enforces [`ChatRequest::response_format`] natively.
+    fn supports_structured_output(&self) -> bool {
+        self.capabilities().structured_output
+    }
+
     /// Warm up the HTTP connection pool (TLS handshake, DNS, HTTP/2 setup).
     /// Default implementation is a no-op; providers with HTTP clients should override.
     async fn warmup(&self) -> anyhow::Result<()> {
@@ -358,8 +760,8 @@ pub trait Provider: Send + Sync {
     }
 
     /// Chat with tool definitions for native function calling support.
-    /// The default implementation falls back to chat_with_history and returns
-    /// an empty tool_calls vector (prompt-based tool use only).
+    /// The default implementation falls back to `chat` without tools, so
+    /// tool use stays prompt-based while usage reporting is preserved.
     async fn chat_with_tools(
         &self,
         messages: &[ChatMessage],
@@ -367,11 +769,17 @@ pub trait Provider: Send + Sync {
         model: &str,
         temperature: f64,
     ) -> anyhow::Result<ChatResponse> {
-        let text = self.chat_with_history(messages, model, temperature).await?;
-        Ok(ChatResponse {
-            text: Some(text),
-            tool_calls: Vec::new(),
-        })
+        self.chat(
+            ChatRequest {
+                messages,
+                tools: None,
+                reasoning: None,
+                response_format: None,
+            },
+            model,
+            temperature,
+        )
+        .await
     }
 
     /// Whether provider supports streaming responses.
@@ -396,7 +804,8 @@ pub trait Provider: Send + Sync {
     }
 
     /// Streaming chat with history.
-    /// Default implementation falls back to stream_chat_with_system with last user message.
+    /// Default implementation yields a single error: callers should check
+    /// `supports_streaming()` first and fall back to `chat`.
     fn stream_chat_with_history(
         &self,
         _messages: &[ChatMessage],
@@ -404,14 +813,43 @@ pub trait Provider: Send + Sync {
         _temperature: f64,
         _options: StreamOptions,
     ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
-        // For default implementation, we need to convert to owned strings
-        // This is a limitation of the default implementation
-        let provider_name = "unknown".to_string();
+        stream::once(async {
+            Err(StreamError::Provider(
+                "provider does not support streaming".to_string(),
+            ))
+        })
+        .boxed()
+    }
+
+    /// Streaming chat with native tool definitions.
+    /// Tool calls arrive as [`ToolCallDelta`] fragments on the chunks.
+    /// The default implementation streams without tools, mirroring
+    /// `chat_with_tools`.
+    fn stream_chat_with_tools(
+        &self,
+        messages: &[ChatMessage],
+        _tools: &[serde_json::Value],
+        model: &str,
+        temperature: f64,
+        options: StreamOptions,
+    ) -> stream::BoxStream<'static, StreamResult<StreamChunk>> {
+        self.stream_chat_with_history(messages, model, temperature, options)
+    }
+}
 
-        // Create a single empty chunk to indicate not supported
-        let chunk = StreamChunk::error(format!("{} does not support streaming", provider_name));
-        stream::once(async move { Ok(chunk) }).boxed()
+/// Copy `messages` with prompt-guided tool instructions appended to the
+/// system message, prepending one if the conversation has none.
+pub fn with_tool_instructions(messages: &[ChatMessage], instructions: &str) -> Vec<ChatMessage> {
+    let mut modified = messages.to_vec();
+    if let Some(system_message) = modified.iter_mut().find(|m| m.role == "system") {
+        if !system_message.content.is_empty() {
+            system_message.content.push_str("\n\n");
+        }
+        system_message.content.push_str(instructions);
+    } else {
+        modified.insert(0, ChatMessage::system(instructions));
     }
+    modified
 }
 
 /// Build tool instructions text for prompt-guided tool calling.
@@ -451,6 +889,42 @@ pub fn build_tool_instructions_text(tools: &[ToolSpec]) -> String {
 mod tests {
     use super::*;
 
+    #[test]
+    fn collect_tool_call_deltas_merges_fragments_by_index() {
+        let deltas = vec![
+            ToolCallDelta {
+                index: 1,
+                id: Some("call_b".into()),
+                name: Some("file_read".into()),
+                arguments: String::new(),
+            },
+            ToolCallDelta {
+                index: 0,
+                id: Some("call_a".into()),
+                name: Some("shell".into()),
+                arguments: r#"{"command":"#.into(),
+            },
+            ToolCallDelta {
+                index: 0,
+                arguments: r#""ls"}"#.into(),
+                ..ToolCallDelta::default()
+            },
+            ToolCallDelta {
+                index: 2,
+                arguments: "{}".into(),
+                ..ToolCallDelta::default()
+            },
+        ];
+
+        let calls = collect_tool_call_deltas(&deltas);
+        assert_eq!(calls.len(), 2, "nameless fragments are dropped");
+        assert_eq!(calls[0].id, "call_a");
+        assert_eq!(calls[0].name, "shell");
+        assert_eq!(calls[0].arguments, r#"{"command":"ls"}"#);
+        assert_eq!(calls[1].name, "file_read");
+        assert_eq!(calls[1].arguments, "{}");
+    }
+
     struct CapabilityMockProvider;
 
     #[async_trait]
@@ -458,6 +932,8 @@ mod tests {
         fn capabilities(&self) -> ProviderCapabilities {
             ProviderCapabilities {
                 native_tool_calling: true,
+                vision: false,
+                structured_output: false,
             }
         }
 
@@ -493,6 +969,8 @@ mod tests {
         let empty = ChatResponse {
             text: None,
             tool_calls: vec![],
+            usage: None,
+            reasoning: None,
         };
         assert!(!empty.has_tool_calls());
         assert_eq!(empty.text_or_empty(), "");
@@ -504,6 +982,8 @@ mod tests {
                 name: "shell".into(),
                 arguments: "{}".into(),
             }],
+            usage: None,
+            reasoning: None,
         };
         assert!(with_tools.has_tool_calls());
         assert_eq!(with_tools.text_or_empty(), "Let me check");
@@ -535,22 +1015,113 @@ mod tests {
         assert!(json.contains("\"type\":\"ToolResults\""));
     }
 
+    #[test]
+    fn reasoning_options_derive_missing_form() {
+        let budget = |tokens| ReasoningOptions {
+            budget_tokens: Some(tokens),
+            ..ReasoningOptions::default()
+        };
+        assert_eq!(budget(1_024).effort(), ReasoningEffort::Low);
+        assert_eq!(budget(8_000).effort(), ReasoningEffort::Medium);
+        assert_eq!(budget(32_000).effort(), ReasoningEffort::High);
+        assert_eq!(budget(8_000).budget_tokens(), 8_000);
+
+        let high = ReasoningOptions {
+            effort: Some(ReasoningEffort::High),
+            ..ReasoningOptions::default()
+        };
+        assert_eq!(high.budget_tokens(), 24_576);
+        assert_eq!(
+            ReasoningOptions::default().effort(),
+            ReasoningEffort::Medium
+        );
+
+        let parsed: ReasoningOptions = toml::from_str("effort = \"low\"").unwrap();
+        assert_eq!(parsed.effort, Some(ReasoningEffort::Low));
+    }
+
+    #[test]
+    fn reasoning_from_parts_skips_empty_output() {
+        assert!(Reasoning::from_parts("  ".into(), None).is_none());
+        let signed = Reasoning::from_parts(String::new(), Some(serde_json::json!(["sig"])));
+        assert!(signed.is_some());
+    }
+
+    #[test]
+    fn user_with_parts_mirrors_text_into_content() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("What is this?"),
+            ContentPart::image(MediaSource::from_bytes("image/png", b"png")),
+            ContentPart::text("Be brief."),
+        ]);
+        assert_eq!(msg.role, "user");
+        assert_eq!(msg.content, "What is this?\nBe brief.");
+        assert!(msg.has_media());
+        assert!(!ChatMessage::user("plain").has_media());
+    }
+
+    #[test]
+    fn text_fallback_replaces_media_with_placeholders() {
+        let msg = ChatMessage::user_with_parts(vec![
+            ContentPart::text("Look"),
+            ContentPart::image(MediaSource::Url {
+                url: "https://example.com/cat.png".into(),
+            }),
+            ContentPart::audio(MediaSource::from_bytes("audio/ogg", b"ogg")),
+        ]);
+        let fallback = msg.text_fallback();
+        assert!(fallback.parts.is_empty());
+        assert!(fallback.content.starts_with("Look\n"));
+        assert!(fallback
+            .content
+            .contains("[image attached: https://example.com/cat.png]"));
+        assert!(fallback.content.contains("[audio attached (audio/ogg)"));
+    }
+
+    #[test]
+    fn media_source_data_url_and_serde_roundtrip() {
+        let source = MediaSource::from_bytes("image/jpeg", b"hi");
+        assert_eq!(source.mime_type(), Some("image/jpeg"));
+        assert_eq!(source.to_url(), "data:image/jpeg;base64,aGk=");
+
+        let msg = ChatMessage::user_with_parts(vec![ContentPart::image(source)]);
+        let json = serde_json::to_string(&msg).unwrap();
+        assert!(json.contains("\"type\":\"image\""));
+        let back: ChatMessage = serde_json::from_str(&json).unwrap();
+        assert_eq!(back.parts, msg.parts);
+
+        // Plain messages keep their old wire shape.
+        let plain = serde_json::to_string(&ChatMessage::user("hi")).unwrap();
+        assert!(!plain.contains("parts"));
+        let legacy: ChatMessage =
+            serde_json::from_str(r#"{"role":"user","content":"hi"}"#).unwrap();
+        assert!(legacy.parts.is_empty());
+    }
+
     #[test]
     fn provider_capabilities_default() {
         let caps = ProviderCapabilities::default();
         assert!(!caps.native_tool_calling);
+        assert!(!caps.vision);
+        assert!(!caps.structured_output);
     }
 
     #[test]
     fn provider_capabilities_equality() {
         let caps1 = ProviderCapabilities {
             native_tool_calling: true,
+            vision: false,
+            structured_output: false,
         };
         let caps2 = ProviderCapabilities {
             native_tool_calling: true,
+            vision: false,
+            structured_output: false,
         };
         let caps3 = ProviderCapabilities {
             native_tool_calling: false,
+            vision: false,
+            structured_output: false,
         };
 
         assert_eq!(caps1, caps2);
@@ -704,6 +1275,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            reasoning: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -721,6 +1294,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: None,
+            reasoning: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -821,6 +1396,8 @@ mod tests {
                 ChatMessage::system("BASE_SYSTEM_PROMPT"),
             ],
             tools: Some(&tools),
+            reasoning: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -843,6 +1420,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::system("BASE"), ChatMessage::user("Hello")],
             tools: Some(&tools),
+            reasoning: None,
+            response_format: None,
         };
 
         let response = provider.chat(request, "model", 0.7).await.unwrap();
@@ -865,6 +1444,8 @@ mod tests {
         let request = ChatRequest {
             messages: &[ChatMessage::user("Hello")],
             tools: Some(&tools),
+            reasoning: None,
+            response_format: None,
         };
 
         let err = provider.chat(request, "model", 0.7).await.unwrap_err();
@@ -119,6 +119,22 @@ impl SecretStore {
         }
     }
 
+    /// Derive a purpose-bound 256-bit subkey from the master key:
+    /// `HMAC-SHA256(master, "zeroclaw:" ‖ purpose)`.
+    ///
+    /// Other subsystems (e.g. memory encryption at rest) use this so they never
+    /// share key material with config secrets. Works even when config secret
+    /// encryption is disabled; the key file is created on first use.
+    pub fn derive_key(&self, purpose: &str) -> Result<[u8; KEY_LEN]> {
+        use hmac::{Hmac, Mac};
+        let master = self.load_or_create_key()?;
+        let mut mac = <Hmac<sha2::Sha256> as Mac>::new_from_slice(&master)
+            .map_err(|e| anyhow::anyhow!("Invalid secret key: {e}"))?;
+        mac.update(b"zeroclaw:");
+        mac.update(purpose.as_bytes());
+        Ok(mac.finalize().into_bytes().into())
+    }
+
     /// Check if a value uses the legacy `enc:` format that should be migrated.
     pub fn needs_migration(value: &str) -> bool {
         value.starts_with("enc:")
@@ -301,6 +317,20 @@ mod tests {
         assert_eq!(decrypted, secret, "Roundtrip must preserve original");
     }
 
+    #[test]
+    fn derive_key_is_stable_and_purpose_bound() {
+        let tmp = TempDir::new().unwrap();
+        let store = SecretStore::new(tmp.path(), false);
+        let a = store.derive_key("memory-content").unwrap();
+        assert_eq!(a, store.derive_key("memory-content").unwrap());
+        assert_ne!(a, store.derive_key("memory-index").unwrap());
+        assert!(tmp.path().join(".secret_key").exists());
+
+        let other = TempDir::new().unwrap();
+        let foreign = SecretStore::new(other.path(), true);
+        assert_ne!(a, foreign.derive_key("memory-content").unwrap());
+    }
+
     #[test]
     fn encrypt_empty_returns_empty() {
         let tmp = TempDir::new().unwrap();
@@ -118,6 +118,9 @@ fn load_skills_from_directory(skills_dir: &Path) -> Vec<Skill> {
         }
     }
 
+    // read_dir order is unspecified; a stable order keeps the system prompt
+    // byte-identical between runs so provider prompt caches keep hitting
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
     skills
 }
 
@@ -155,6 +158,7 @@ fn load_open_skills(repo_dir: &Path) -> Vec<Skill> {
         }
     }
 
+    skills.sort_by(|a, b| a.name.cmp(&b.name));
     skills
 }
 
@@ -374,7 +378,9 @@ pub fn skills_to_prompt(skills: &[Skill]) -> String {
                 let _ = writeln!(
                     prompt,
                     "- **{}**: {} ({})",
-                    tool.name, tool.description, tool.kind
+                    skill_tool_name(&skill.name, &tool.name),
+                    tool.description,
+                    tool.kind
                 );
             }
         }
@@ -390,6 +396,39 @@ pub fn skills_to_prompt(skills: &[Skill]) -> String {
     prompt
 }
 
+/// Maximum tool-name length accepted by the OpenAI and Anthropic APIs.
+const MAX_TOOL_NAME_LEN: usize = 64;
+
+/// Registry name for a skill-defined tool: `skill__<skill>__<tool>`.
+///
+/// Provider APIs only accept `^[a-zA-Z0-9_-]{1,64}$`, so characters outside
+/// that set become `_`. Names longer than 64 characters are truncated and
+/// suffixed with a short hash of the full name to keep them unique.
+pub fn skill_tool_name(skill: &str, tool: &str) -> String {
+    fn sanitize(part: &str) -> String {
+        part.trim()
+            .chars()
+            .map(|c| {
+                if c.is_ascii_alphanumeric() || c == '_' || c == '-' {
+                    c
+                } else {
+                    '_'
+                }
+            })
+            .collect()
+    }
+    let name = format!("skill__{}__{}", sanitize(skill), sanitize(tool));
+    if name.len() <= MAX_TOOL_NAME_LEN {
+        return name;
+    }
+
+    use sha2::{Digest, Sha256};
+    let digest = hex::encode(Sha256::digest(name.as_bytes()));
+    let suffix = &digest[..8];
+    // Sanitized names are ASCII, so byte slicing stays on char boundaries.
+    format!("{}_{suffix}", &name[..MAX_TOOL_NAME_LEN - suffix.len() - 1])
+}
+
 /// Get the skills directory path
 pub fn skills_dir(workspace_dir: &Path) -> PathBuf {
     workspace_dir.join("skills")
@@ -766,6 +805,8 @@ command = "echo hello"
 
         let skills = load_skills(dir.path());
         assert_eq!(skills.len(), 3);
+        let names: Vec<&str> = skills.iter().map(|s| s.name.as_str()).collect();
+        assert_eq!(names, vec!["alpha", "beta", "gamma"]);
     }
 
     #[test]
@@ -891,11 +932,34 @@ description = "Bare minimum"
         }];
         let prompt = skills_to_prompt(&skills);
         assert!(prompt.contains("weather"));
-        assert!(prompt.contains("get_weather"));
+        assert!(prompt.contains("skill__weather__get_weather"));
         assert!(prompt.contains("Fetch forecast"));
         assert!(prompt.contains("shell"));
     }
 
+    #[test]
+    fn skill_tool_name_is_namespaced_and_sanitized() {
+        assert_eq!(
+            skill_tool_name("weather", "get_weather"),
+            "skill__weather__get_weather"
+        );
+        assert_eq!(skill_tool_name("my skill", "a.b"), "skill__my_skill__a_b");
+    }
+
+    #[test]
+    fn skill_tool_name_fits_provider_limits() {
+        let long_skill = "s".repeat(60);
+        let a = skill_tool_name(&long_skill, "first_tool");
+        let b = skill_tool_name(&long_skill, "second_tool");
+        assert_eq!(a.len(), 64);
+        assert_ne!(a, b);
+        for name in [&a, &b] {
+            assert!(name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-'));
+        }
+    }
+
     #[test]
     fn skills_dir_path() {
         let base = std::path::Path::new("/home/user/.zeroclaw");
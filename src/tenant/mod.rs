@@ -2,7 +2,13 @@
 //!
 //! Manages per-user isolated SQLite databases for storing profiles,
 //! goals, conversation history, and feature settings.
+//!
+//! With memory encryption on, profile values, goal texts and conversation
+//! content are sealed at rest (see `memory::encryption`).
+
+pub mod router;
 
+use crate::memory::encryption::{self, MemoryCipher};
 use anyhow::{Context, Result};
 use chrono::Utc;
 use parking_lot::Mutex;
@@ -68,11 +74,16 @@ pub struct TenantDb {
     conn: Mutex<Connection>,
     user_id: String,
     db_path: PathBuf,
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
 impl TenantDb {
     /// Open or create tenant database
-    pub fn open(base_path: &Path, user_id: &str) -> Result<Self> {
+    pub fn open(
+        base_path: &Path,
+        user_id: &str,
+        cipher: Option<Arc<MemoryCipher>>,
+    ) -> Result<Self> {
         let tenant_dir = base_path.join("tenants").join(user_id);
         std::fs::create_dir_all(&tenant_dir)?;
 
@@ -81,11 +92,15 @@ impl TenantDb {
             .with_context(|| format!("Failed to open tenant DB at {:?}", db_path))?;
 
         Self::init_schema(&conn)?;
+        if cipher.is_some() {
+            encryption::enable_secure_delete(&conn)?;
+        }
 
         Ok(Self {
             conn: Mutex::new(conn),
             user_id: user_id.to_string(),
             db_path,
+            cipher,
         })
     }
 
@@ -155,6 +170,65 @@ impl TenantDb {
         &self.db_path
     }
 
+    /// Encrypt a sensitive field for storage (no-op without a cipher)
+    fn seal(&self, value: &str) -> Result<String> {
+        encryption::seal_text(self.cipher.as_deref(), value)
+    }
+
+    /// Decrypt a sensitive column inside a row mapper
+    fn open_column(&self, index: usize, value: String) -> rusqlite::Result<String> {
+        encryption::open_text(self.cipher.as_deref(), value)
+            .map_err(|e| encryption::column_error(index, e))
+    }
+
+    /// Encrypt (or decrypt) every sensitive field in place, then vacuum so no
+    /// old values survive in free pages or the WAL. Returns rows rewritten.
+    pub fn migrate_encryption(&self, cipher: &MemoryCipher, encrypt: bool) -> Result<usize> {
+        let reseal = |value: &str| encryption::reseal_text(cipher, value, encrypt);
+
+        let conn = self.conn.lock();
+        encryption::enable_secure_delete(&conn)?;
+        let profile: Vec<(String, String)> = conn
+            .prepare("SELECT key, value FROM profile")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+        let goals: Vec<(String, String, String, Option<String>)> = conn
+            .prepare("SELECT id, original_text, smart_text, milestones FROM goals")?
+            .query_map([], |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })?
+            .collect::<rusqlite::Result<_>>()?;
+        let messages: Vec<(String, String)> = conn
+            .prepare("SELECT id, content FROM conversations")?
+            .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?
+            .collect::<rusqlite::Result<_>>()?;
+
+        let tx = conn.unchecked_transaction()?;
+        for (key, value) in &profile {
+            tx.execute(
+                "UPDATE profile SET value = ?1 WHERE key = ?2",
+                params![reseal(value)?, key],
+            )?;
+        }
+        for (id, original_text, smart_text, milestones) in &goals {
+            let milestones = milestones.as_deref().map(&reseal).transpose()?;
+            tx.execute(
+                "UPDATE goals SET original_text = ?1, smart_text = ?2, milestones = ?3 WHERE id = ?4",
+                params![reseal(original_text)?, reseal(smart_text)?, milestones, id],
+            )?;
+        }
+        for (id, content) in &messages {
+            tx.execute(
+                "UPDATE conversations SET content = ?1 WHERE id = ?2",
+                params![reseal(content)?, id],
+            )?;
+        }
+        tx.commit()?;
+        encryption::scrub_database(&conn, &[])?;
+
+        Ok(profile.len() + goals.len() + messages.len())
+    }
+
     // ===== Profile Operations =====
 
     /// Set profile value
@@ -164,7 +238,7 @@ impl TenantDb {
 
         conn.execute(
             "INSERT OR REPLACE INTO profile (key, value, updated_at) VALUES (?1, ?2, ?3)",
-            params![key, value, &now],
+            params![key, self.seal(value)?, &now],
         )
         .context("Failed to set profile value")?;
 
@@ -178,7 +252,7 @@ impl TenantDb {
         let result = conn.query_row(
             "SELECT value FROM profile WHERE key = ?1",
             params![key],
-            |row| row.get(0),
+            |row| self.open_column(0, row.get(0)?),
         );
 
         match result {
@@ -194,7 +268,7 @@ impl TenantDb {
 
         let mut stmt = conn.prepare("SELECT key, value FROM profile")?;
         let rows = stmt.query_map([], |row| {
-            Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            Ok((row.get::<_, String>(0)?, self.open_column(1, row.get(1)?)?))
         })?;
 
         let mut profile = UserProfile::default();
@@ -271,7 +345,7 @@ impl TenantDb {
         conn.execute(
             r#"INSERT INTO goals (id, original_text, smart_text, category, status, progress, milestones, created_at, updated_at)
                VALUES (?1, ?2, ?3, ?4, 'active', 0, '[]', ?5, ?6)"#,
-            params![&id, original_text, smart_text, category, &now, &now],
+            params![&id, self.seal(original_text)?, self.seal(smart_text)?, category, &now, &now],
         )
         .context("Failed to create goal")?;
 
@@ -304,10 +378,11 @@ impl TenantDb {
 
         let mut stmt = conn.prepare(query)?;
 
+        let map_goal = |row: &rusqlite::Row| self.row_to_goal(row);
         let rows = if let Some(s) = status {
-            stmt.query_map(params![s], Self::row_to_goal)?
+            stmt.query_map(params![s], map_goal)?
         } else {
-            stmt.query_map([], Self::row_to_goal)?
+            stmt.query_map([], map_goal)?
         };
 
         let mut goals = Vec::new();
@@ -318,14 +393,14 @@ impl TenantDb {
         Ok(goals)
     }
 
-    fn row_to_goal(row: &rusqlite::Row) -> rusqlite::Result<Goal> {
-        let milestones_str: String = row.get(6)?;
+    fn row_to_goal(&self, row: &rusqlite::Row) -> rusqlite::Result<Goal> {
+        let milestones_str = self.open_column(6, row.get(6)?)?;
         let milestones: Vec<String> = serde_json::from_str(&milestones_str).unwrap_or_default();
 
         Ok(Goal {
             id: row.get(0)?,
-            original_text: row.get(1)?,
-            smart_text: row.get(2)?,
+            original_text: self.open_column(1, row.get(1)?)?,
+            smart_text: self.open_column(2, row.get(2)?)?,
             category: row.get(3)?,
             status: row.get(4)?,
             progress: row.get(5)?,
@@ -343,7 +418,7 @@ impl TenantDb {
         let result = conn.query_row(
             "SELECT id, original_text, smart_text, category, status, progress, milestones, notion_page_id, created_at, updated_at FROM goals WHERE id = ?1",
             params![goal_id],
-            Self::row_to_goal,
+            |row| self.row_to_goal(row),
         );
 
         match result {
@@ -377,7 +452,7 @@ impl TenantDb {
 
         conn.execute(
             "UPDATE goals SET milestones = ?1, updated_at = ?2 WHERE id = ?3",
-            params![&milestones_json, &now, goal_id],
+            params![self.seal(&milestones_json)?, &now, goal_id],
         )
         .context("Failed to update goal milestones")?;
 
@@ -415,7 +490,7 @@ impl TenantDb {
 
         conn.execute(
             "INSERT INTO conversations (id, role, content, tokens_used, provider, created_at) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
-            params![&id, role, content, tokens_used, provider, &now],
+            params![&id, role, self.seal(content)?, tokens_used, provider, &now],
         )
         .context("Failed to add message")?;
 
@@ -441,7 +516,7 @@ impl TenantDb {
             Ok(ConversationMessage {
                 id: row.get(0)?,
                 role: row.get(1)?,
-                content: row.get(2)?,
+                content: self.open_column(2, row.get(2)?)?,
                 tokens_used: row.get(3)?,
                 provider: row.get(4)?,
                 created_at: row.get(5)?,
@@ -544,6 +619,7 @@ impl TenantDb {
 pub struct TenantManager {
     base_path: PathBuf,
     tenants: Mutex<HashMap<String, Arc<TenantDb>>>,
+    cipher: Option<Arc<MemoryCipher>>,
 }
 
 impl TenantManager {
@@ -552,9 +628,16 @@ impl TenantManager {
         Self {
             base_path: workspace_dir.to_path_buf(),
             tenants: Mutex::new(HashMap::new()),
+            cipher: None,
         }
     }
 
+    /// Encrypt sensitive tenant data at rest
+    pub fn with_cipher(mut self, cipher: Option<Arc<MemoryCipher>>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
     /// Get or create tenant database
     pub fn get_tenant(&self, user_id: &str) -> Result<Arc<TenantDb>> {
         let mut tenants = self.tenants.lock();
@@ -563,7 +646,7 @@ impl TenantManager {
             return Ok(Arc::clone(tenant));
         }
 
-        let tenant = Arc::new(TenantDb::open(&self.base_path, user_id)?);
+        let tenant = Arc::new(TenantDb::open(&self.base_path, user_id, self.cipher.clone())?);
         tenants.insert(user_id.to_string(), Arc::clone(&tenant));
 
         Ok(tenant)
@@ -622,6 +705,29 @@ impl TenantManager {
     pub fn base_path(&self) -> &Path {
         &self.base_path
     }
+
+    /// Directory holding a tenant's database and memory store
+    pub fn tenant_dir(&self, user_id: &str) -> PathBuf {
+        self.base_path.join("tenants").join(user_id)
+    }
+
+    /// Encrypt (or decrypt) every tenant database and tenant memory store in place.
+    /// Returns the number of tenants.
+    pub fn migrate_encryption(&self, cipher: &MemoryCipher, encrypt: bool) -> Result<usize> {
+        let tenants = self.list_tenants()?;
+        for user_id in &tenants {
+            TenantDb::open(&self.base_path, user_id, None)?
+                .migrate_encryption(cipher, encrypt)
+                .with_context(|| format!("Failed to migrate tenant {user_id}"))?;
+            crate::memory::SqliteMemory::migrate_encryption(
+                &self.tenant_dir(user_id),
+                cipher,
+                encrypt,
+            )
+            .with_context(|| format!("Failed to migrate memory of tenant {user_id}"))?;
+        }
+        Ok(tenants.len())
+    }
 }
 
 #[cfg(test)]
@@ -777,6 +883,52 @@ mod tests {
         assert!(tenants.contains(&"user3".to_string()));
     }
 
+    #[test]
+    fn test_encrypted_tenant_data_roundtrip() {
+        let tmp = TempDir::new().unwrap();
+        let cipher = Arc::new(MemoryCipher::for_config_dir(tmp.path()).unwrap());
+        let manager = TenantManager::new(tmp.path()).with_cipher(Some(Arc::clone(&cipher)));
+        let tenant = manager.get_tenant("user123").unwrap();
+
+        tenant.set_profile_value("birthdate", "1990-01-15").unwrap();
+        let goal = tenant
+            .create_goal("run more", "Run 10km by June", None)
+            .unwrap();
+        tenant
+            .update_goal_milestones(&goal.id, &["5km".to_string()])
+            .unwrap();
+        tenant
+            .add_message("user", "my private note", None, None)
+            .unwrap();
+
+        let raw: String = tenant
+            .conn
+            .lock()
+            .query_row("SELECT content FROM conversations", [], |row| row.get(0))
+            .unwrap();
+        assert!(MemoryCipher::is_encrypted(&raw));
+
+        assert_eq!(
+            tenant.get_profile().unwrap().birthdate.as_deref(),
+            Some("1990-01-15")
+        );
+        let loaded = tenant.get_goal(&goal.id).unwrap().unwrap();
+        assert_eq!(loaded.smart_text, "Run 10km by June");
+        assert_eq!(loaded.milestones, vec!["5km".to_string()]);
+        assert_eq!(
+            tenant.get_conversation_history(10).unwrap()[0].content,
+            "my private note"
+        );
+
+        // Decrypting in place leaves plaintext readable without a cipher
+        assert_eq!(manager.migrate_encryption(&cipher, false).unwrap(), 1);
+        let plain = TenantDb::open(tmp.path(), "user123", None).unwrap();
+        assert_eq!(
+            plain.get_conversation_history(10).unwrap()[0].content,
+            "my private note"
+        );
+    }
+
     #[test]
     fn test_delete_tenant() {
         let (_tmp, manager) = test_tenant_manager();
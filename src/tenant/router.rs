@@ -0,0 +1,519 @@
+//! Sender → tenant routing for the channel runtime.
+//!
+//! Every inbound channel message is mapped to a tenant: a linked web account
+//! when the auth database knows the numeric Telegram user id, otherwise a stable
+//! hash of channel and sender. Each tenant gets its own memory store under
+//! `workspace/tenants/{user_id}`, its profile as prompt context, and a tool
+//! set gated by its `FeatureSetting`s.
+
+use super::{FeatureSetting, TenantDb, TenantManager};
+use crate::auth::AuthManager;
+use crate::config::MemoryConfig;
+use crate::memory::{self, Memory, MemoryCipher};
+use crate::tools::{
+    EsotericTool, GoalsTool, MemoryForgetTool, MemoryRecallTool, MemoryStoreTool, Tool, ToolResult,
+};
+use anyhow::Result;
+use async_trait::async_trait;
+use parking_lot::Mutex;
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Tools that only run when the tenant's matching feature is enabled.
+/// A tenant without a setting for the feature keeps the tool.
+const FEATURE_TOOLS: &[(&str, &str)] = &[
+    ("goals", "goals"),
+    ("esoteric", "esoteric"),
+    ("rss", "rss"),
+    ("social_media", "social_media"),
+];
+
+/// Tenant memory stores kept open at once; the least recently used store
+/// is closed past this
+const MAX_OPEN_TENANT_MEMORIES: usize = 64;
+
+/// A resolved tenant: its database and its memory store
+#[derive(Clone)]
+pub struct Tenant {
+    pub user_id: String,
+    pub db: Arc<TenantDb>,
+    pub memory: Arc<dyn Memory>,
+}
+
+/// Resolves channel senders to tenants and caches their memory stores
+pub struct TenantRouter {
+    manager: Arc<TenantManager>,
+    auth: Option<Arc<AuthManager>>,
+    memory_config: MemoryConfig,
+    api_key: Option<String>,
+    cipher: Option<Arc<MemoryCipher>>,
+    memories: Mutex<MemoryCache>,
+}
+
+/// Open tenant memory stores with a least-recently-used cap
+struct MemoryCache {
+    entries: HashMap<String, (Arc<dyn Memory>, u64)>,
+    tick: u64,
+    capacity: usize,
+}
+
+impl MemoryCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            tick: 0,
+            capacity: capacity.max(1),
+        }
+    }
+
+    fn get(&mut self, user_id: &str) -> Option<Arc<dyn Memory>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(user_id).map(|(memory, used)| {
+            *used = tick;
+            Arc::clone(memory)
+        })
+    }
+
+    fn insert(&mut self, user_id: String, memory: Arc<dyn Memory>) {
+        while self.entries.len() >= self.capacity {
+            let Some(oldest) = self
+                .entries
+                .iter()
+                .min_by_key(|(_, (_, used))| *used)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            // In-flight turns keep their Arc; the store closes once they finish
+            self.entries.remove(&oldest);
+        }
+        self.tick += 1;
+        self.entries.insert(user_id, (memory, self.tick));
+    }
+}
+
+impl TenantRouter {
+    pub fn new(
+        workspace_dir: &Path,
+        memory_config: &MemoryConfig,
+        api_key: Option<&str>,
+        cipher: Option<Arc<MemoryCipher>>,
+    ) -> Self {
+        Self {
+            manager: Arc::new(TenantManager::new(workspace_dir).with_cipher(cipher.clone())),
+            auth: None,
+            memory_config: memory_config.clone(),
+            api_key: api_key.map(str::to_string),
+            cipher,
+            memories: Mutex::new(MemoryCache::new(MAX_OPEN_TENANT_MEMORIES)),
+        }
+    }
+
+    /// Cap on tenant memory stores kept open at once
+    pub fn with_max_open_memories(mut self, max: usize) -> Self {
+        self.memories = Mutex::new(MemoryCache::new(max));
+        self
+    }
+
+    /// Resolve linked accounts through the auth database
+    pub fn with_auth_manager(mut self, auth: Option<Arc<AuthManager>>) -> Self {
+        self.auth = auth;
+        self
+    }
+
+    /// Tenant ID for a sender: the linked account's user ID for known
+    /// Telegram ids, otherwise `{channel}-{hash}`.
+    ///
+    /// `sender` must be the channel's stable account id (see
+    /// [`ChannelMessage::sender_id`](crate::channels::traits::ChannelMessage::sender_id)),
+    /// never a re-claimable handle such as a Telegram username.
+    pub fn resolve_user_id(&self, channel: &str, sender: &str) -> String {
+        if channel == "telegram" {
+            if let Some(auth) = self.auth.as_ref() {
+                if let Ok(user) = auth.get_user_by_telegram(sender) {
+                    return user.id;
+                }
+            }
+        }
+        hashed_user_id(channel, sender)
+    }
+
+    /// Resolve a sender and open (or reuse) its database and memory store
+    pub fn tenant(&self, channel: &str, sender: &str) -> Result<Tenant> {
+        let user_id = self.resolve_user_id(channel, sender);
+        let db = self.manager.get_tenant(&user_id)?;
+
+        let mut memories = self.memories.lock();
+        let memory = match memories.get(&user_id) {
+            Some(memory) => memory,
+            None => {
+                let memory: Arc<dyn Memory> = Arc::from(memory::create_memory(
+                    &self.memory_config,
+                    &self.manager.tenant_dir(&user_id),
+                    self.api_key.as_deref(),
+                    self.cipher.clone(),
+                )?);
+                memories.insert(user_id.clone(), Arc::clone(&memory));
+                memory
+            }
+        };
+
+        Ok(Tenant {
+            user_id,
+            db,
+            memory,
+        })
+    }
+}
+
+fn hashed_user_id(channel: &str, sender: &str) -> String {
+    use sha2::{Digest, Sha256};
+    let digest = Sha256::digest(format!("{channel}:{sender}").as_bytes());
+    let prefix: String = channel
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    format!("{prefix}-{}", hex::encode(&digest[..8]))
+}
+
+/// The tenant's profile as a prompt block; empty when nothing is known yet
+pub fn profile_context(db: &TenantDb) -> String {
+    let Ok(profile) = db.get_profile() else {
+        return String::new();
+    };
+
+    let mut lines = Vec::new();
+    let fields = [
+        ("Name", profile.name.as_deref()),
+        ("Birthdate", profile.birthdate.as_deref()),
+        ("Birth time", profile.birth_time.as_deref()),
+        ("Birth place", profile.birth_place.as_deref()),
+        ("MBTI", profile.mbti_type.as_deref()),
+    ];
+    for (label, value) in fields {
+        if let Some(value) = value.filter(|v| !v.trim().is_empty()) {
+            lines.push(format!("- {label}: {value}"));
+        }
+    }
+    if !profile.selected_features.is_empty() {
+        lines.push(format!(
+            "- Interested in: {}",
+            profile.selected_features.join(", ")
+        ));
+    }
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let mut context = String::from("[User profile]\n");
+    for line in lines {
+        let _ = writeln!(context, "{line}");
+    }
+    context
+}
+
+/// Whether `tool` may run for a tenant with these feature settings
+pub fn tool_enabled(features: &[FeatureSetting], tool: &str) -> bool {
+    let Some((feature, _)) = FEATURE_TOOLS.iter().find(|(_, name)| *name == tool) else {
+        return true;
+    };
+    features
+        .iter()
+        .find(|setting| setting.feature == *feature)
+        .is_none_or(|setting| setting.enabled)
+}
+
+impl TenantRouter {
+    /// The tool set for one tenant: shared tools its features allow, with
+    /// memory, goal and esoteric tools bound to the tenant's own data. Tools
+    /// that take a `user_id` argument have it pinned to the tenant. Also
+    /// returns the names of tools withheld by feature settings.
+    pub fn tools_for(
+        &self,
+        tenant: &Tenant,
+        shared: &Arc<Vec<Box<dyn Tool>>>,
+    ) -> (Vec<Box<dyn Tool>>, Vec<String>) {
+        let features = tenant.db.get_all_features().unwrap_or_else(|e| {
+            tracing::warn!("Failed to load features for tenant {}: {e}", tenant.user_id);
+            Vec::new()
+        });
+
+        let mut tools: Vec<Box<dyn Tool>> = Vec::with_capacity(shared.len());
+        let mut disabled = Vec::new();
+        for (index, tool) in shared.iter().enumerate() {
+            let name = tool.name();
+            if !tool_enabled(&features, name) {
+                disabled.push(name.to_string());
+                continue;
+            }
+            let tool: Box<dyn Tool> = match name {
+                "memory_store" => Box::new(MemoryStoreTool::new(Arc::clone(&tenant.memory))),
+                "memory_recall" => Box::new(MemoryRecallTool::new(Arc::clone(&tenant.memory))),
+                "memory_forget" => Box::new(MemoryForgetTool::new(Arc::clone(&tenant.memory))),
+                "goals" => Box::new(GoalsTool::new(Some(Arc::clone(&self.manager)))),
+                "esoteric" => Box::new(EsotericTool::new(Some(Arc::clone(&self.manager)))),
+                _ => Box::new(SharedTool {
+                    registry: Arc::clone(shared),
+                    index,
+                }),
+            };
+            if takes_user_id(tool.as_ref()) {
+                tools.push(Box::new(UserScopedTool {
+                    inner: tool,
+                    user_id: tenant.user_id.clone(),
+                }));
+            } else {
+                tools.push(tool);
+            }
+        }
+        (tools, disabled)
+    }
+}
+
+fn takes_user_id(tool: &dyn Tool) -> bool {
+    tool.parameters_schema()
+        .pointer("/properties/user_id")
+        .is_some()
+}
+
+/// A tool borrowed from the shared registry by position
+struct SharedTool {
+    registry: Arc<Vec<Box<dyn Tool>>>,
+    index: usize,
+}
+
+impl SharedTool {
+    fn inner(&self) -> &dyn Tool {
+        self.registry[self.index].as_ref()
+    }
+}
+
+#[async_trait]
+impl Tool for SharedTool {
+    fn name(&self) -> &str {
+        self.inner().name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner().description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        self.inner().parameters_schema()
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> Result<ToolResult> {
+        self.inner().execute(args).await
+    }
+
+    fn parallel_safe(&self) -> bool {
+        self.inner().parallel_safe()
+    }
+}
+
+/// Hides the `user_id` parameter from the model and always passes the
+/// tenant's own ID, so one sender cannot reach another tenant's data.
+struct UserScopedTool {
+    inner: Box<dyn Tool>,
+    user_id: String,
+}
+
+#[async_trait]
+impl Tool for UserScopedTool {
+    fn name(&self) -> &str {
+        self.inner.name()
+    }
+
+    fn description(&self) -> &str {
+        self.inner.description()
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut schema = self.inner.parameters_schema();
+        if let Some(properties) = schema
+            .get_mut("properties")
+            .and_then(serde_json::Value::as_object_mut)
+        {
+            properties.remove("user_id");
+        }
+        if let Some(required) = schema
+            .get_mut("required")
+            .and_then(serde_json::Value::as_array_mut)
+        {
+            required.retain(|field| field != "user_id");
+        }
+        schema
+    }
+
+    async fn execute(&self, mut args: serde_json::Value) -> Result<ToolResult> {
+        if let Some(args) = args.as_object_mut() {
+            args.insert("user_id".into(), self.user_id.clone().into());
+        }
+        self.inner.execute(args).await
+    }
+
+    fn parallel_safe(&self) -> bool {
+        self.inner.parallel_safe()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::memory::MemoryCategory;
+    use tempfile::TempDir;
+
+    fn router(tmp: &TempDir) -> TenantRouter {
+        let config = MemoryConfig {
+            backend: "sqlite".into(),
+            embedding_provider: "none".into(),
+            ..MemoryConfig::default()
+        };
+        TenantRouter::new(tmp.path(), &config, None, None)
+    }
+
+    #[test]
+    fn unlinked_senders_get_stable_distinct_ids() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp);
+        let alice = router.resolve_user_id("telegram", "alice");
+        assert_eq!(alice, router.resolve_user_id("telegram", "alice"));
+        assert_ne!(alice, router.resolve_user_id("telegram", "bob"));
+        assert_ne!(alice, router.resolve_user_id("discord", "alice"));
+        assert!(alice.starts_with("telegram-"));
+    }
+
+    #[test]
+    fn linked_telegram_accounts_resolve_to_the_user() {
+        let tmp = TempDir::new().unwrap();
+        let auth = Arc::new(AuthManager::new(tmp.path(), "secret".into()).unwrap());
+        let (user_id, _) = auth.register("a@example.com", "password123").unwrap();
+        auth.link_telegram(&user_id, "4242", Some("alice")).unwrap();
+
+        let router = router(&tmp).with_auth_manager(Some(auth));
+        assert_eq!(router.resolve_user_id("telegram", "4242"), user_id);
+        assert_ne!(router.resolve_user_id("telegram", "4343"), user_id);
+        // Usernames can be changed and re-claimed, so they never resolve
+        assert_ne!(router.resolve_user_id("telegram", "alice"), user_id);
+        assert_ne!(router.resolve_user_id("telegram", "@alice"), user_id);
+    }
+
+    #[tokio::test]
+    async fn tenant_memories_are_isolated() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp);
+        let alice = router.tenant("telegram", "alice").unwrap();
+        let bob = router.tenant("telegram", "bob").unwrap();
+
+        alice
+            .memory
+            .store("secret", "alice likes jazz", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        assert!(bob.memory.recall("jazz", 5, None).await.unwrap().is_empty());
+        assert_eq!(alice.memory.recall("jazz", 5, None).await.unwrap().len(), 1);
+
+        // The store is cached per tenant
+        let again = router.tenant("telegram", "alice").unwrap();
+        assert!(Arc::ptr_eq(&again.memory, &alice.memory));
+    }
+
+    #[tokio::test]
+    async fn least_recently_used_memories_are_closed() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp).with_max_open_memories(2);
+        let alice = router.tenant("telegram", "alice").unwrap();
+        alice
+            .memory
+            .store("secret", "alice likes jazz", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        let bob = router.tenant("telegram", "bob").unwrap();
+        // Touch alice so bob is the least recently used
+        router.tenant("telegram", "alice").unwrap();
+        router.tenant("telegram", "carol").unwrap();
+
+        assert_eq!(router.memories.lock().entries.len(), 2);
+        let again = router.tenant("telegram", "alice").unwrap();
+        assert!(Arc::ptr_eq(&again.memory, &alice.memory));
+        let reopened = router.tenant("telegram", "bob").unwrap();
+        assert!(!Arc::ptr_eq(&reopened.memory, &bob.memory));
+
+        // Evicting alice reopens her store from disk with her data intact
+        drop(alice);
+        drop(again);
+        router.tenant("telegram", "dave").unwrap();
+        router.tenant("telegram", "erin").unwrap();
+        let alice = router.tenant("telegram", "alice").unwrap();
+        assert_eq!(alice.memory.recall("jazz", 5, None).await.unwrap().len(), 1);
+    }
+
+    #[test]
+    fn profile_context_lists_known_fields() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp);
+        let tenant = router.tenant("telegram", "alice").unwrap();
+        assert!(profile_context(&tenant.db).is_empty());
+
+        tenant.db.set_profile_value("name", "Alice").unwrap();
+        tenant.db.set_profile_value("mbti_type", "INTJ").unwrap();
+        let context = profile_context(&tenant.db);
+        assert!(context.starts_with("[User profile]"));
+        assert!(context.contains("- Name: Alice"));
+        assert!(context.contains("- MBTI: INTJ"));
+    }
+
+    #[test]
+    fn feature_settings_gate_tools() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp);
+        let tenant = router.tenant("telegram", "alice").unwrap();
+        tenant.db.set_feature("esoteric", false, None).unwrap();
+        tenant.db.set_feature("goals", true, None).unwrap();
+
+        let shared: Arc<Vec<Box<dyn Tool>>> = Arc::new(vec![
+            Box::new(crate::tools::GoalsTool::new(None)),
+            Box::new(crate::tools::EsotericTool::new(None)),
+            Box::new(crate::tools::RssTool::new()),
+            Box::new(MemoryRecallTool::new(Arc::new(
+                crate::memory::NoneMemory::new(),
+            ))),
+        ]);
+        let (tools, disabled) = router.tools_for(&tenant, &shared);
+        let names: Vec<&str> = tools.iter().map(|t| t.name()).collect();
+        assert_eq!(names, vec!["goals", "rss", "memory_recall"]);
+        assert_eq!(disabled, vec!["esoteric".to_string()]);
+    }
+
+    #[tokio::test]
+    async fn goal_tools_are_pinned_to_the_tenant() {
+        let tmp = TempDir::new().unwrap();
+        let router = router(&tmp);
+        let alice = router.tenant("telegram", "alice").unwrap();
+        let bob = router.tenant("telegram", "bob").unwrap();
+
+        let shared: Arc<Vec<Box<dyn Tool>>> =
+            Arc::new(vec![Box::new(crate::tools::GoalsTool::new(None))]);
+        let (tools, _) = router.tools_for(&alice, &shared);
+        let goals = &tools[0];
+        assert!(goals
+            .parameters_schema()
+            .pointer("/properties/user_id")
+            .is_none());
+
+        let result = goals
+            .execute(serde_json::json!({
+                "action": "create",
+                "goal_text": "I want to run a marathon",
+                "user_id": bob.user_id,
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+        assert_eq!(alice.db.get_goals(None).unwrap().len(), 1);
+        assert!(bob.db.get_goals(None).unwrap().is_empty());
+    }
+}
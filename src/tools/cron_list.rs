@@ -33,6 +33,10 @@ impl Tool for CronListTool {
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, _args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if !self.config.cron.enabled {
             return Ok(ToolResult {
@@ -50,6 +50,10 @@ impl Tool for CronRunsTool {
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         if !self.config.cron.enabled {
             return Ok(ToolResult {
@@ -40,6 +40,10 @@ impl Tool for FileReadTool {
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path = args
             .get("path")
@@ -11,9 +11,10 @@ const MAX_IMAGE_BYTES: u64 = 5_242_880;
 
 /// Tool to read image metadata and optionally return base64-encoded data.
 ///
-/// Since providers are currently text-only, this tool extracts what it can
-/// (file size, format, dimensions from header bytes) and provides base64
-/// data for future multimodal provider support.
+/// Tool results are plain text, so this tool extracts what it can (file size,
+/// format, dimensions from header bytes) and can return base64 data. Images
+/// that users send over channels reach vision-capable providers directly as
+/// [`ContentPart::Image`](crate::providers::ContentPart) parts.
 pub struct ImageInfoTool {
     security: Arc<SecurityPolicy>,
 }
@@ -145,6 +146,10 @@ impl Tool for ImageInfoTool {
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let path_str = args
             .get("path")
@@ -1,6 +1,7 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::Memory;
+use crate::memory::{Memory, MemoryCategory, RecallFilter};
 use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde_json::json;
 use std::fmt::Write;
 use std::sync::Arc;
@@ -14,6 +15,67 @@ impl MemoryRecallTool {
     pub fn new(memory: Arc<dyn Memory>) -> Self {
         Self { memory }
     }
+
+    fn string_list(args: &serde_json::Value, field: &str) -> Vec<String> {
+        args.get(field)
+            .and_then(serde_json::Value::as_array)
+            .map(|items| {
+                items
+                    .iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Parse an RFC 3339 instant or a `YYYY-MM-DD` date. A bare `until` date
+    /// covers that whole day.
+    fn parse_time(
+        args: &serde_json::Value,
+        field: &str,
+        end_of_day: bool,
+    ) -> anyhow::Result<Option<DateTime<Utc>>> {
+        let Some(raw) = args.get(field).and_then(|v| v.as_str()) else {
+            return Ok(None);
+        };
+        if let Ok(ts) = DateTime::parse_from_rfc3339(raw) {
+            return Ok(Some(ts.with_timezone(&Utc)));
+        }
+        let date = NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|_| {
+            anyhow::anyhow!("'{field}' must be an RFC 3339 timestamp or YYYY-MM-DD date")
+        })?;
+        let time = if end_of_day {
+            date.and_hms_opt(23, 59, 59)
+        } else {
+            date.and_hms_opt(0, 0, 0)
+        };
+        Ok(time.map(|t| t.and_utc()))
+    }
+
+    fn parse_filter(args: &serde_json::Value) -> anyhow::Result<RecallFilter> {
+        let categories = Self::string_list(args, "categories")
+            .into_iter()
+            .map(|c| match c.as_str() {
+                "core" => MemoryCategory::Core,
+                "daily" => MemoryCategory::Daily,
+                "conversation" => MemoryCategory::Conversation,
+                _ => MemoryCategory::Custom(c),
+            })
+            .collect();
+        Ok(RecallFilter {
+            session_id: None,
+            categories,
+            tags: Self::string_list(args, "tags"),
+            since: Self::parse_time(args, "since", false)?,
+            until: Self::parse_time(args, "until", true)?,
+            min_score: args.get("min_score").and_then(serde_json::Value::as_f64),
+            source: args
+                .get("source")
+                .and_then(|v| v.as_str())
+                .map(str::to_string),
+            record_access: true,
+        })
+    }
 }
 
 #[async_trait]
@@ -37,12 +99,42 @@ impl Tool for MemoryRecallTool {
                 "limit": {
                     "type": "integer",
                     "description": "Max results to return (default: 5)"
+                },
+                "categories": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return these categories (e.g. ['core', 'docs'])"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Only return memories carrying all of these tags"
+                },
+                "since": {
+                    "type": "string",
+                    "description": "Only memories written at or after this time (RFC 3339 or YYYY-MM-DD)"
+                },
+                "until": {
+                    "type": "string",
+                    "description": "Only memories written at or before this time (RFC 3339 or YYYY-MM-DD)"
+                },
+                "min_score": {
+                    "type": "number",
+                    "description": "Drop results scoring below this relevance"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Only memories from this source (e.g. 'user')"
                 }
             },
             "required": ["query"]
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
         let query = args
             .get("query")
@@ -55,7 +147,9 @@ impl Tool for MemoryRecallTool {
             .and_then(serde_json::Value::as_u64)
             .map_or(5, |v| v as usize);
 
-        match self.memory.recall(query, limit, None).await {
+        let filter = Self::parse_filter(&args)?;
+
+        match self.memory.recall_filtered(query, limit, &filter).await {
             Ok(entries) if entries.is_empty() => Ok(ToolResult {
                 success: true,
                 output: "No memories found matching that query.".into(),
@@ -67,9 +161,14 @@ impl Tool for MemoryRecallTool {
                     let score = entry
                         .score
                         .map_or_else(String::new, |s| format!(" [{s:.0}%]"));
+                    let tags = if entry.metadata.tags.is_empty() {
+                        String::new()
+                    } else {
+                        format!(" #{}", entry.metadata.tags.join(" #"))
+                    };
                     let _ = writeln!(
                         output,
-                        "- [{}] {}: {}{score}",
+                        "- [{}] {}: {}{score}{tags}",
                         entry.category, entry.key, entry.content
                     );
                 }
@@ -91,7 +190,7 @@ impl Tool for MemoryRecallTool {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::memory::{MemoryCategory, SqliteMemory};
+    use crate::memory::{MemoryMetadata, SqliteMemory};
     use tempfile::TempDir;
 
     fn seeded_mem() -> (TempDir, Arc<dyn Memory>) {
@@ -100,6 +199,12 @@ mod tests {
         (tmp, Arc::new(mem))
     }
 
+    #[test]
+    fn recall_may_run_in_parallel() {
+        let (_tmp, mem) = seeded_mem();
+        assert!(MemoryRecallTool::new(mem).parallel_safe());
+    }
+
     #[tokio::test]
     async fn recall_empty() {
         let (_tmp, mem) = seeded_mem();
@@ -164,4 +269,47 @@ mod tests {
         assert_eq!(tool.name(), "memory_recall");
         assert!(tool.parameters_schema()["properties"]["query"].is_object());
     }
+
+    #[tokio::test]
+    async fn recall_filters_by_tag_and_category() {
+        let (_tmp, mem) = seeded_mem();
+        let tagged = MemoryMetadata {
+            tags: vec!["work".into()],
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata("a", "Rust at work", MemoryCategory::Core, None, tagged)
+            .await
+            .unwrap();
+        mem.store("b", "Rust at home", MemoryCategory::Core, None)
+            .await
+            .unwrap();
+        mem.store("c", "Rust daily note", MemoryCategory::Daily, None)
+            .await
+            .unwrap();
+
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "Rust", "tags": ["work"]}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("#work"));
+
+        let result = tool
+            .execute(json!({"query": "Rust", "categories": ["daily"]}))
+            .await
+            .unwrap();
+        assert!(result.output.contains("Found 1"));
+        assert!(result.output.contains("daily note"));
+    }
+
+    #[tokio::test]
+    async fn recall_rejects_bad_dates() {
+        let (_tmp, mem) = seeded_mem();
+        let tool = MemoryRecallTool::new(mem);
+        let result = tool
+            .execute(json!({"query": "Rust", "since": "last tuesday"}))
+            .await;
+        assert!(result.is_err());
+    }
 }
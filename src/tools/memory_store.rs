@@ -1,5 +1,5 @@
 use super::traits::{Tool, ToolResult};
-use crate::memory::{Memory, MemoryCategory};
+use crate::memory::{Memory, MemoryCategory, MemoryMetadata};
 use async_trait::async_trait;
 use serde_json::json;
 use std::sync::Arc;
@@ -13,6 +13,40 @@ impl MemoryStoreTool {
     pub fn new(memory: Arc<dyn Memory>) -> Self {
         Self { memory }
     }
+
+    fn parse_metadata(args: &serde_json::Value) -> anyhow::Result<MemoryMetadata> {
+        let mut metadata = MemoryMetadata {
+            extra: args
+                .get("metadata")
+                .and_then(serde_json::Value::as_object)
+                .cloned()
+                .unwrap_or_default(),
+            ..MemoryMetadata::default()
+        };
+        if let Some(tags) = args.get("tags").and_then(serde_json::Value::as_array) {
+            metadata.tags = tags
+                .iter()
+                .filter_map(|t| t.as_str().map(str::to_string))
+                .collect();
+        }
+        metadata.source = args
+            .get("source")
+            .and_then(|v| v.as_str())
+            .map(str::to_string);
+        metadata.importance = args.get("importance").and_then(serde_json::Value::as_f64);
+        if let Some(ttl) = args.get("ttl_seconds") {
+            let secs = ttl
+                .as_i64()
+                .filter(|secs| *secs > 0)
+                .ok_or_else(|| anyhow::anyhow!("'ttl_seconds' must be a positive integer"))?;
+            let ttl = chrono::TimeDelta::try_seconds(secs)
+                .ok_or_else(|| anyhow::anyhow!("'ttl_seconds' is too large"))?;
+            metadata = metadata
+                .with_ttl(ttl)
+                .map_err(|_| anyhow::anyhow!("'ttl_seconds' is too large"))?;
+        }
+        Ok(metadata)
+    }
 }
 
 #[async_trait]
@@ -41,6 +75,27 @@ impl Tool for MemoryStoreTool {
                     "type": "string",
                     "enum": ["core", "daily", "conversation"],
                     "description": "Memory category: core (permanent), daily (session), conversation (chat)"
+                },
+                "tags": {
+                    "type": "array",
+                    "items": { "type": "string" },
+                    "description": "Labels for filtering recall later (e.g. ['project', 'rust'])"
+                },
+                "source": {
+                    "type": "string",
+                    "description": "Where this came from (e.g. 'user', 'web', a URL)"
+                },
+                "importance": {
+                    "type": "number",
+                    "description": "How important this is, from 0 (trivial) to 1 (critical)"
+                },
+                "ttl_seconds": {
+                    "type": "integer",
+                    "description": "Forget this memory automatically after this many seconds"
+                },
+                "metadata": {
+                    "type": "object",
+                    "description": "Any additional structured fields to keep with the memory"
                 }
             },
             "required": ["key", "content"]
@@ -64,7 +119,22 @@ impl Tool for MemoryStoreTool {
             _ => MemoryCategory::Core,
         };
 
-        match self.memory.store(key, content, category, None).await {
+        let metadata = match Self::parse_metadata(&args) {
+            Ok(metadata) => metadata,
+            Err(e) => {
+                return Ok(ToolResult {
+                    success: false,
+                    output: String::new(),
+                    error: Some(e.to_string()),
+                })
+            }
+        };
+
+        match self
+            .memory
+            .store_with_metadata(key, content, category, None, metadata)
+            .await
+        {
             Ok(()) => Ok(ToolResult {
                 success: true,
                 output: format!("Stored memory: {key}"),
@@ -99,6 +169,7 @@ mod tests {
         let schema = tool.parameters_schema();
         assert!(schema["properties"]["key"].is_object());
         assert!(schema["properties"]["content"].is_object());
+        assert!(!tool.parallel_safe());
     }
 
     #[tokio::test]
@@ -143,4 +214,57 @@ mod tests {
         let result = tool.execute(json!({"key": "no_content"})).await;
         assert!(result.is_err());
     }
+
+    #[tokio::test]
+    async fn store_with_tags_source_and_ttl() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone());
+        let result = tool
+            .execute(json!({
+                "key": "standup",
+                "content": "Standup moved to 10am",
+                "tags": ["work", "schedule"],
+                "source": "user",
+                "importance": 0.7,
+                "ttl_seconds": 3600,
+                "metadata": {"team": "core"}
+            }))
+            .await
+            .unwrap();
+        assert!(result.success);
+
+        let entry = mem.get("standup").await.unwrap().unwrap();
+        assert!(entry.metadata.has_tag("schedule"));
+        assert_eq!(entry.metadata.source.as_deref(), Some("user"));
+        assert_eq!(entry.metadata.importance, Some(0.7));
+        assert!(entry.metadata.expires_at.is_some());
+        assert_eq!(entry.metadata.extra["team"], "core");
+    }
+
+    #[tokio::test]
+    async fn store_rejects_invalid_ttl() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem);
+        let result = tool
+            .execute(json!({"key": "k", "content": "v", "ttl_seconds": -5}))
+            .await
+            .unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("positive integer"));
+    }
+
+    #[tokio::test]
+    async fn store_rejects_overflowing_ttl() {
+        let (_tmp, mem) = test_mem();
+        let tool = MemoryStoreTool::new(mem.clone());
+        for secs in [10_000_000_000_000_i64, i64::MAX] {
+            let result = tool
+                .execute(json!({"key": "k", "content": "v", "ttl_seconds": secs}))
+                .await
+                .unwrap();
+            assert!(!result.success);
+            assert!(result.error.unwrap().contains("too large"));
+        }
+        assert!(mem.get("k").await.unwrap().is_none());
+    }
 }
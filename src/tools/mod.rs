@@ -28,6 +28,7 @@ pub mod schedule;
 pub mod schema;
 pub mod screenshot;
 pub mod shell;
+pub mod skill_tool;
 pub mod traits;
 
 pub use browser::{BrowserTool, ComputerUseConfig};
@@ -61,6 +62,7 @@ pub use schedule::ScheduleTool;
 pub use schema::{CleaningStrategy, SchemaCleanr};
 pub use screenshot::ScreenshotTool;
 pub use shell::ShellTool;
+pub use skill_tool::skill_tools;
 pub use traits::Tool;
 #[allow(unused_imports)]
 pub use traits::{ToolResult, ToolSpec};
@@ -267,7 +269,7 @@ mod tests {
             ..MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, None).unwrap());
 
         let browser = BrowserConfig {
             enabled: false,
@@ -306,7 +308,7 @@ mod tests {
             ..MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, None).unwrap());
 
         let browser = BrowserConfig {
             enabled: true,
@@ -438,7 +440,7 @@ mod tests {
             ..MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, None).unwrap());
 
         let browser = BrowserConfig::default();
         let http = crate::config::HttpRequestConfig::default();
@@ -483,7 +485,7 @@ mod tests {
             ..MemoryConfig::default()
         };
         let mem: Arc<dyn Memory> =
-            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None).unwrap());
+            Arc::from(crate::memory::create_memory(&mem_cfg, tmp.path(), None, None).unwrap());
 
         let browser = BrowserConfig::default();
         let http = crate::config::HttpRequestConfig::default();
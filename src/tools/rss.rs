@@ -193,6 +193,10 @@ impl Tool for RssTool {
         })
     }
 
+    fn parallel_safe(&self) -> bool {
+        true
+    }
+
     async fn execute(&self, params: Value) -> anyhow::Result<ToolResult> {
         let action = params
             .get("action")
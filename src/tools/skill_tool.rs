@@ -0,0 +1,412 @@
+use super::http_request::HttpRequestTool;
+use super::shell::ShellTool;
+use super::traits::{Tool, ToolResult};
+use crate::config::HttpRequestConfig;
+use crate::runtime::RuntimeAdapter;
+use crate::security::SecurityPolicy;
+use crate::skills::{skill_tool_name, Skill, SkillTool};
+use async_trait::async_trait;
+use serde_json::json;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// How a skill tool runs, resolved from `SkillTool.kind`.
+enum Backend {
+    /// `shell` and `script` kinds: the rendered command goes through the
+    /// regular shell tool, so the command allowlist, risk gating, rate limits,
+    /// runtime adapter and environment scrubbing all apply unchanged.
+    Shell(ShellTool),
+    /// `http` kind: the rendered URL goes through the regular HTTP request
+    /// tool and its `[http_request]` domain allowlist.
+    Http(HttpRequestTool),
+}
+
+/// Exposes one `[[tools]]` entry from a SKILL.toml as a callable agent tool.
+///
+/// The command is a template: `{{arg}}` placeholders are replaced with the
+/// call's arguments, shell-quoted for `shell`/`script` kinds and
+/// percent-encoded for `http`.
+pub struct SkillToolAdapter {
+    name: String,
+    description: String,
+    tool: SkillTool,
+    skill_dir: Option<PathBuf>,
+    backend: Backend,
+}
+
+impl SkillToolAdapter {
+    pub fn new(
+        skill: &Skill,
+        tool: &SkillTool,
+        security: Arc<SecurityPolicy>,
+        runtime: Arc<dyn RuntimeAdapter>,
+        http_config: &HttpRequestConfig,
+    ) -> anyhow::Result<Self> {
+        let backend = match tool.kind.trim().to_ascii_lowercase().as_str() {
+            "shell" | "script" => Backend::Shell(ShellTool::new(security, runtime)),
+            "http" => Backend::Http(HttpRequestTool::new(
+                security,
+                http_config.allowed_domains.clone(),
+                http_config.max_response_size,
+                http_config.timeout_secs,
+            )),
+            other => anyhow::bail!("unsupported skill tool kind '{other}'"),
+        };
+
+        Ok(Self {
+            name: skill_tool_name(&skill.name, &tool.name),
+            description: format!("{} (from skill '{}')", tool.description, skill.name),
+            tool: tool.clone(),
+            skill_dir: skill
+                .location
+                .as_deref()
+                .and_then(std::path::Path::parent)
+                .map(std::path::Path::to_path_buf),
+            backend,
+        })
+    }
+
+    /// Substitute `{{arg}}` placeholders in a single pass over the template,
+    /// so inserted values are never scanned for further placeholders.
+    fn render(&self, args: &serde_json::Value, encode: fn(&str) -> String) -> String {
+        let template = self.tool.command.as_str();
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let Some(end) = after.find("}}") else {
+                break;
+            };
+            let name = &after[..end];
+            rendered.push_str(&rest[..start]);
+            if self.tool.args.contains_key(name) {
+                let value = match args.get(name) {
+                    Some(serde_json::Value::String(s)) => s.clone(),
+                    Some(serde_json::Value::Null) | None => String::new(),
+                    Some(other) => other.to_string(),
+                };
+                rendered.push_str(&encode(&value));
+                rest = &after[end + 2..];
+            } else {
+                rendered.push_str("{{");
+                rest = after;
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+
+    /// Resolve a `script` kind's leading path against the skill directory.
+    fn resolve_script(&self, command: &str) -> anyhow::Result<String> {
+        let command = command.trim_start();
+        let (script, rest) = command
+            .split_once(char::is_whitespace)
+            .unwrap_or((command, ""));
+        if script.is_empty() {
+            anyhow::bail!("script tool has an empty command");
+        }
+
+        let path = match &self.skill_dir {
+            Some(dir) if !std::path::Path::new(script).is_absolute() => dir.join(script),
+            _ => PathBuf::from(script),
+        };
+        let path = path.to_string_lossy();
+        if path
+            .chars()
+            .any(|c| c.is_whitespace() || "'\"\\;|&$`<>".contains(c))
+        {
+            anyhow::bail!("script path contains unsupported characters: {path}");
+        }
+
+        Ok(format!("{path} {rest}").trim_end().to_string())
+    }
+}
+
+#[async_trait]
+impl Tool for SkillToolAdapter {
+    fn name(&self) -> &str {
+        &self.name
+    }
+
+    fn description(&self) -> &str {
+        &self.description
+    }
+
+    fn parameters_schema(&self) -> serde_json::Value {
+        let mut names: Vec<&String> = self.tool.args.keys().collect();
+        names.sort();
+
+        let mut properties = serde_json::Map::new();
+        for name in &names {
+            properties.insert(
+                (*name).clone(),
+                json!({ "type": "string", "description": self.tool.args[*name] }),
+            );
+        }
+        if matches!(self.backend, Backend::Shell(_)) {
+            properties.insert(
+                "approved".into(),
+                json!({
+                    "type": "boolean",
+                    "description": "Set true to explicitly approve medium/high-risk commands in supervised mode",
+                    "default": false
+                }),
+            );
+        }
+
+        json!({
+            "type": "object",
+            "properties": properties,
+            "required": names,
+        })
+    }
+
+    fn parallel_safe(&self) -> bool {
+        matches!(self.backend, Backend::Http(_))
+    }
+
+    async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult> {
+        match &self.backend {
+            Backend::Shell(shell) => {
+                let mut command = self.render(&args, shell_quote);
+                if self.tool.kind.trim().eq_ignore_ascii_case("script") {
+                    command = match self.resolve_script(&command) {
+                        Ok(command) => command,
+                        Err(e) => {
+                            return Ok(ToolResult {
+                                success: false,
+                                output: String::new(),
+                                error: Some(e.to_string()),
+                            })
+                        }
+                    };
+                }
+                let approved = args
+                    .get("approved")
+                    .and_then(serde_json::Value::as_bool)
+                    .unwrap_or(false);
+                shell
+                    .execute(json!({ "command": command, "approved": approved }))
+                    .await
+            }
+            Backend::Http(http) => {
+                let rendered = self.render(&args, percent_encode);
+                let rendered = rendered.trim();
+                let (method, url) = match rendered.split_once(char::is_whitespace) {
+                    Some((method, url)) if !method.contains("://") => (method, url.trim()),
+                    _ => ("GET", rendered),
+                };
+                http.execute(json!({ "url": url, "method": method })).await
+            }
+        }
+    }
+}
+
+/// Build adapters for every tool declared by `skills`.
+///
+/// Tools with an unknown kind are skipped with a warning rather than failing
+/// the whole registry.
+pub fn skill_tools(
+    skills: &[Skill],
+    security: &Arc<SecurityPolicy>,
+    runtime: &Arc<dyn RuntimeAdapter>,
+    http_config: &HttpRequestConfig,
+) -> Vec<Box<dyn Tool>> {
+    let mut tools: Vec<Box<dyn Tool>> = Vec::new();
+    for skill in skills {
+        for tool in &skill.tools {
+            match SkillToolAdapter::new(skill, tool, security.clone(), runtime.clone(), http_config)
+            {
+                Ok(adapter) => tools.push(Box::new(adapter)),
+                Err(e) => tracing::warn!(
+                    skill = %skill.name,
+                    tool = %tool.name,
+                    "Skipping skill tool: {e}"
+                ),
+            }
+        }
+    }
+    tools
+}
+
+fn shell_quote(value: &str) -> String {
+    format!("'{}'", value.replace('\'', r"'\''"))
+}
+
+fn percent_encode(value: &str) -> String {
+    use std::fmt::Write;
+
+    let mut encoded = String::with_capacity(value.len());
+    for byte in value.bytes() {
+        if byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'_' | b'.' | b'~') {
+            encoded.push(char::from(byte));
+        } else {
+            let _ = write!(encoded, "%{byte:02X}");
+        }
+    }
+    encoded
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::runtime::NativeRuntime;
+    use crate::security::AutonomyLevel;
+    use std::collections::HashMap;
+
+    fn skill_with(tool: SkillTool) -> Skill {
+        Skill {
+            name: "weather".into(),
+            description: "Weather lookups".into(),
+            version: "1.0.0".into(),
+            author: None,
+            tags: vec![],
+            tools: vec![tool],
+            prompts: vec![],
+            location: None,
+        }
+    }
+
+    fn skill_tool(kind: &str, command: &str) -> SkillTool {
+        SkillTool {
+            name: "forecast".into(),
+            description: "Fetch the forecast".into(),
+            kind: kind.into(),
+            command: command.into(),
+            args: HashMap::from([("city".to_string(), "City name".to_string())]),
+        }
+    }
+
+    fn security(workspace: &std::path::Path) -> Arc<SecurityPolicy> {
+        Arc::new(SecurityPolicy {
+            autonomy: AutonomyLevel::Full,
+            workspace_dir: workspace.to_path_buf(),
+            ..SecurityPolicy::default()
+        })
+    }
+
+    fn adapter(tool: SkillTool, security: Arc<SecurityPolicy>) -> SkillToolAdapter {
+        let skill = skill_with(tool.clone());
+        SkillToolAdapter::new(
+            &skill,
+            &tool,
+            security,
+            Arc::new(NativeRuntime::new()),
+            &HttpRequestConfig::default(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn adapter_namespaces_name_and_builds_schema_from_args() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = adapter(
+            skill_tool("http", "https://wttr.in/{{city}}"),
+            security(tmp.path()),
+        );
+
+        assert_eq!(tool.name(), "skill__weather__forecast");
+        assert!(tool.description().contains("Fetch the forecast"));
+        assert!(tool.parallel_safe());
+
+        let schema = tool.parameters_schema();
+        assert_eq!(schema["properties"]["city"]["type"], "string");
+        assert_eq!(schema["properties"]["city"]["description"], "City name");
+        assert_eq!(schema["required"], json!(["city"]));
+        assert!(schema["properties"].get("approved").is_none());
+    }
+
+    #[test]
+    fn unknown_kind_is_skipped() {
+        let tmp = tempfile::tempdir().unwrap();
+        let skill = skill_with(skill_tool("grpc", "x"));
+        let runtime: Arc<dyn RuntimeAdapter> = Arc::new(NativeRuntime::new());
+        let tools = skill_tools(
+            &[skill],
+            &security(tmp.path()),
+            &runtime,
+            &HttpRequestConfig::default(),
+        );
+        assert!(tools.is_empty());
+    }
+
+    #[test]
+    fn render_quotes_shell_args_and_encodes_http_args() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = adapter(skill_tool("shell", "echo {{city}}"), security(tmp.path()));
+        let args = json!({ "city": "it's; rm -rf /" });
+
+        assert_eq!(tool.render(&args, shell_quote), r"echo 'it'\''s; rm -rf /'");
+        assert_eq!(
+            tool.render(&json!({ "city": "São Paulo" }), percent_encode),
+            "echo S%C3%A3o%20Paulo"
+        );
+    }
+
+    #[test]
+    fn render_does_not_expand_placeholders_inside_values() {
+        let tmp = tempfile::tempdir().unwrap();
+        let mut tool = skill_tool("shell", "echo {{a}} {{b}} {{unknown}}");
+        tool.args = HashMap::from([
+            ("a".to_string(), "First".to_string()),
+            ("b".to_string(), "Second".to_string()),
+        ]);
+        let tool = adapter(tool, security(tmp.path()));
+        let args = json!({ "a": "{{b}}", "b": "; touch pwned #" });
+
+        assert_eq!(
+            tool.render(&args, shell_quote),
+            "echo '{{b}}' '; touch pwned #' {{unknown}}"
+        );
+    }
+
+    #[tokio::test]
+    async fn shell_kind_runs_through_shell_policy() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = adapter(skill_tool("shell", "echo {{city}}"), security(tmp.path()));
+
+        let result = tool.execute(json!({ "city": "Lisbon" })).await.unwrap();
+        assert!(result.success, "{:?}", result.error);
+        assert_eq!(result.output.trim(), "Lisbon");
+
+        let blocked = adapter(skill_tool("shell", "rm {{city}}"), security(tmp.path()));
+        let result = blocked.execute(json!({ "city": "x" })).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("not allowed"));
+    }
+
+    #[tokio::test]
+    async fn http_kind_enforces_domain_allowlist() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool = adapter(
+            skill_tool("http", "GET https://wttr.in/{{city}}"),
+            security(tmp.path()),
+        );
+
+        let result = tool.execute(json!({ "city": "Lisbon" })).await.unwrap();
+        assert!(!result.success);
+        assert!(result.error.unwrap().contains("allowed_domains"));
+    }
+
+    #[test]
+    fn script_kind_resolves_against_skill_dir() {
+        let tmp = tempfile::tempdir().unwrap();
+        let tool_def = skill_tool("script", "scripts/fetch.sh {{city}}");
+        let mut skill = skill_with(tool_def.clone());
+        skill.location = Some(tmp.path().join("weather").join("SKILL.toml"));
+        let tool = SkillToolAdapter::new(
+            &skill,
+            &tool_def,
+            security(tmp.path()),
+            Arc::new(NativeRuntime::new()),
+            &HttpRequestConfig::default(),
+        )
+        .unwrap();
+
+        let rendered = tool.render(&json!({ "city": "Oslo" }), shell_quote);
+        let command = tool.resolve_script(&rendered).unwrap();
+        let expected = tmp.path().join("weather").join("scripts").join("fetch.sh");
+        assert_eq!(command, format!("{} 'Oslo'", expected.display()));
+        assert!(!tool.parallel_safe());
+    }
+}
@@ -32,6 +32,13 @@ pub trait Tool: Send + Sync {
     /// Execute the tool with given arguments
     async fn execute(&self, args: serde_json::Value) -> anyhow::Result<ToolResult>;
 
+    /// Whether calls to this tool may run concurrently with other tool calls
+    /// from the same model response. Tools run alone unless they opt in;
+    /// only read-only tools (recall, file reads, listings) return `true`.
+    fn parallel_safe(&self) -> bool {
+        false
+    }
+
     /// Get the full spec for LLM registration
     fn spec(&self) -> ToolSpec {
         ToolSpec {
@@ -91,6 +98,11 @@ mod tests {
         assert_eq!(spec.parameters["properties"]["value"]["type"], "string");
     }
 
+    #[test]
+    fn tools_run_alone_by_default() {
+        assert!(!DummyTool.parallel_safe());
+    }
+
     #[tokio::test]
     async fn execute_returns_expected_output() {
         let tool = DummyTool;
@@ -2,11 +2,17 @@
 //!
 //! Run with: cargo test --test memory_comparison -- --nocapture
 
+use async_trait::async_trait;
+use std::sync::Arc;
 use std::time::Instant;
 use tempfile::TempDir;
 
 // We test both backends through the public memory module
-use zeroclaw::memory::{markdown::MarkdownMemory, sqlite::SqliteMemory, Memory, MemoryCategory};
+use zeroclaw::memory::{
+    embeddings::EmbeddingProvider, ivf::VectorIndexSettings, markdown::MarkdownMemory,
+    ranking::RankingSettings, sqlite::SqliteMemory, Memory, MemoryCategory, MemoryMetadata,
+    RecallFilter,
+};
 
 // ── Helpers ────────────────────────────────────────────────────
 
@@ -445,3 +451,308 @@ async fn compare_category_filter() {
     assert!(!md_core.is_empty());
     assert!(!md_all.is_empty());
 }
+
+// ── Test 8: IVF vector index vs exact scan ─────────────────────
+
+const EMBED_DIMS: usize = 64;
+const CLUSTERS: u64 = 40;
+
+/// Synthetic clustered embeddings: "doc N" / "query N" map to a point near
+/// the centre of cluster N % CLUSTERS, with per-text deterministic noise.
+struct ClusteredEmbedding;
+
+fn pseudo_random(seed: u64, dims: usize) -> Vec<f32> {
+    let mut state = seed.wrapping_mul(6_364_136_223_846_793_005).wrapping_add(1);
+    (0..dims)
+        .map(|_| {
+            state = state
+                .wrapping_mul(6_364_136_223_846_793_005)
+                .wrapping_add(1_442_695_040_888_963_407);
+            #[allow(clippy::cast_precision_loss)]
+            let unit = (state >> 40) as f32 / (1u64 << 24) as f32;
+            unit * 2.0 - 1.0
+        })
+        .collect()
+}
+
+fn clustered_embedding(text: &str) -> Vec<f32> {
+    let (kind, n) = text.split_once(' ').unwrap_or((text, "0"));
+    let n: u64 = n.parse().unwrap_or(0);
+    let noise_seed = if kind == "query" { n + 1_000_000 } else { n };
+    let centre = pseudo_random(n % CLUSTERS + 10_000_000, EMBED_DIMS);
+    let noise = pseudo_random(noise_seed, EMBED_DIMS);
+    centre.iter().zip(noise).map(|(c, e)| c + 0.3 * e).collect()
+}
+
+#[async_trait]
+impl EmbeddingProvider for ClusteredEmbedding {
+    fn name(&self) -> &str {
+        "clustered"
+    }
+
+    fn dimensions(&self) -> usize {
+        EMBED_DIMS
+    }
+
+    async fn embed(&self, texts: &[&str]) -> anyhow::Result<Vec<Vec<f32>>> {
+        Ok(texts.iter().map(|t| clustered_embedding(t)).collect())
+    }
+}
+
+fn vector_backend(dir: &std::path::Path, enabled: bool) -> SqliteMemory {
+    SqliteMemory::with_embedder(dir, Arc::new(ClusteredEmbedding), 1.0, 0.0, 100)
+        .expect("SQLite init failed")
+        .with_vector_index(VectorIndexSettings {
+            enabled,
+            min_rows: 1_000,
+            probes: 8,
+        })
+        // Measure the index alone, not the re-ranking on top of it
+        .with_ranking(RankingSettings {
+            enabled: false,
+            ..RankingSettings::default()
+        })
+}
+
+#[tokio::test]
+async fn compare_vector_index_recall() {
+    let tmp = TempDir::new().unwrap();
+    let indexed = vector_backend(tmp.path(), true);
+
+    let n = 3_000;
+    for i in 0..n {
+        indexed
+            .store(
+                &format!("d{i}"),
+                &format!("doc {i}"),
+                MemoryCategory::Core,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+    indexed.reindex().await.unwrap();
+
+    // Same database, index ignored → exact cosine scan
+    let exact = vector_backend(tmp.path(), false);
+
+    let queries = 50;
+    let k = 10;
+    let mut overlap = 0;
+    let mut exact_dur = std::time::Duration::ZERO;
+    let mut indexed_dur = std::time::Duration::ZERO;
+    for q in 0..queries {
+        let query = format!("query {q}");
+
+        let start = Instant::now();
+        let truth = exact.recall(&query, k, None).await.unwrap();
+        exact_dur += start.elapsed();
+
+        let start = Instant::now();
+        let approx = indexed.recall(&query, k, None).await.unwrap();
+        indexed_dur += start.elapsed();
+
+        overlap += approx
+            .iter()
+            .filter(|a| truth.iter().any(|t| t.key == a.key))
+            .count();
+    }
+
+    #[allow(clippy::cast_precision_loss)]
+    let recall_at_k = overlap as f64 / (queries * k) as f64;
+
+    println!("\n============================================================");
+    println!("VECTOR RECALL over {n} embeddings ({EMBED_DIMS} dims, {queries} queries, k={k}):");
+    println!("  Exact scan: {:?} total", exact_dur);
+    println!("  IVF index:  {:?} total", indexed_dur);
+    println!("  IVF recall@{k} vs exact: {recall_at_k:.3}");
+
+    assert!(
+        recall_at_k >= 0.8,
+        "IVF recall@{k} too low: {recall_at_k:.3}"
+    );
+}
+
+// ── Test 9: Ranked recall vs plain hybrid order ────────────────
+
+/// (key, content, category, age in days, importance)
+type RankingFixture = (&'static str, &'static str, MemoryCategory, i64, Option<f64>);
+
+fn ranking_fixtures() -> Vec<RankingFixture> {
+    vec![
+        // Recency: the same topic months apart
+        (
+            "deploy_old",
+            "we deploy the backend service on fridays",
+            MemoryCategory::Conversation,
+            120,
+            None,
+        ),
+        (
+            "deploy_new",
+            "we deploy the backend service on mondays",
+            MemoryCategory::Conversation,
+            1,
+            None,
+        ),
+        // Importance: a decision vs an incidental note
+        (
+            "db_note",
+            "billing database vacuum runs nightly",
+            MemoryCategory::Core,
+            10,
+            Some(0.1),
+        ),
+        (
+            "db_choice",
+            "billing database is postgres by decision",
+            MemoryCategory::Core,
+            10,
+            Some(1.0),
+        ),
+        // Diversity: three restatements and two distinct facts
+        (
+            "theme_1",
+            "user prefers the dark theme in the editor",
+            MemoryCategory::Core,
+            2,
+            None,
+        ),
+        (
+            "theme_2",
+            "user prefers the dark theme in the editor",
+            MemoryCategory::Core,
+            2,
+            None,
+        ),
+        (
+            "theme_3",
+            "user prefers the dark theme in the editor",
+            MemoryCategory::Core,
+            2,
+            None,
+        ),
+        (
+            "indent",
+            "user prefers tabs over spaces for indentation in the editor",
+            MemoryCategory::Core,
+            2,
+            None,
+        ),
+        (
+            "font",
+            "user prefers a large monospace font size in the editor",
+            MemoryCategory::Core,
+            2,
+            None,
+        ),
+    ]
+}
+
+async fn seed_ranking_fixtures(dir: &std::path::Path) {
+    let mem = sqlite_backend(dir);
+    for (key, content, category, _, importance) in ranking_fixtures() {
+        let metadata = MemoryMetadata {
+            importance,
+            ..MemoryMetadata::default()
+        };
+        mem.store_with_metadata(key, content, category, None, metadata)
+            .await
+            .unwrap();
+    }
+    drop(mem);
+
+    // Backdate rows so recency has something to act on
+    let conn = rusqlite::Connection::open(dir.join("memory").join("brain.db")).unwrap();
+    for (key, _, _, age_days, _) in ranking_fixtures() {
+        let created = (chrono::Utc::now() - chrono::Duration::days(age_days)).to_rfc3339();
+        conn.execute(
+            "UPDATE memories SET created_at = ?1 WHERE key = ?2",
+            rusqlite::params![created, key],
+        )
+        .unwrap();
+    }
+}
+
+#[tokio::test]
+async fn compare_ranked_recall() {
+    let tmp = TempDir::new().unwrap();
+    seed_ranking_fixtures(tmp.path()).await;
+
+    let ranked = sqlite_backend(tmp.path());
+    let plain = sqlite_backend(tmp.path()).with_ranking(RankingSettings {
+        enabled: false,
+        ..RankingSettings::default()
+    });
+
+    // (query, limit, keys that must all appear in the ranked top `limit`)
+    let cases: [(&str, usize, &[&str]); 3] = [
+        ("deploy backend service", 1, &["deploy_new"]),
+        ("billing database", 1, &["db_choice"]),
+        ("user prefers editor", 3, &["indent", "font"]),
+    ];
+
+    let mut ranked_hits = 0;
+    let mut plain_hits = 0;
+    println!("\n============================================================");
+    println!("RANKED vs PLAIN recall ({} fixture queries):", cases.len());
+    for (query, limit, expected) in cases {
+        let r = ranked.recall(query, limit, None).await.unwrap();
+        let p = plain.recall(query, limit, None).await.unwrap();
+        let hit = |entries: &[zeroclaw::memory::MemoryEntry]| {
+            expected
+                .iter()
+                .all(|key| entries.iter().any(|e| e.key == *key))
+        };
+        ranked_hits += usize::from(hit(&r));
+        plain_hits += usize::from(hit(&p));
+        println!(
+            "  {query:?}: ranked={:?} plain={:?}",
+            r.iter().map(|e| e.key.as_str()).collect::<Vec<_>>(),
+            p.iter().map(|e| e.key.as_str()).collect::<Vec<_>>()
+        );
+    }
+    println!("  Ranked hits: {ranked_hits}/{}", cases.len());
+    println!("  Plain hits:  {plain_hits}/{}", cases.len());
+
+    assert_eq!(ranked_hits, cases.len());
+    assert!(ranked_hits >= plain_hits);
+}
+
+#[tokio::test]
+async fn compare_access_frequency_boost() {
+    let tmp = TempDir::new().unwrap();
+    let mem = sqlite_backend(tmp.path()).with_ranking(RankingSettings {
+        access_weight: 0.5,
+        ..RankingSettings::default()
+    });
+    mem.store(
+        "lang_a",
+        "favourite language rust",
+        MemoryCategory::Core,
+        None,
+    )
+    .await
+    .unwrap();
+    mem.store(
+        "lang_b",
+        "favourite language go",
+        MemoryCategory::Core,
+        None,
+    )
+    .await
+    .unwrap();
+
+    // Explicitly recalling "go" repeatedly makes it the preferred answer for
+    // the shared terms
+    let explicit = RecallFilter {
+        record_access: true,
+        ..RecallFilter::default()
+    };
+    for _ in 0..10 {
+        let hits = mem.recall_filtered("go", 1, &explicit).await.unwrap();
+        assert_eq!(hits[0].key, "lang_b");
+    }
+    let top = mem.recall("favourite language", 1, None).await.unwrap();
+    assert_eq!(top[0].key, "lang_b");
+}